@@ -0,0 +1,251 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use sha2::{Digest, Sha256, Sha512};
+
+/// Digest algorithm of the hashes, set with [`Hasher::algorithm`](crate::Hasher::algorithm).
+///
+/// All algorithms hash exactly the same normalized content, only the digest and thus the length
+/// of the hex output differs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Algorithm {
+    /// SHA-256, with 64 hex digits.
+    #[default]
+    Sha256,
+
+    /// SHA-512, with 128 hex digits.
+    Sha512,
+
+    /// BLAKE3, with 64 hex digits like SHA-256.
+    Blake3,
+}
+
+impl Algorithm {
+    /// All supported algorithms.
+    pub const ALL: [Algorithm; 3] = [Algorithm::Sha256, Algorithm::Sha512, Algorithm::Blake3];
+
+    /// Lowercase name of the algorithm, like `sha256`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Sha256 => "sha256",
+            Algorithm::Sha512 => "sha512",
+            Algorithm::Blake3 => "blake3",
+        }
+    }
+
+    /// Name of the algorithm as written in documents, like `SHA-256`.
+    pub fn label(self) -> &'static str {
+        match self {
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Sha512 => "SHA-512",
+            Algorithm::Blake3 => "BLAKE3",
+        }
+    }
+
+    /// Algorithm whose digests have `size` bytes, `None` if no supported algorithm matches.
+    ///
+    /// The size cannot tell SHA-256 and BLAKE3 apart, so SHA-256 is returned for 32 bytes. BLAKE3
+    /// digests have to be named explicitly, like with
+    /// [`Encoding::encode_with`](crate::Encoding::encode_with).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Algorithm;
+    ///
+    /// assert_eq!(Algorithm::from_output_size(64), Some(Algorithm::Sha512));
+    /// assert_eq!(Algorithm::from_output_size(20), None);
+    /// ```
+    pub fn from_output_size(size: usize) -> Option<Algorithm> {
+        Algorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.output_size() == size)
+    }
+
+    /// Algorithm of a hash in hex, told by its length, `None` if it is no hash in hex.
+    ///
+    /// As with [`from_output_size`](Algorithm::from_output_size), 64 hex digits are SHA-256, even
+    /// if they are a BLAKE3 hash. The algorithm of such hashes has to be known from elsewhere, like
+    /// a BSD-style line or a checksum header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Algorithm;
+    ///
+    /// let hash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+    /// assert_eq!(Algorithm::of_hex(hash), Some(Algorithm::Sha256));
+    /// assert_eq!(Algorithm::of_hex(&hash[1..]), None);
+    /// ```
+    pub fn of_hex(hash: &str) -> Option<Algorithm> {
+        if !hash.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        Algorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.hex_len() == hash.len())
+    }
+
+    /// Size of a digest in bytes.
+    pub fn output_size(self) -> usize {
+        match self {
+            Algorithm::Sha256 => 32,
+            Algorithm::Sha512 => 64,
+            Algorithm::Blake3 => blake3::OUT_LEN,
+        }
+    }
+
+    /// Number of hex digits of a hash.
+    pub fn hex_len(self) -> usize {
+        2 * self.output_size()
+    }
+
+    /// Function code of the algorithm in a multihash.
+    pub(crate) fn multihash_code(self) -> u8 {
+        match self {
+            Algorithm::Sha256 => 0x12,
+            Algorithm::Sha512 => 0x13,
+            Algorithm::Blake3 => 0x1e,
+        }
+    }
+
+    /// Create a fresh digest of this algorithm.
+    pub(crate) fn digest(self) -> AnyDigest {
+        match self {
+            Algorithm::Sha256 => AnyDigest::Sha256(Sha256::new()),
+            Algorithm::Sha512 => AnyDigest::Sha512(Sha512::new()),
+            Algorithm::Blake3 => AnyDigest::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error of parsing an unknown [`Algorithm`] name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownAlgorithm(String);
+
+impl fmt::Display for UnknownAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown digest algorithm '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownAlgorithm {}
+
+#[cfg(feature = "config")]
+impl<'de> serde::Deserialize<'de> for Algorithm {
+    /// Deserialize a name as parsed by [`FromStr`].
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = UnknownAlgorithm;
+
+    /// Parse a name like `sha256`, ignoring case and a dash like in `SHA-256`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let normalized = name.to_ascii_lowercase().replace('-', "");
+        Algorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name() == normalized)
+            .ok_or_else(|| UnknownAlgorithm(name.to_string()))
+    }
+}
+
+/// Digest of any [`Algorithm`], chosen at runtime.
+pub(crate) enum AnyDigest {
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl AnyDigest {
+    /// Finish the digest as lowercase hex.
+    pub(crate) fn finalize_hex(self) -> String {
+        match self {
+            AnyDigest::Sha256(digest) => base16ct::lower::encode_string(&digest.finalize()),
+            AnyDigest::Sha512(digest) => base16ct::lower::encode_string(&digest.finalize()),
+            AnyDigest::Blake3(digest) => digest.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+impl sha2::digest::Update for AnyDigest {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            AnyDigest::Sha256(digest) => Digest::update(digest, data),
+            AnyDigest::Sha512(digest) => Digest::update(digest, data),
+            AnyDigest::Blake3(digest) => {
+                digest.update(data);
+            }
+        }
+    }
+}
+
+impl Write for AnyDigest {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        sha2::digest::Update::update(self, buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_from_str() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(algorithm.name().parse(), Ok(algorithm));
+            assert_eq!(algorithm.to_string(), algorithm.name());
+        }
+        assert_eq!("SHA-512".parse(), Ok(Algorithm::Sha512));
+        assert_eq!("BLAKE3".parse(), Ok(Algorithm::Blake3));
+        assert_eq!(
+            "md5".parse::<Algorithm>(),
+            Err(UnknownAlgorithm("md5".to_string()))
+        );
+    }
+
+    #[test]
+    fn check_known_answers() {
+        let cases = [
+            (
+                Algorithm::Sha256,
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                Algorithm::Blake3,
+                "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85",
+            ),
+        ];
+
+        for (algorithm, hash) in cases {
+            let mut digest = algorithm.digest();
+            digest.write_all(b"abc").unwrap();
+            assert_eq!(digest.finalize_hex(), hash, "{algorithm}");
+        }
+    }
+
+    #[test]
+    fn check_ambiguous_length() {
+        let hash = "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85";
+        assert_eq!(Algorithm::of_hex(hash), Some(Algorithm::Sha256));
+        assert_eq!(Algorithm::from_output_size(32), Some(Algorithm::Sha256));
+        assert_eq!(Algorithm::Blake3.hex_len(), hash.len());
+    }
+}
@@ -0,0 +1,55 @@
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::path::open_input;
+use crate::ErrorKind;
+
+/// Number of bytes that are inspected to detect binary files.
+const BINARY_PROBE_LEN: u64 = 8000;
+
+/// Check if a file looks like a binary file.
+///
+/// Like git, a file is considered binary if it contains a NUL byte within its first 8000 bytes.
+/// Such files are not meant to be normalized, so they are skipped when walking directory trees.
+///
+/// # Example
+///
+/// ```no_run
+/// use normalized_hash::is_binary;
+///
+/// if is_binary("image.png").unwrap() {
+///     println!("image.png is a binary file");
+/// }
+/// ```
+pub fn is_binary(file: impl AsRef<Path>) -> io::Result<bool> {
+    let mut buf = Vec::new();
+    open_input(file.as_ref())?
+        .take(BINARY_PROBE_LEN)
+        .read_to_end(&mut buf)
+        .map_err(ErrorKind::tag_read)?;
+
+    Ok(buf.contains(&0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::io::Write;
+
+    use tempfile::NamedTempFile;
+
+    use super::*;
+
+    #[test]
+    fn check_is_binary() -> Result<(), Box<dyn Error>> {
+        let mut text = NamedTempFile::new()?;
+        text.write_all(b"A B\r\nC D\r\n")?;
+        assert!(!is_binary(&text)?);
+
+        let mut binary = NamedTempFile::new()?;
+        binary.write_all(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR")?;
+        assert!(is_binary(&binary)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::{Algorithm, ErrorKind};
+
+/// File name of configuration files, which are searched in a directory and its parents.
+pub const CONFIG_FILE: &str = ".normalized-hasher.toml";
+
+/// Settings of a configuration file, like `.normalized-hasher.toml`.
+///
+/// The file is written in TOML, with the names of the command line flags of
+/// `normalized-hasher` as keys. Settings that are not given are `None`, so several files can be
+/// layered with [`Config::merge`]. Unknown keys are an error, so misspelled settings are not
+/// ignored silently.
+///
+/// # Example
+///
+/// ```
+/// use normalized_hash::Config;
+///
+/// let config: Config = "eol = \"crlf\"\nexclude = [\"*.min.js\"]\n".parse().unwrap();
+/// assert_eq!(config.eol.as_deref(), Some("crlf"));
+/// assert_eq!(config.no_eof, None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct Config {
+    /// End-of-line sequence, either literally or as one of the names `lf`, `crlf` and `cr`.
+    pub eol: Option<String>,
+
+    /// Ignore all whitespaces.
+    pub ignore_whitespaces: Option<bool>,
+
+    /// Skip last end-of-line on end-of-file.
+    pub no_eof: Option<bool>,
+
+    /// Digest algorithm of the hashes, by its name like `sha512`.
+    pub algorithm: Option<Algorithm>,
+
+    /// Glob patterns of files to be excluded when walking directories.
+    pub exclude: Option<Vec<String>>,
+
+    /// Skip hidden files and directories when walking directories.
+    pub no_hidden: Option<bool>,
+
+    /// Don't respect `.gitignore` files when walking directories.
+    pub no_ignore: Option<bool>,
+}
+
+impl Config {
+    /// Read a configuration file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(path).map_err(|err| ErrorKind::Open.tag(err))?;
+        content.parse()
+    }
+
+    /// Find the nearest configuration file in `dir` or one of its parents.
+    pub fn find(dir: impl AsRef<Path>) -> Option<PathBuf> {
+        dir.as_ref()
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE))
+            .find(|path| path.is_file())
+    }
+
+    /// Find all configuration files that apply to `dir`, from the farthest to the nearest.
+    ///
+    /// These are the files in `dir` and its parents, up to the root of the Git repository that
+    /// contains `dir`, or up to the root of the filesystem outside of repositories. Merging them
+    /// in the returned order with [`Config::merge`] lets nearer files override farther ones, like
+    /// a subtree that keeps other line endings than the rest of the repository.
+    pub fn find_all(dir: impl AsRef<Path>) -> Vec<PathBuf> {
+        let mut found = Vec::new();
+        for dir in dir.as_ref().ancestors() {
+            let path = dir.join(CONFIG_FILE);
+            if path.is_file() {
+                found.push(path);
+            }
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+
+        found.reverse();
+        found
+    }
+
+    /// Take over all settings that are given in `other`, keeping the remaining ones.
+    pub fn merge(&mut self, other: Config) {
+        let Config {
+            eol,
+            ignore_whitespaces,
+            no_eof,
+            algorithm,
+            exclude,
+            no_hidden,
+            no_ignore,
+        } = other;
+
+        self.eol = eol.or(self.eol.take());
+        self.ignore_whitespaces = ignore_whitespaces.or(self.ignore_whitespaces);
+        self.no_eof = no_eof.or(self.no_eof);
+        self.algorithm = algorithm.or(self.algorithm);
+        self.exclude = exclude.or(self.exclude.take());
+        self.no_hidden = no_hidden.or(self.no_hidden);
+        self.no_ignore = no_ignore.or(self.no_ignore);
+    }
+}
+
+impl FromStr for Config {
+    type Err = io::Error;
+
+    fn from_str(content: &str) -> io::Result<Self> {
+        toml::from_str(content).map_err(|err| {
+            let message = match err.span() {
+                Some(span) => {
+                    let line = content[..span.start].matches('\n').count() + 1;
+                    format!("line {}: {}", line, err.message())
+                }
+                None => err.message().to_string(),
+            };
+            io::Error::new(io::ErrorKind::InvalidData, message)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_parse() {
+        let config: Config = "eol = \"\\r\\n\"\nno-eof = true\nexclude = [\"*.bak\"]\n"
+            .parse()
+            .unwrap();
+        assert_eq!(config.eol.as_deref(), Some("\r\n"));
+        assert_eq!(config.no_eof, Some(true));
+        assert_eq!(config.ignore_whitespaces, None);
+        assert_eq!(config.exclude, Some(vec!["*.bak".to_string()]));
+        assert_eq!(config.algorithm, None);
+
+        let config: Config = "algorithm = \"SHA-512\"\n".parse().unwrap();
+        assert_eq!(config.algorithm, Some(Algorithm::Sha512));
+
+        assert_eq!("".parse::<Config>().unwrap(), Config::default());
+    }
+
+    #[test]
+    fn check_parse_errors() {
+        let err = "eol = \"\\n\"\nno-eof = \"yes\"\n"
+            .parse::<Config>()
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 2: "), "{}", err);
+
+        let err = "no_eof = true\n".parse::<Config>().unwrap_err();
+        assert!(err.to_string().contains("unknown field"), "{}", err);
+
+        let err = "algorithm = \"md5\"\n".parse::<Config>().unwrap_err();
+        assert!(
+            err.to_string().contains("unknown digest algorithm"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn check_merge() {
+        let mut config: Config = "eol = \"cr\"\nno-eof = true\n".parse().unwrap();
+        config.merge(
+            "no-eof = false\nno-hidden = true\nalgorithm = \"blake3\"\n"
+                .parse()
+                .unwrap(),
+        );
+
+        assert_eq!(config.eol.as_deref(), Some("cr"));
+        assert_eq!(config.no_eof, Some(false));
+        assert_eq!(config.no_hidden, Some(true));
+        assert_eq!(config.algorithm, Some(Algorithm::Blake3));
+    }
+
+    #[test]
+    fn check_find() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a/b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(dir.path().join("a").join(CONFIG_FILE), "").unwrap();
+
+        assert_eq!(
+            Config::find(&nested),
+            Some(dir.path().join("a").join(CONFIG_FILE))
+        );
+        assert_eq!(Config::find(dir.path()), None);
+    }
+
+    #[test]
+    fn check_find_all() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("repo/a/b");
+        fs::create_dir_all(&nested).unwrap();
+        for config in ["", "repo", "repo/a/b"] {
+            fs::write(dir.path().join(config).join(CONFIG_FILE), "").unwrap();
+        }
+
+        let found = |path: &str| dir.path().join(path).join(CONFIG_FILE);
+        assert_eq!(
+            Config::find_all(&nested),
+            [found(""), found("repo"), found("repo/a/b")]
+        );
+
+        // The search ends at the root of a repository
+        fs::create_dir(dir.path().join("repo/.git")).unwrap();
+        assert_eq!(
+            Config::find_all(&nested),
+            [found("repo"), found("repo/a/b")]
+        );
+        assert_eq!(Config::find_all(dir.path().join("repo/a")), [found("repo")]);
+    }
+}
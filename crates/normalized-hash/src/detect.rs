@@ -0,0 +1,217 @@
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::path::open_input;
+use crate::ErrorKind;
+
+/// Characters of Windows-1252 for the bytes `0x80` to `0x9F`, where it differs from Latin-1.
+///
+/// Unassigned bytes are mapped to the C1 control character of the same value, like Windows does.
+const WINDOWS_1252: [char; 32] = [
+    '\u{20ac}', '\u{81}', '\u{201a}', '\u{192}', '\u{201e}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{2c6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8d}', '\u{17d}', '\u{8f}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201c}', '\u{201d}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{2dc}', '\u{2122}', '\u{161}', '\u{203a}', '\u{153}', '\u{9d}', '\u{17e}', '\u{178}',
+];
+
+/// Character encoding of a file, as guessed by [`detect_encoding`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TextEncoding {
+    /// UTF-8, which includes plain ASCII.
+    Utf8,
+
+    /// UTF-16 in little endian byte order, as written by many Windows tools.
+    Utf16Le,
+
+    /// UTF-16 in big endian byte order.
+    Utf16Be,
+
+    /// Not valid UTF-8, most likely Windows-1252 or another single-byte encoding.
+    Windows1252,
+
+    /// Not a text file at all.
+    Binary,
+}
+
+impl Display for TextEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+            TextEncoding::Windows1252 => "Windows-1252",
+            TextEncoding::Binary => "binary",
+        })
+    }
+}
+
+/// Result of [`detect_encoding`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Detection {
+    /// Guessed encoding of the file.
+    pub encoding: TextEncoding,
+
+    /// Whether the file starts with a byte order mark of its encoding.
+    pub bom: bool,
+
+    /// Content of the file decoded to a string, without byte order mark, `None` if binary.
+    pub text: Option<String>,
+}
+
+/// Guess the character encoding of a file.
+///
+/// A byte order mark decides the encoding if present. Otherwise, a file with NUL bytes is
+/// UTF-16 if the NUL bytes are the high bytes of ASCII characters, and binary if not. A file
+/// without NUL bytes is UTF-8 if it is valid UTF-8, and Windows-1252 if not. Since every byte
+/// sequence is valid Windows-1252, this is only a likely guess.
+///
+/// Only UTF-8 files can be hashed, the other encodings are detected to help finding out why a
+/// file cannot be hashed or why its hash differs.
+///
+/// # Example
+///
+/// ```no_run
+/// use normalized_hash::{detect_encoding, TextEncoding};
+///
+/// let detection = detect_encoding("input.txt").unwrap();
+/// if detection.encoding != TextEncoding::Utf8 {
+///     println!("input.txt is encoded in {}", detection.encoding);
+/// }
+/// ```
+pub fn detect_encoding(file: impl AsRef<Path>) -> io::Result<Detection> {
+    let mut bytes = Vec::new();
+    open_input(file.as_ref())?
+        .read_to_end(&mut bytes)
+        .map_err(|err| ErrorKind::Read.tag(err))?;
+
+    Ok(detect_bytes(&bytes))
+}
+
+fn detect_bytes(bytes: &[u8]) -> Detection {
+    if let Some(rest) = bytes.strip_prefix(b"\xef\xbb\xbf") {
+        if let Ok(text) = std::str::from_utf8(rest) {
+            return detection(TextEncoding::Utf8, true, Some(text.to_string()));
+        }
+    }
+
+    if let Some(rest) = bytes.strip_prefix(b"\xff\xfe") {
+        return detection(
+            TextEncoding::Utf16Le,
+            true,
+            decode_utf16(rest, u16::from_le_bytes),
+        );
+    }
+
+    if let Some(rest) = bytes.strip_prefix(b"\xfe\xff") {
+        return detection(
+            TextEncoding::Utf16Be,
+            true,
+            decode_utf16(rest, u16::from_be_bytes),
+        );
+    }
+
+    if bytes.contains(&0) {
+        // Each NUL byte has to be the high byte of a character, and at least half of the
+        // characters have to be ASCII
+        let is_utf16 = |high: usize| {
+            let pairs = bytes.len() / 2;
+            bytes.len().is_multiple_of(2)
+                && bytes.chunks(2).all(|pair| pair[1 - high] != 0)
+                && bytes.chunks(2).filter(|pair| pair[high] == 0).count() * 2 >= pairs
+        };
+
+        if is_utf16(1) {
+            let text = decode_utf16(bytes, u16::from_le_bytes);
+            if text.is_some() {
+                return detection(TextEncoding::Utf16Le, false, text);
+            }
+        } else if is_utf16(0) {
+            let text = decode_utf16(bytes, u16::from_be_bytes);
+            if text.is_some() {
+                return detection(TextEncoding::Utf16Be, false, text);
+            }
+        }
+
+        return detection(TextEncoding::Binary, false, None);
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) => detection(TextEncoding::Utf8, false, Some(text.to_string())),
+        Err(_) => {
+            let text = bytes
+                .iter()
+                .map(|&byte| match byte {
+                    0x80..=0x9f => WINDOWS_1252[usize::from(byte - 0x80)],
+                    _ => char::from(byte),
+                })
+                .collect();
+            detection(TextEncoding::Windows1252, false, Some(text))
+        }
+    }
+}
+
+fn detection(encoding: TextEncoding, bom: bool, text: Option<String>) -> Detection {
+    // Undecodable content is not text
+    let encoding = if text.is_none() {
+        TextEncoding::Binary
+    } else {
+        encoding
+    };
+
+    Detection {
+        encoding,
+        bom: bom && text.is_some(),
+        text,
+    }
+}
+
+/// Decode UTF-16 with the given byte order, `None` if invalid.
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Option<String> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let units = bytes.chunks(2).map(|pair| from_bytes([pair[0], pair[1]]));
+
+    char::decode_utf16(units).collect::<Result<_, _>>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_detect_bytes() {
+        let detection = detect_bytes(b"A\r\nB\n");
+        assert_eq!(detection.encoding, TextEncoding::Utf8);
+        assert!(!detection.bom);
+        assert_eq!(detection.text.as_deref(), Some("A\r\nB\n"));
+
+        let detection = detect_bytes(b"\xef\xbb\xbfA\n");
+        assert_eq!(detection.encoding, TextEncoding::Utf8);
+        assert!(detection.bom);
+        assert_eq!(detection.text.as_deref(), Some("A\n"));
+
+        let detection = detect_bytes(b"\xff\xfeA\0\r\0\n\0");
+        assert_eq!(detection.encoding, TextEncoding::Utf16Le);
+        assert!(detection.bom);
+        assert_eq!(detection.text.as_deref(), Some("A\r\n"));
+
+        let detection = detect_bytes(b"A\0B\0\n\0");
+        assert_eq!(detection.encoding, TextEncoding::Utf16Le);
+        assert!(!detection.bom);
+
+        let detection = detect_bytes(b"\0A\0B\0\n");
+        assert_eq!(detection.encoding, TextEncoding::Utf16Be);
+
+        let detection = detect_bytes(b"caf\xe9 \x80\n");
+        assert_eq!(detection.encoding, TextEncoding::Windows1252);
+        assert_eq!(detection.text.as_deref(), Some("caf\u{e9} \u{20ac}\n"));
+
+        let detection = detect_bytes(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+        assert_eq!(detection.encoding, TextEncoding::Binary);
+        assert_eq!(detection.text, None);
+    }
+}
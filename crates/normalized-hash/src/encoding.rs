@@ -0,0 +1,496 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use base64ct::{Base64, Encoding as _};
+
+use crate::Algorithm;
+
+/// Characters that are invisible in documents, like soft hyphens and zero-width spaces.
+const INVISIBLE: [char; 6] = [
+    '\u{ad}', '\u{200b}', '\u{200c}', '\u{200d}', '\u{2060}', '\u{feff}',
+];
+
+/// Quotes that documents put around values, including typographic ones.
+const QUOTES: [char; 9] = [
+    '"', '\'', '`', '\u{201c}', '\u{201d}', '\u{2018}', '\u{2019}', '\u{ab}', '\u{bb}',
+];
+
+/// Reason why a pasted value is no hash of any [`Algorithm`], returned by
+/// [`Encoding::decode_pasted`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvalidHash {
+    /// Nothing is left after removing whitespace, quotes and the algorithm prefix.
+    Empty,
+
+    /// The value is prefixed by an unsupported algorithm, like `SHA-1:`.
+    Algorithm(String),
+
+    /// The value is prefixed by an algorithm, but the hash has the length of another one.
+    AlgorithmLength(Algorithm),
+
+    /// The value only consists of hex digits, but not of as many as a hash of any algorithm.
+    HexLength(usize),
+
+    /// The value contains a character that is not part of any encoding of a hash.
+    Character(char),
+
+    /// The value looks like Base64, but is no digest in Base64.
+    Base64,
+}
+
+impl Display for InvalidHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidHash::Empty => f.write_str("found no hash"),
+            InvalidHash::Algorithm(name) => {
+                write!(f, "found a {} hash, which is not supported", name)
+            }
+            InvalidHash::AlgorithmLength(algorithm) => write!(
+                f,
+                "found a {} prefix, but a hash of another length",
+                algorithm.label()
+            ),
+            InvalidHash::HexLength(len) => {
+                let mut lens = Algorithm::ALL.map(|algorithm| algorithm.hex_len()).to_vec();
+                lens.sort_unstable();
+                lens.dedup();
+                let lens = lens.iter().map(usize::to_string).collect::<Vec<_>>();
+                write!(
+                    f,
+                    "found {} hex digits, but a hash has {} or {}",
+                    len,
+                    lens[..lens.len() - 1].join(", "),
+                    lens[lens.len() - 1]
+                )
+            }
+            InvalidHash::Character(c) => write!(
+                f,
+                "found the character {:?} (U+{:04X}), which is not part of a hash",
+                c, *c as u32
+            ),
+            InvalidHash::Base64 => {
+                f.write_str("found no hash in hex, base64, SRI or multihash encoding")
+            }
+        }
+    }
+}
+
+impl Error for InvalidHash {}
+
+/// Split an algorithm prefix like `SHA-256:` or `sha256 ` from a value.
+///
+/// The name has to contain a letter that is no hex digit, so hex digits in groups are not taken
+/// for a name.
+fn split_algorithm(value: &str) -> Option<(&str, &str)> {
+    let end = value.find(|c: char| c == ':' || c.is_whitespace())?;
+    let (name, rest) = value.split_at(end);
+
+    let is_name = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && name
+            .chars()
+            .any(|c| c.is_ascii_alphabetic() && !c.is_ascii_hexdigit());
+
+    is_name.then(|| (name, rest.strip_prefix(':').unwrap_or(rest)))
+}
+
+/// Textual encoding of a digest of any [`Algorithm`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// Lowercase hex, like `e3b0c442...`, as used by `sha256sum`.
+    #[default]
+    Hex,
+
+    /// Uppercase hex, like `E3B0C442...`.
+    UpperHex,
+
+    /// Standard Base64 with padding, like `47DEQpj8...=`.
+    Base64,
+
+    /// Subresource Integrity, Base64 with the algorithm as prefix, like `sha256-47DEQpj8...=`.
+    Sri,
+
+    /// Multihash in lowercase hex, the digest prefixed by function code and length, like
+    /// `1220e3b0c442...`.
+    Multihash,
+}
+
+/// Prefix of a Subresource Integrity value, like `sha256-`.
+fn sri_prefix(algorithm: Algorithm) -> String {
+    format!("{}-", algorithm.name())
+}
+
+/// Prefix of a multihash, the function code and the digest length, like `[0x12, 0x20]`.
+fn multihash_prefix(algorithm: Algorithm) -> [u8; 2] {
+    [algorithm.multihash_code(), algorithm.output_size() as u8]
+}
+
+/// Decode a multihash in hex of any algorithm into its digest.
+///
+/// Algorithms with digests of the same length are told apart by the function code.
+fn decode_multihash(value: &str) -> Option<Vec<u8>> {
+    let multihash = base16ct::mixed::decode_vec(value).ok()?;
+    Algorithm::ALL.into_iter().find_map(|algorithm| {
+        let digest = multihash.strip_prefix(&multihash_prefix(algorithm)[..])?;
+        (digest.len() == algorithm.output_size()).then(|| digest.to_vec())
+    })
+}
+
+impl Encoding {
+    /// Encode a digest.
+    ///
+    /// The algorithm of SRI and multihash values is told by the length of the digest, digests of
+    /// other lengths are named SHA-256. BLAKE3 digests have the length of SHA-256 digests, use
+    /// [`Encoding::encode_with`] to name them correctly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Encoding;
+    ///
+    /// let digest = [0xab; 32];
+    /// assert!(Encoding::Sri.encode(&digest).starts_with("sha256-q6ur"));
+    /// ```
+    pub fn encode(self, digest: &[u8]) -> String {
+        let algorithm = Algorithm::from_output_size(digest.len()).unwrap_or_default();
+        self.encode_with(algorithm, digest)
+    }
+
+    /// Encode a digest of `algorithm`, which names the algorithm of SRI and multihash values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::{Algorithm, Encoding};
+    ///
+    /// let digest = [0xab; 32];
+    /// assert!(Encoding::Sri
+    ///     .encode_with(Algorithm::Blake3, &digest)
+    ///     .starts_with("blake3-q6ur"));
+    /// ```
+    pub fn encode_with(self, algorithm: Algorithm, digest: &[u8]) -> String {
+        match self {
+            Encoding::Hex => base16ct::lower::encode_string(digest),
+            Encoding::UpperHex => base16ct::upper::encode_string(digest),
+            Encoding::Base64 => Base64::encode_string(digest),
+            Encoding::Sri => {
+                format!("{}{}", sri_prefix(algorithm), Base64::encode_string(digest))
+            }
+            Encoding::Multihash => {
+                let multihash = [&multihash_prefix(algorithm)[..], digest].concat();
+                base16ct::lower::encode_string(&multihash)
+            }
+        }
+    }
+
+    /// Re-encode a hex encoded digest, as returned by [`Hasher::hash_file`].
+    ///
+    /// Returns `None` if `hash` is not valid hex.
+    ///
+    /// [`Hasher::hash_file`]: crate::Hasher::hash_file
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Encoding;
+    ///
+    /// assert_eq!(Encoding::UpperHex.reencode("ab01").as_deref(), Some("AB01"));
+    /// ```
+    pub fn reencode(self, hash: &str) -> Option<String> {
+        let digest = base16ct::mixed::decode_vec(hash).ok()?;
+        Some(self.encode(&digest))
+    }
+
+    /// Re-encode a hex encoded digest of `algorithm`, like [`Encoding::reencode`] but naming the
+    /// algorithm as [`Encoding::encode_with`] does.
+    pub fn reencode_with(self, algorithm: Algorithm, hash: &str) -> Option<String> {
+        let digest = base16ct::mixed::decode_vec(hash).ok()?;
+        Some(self.encode_with(algorithm, &digest))
+    }
+
+    /// Decode a digest in any of the encodings, detecting the encoding automatically.
+    ///
+    /// The digest may be of any [`Algorithm`], which is told by its length. Surrounding
+    /// whitespace is ignored. Returns `None` if the value is not a digest of any algorithm in
+    /// any known encoding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Encoding;
+    ///
+    /// let hex = Encoding::decode("E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855");
+    /// let sri = Encoding::decode("sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=");
+    /// assert!(hex.is_some());
+    /// assert_eq!(hex, sri);
+    /// ```
+    pub fn decode(value: &str) -> Option<Vec<u8>> {
+        let value = value.trim();
+
+        let sri = Algorithm::ALL.into_iter().find_map(|algorithm| {
+            let base64 = value.strip_prefix(&sri_prefix(algorithm))?;
+            Some((algorithm, base64))
+        });
+        if let Some((algorithm, base64)) = sri {
+            let digest = Base64::decode_vec(base64).ok()?;
+            return (digest.len() == algorithm.output_size()).then_some(digest);
+        }
+
+        // Hex takes precedence over Base64 of the same length
+        let is_hex_len = Algorithm::ALL
+            .into_iter()
+            .any(|algorithm| value.len() == algorithm.hex_len());
+        let digest = if let Some(digest) = decode_multihash(value) {
+            digest
+        } else if is_hex_len {
+            base16ct::mixed::decode_vec(value).ok()?
+        } else {
+            Base64::decode_vec(value).ok()?
+        };
+
+        Algorithm::from_output_size(digest.len()).map(|_| digest)
+    }
+
+    /// Decode a digest that was copied from a document, like a specification table.
+    ///
+    /// Such values are often mangled, so in addition to [`Encoding::decode`], all whitespace is
+    /// removed, including non-breaking spaces and line breaks, as are invisible characters like
+    /// soft hyphens and zero-width spaces and hyphens at the end of a line. Surrounding quotes
+    /// and an algorithm prefix like `SHA-256:`, `sha256:` or `SHA256 ` are removed as well, after
+    /// checking that the digest has the length of the named algorithm. Returns the reason if
+    /// the remainder is no digest in any of the encodings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::{Encoding, InvalidHash};
+    ///
+    /// let pasted = "\u{201c}SHA-256: E3B0C442 98FC1C14 9AFBF4C8 996FB924 27AE41E4 649B934C \
+    ///               A495991B\u{ad}\n7852B855\u{201d}";
+    /// assert_eq!(
+    ///     Encoding::decode_pasted(pasted),
+    ///     Ok(Encoding::decode("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855").unwrap())
+    /// );
+    ///
+    /// let err = Encoding::decode_pasted("SHA-1: da39a3ee5e6b4b0d3255bfef95601890afd80709");
+    /// assert_eq!(err, Err(InvalidHash::Algorithm("SHA-1".to_string())));
+    /// ```
+    pub fn decode_pasted(value: &str) -> Result<Vec<u8>, InvalidHash> {
+        let value = value
+            .replace(INVISIBLE, "")
+            .replace("-\r\n", "")
+            .replace("-\n", "");
+        let trim = |value: &str| {
+            value
+                .trim_matches(|c: char| c.is_whitespace() || QUOTES.contains(&c))
+                .to_string()
+        };
+        let mut value = trim(&value);
+
+        let mut named = None;
+        if let Some((name, rest)) = split_algorithm(&value) {
+            let algorithm = name
+                .parse::<Algorithm>()
+                .map_err(|_| InvalidHash::Algorithm(name.to_string()))?;
+            named = Some(algorithm);
+            value = trim(rest);
+        }
+
+        let value = value.replace(char::is_whitespace, "");
+        if value.is_empty() {
+            return Err(InvalidHash::Empty);
+        }
+
+        if let Some(digest) = Encoding::decode(&value) {
+            return match named {
+                Some(algorithm) if digest.len() != algorithm.output_size() => {
+                    Err(InvalidHash::AlgorithmLength(algorithm))
+                }
+                _ => Ok(digest),
+            };
+        }
+
+        let is_base64 = |c: char| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '-');
+        if value.chars().all(|c| c.is_ascii_hexdigit()) {
+            Err(InvalidHash::HexLength(value.len()))
+        } else if let Some(c) = value.chars().find(|&c| !is_base64(c)) {
+            Err(InvalidHash::Character(c))
+        } else {
+            Err(InvalidHash::Base64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SHA-256 digest of the empty input.
+    const EMPTY: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    #[test]
+    fn check_encode_decode() {
+        let digest = base16ct::lower::decode_vec(EMPTY).unwrap();
+
+        let encoded = [
+            (Encoding::Hex, EMPTY.to_string()),
+            (Encoding::UpperHex, EMPTY.to_uppercase()),
+            (
+                Encoding::Base64,
+                String::from("47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU="),
+            ),
+            (
+                Encoding::Sri,
+                String::from("sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU="),
+            ),
+            (Encoding::Multihash, format!("1220{}", EMPTY)),
+        ];
+
+        for (encoding, value) in encoded {
+            assert_eq!(encoding.encode(&digest), value);
+            assert_eq!(encoding.reencode(EMPTY), Some(value.clone()));
+            assert_eq!(Encoding::decode(&value).as_ref(), Some(&digest));
+        }
+
+        assert_eq!(Encoding::decode("sha256-"), None);
+        assert_eq!(Encoding::decode(&EMPTY[1..]), None);
+        assert_eq!(Encoding::decode(&format!("1320{}", EMPTY)), None);
+    }
+
+    #[test]
+    fn check_encode_decode_sha512() {
+        let hash = "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+                    47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e";
+        let digest = base16ct::lower::decode_vec(hash).unwrap();
+
+        let sri = Encoding::Sri.encode(&digest);
+        assert!(sri.starts_with("sha512-z4PhNX7vuL3xVChQ1m2AB9Yg5AULVxXcg"));
+        assert_eq!(Encoding::Multihash.encode(&digest), format!("1340{}", hash));
+
+        for encoding in [
+            Encoding::Hex,
+            Encoding::UpperHex,
+            Encoding::Base64,
+            Encoding::Sri,
+            Encoding::Multihash,
+        ] {
+            let value = encoding.encode(&digest);
+            assert_eq!(
+                Encoding::decode(&value).as_ref(),
+                Some(&digest),
+                "{}",
+                value
+            );
+        }
+
+        assert_eq!(Encoding::decode(&sri.replace("sha512-", "sha256-")), None);
+
+        // Such values are neither hex nor Base64 of a digest
+        assert_eq!(Encoding::decode(&"x".repeat(64)), None);
+        assert_eq!(
+            Encoding::decode_pasted(&format!("SHA-512: {}", hash)),
+            Ok(digest)
+        );
+        assert_eq!(
+            Encoding::decode_pasted(&format!("SHA-512: {}", EMPTY)),
+            Err(InvalidHash::AlgorithmLength(Algorithm::Sha512))
+        );
+    }
+
+    #[test]
+    fn check_encode_decode_blake3() {
+        let hash = "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262";
+        let digest = base16ct::lower::decode_vec(hash).unwrap();
+
+        let sri = Encoding::Sri.encode_with(Algorithm::Blake3, &digest);
+        assert_eq!(sri, "blake3-rxNJufX5oaagQE3qNtzJSZvLJcmtwRK3zJqTyuQfMmI=");
+        let multihash = Encoding::Multihash.encode_with(Algorithm::Blake3, &digest);
+        assert_eq!(multihash, format!("1e20{}", hash));
+        assert_eq!(
+            Encoding::Multihash.reencode_with(Algorithm::Blake3, hash),
+            Some(multihash.clone())
+        );
+
+        // Told apart from SHA-256 digests of the same length by the prefix
+        assert_eq!(Encoding::decode(&sri), Some(digest.clone()));
+        assert_eq!(Encoding::decode(&multihash), Some(digest.clone()));
+        assert_eq!(
+            Encoding::decode_pasted(&format!("BLAKE3: {}", hash)),
+            Ok(digest)
+        );
+    }
+
+    #[test]
+    fn check_decode_pasted() {
+        let digest = base16ct::lower::decode_vec(EMPTY).unwrap();
+        let upper = EMPTY.to_uppercase();
+        let (first, second) = EMPTY.split_at(32);
+
+        let pasted = [
+            EMPTY.to_string(),
+            upper.clone(),
+            format!("  {}\n", upper),
+            format!("\u{201c}{}\u{201d}", EMPTY),
+            format!("\"{}\"", EMPTY),
+            format!("'SHA-256: {}'", upper),
+            format!("SHA-256:{}", EMPTY),
+            format!("sha256:{}", EMPTY),
+            format!("SHA256 {}", EMPTY),
+            format!("Sha-256 \u{a0}{}", EMPTY),
+            format!("{}\u{ad}\n{}", first, second),
+            format!("{}-\r\n{}", first, second),
+            format!("{}\u{a0}{}", first, second),
+            format!("{}\u{200b}{}", first, second),
+            format!("\u{feff}{}", EMPTY),
+            upper
+                .as_bytes()
+                .chunks(8)
+                .map(|chunk| std::str::from_utf8(chunk).unwrap())
+                .collect::<Vec<_>>()
+                .join(" "),
+            String::from("sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU="),
+            String::from("SHA-256: 47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU="),
+        ];
+
+        for value in pasted {
+            assert_eq!(
+                Encoding::decode_pasted(&value).as_ref(),
+                Ok(&digest),
+                "{:?}",
+                value
+            );
+        }
+
+        let invalid = [
+            ("", InvalidHash::Empty),
+            ("\u{201c}\u{201d}", InvalidHash::Empty),
+            ("SHA-256:", InvalidHash::Empty),
+            (
+                "SHA-1: da39a3ee5e6b4b0d3255bfef95601890afd80709",
+                InvalidHash::Algorithm(String::from("SHA-1")),
+            ),
+            (
+                "MD5: d41d8cd98f00b204e9800998ecf8427e",
+                InvalidHash::Algorithm(String::from("MD5")),
+            ),
+            (&EMPTY[1..], InvalidHash::HexLength(63)),
+            ("e3b0c442…7852b855", InvalidHash::Character('…')),
+            ("e3b0c442#98fc1c14", InvalidHash::Character('#')),
+            ("not-a-hash", InvalidHash::Base64),
+        ];
+
+        for (value, reason) in invalid {
+            assert_eq!(Encoding::decode_pasted(value), Err(reason), "{:?}", value);
+        }
+
+        assert_eq!(
+            InvalidHash::HexLength(63).to_string(),
+            "found 63 hex digits, but a hash has 64 or 128"
+        );
+        assert_eq!(
+            InvalidHash::Character('…').to_string(),
+            "found the character '…' (U+2026), which is not part of a hash"
+        );
+    }
+}
@@ -0,0 +1,403 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Kind of failure of an IO error returned by this crate.
+///
+/// All functions of this crate return plain [`io::Error`]s, so that they can easily be combined
+/// with other IO operations. Errors that happen while processing a file are tagged with the step
+/// that failed, which can be queried with [`ErrorKind::of`]. The [`io::ErrorKind`] and the
+/// message of a tagged error are the same as those of the underlying error.
+///
+/// # Example
+///
+/// ```
+/// use std::path::PathBuf;
+/// use normalized_hash::{ErrorKind, Hasher};
+///
+/// let err = Hasher::new()
+///     .try_hash_file("missing.txt", None::<PathBuf>)
+///     .unwrap_err();
+/// assert_eq!(ErrorKind::of(&err), Some(ErrorKind::Open));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The input file could not be opened.
+    Open,
+
+    /// The input is no file that can be read, like a directory, see [`NotAFile`].
+    NotAFile,
+
+    /// The input is a symbolic link whose target does not exist, see [`BrokenSymlink`].
+    BrokenSymlink,
+
+    /// The input file could not be read.
+    Read,
+
+    /// The input file is not valid UTF-8.
+    Decode,
+
+    /// An output file could not be created, like in a missing directory.
+    Create,
+
+    /// An output file could not be written.
+    Write,
+
+    /// The input file was modified while it was read, see [`Hasher::retry_on_modify`].
+    ///
+    /// [`Hasher::retry_on_modify`]: crate::Hasher::retry_on_modify
+    ModifiedDuringRead,
+
+    /// A written output file does not have the computed hash, see [`Hasher::verify_output`].
+    ///
+    /// [`Hasher::verify_output`]: crate::Hasher::verify_output
+    OutputMismatch,
+}
+
+impl ErrorKind {
+    /// Get the kind of an error returned by this crate, `None` if it was not tagged.
+    pub fn of(err: &io::Error) -> Option<ErrorKind> {
+        err.get_ref()
+            .and_then(|inner| inner.downcast_ref::<TaggedError>())
+            .map(|tagged| tagged.kind)
+    }
+
+    /// Tag an error with this kind, unless it is already tagged.
+    pub(crate) fn tag(self, err: io::Error) -> io::Error {
+        if ErrorKind::of(&err).is_some() {
+            return err;
+        }
+
+        io::Error::new(err.kind(), TaggedError { kind: self, err })
+    }
+
+    /// Tag an error as [`ErrorKind::Decode`] if it is caused by invalid UTF-8, otherwise as
+    /// [`ErrorKind::Read`].
+    pub(crate) fn tag_read(err: io::Error) -> io::Error {
+        if err.kind() == io::ErrorKind::InvalidData {
+            ErrorKind::Decode.tag(err)
+        } else {
+            ErrorKind::Read.tag(err)
+        }
+    }
+}
+
+/// Kind of an input that is no file that can be read, see [`NotAFile`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FileKind {
+    /// A directory, whose files can be walked with [`Walker`](crate::Walker) instead.
+    Directory,
+
+    /// A Unix domain socket, which cannot be opened like a file.
+    Socket,
+
+    /// A symbolic link, which is not followed with [`SymlinkInput::Error`].
+    ///
+    /// [`SymlinkInput::Error`]: crate::SymlinkInput::Error
+    Symlink,
+}
+
+impl Display for FileKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FileKind::Directory => f.write_str("directory"),
+            FileKind::Socket => f.write_str("socket"),
+            FileKind::Symlink => f.write_str("symbolic link"),
+        }
+    }
+}
+
+/// Error of an input that is no file that can be read, with [`ErrorKind::NotAFile`].
+///
+/// Inputs are checked before they are opened, so a directory results in this error on all
+/// platforms instead of a platform-specific error while reading it. Named pipes and devices,
+/// like `/dev/stdin`, are read like regular files.
+///
+/// # Example
+///
+/// ```
+/// use std::path::PathBuf;
+/// use normalized_hash::{FileKind, Hasher, NotAFile};
+///
+/// let err = Hasher::new().try_hash_file("src", None::<PathBuf>).unwrap_err();
+/// let not_a_file = NotAFile::of(&err).unwrap();
+/// assert_eq!(not_a_file.kind, FileKind::Directory);
+/// assert_eq!(err.to_string(), "src is a directory");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct NotAFile {
+    /// Path of the input, as given.
+    pub path: PathBuf,
+
+    /// What the input is instead of a file.
+    pub kind: FileKind,
+}
+
+impl NotAFile {
+    /// Get the details of an error of kind [`ErrorKind::NotAFile`], `None` for other errors.
+    pub fn of(err: &io::Error) -> Option<&NotAFile> {
+        details(err)
+    }
+
+    /// Create the error for `path`, tagged as [`ErrorKind::NotAFile`].
+    pub(crate) fn error(path: PathBuf, kind: FileKind) -> io::Error {
+        let err = io::Error::new(io::ErrorKind::InvalidInput, NotAFile { path, kind });
+        ErrorKind::NotAFile.tag(err)
+    }
+}
+
+impl Display for NotAFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is a {}", self.path.display(), self.kind)
+    }
+}
+
+impl Error for NotAFile {}
+
+/// Error of an input that is a symbolic link to a missing target, with
+/// [`ErrorKind::BrokenSymlink`].
+///
+/// The [`io::ErrorKind`] is [`io::ErrorKind::NotFound`], like for any missing file, but the
+/// message names the target, which is what is actually missing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BrokenSymlink {
+    /// Path of the input, as given.
+    pub path: PathBuf,
+
+    /// Target of the symbolic link, as stored in the link.
+    pub target: PathBuf,
+}
+
+impl BrokenSymlink {
+    /// Get the details of an error of kind [`ErrorKind::BrokenSymlink`], `None` for other
+    /// errors.
+    pub fn of(err: &io::Error) -> Option<&BrokenSymlink> {
+        details(err)
+    }
+
+    /// Create the error for `path`, tagged as [`ErrorKind::BrokenSymlink`].
+    pub(crate) fn error(path: PathBuf, target: PathBuf) -> io::Error {
+        let err = io::Error::new(io::ErrorKind::NotFound, BrokenSymlink { path, target });
+        ErrorKind::BrokenSymlink.tag(err)
+    }
+}
+
+impl Display for BrokenSymlink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is a symbolic link to {}, which does not exist",
+            self.path.display(),
+            self.target.display()
+        )
+    }
+}
+
+impl Error for BrokenSymlink {}
+
+/// Error of [`Hasher::hash_file`], which tells which step failed for which file.
+///
+/// Unlike the plain [`io::Error`]s of the other functions, this error carries the path of the
+/// offending file, so it can be reported on its own, like with `anyhow`. It can still be
+/// converted back into the underlying [`io::Error`], which keeps its [`ErrorKind`].
+///
+/// [`Hasher::hash_file`]: crate::Hasher::hash_file
+///
+/// # Example
+///
+/// ```
+/// use std::path::PathBuf;
+/// use normalized_hash::{HashError, Hasher};
+///
+/// let err = Hasher::new().hash_file("missing.txt", None::<PathBuf>).unwrap_err();
+/// assert!(matches!(err, HashError::Open { .. }));
+/// assert!(err.to_string().starts_with("cannot open missing.txt: "));
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum HashError {
+    /// The input file could not be opened, or it is no file that can be read.
+    Open { path: PathBuf, source: io::Error },
+
+    /// The output file could not be created.
+    Create { path: PathBuf, source: io::Error },
+
+    /// The input file could not be read while hashing, or it is not valid UTF-8 text.
+    Read { path: PathBuf, source: io::Error },
+
+    /// The output file could not be written, or it does not have the computed hash.
+    Write { path: PathBuf, source: io::Error },
+}
+
+impl HashError {
+    /// Assign an error of hashing `file_in` into `file_out` to the step that failed.
+    pub(crate) fn new(source: io::Error, file_in: &Path, file_out: Option<&Path>) -> Self {
+        let file_out = || file_out.unwrap_or(file_in).to_path_buf();
+
+        match ErrorKind::of(&source) {
+            Some(ErrorKind::Open | ErrorKind::NotAFile | ErrorKind::BrokenSymlink) => {
+                HashError::Open {
+                    path: file_in.to_path_buf(),
+                    source,
+                }
+            }
+            Some(ErrorKind::Create) => HashError::Create {
+                path: file_out(),
+                source,
+            },
+            Some(ErrorKind::Write | ErrorKind::OutputMismatch) => HashError::Write {
+                path: file_out(),
+                source,
+            },
+            _ => HashError::Read {
+                path: file_in.to_path_buf(),
+                source,
+            },
+        }
+    }
+
+    /// Path of the file that could not be opened, read, created or written.
+    pub fn path(&self) -> &Path {
+        match self {
+            HashError::Open { path, .. }
+            | HashError::Create { path, .. }
+            | HashError::Read { path, .. }
+            | HashError::Write { path, .. } => path,
+        }
+    }
+
+    /// Underlying IO error, whose [`ErrorKind`] can be queried.
+    pub fn io_error(&self) -> &io::Error {
+        match self {
+            HashError::Open { source, .. }
+            | HashError::Create { source, .. }
+            | HashError::Read { source, .. }
+            | HashError::Write { source, .. } => source,
+        }
+    }
+}
+
+impl Display for HashError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let step = match self {
+            HashError::Open { .. } => "open",
+            HashError::Create { .. } => "create",
+            HashError::Read { .. } => "read",
+            HashError::Write { .. } => "write",
+        };
+        write!(
+            f,
+            "cannot {} {}: {}",
+            step,
+            self.path().display(),
+            self.io_error()
+        )
+    }
+}
+
+impl Error for HashError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.io_error())
+    }
+}
+
+impl From<HashError> for io::Error {
+    fn from(err: HashError) -> Self {
+        match err {
+            HashError::Open { source, .. }
+            | HashError::Create { source, .. }
+            | HashError::Read { source, .. }
+            | HashError::Write { source, .. } => source,
+        }
+    }
+}
+
+/// Get the details of an error created by this crate, also if it is wrapped by a tag.
+fn details<T: Error + 'static>(err: &io::Error) -> Option<&T> {
+    let inner = err.get_ref()?;
+    let inner = match inner.downcast_ref::<TaggedError>() {
+        Some(tagged) => tagged.err.get_ref()?,
+        None => inner,
+    };
+    inner.downcast_ref()
+}
+
+/// An IO error together with its kind, wrapped into an [`io::Error`].
+#[derive(Debug)]
+struct TaggedError {
+    kind: ErrorKind,
+    err: io::Error,
+}
+
+impl Display for TaggedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.err.fmt(f)
+    }
+}
+
+impl Error for TaggedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.err.source()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_tag() {
+        let err = io::Error::new(io::ErrorKind::NotFound, "not found");
+        assert_eq!(ErrorKind::of(&err), None);
+
+        let err = ErrorKind::Open.tag(err);
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::Open));
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(err.to_string(), "not found");
+
+        let err = ErrorKind::Write.tag(err);
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::Open));
+
+        let err = ErrorKind::tag_read(io::Error::new(io::ErrorKind::InvalidData, "invalid"));
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::Decode));
+    }
+
+    #[test]
+    fn check_not_a_file() {
+        let err = NotAFile::error(PathBuf::from("dir"), FileKind::Directory);
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::NotAFile));
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "dir is a directory");
+        assert_eq!(
+            NotAFile::of(&err),
+            Some(&NotAFile {
+                path: PathBuf::from("dir"),
+                kind: FileKind::Directory
+            })
+        );
+
+        let err = ErrorKind::Open.tag(io::Error::new(io::ErrorKind::NotFound, "not found"));
+        assert_eq!(NotAFile::of(&err), None);
+    }
+
+    #[test]
+    fn check_broken_symlink() {
+        let err = BrokenSymlink::error(PathBuf::from("link"), PathBuf::from("missing"));
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::BrokenSymlink));
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(
+            err.to_string(),
+            "link is a symbolic link to missing, which does not exist"
+        );
+        assert_eq!(
+            BrokenSymlink::of(&err).map(|broken| &broken.target),
+            Some(&PathBuf::from("missing"))
+        );
+        assert_eq!(NotAFile::of(&err), None);
+    }
+}
@@ -0,0 +1,187 @@
+use std::borrow::Cow;
+use std::ffi::OsString;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Parse two hex digits at the start of `bytes`.
+fn hex_byte(bytes: &[u8]) -> Option<u8> {
+    let digits = std::str::from_utf8(bytes.get(..2)?).ok()?;
+    u8::from_str_radix(digits, 16).ok()
+}
+
+/// Check if a `%` followed by `rest` would be taken for an escape sequence.
+///
+/// Escaped bytes are always at least `0x80`, and `%25` is only an escaped `%` if it is followed
+/// by something that looks like an escape sequence again.
+fn is_ambiguous(rest: &[u8]) -> bool {
+    rest.starts_with(b"25") || hex_byte(rest).is_some_and(|byte| byte >= 0x80)
+}
+
+/// Convert a path into text that can be written into checksum files, manifests and reports.
+///
+/// Paths that are valid UTF-8 are returned as they are, unless they contain a `%` that would be
+/// taken for an escape sequence. Bytes that are not part of valid UTF-8, like in file names that
+/// are encoded in Latin-1, are escaped as `%` followed by two uppercase hex digits, like `%E9`.
+/// A `%` that is followed by such a sequence or by `25` is escaped as `%25`. Thus,
+/// [`unescape_path`] restores the original path exactly, while ordinary names stay unchanged.
+///
+/// On Windows, only file names with unpaired surrogates are not valid UTF-8. They are escaped
+/// in their WTF-8 form, which cannot be restored exactly.
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+/// use normalized_hash::escape_path;
+///
+/// assert_eq!(escape_path(Path::new("dir/café.txt")), "dir/café.txt");
+/// assert_eq!(escape_path(Path::new("100%.txt")), "100%.txt");
+/// assert_eq!(escape_path(Path::new("%E9.txt")), "%25E9.txt");
+/// ```
+pub fn escape_path(path: &Path) -> Cow<'_, str> {
+    if let Some(text) = path.to_str() {
+        if !text.contains('%') {
+            return Cow::Borrowed(text);
+        }
+    }
+
+    let mut escaped = String::new();
+    for chunk in path.as_os_str().as_encoded_bytes().utf8_chunks() {
+        let valid = chunk.valid();
+        for (index, c) in valid.char_indices() {
+            if c == '%' && is_ambiguous(&valid.as_bytes()[index + 1..]) {
+                escaped.push_str("%25");
+            } else {
+                escaped.push(c);
+            }
+        }
+
+        for byte in chunk.invalid() {
+            // Writing into a string cannot fail
+            let _ = write!(escaped, "%{:02X}", byte);
+        }
+    }
+
+    Cow::Owned(escaped)
+}
+
+/// Convert text written by [`escape_path`] back into a path.
+///
+/// Escape sequences are accepted in upper and lower case. A `%` that is not followed by an
+/// escape sequence is kept as it is. On platforms other than Unix, escaped bytes that do not
+/// form valid UTF-8 are replaced by `U+FFFD`.
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+/// use normalized_hash::unescape_path;
+///
+/// assert_eq!(unescape_path("dir/café.txt"), Path::new("dir/café.txt"));
+/// assert_eq!(unescape_path("%25E9.txt"), Path::new("%E9.txt"));
+/// ```
+pub fn unescape_path(text: &str) -> PathBuf {
+    if !text.contains('%') {
+        return PathBuf::from(text);
+    }
+
+    let bytes = text.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let rest = &bytes[index + 1..];
+        if bytes[index] == b'%' {
+            if let Some(byte) = hex_byte(rest).filter(|&byte| byte >= 0x80) {
+                unescaped.push(byte);
+                index += 3;
+                continue;
+            }
+            if rest.starts_with(b"25") && is_ambiguous(&rest[2..]) {
+                unescaped.push(b'%');
+                index += 3;
+                continue;
+            }
+        }
+
+        unescaped.push(bytes[index]);
+        index += 1;
+    }
+
+    PathBuf::from(os_string(unescaped))
+}
+
+/// Convert the bytes of an unescaped path into an [`OsString`].
+#[cfg(unix)]
+fn os_string(bytes: Vec<u8>) -> OsString {
+    use std::os::unix::ffi::OsStringExt;
+
+    OsString::from_vec(bytes)
+}
+
+/// Convert the bytes of an unescaped path into an [`OsString`].
+#[cfg(not(unix))]
+fn os_string(bytes: Vec<u8>) -> OsString {
+    match String::from_utf8(bytes) {
+        Ok(text) => OsString::from(text),
+        Err(err) => OsString::from(String::from_utf8_lossy(err.as_bytes()).into_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_escape_round_trip() {
+        let names = [
+            "input.txt",
+            "café/naïve.txt",
+            "100%.txt",
+            "%",
+            "%2",
+            "%25",
+            "%2525",
+            "%252",
+            "%E9",
+            "%e9%",
+            "%7F",
+            "%%E9",
+            "a%20b",
+        ];
+
+        for name in names {
+            let escaped = escape_path(Path::new(name));
+            assert_eq!(unescape_path(&escaped), Path::new(name), "{}", escaped);
+        }
+
+        assert_eq!(escape_path(Path::new("a%20b")), "a%20b");
+        assert_eq!(escape_path(Path::new("%2525")), "%252525");
+        assert_eq!(escape_path(Path::new("%e9%")), "%25e9%");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_escape_invalid_bytes() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let names: [&[u8]; 5] = [
+            b"caf\xe9.txt",
+            b"\xff\xfe",
+            b"%\xe9",
+            b"%25\xe9%E9",
+            b"\xc3",
+        ];
+
+        for name in names {
+            let path = Path::new(OsStr::from_bytes(name));
+            let escaped = escape_path(path);
+            assert!(escaped.is_ascii(), "{}", escaped);
+            assert_eq!(unescape_path(&escaped), path, "{}", escaped);
+        }
+
+        let path = Path::new(OsStr::from_bytes(b"caf\xe9.txt"));
+        assert_eq!(escape_path(path), "caf%E9.txt");
+        assert_eq!(unescape_path("caf%e9.txt"), path);
+    }
+}
@@ -35,35 +35,115 @@
 //! ```rust no_run
 //! use std::path::PathBuf;
 //!
-//! use normalized_hash::Hasher;
+//! use normalized_hash::{HashError, Hasher};
 //!
-//! fn main() {
+//! fn main() -> Result<(), HashError> {
 //!     let file_in = PathBuf::from("input.txt");
 //!     let file_out = PathBuf::from("output.txt");
 //!
 //!     // Simple example with default options, without writing an output file
-//!     let hash = Hasher::new().hash_file(&file_in, None::<PathBuf>);
+//!     let hash = Hasher::new().hash_file(&file_in, None::<PathBuf>)?;
 //!     println!("{}", hash);
 //!
 //!     // More complex example, with writing output
 //!     let hash = Hasher::new()
 //!         .eol("\r\n")
 //!         .no_eof(true)
-//!         .hash_file(&file_in, Some(file_out));
+//!         .hash_file(&file_in, Some(file_out))?;
 //!     println!("{}", hash);
+//!
+//!     Ok(())
 //! }
 //! ```
 
-use std::fs::File;
-use std::io::{BufRead, BufReader, BufWriter, Write};
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File, Metadata};
+use std::io::{self, BufRead, BufReader, BufWriter, Lines, Read, Write};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use rayon::ThreadPoolBuilder;
+use regex::Regex;
+use sha2::digest::Update;
+use tempfile::NamedTempFile;
+
+use algorithm::AnyDigest;
+use output::CountingWriter;
+use path::{open_input, unfollowed_link};
+use style::Scanned;
+
+pub use algorithm::{Algorithm, UnknownAlgorithm};
+pub use binary::is_binary;
+#[cfg(feature = "config")]
+pub use config::{Config, CONFIG_FILE};
+pub use detect::{detect_encoding, Detection, TextEncoding};
+pub use encoding::{Encoding, InvalidHash};
+pub use error::{BrokenSymlink, ErrorKind, FileKind, HashError, NotAFile};
+pub use escape::{escape_path, unescape_path};
+pub use manifest::{manifest_file, manifest_path, Manifest, ManifestEntry};
+pub use output::OutputReport;
+pub use path::{long_path, SymlinkInput};
+pub use regex;
+pub use sha2::digest;
+pub use sidecar::{sidecar_path, store_sidecar, SidecarStatus, SIDECAR_SUFFIX};
+pub use stats::{is_invisible, Stats};
+pub use style::{OutputEol, STYLE_SCAN_LIMIT};
+pub use timing::Timing;
+pub use update::update_copy;
+pub use vectors::{self_test, SelfTestFailure};
+pub use walk::{sort_paths, Exclude, Skipped, Walker};
 
-use sha2::{Digest, Sha256};
+mod algorithm;
+mod binary;
+#[cfg(feature = "config")]
+mod config;
+mod detect;
+mod encoding;
+mod error;
+mod escape;
+mod manifest;
+mod output;
+mod path;
+mod sidecar;
+mod stats;
+mod style;
+mod timing;
+mod update;
+pub mod vectors;
+mod walk;
+
+/// Callback that is invoked with the number of bytes read, set with [`Hasher::progress`].
+type Progress = Box<dyn Fn(u64) + Send + Sync>;
+
+/// Callback that decides if an IO error is transient, set with [`Hasher::retry_if`].
+type RetryIf = Box<dyn Fn(&io::Error) -> bool + Send + Sync>;
 
 pub struct Hasher {
     eol: String,
     ignore_whitespaces: bool,
+    redactions: Vec<(Regex, String)>,
     no_eof: bool,
+    algorithm: Algorithm,
+    paragraph_mode: bool,
+    hard_breaks: bool,
+    retry_on_modify: u32,
+    retries: u32,
+    retry_delay: Duration,
+    retry_if: Option<RetryIf>,
+    symlink_input: SymlinkInput,
+    verify_output: bool,
+    sync_output: bool,
+    output_eol: OutputEol,
+    progress: Option<Progress>,
+
+    /// Invoked with the path of a written output before it is verified, to corrupt it in tests.
+    #[cfg(test)]
+    before_verify: Option<fn(&Path)>,
 }
 
 impl Default for Hasher {
@@ -71,7 +151,22 @@ impl Default for Hasher {
         Self {
             eol: "\n".to_string(),
             ignore_whitespaces: false,
+            redactions: Vec::new(),
             no_eof: false,
+            algorithm: Algorithm::Sha256,
+            paragraph_mode: false,
+            hard_breaks: false,
+            retry_on_modify: 0,
+            retries: 0,
+            retry_delay: Duration::from_millis(100),
+            retry_if: None,
+            symlink_input: SymlinkInput::Follow,
+            verify_output: false,
+            sync_output: false,
+            output_eol: OutputEol::Hash,
+            progress: None,
+            #[cfg(test)]
+            before_verify: None,
         }
     }
 }
@@ -97,6 +192,10 @@ impl Hasher {
     ///     Skip last end-of-line on end-of-file. If this is set to true, no trailing EOL will be
     ///     appended at the end of the file.
     ///
+    /// -   `algorithm`: [`Algorithm::Sha256`]
+    ///
+    ///     Digest algorithm of the hashes.
+    ///
     /// # Example
     ///
     /// ```
@@ -132,6 +231,30 @@ impl Hasher {
         self
     }
 
+    /// Replace all matches of `pattern` in each line with `replacement` before hashing.
+    ///
+    /// Unlike dropping lines, the redacted line still counts, so only the sensitive part of it
+    /// is left out of the hash. The replacement is expanded like in [`Regex::replace_all`], so
+    /// `$1` stands for the first capture group. Can be called multiple times, the patterns are
+    /// applied in this order, each to the result of the previous ones, and before whitespaces
+    /// are removed with [`Hasher::ignore_whitespaces`]. Normalized outputs contain the redacted
+    /// lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::regex::Regex;
+    /// use normalized_hash::Hasher;
+    ///
+    /// let pattern = Regex::new(r"(password)=\S+").unwrap();
+    /// let hasher = Hasher::new().redact(pattern, "$1=***");
+    /// assert_eq!(hasher.normalize_line("password=hunter2").as_deref(), Some("password=***"));
+    /// ```
+    pub fn redact(mut self, pattern: Regex, replacement: &str) -> Self {
+        self.redactions.push((pattern, replacement.to_string()));
+        self
+    }
+
     /// Skip last end-of-line on end-of-file.
     ///
     /// If this is set to true, no trailing EOL will be appended at the end of the file.
@@ -149,6 +272,289 @@ impl Hasher {
         self
     }
 
+    /// Change the digest algorithm.
+    ///
+    /// The normalized content is the same for all algorithms, only the hashes differ, including
+    /// their length. Defaults to [`Algorithm::Sha256`], whose hashes stay the same as before
+    /// other algorithms were supported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::{Algorithm, Hasher};
+    ///
+    /// let hash = Hasher::new().algorithm(Algorithm::Sha512).hash_string("A B\n");
+    /// assert_eq!(hash.len(), Algorithm::Sha512.hex_len());
+    /// ```
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Digest algorithm of the hashes, as set with [`Hasher::algorithm`].
+    pub fn digest_algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Hash paragraphs instead of lines, so that wrapping text at another width keeps the hash.
+    ///
+    /// Paragraphs are separated by blank lines, which only contain whitespaces. Within a
+    /// paragraph, each line break and the whitespaces around it are replaced by a single space,
+    /// before whitespaces are removed with [`Hasher::ignore_whitespaces`]. Blank lines are
+    /// dropped, so the paragraphs are joined with a single eol sequence for hashing and in
+    /// normalized outputs. Other per-line transformations like [`Hasher::redact`] still apply to
+    /// each line before it is joined. The spans of [`Hasher::normalized_lines_with_spans`] stay
+    /// those of the single lines.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Hasher;
+    ///
+    /// let hasher = Hasher::new().paragraph_mode(true);
+    /// let (narrow, _) = hasher.filter("A B\nC\n\nD\n".as_bytes(), std::io::sink()).unwrap();
+    /// let (wide, _) = hasher.filter("A\r\nB C\r\n\r\n\r\nD\r\n".as_bytes(), std::io::sink()).unwrap();
+    /// assert_eq!(narrow, wide);
+    /// ```
+    pub fn paragraph_mode(mut self, paragraph_mode: bool) -> Self {
+        self.paragraph_mode = paragraph_mode;
+        self
+    }
+
+    /// Keep hard line breaks within paragraphs with [`Hasher::paragraph_mode`].
+    ///
+    /// Like in Markdown, a line that ends with two spaces or a backslash ends with a hard break.
+    /// With this option, such a line is kept unchanged and ends with the eol sequence instead of
+    /// being joined with the next line.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Hasher;
+    ///
+    /// let hasher = Hasher::new().paragraph_mode(true).preserve_hard_breaks(true);
+    /// let lines = hasher
+    ///     .normalized_lines_from("A\\\nB\nC\n".as_bytes())
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(lines, ["A\\", "B C"]);
+    /// ```
+    pub fn preserve_hard_breaks(mut self, preserve_hard_breaks: bool) -> Self {
+        self.hard_breaks = preserve_hard_breaks;
+        self
+    }
+
+    /// Hash a file again if it was modified while it was read, up to `retries` times.
+    ///
+    /// The size and modification time of each input file are compared before and after it is
+    /// read. If they differ, for example because another process is still writing the file, the
+    /// hash may be that of a half-written file. Then the file is hashed again, and if it is still
+    /// modified after all retries, the error [`ErrorKind::ModifiedDuringRead`] is returned.
+    ///
+    /// Defaults to `0`, so a modified file is an error right away.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Hasher;
+    /// let hasher = Hasher::new().retry_on_modify(3);
+    /// ```
+    pub fn retry_on_modify(mut self, retries: u32) -> Self {
+        self.retry_on_modify = retries;
+        self
+    }
+
+    /// Hash a file again after a transient error while opening or reading it, up to `retries`
+    /// times.
+    ///
+    /// Network filesystems occasionally fail in the middle of reading a file. Instead of failing
+    /// right away, the file is opened again after a delay and hashed from scratch, writing any
+    /// output file again. Errors of kind [`io::ErrorKind::Interrupted`],
+    /// [`io::ErrorKind::ConnectionReset`] and [`io::ErrorKind::TimedOut`] are transient, and so
+    /// are errors accepted by [`Hasher::retry_if`]. Missing files and denied permissions are
+    /// never retried. The number of retries is counted in [`Stats::retries`].
+    ///
+    /// Defaults to `0`, so all errors are returned right away.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use normalized_hash::Hasher;
+    ///
+    /// let hasher = Hasher::new()
+    ///     .retries(3)
+    ///     .retry_delay(Duration::from_secs(1));
+    /// ```
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Change the delay before the first retry after a transient error.
+    ///
+    /// The delay doubles with each further retry of the same file, so with the default of
+    /// 100 milliseconds, the retries follow after 100, 200, 400 milliseconds and so on.
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Treat further errors as transient, in addition to those listed in [`Hasher::retries`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Hasher;
+    ///
+    /// // EIO, as returned by some network filesystems on Linux
+    /// let hasher = Hasher::new()
+    ///     .retries(3)
+    ///     .retry_if(|err| err.raw_os_error() == Some(5));
+    /// ```
+    pub fn retry_if(
+        mut self,
+        retry_if: impl Fn(&io::Error) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retry_if = Some(Box::new(retry_if));
+        self
+    }
+
+    /// Choose how input files that are symbolic links are hashed.
+    ///
+    /// By default, links are followed silently, so the hash is that of the file they point to.
+    /// For evidence of what was hashed, links can be an error instead, or the target path they
+    /// store can be hashed. This applies to [`Hasher::try_hash_file`] and its variants and to
+    /// [`Hasher::hash_many`]. Independent of the policy, a link to a missing target fails with
+    /// [`BrokenSymlink`] when it is followed.
+    ///
+    /// Defaults to [`SymlinkInput::Follow`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::{Hasher, SymlinkInput};
+    /// let hasher = Hasher::new().symlink_input(SymlinkInput::Error);
+    /// ```
+    pub fn symlink_input(mut self, symlink_input: SymlinkInput) -> Self {
+        self.symlink_input = symlink_input;
+        self
+    }
+
+    /// Verify written output files by hashing them again.
+    ///
+    /// After an output file is written, it is read back and its content is hashed, which must
+    /// result in the same hash, since the output is exactly the content that was hashed. This
+    /// catches files that are not written as intended, at the cost of reading each output once
+    /// more. Files that are replaced atomically are verified before they replace the target. A
+    /// mismatch results in the error [`ErrorKind::OutputMismatch`].
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Hasher;
+    /// let hasher = Hasher::new().verify_output(true);
+    /// ```
+    pub fn verify_output(mut self, verify_output: bool) -> Self {
+        self.verify_output = verify_output;
+        self
+    }
+
+    /// Flush written output files to the storage device before reporting success.
+    ///
+    /// Each output file is synced with [`File::sync_all`], before it replaces the target for
+    /// atomically replaced files, and afterwards the directory that contains it is synced as
+    /// well, so a power loss right after success cannot leave an empty or missing file behind.
+    /// Directories are only synced on Unix, where this is needed to make the rename durable.
+    ///
+    /// Syncing waits for the storage device and can make writing many small files considerably
+    /// slower, so it is off by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Hasher;
+    /// let hasher = Hasher::new().sync_output(true);
+    /// ```
+    pub fn sync_output(mut self, sync_output: bool) -> Self {
+        self.sync_output = sync_output;
+        self
+    }
+
+    /// Choose the line endings of written outputs.
+    ///
+    /// With [`OutputEol::MatchInput`], outputs keep the predominant line ending of their input,
+    /// so a CRLF file stays CRLF and an LF file stays LF, while the hash is computed over the
+    /// eol sequence as usual. Thus, files can be fixed in place in their native style and still
+    /// have the same hash on every platform. Applies to the outputs of all methods that write
+    /// them, and [`Hasher::is_normalized`] compares against the output that would be written.
+    /// With `verify_output`, the output is verified against the content that was written.
+    ///
+    /// Defaults to [`OutputEol::Hash`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::{Hasher, OutputEol};
+    ///
+    /// let mut output = Vec::new();
+    /// let hasher = Hasher::new().output_eol(OutputEol::MatchInput);
+    /// let (hash, _) = hasher.filter("A B\r\nC D\r\nE F\n".as_bytes(), &mut output).unwrap();
+    /// assert_eq!(output, b"A B\r\nC D\r\nE F\r\n");
+    /// assert_eq!(hash, Hasher::new().filter("A B\nC D\nE F\n".as_bytes(), Vec::new()).unwrap().0);
+    /// ```
+    pub fn output_eol(mut self, output_eol: OutputEol) -> Self {
+        self.output_eol = output_eol;
+        self
+    }
+
+    /// Read ahead into `input` to find the line ending of outputs, if it differs from the eol.
+    fn scan_style<R: Read>(&self, input: R) -> io::Result<(Option<&'static str>, Scanned<R>)> {
+        if self.output_eol == OutputEol::Hash {
+            return Ok((None, style::unscanned(input)));
+        }
+
+        let (eol, input) = style::dominant_eol(input).map_err(ErrorKind::tag_read)?;
+        Ok((eol.filter(|eol| *eol != self.eol), input))
+    }
+
+    /// Report the progress of reading input files.
+    ///
+    /// The callback is invoked with the number of bytes that were read since its last
+    /// invocation, after each line that is read for hashing or by [`Hasher::line_changes`]. The
+    /// sum of all reported bytes is the size of all files read, including files that are read
+    /// again with [`Hasher::retry_on_modify`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    /// use std::sync::Arc;
+    /// use normalized_hash::Hasher;
+    ///
+    /// let read = Arc::new(AtomicU64::new(0));
+    /// let hasher = Hasher::new().progress({
+    ///     let read = Arc::clone(&read);
+    ///     move |bytes| {
+    ///         read.fetch_add(bytes, Ordering::Relaxed);
+    ///     }
+    /// });
+    ///
+    /// hasher.hash_file("input.txt", None::<PathBuf>).unwrap();
+    /// println!("{} bytes read", read.load(Ordering::Relaxed));
+    /// ```
+    pub fn progress(mut self, progress: impl Fn(u64) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
     /// Create hash from a text file, regardless of line endings.
     ///
     /// This function reads `file_in` linewise, replacing whatever line ending is present with a
@@ -156,6 +562,9 @@ impl Hasher {
     ///
     /// Optionally, it is possible to write the normalized input to `file_out`.
     ///
+    /// Returns a [`HashError`] that tells if `file_in` could not be opened or read, or if
+    /// `file_out` could not be created or written, together with the path of the file.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -163,230 +572,2632 @@ impl Hasher {
     ///     use normalized_hash::Hasher;
     ///
     ///     let hash_without_output = Hasher::new()
-    ///         .hash_file(PathBuf::from("input.txt"), None::<PathBuf>);
+    ///         .hash_file(PathBuf::from("input.txt"), None::<PathBuf>)?;
     ///
     ///     let hash_with_output = Hasher::new().hash_file(
     ///         PathBuf::from("input.txt"),
     ///         Some(PathBuf::from("output.txt"))
-    ///     );
+    ///     )?;
+    /// # Ok::<(), normalized_hash::HashError>(())
     /// ```
     pub fn hash_file(
         &self,
         file_in: impl AsRef<Path>,
         file_out: Option<impl AsRef<Path>>,
+    ) -> Result<String, HashError> {
+        let file_out = file_out.as_ref().map(|file_out| file_out.as_ref());
+        self.try_hash_file(file_in.as_ref(), file_out)
+            .map_err(|err| HashError::new(err, file_in.as_ref(), file_out))
+    }
+
+    /// Create hash from a text file, panicking on any error.
+    ///
+    /// This is how [`Hasher::hash_file`] used to work, before it returned errors.
+    #[deprecated(note = "use `hash_file`, which returns errors instead of panicking")]
+    pub fn hash_file_unchecked(
+        &self,
+        file_in: impl AsRef<Path>,
+        file_out: Option<impl AsRef<Path>>,
     ) -> String {
-        let file_in = File::open(file_in).unwrap();
-        let file_in = BufReader::new(file_in);
+        self.hash_file(file_in, file_out).unwrap()
+    }
 
-        let mut file_out = file_out.and_then(|file_out| {
-            let file_out = File::create(file_out).unwrap();
-            let file_out = BufWriter::new(file_out);
-            Some(file_out)
-        });
+    /// Create hash from a text file, regardless of line endings, returning a plain IO error.
+    ///
+    /// This works exactly like [`Hasher::hash_file`], but returns an [`io::Error`], which can
+    /// easily be combined with other IO operations. The failed step can be queried with
+    /// [`ErrorKind::of`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use normalized_hash::Hasher;
+    ///
+    /// match Hasher::new().try_hash_file("input.txt", None::<PathBuf>) {
+    ///     Ok(hash) => println!("{}", hash),
+    ///     Err(err) => eprintln!("{}", err),
+    /// }
+    /// ```
+    pub fn try_hash_file(
+        &self,
+        file_in: impl AsRef<Path>,
+        file_out: Option<impl AsRef<Path>>,
+    ) -> io::Result<String> {
+        self.try_hash_file_with_stats(file_in, file_out)
+            .map(|(hash, _)| hash)
+    }
+
+    /// Create hash from a text file and collect statistics about the normalization.
+    ///
+    /// This works exactly like [`Hasher::try_hash_file`], but also returns the [`Stats`] of
+    /// `file_in`, which are collected in the same pass.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use normalized_hash::Hasher;
+    ///
+    /// let (hash, stats) = Hasher::new()
+    ///     .try_hash_file_with_stats("input.txt", None::<PathBuf>)
+    ///     .unwrap();
+    /// println!("{}: {} CRLF converted", hash, stats.converted_crlf);
+    /// ```
+    pub fn try_hash_file_with_stats(
+        &self,
+        file_in: impl AsRef<Path>,
+        file_out: Option<impl AsRef<Path>>,
+    ) -> io::Result<(String, Stats)> {
+        self.try_hash_file_timed(file_in, file_out)
+            .map(|(hash, stats, _)| (hash, stats))
+    }
 
-        let mut hasher = Sha256::new();
+    /// Create hash from a text file and measure how long it took.
+    ///
+    /// This works exactly like [`Hasher::try_hash_file_with_stats`], but also returns the
+    /// [`Timing`] of hashing `file_in`, including any retries.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use normalized_hash::Hasher;
+    ///
+    /// let (hash, _, timing) = Hasher::new()
+    ///     .try_hash_file_timed("input.txt", None::<PathBuf>)
+    ///     .unwrap();
+    /// println!("{} in {:?}", hash, timing.duration);
+    /// ```
+    pub fn try_hash_file_timed(
+        &self,
+        file_in: impl AsRef<Path>,
+        file_out: Option<impl AsRef<Path>>,
+    ) -> io::Result<(String, Stats, Timing)> {
+        let start = Instant::now();
+        let file_out: Option<&Path> = file_out.as_ref().map(|file_out| file_out.as_ref());
+        // Decided before the first attempt, which creates the output for any retries
+        let report = file_out.map(OutputReport::before_writing);
 
-        let mut is_first_line = true;
-        for line in file_in.lines() {
-            let line = line.unwrap();
+        let ((hash, mut stats), retries) =
+            self.retrying(|| self.hash_file_once(file_in.as_ref(), file_out, report.as_ref()))?;
+        stats.retries = retries;
 
-            let line = if self.ignore_whitespaces {
-                line.replace(|c: char| c.is_whitespace(), "")
-            } else {
-                line
-            };
+        let timing = Timing::new(stats.bytes, start.elapsed());
 
-            let line = if !is_first_line {
-                format!("{}{}", &self.eol, line)
-            } else {
-                line
-            };
+        Ok((hash, stats, timing))
+    }
 
-            hasher.update(&line);
+    /// Invoke `attempt` until it succeeds, retrying after transient errors and modifications.
+    ///
+    /// Returns the result of the successful attempt and the number of retries.
+    fn retrying<T>(&self, mut attempt: impl FnMut() -> io::Result<T>) -> io::Result<(T, u32)> {
+        let (mut modified, mut transient) = (0, 0);
 
-            if let Some(file_out) = &mut file_out {
-                file_out.write_all(line.as_bytes()).unwrap();
+        loop {
+            match attempt() {
+                Ok(value) => return Ok((value, modified + transient)),
+                Err(err)
+                    if ErrorKind::of(&err) == Some(ErrorKind::ModifiedDuringRead)
+                        && modified < self.retry_on_modify =>
+                {
+                    modified += 1;
+                }
+                Err(err) if self.is_transient(&err) && transient < self.retries => {
+                    thread::sleep(self.retry_delay.saturating_mul(1 << transient.min(16)));
+                    transient += 1;
+                }
+                Err(err) => return Err(err),
             }
-
-            is_first_line = false;
         }
+    }
 
-        if !self.no_eof {
-            hasher.update(&self.eol);
+    /// Check if an error while opening or reading an input is worth a retry.
+    fn is_transient(&self, err: &io::Error) -> bool {
+        if !matches!(ErrorKind::of(err), Some(ErrorKind::Open | ErrorKind::Read)) {
+            return false;
+        }
 
-            if let Some(file_out) = &mut file_out {
-                file_out.write_all(&self.eol.as_bytes()).unwrap();
-            }
+        match err.kind() {
+            io::ErrorKind::NotFound | io::ErrorKind::PermissionDenied => false,
+            io::ErrorKind::Interrupted
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::TimedOut => true,
+            _ => self.retry_if.as_ref().is_some_and(|retry_if| retry_if(err)),
         }
+    }
 
-        let hash = hasher.finalize();
+    /// Hash `file_in` a single time, failing if it is modified while it is read.
+    ///
+    /// The `report` of `file_out` is completed with the number of written bytes.
+    fn hash_file_once(
+        &self,
+        file_in: &Path,
+        file_out: Option<&Path>,
+        report: Option<&OutputReport>,
+    ) -> io::Result<(String, Stats)> {
+        let (input, before): (Box<dyn Read>, _) =
+            match unfollowed_link(file_in, self.symlink_input)? {
+                Some(target) => {
+                    let target = escape_path(&target).into_owned();
+                    (Box::new(io::Cursor::new(target)), None)
+                }
+                None => {
+                    let file = open_input(file_in)?;
+                    let before = snapshot(file.metadata());
+                    (Box::new(file), before)
+                }
+            };
+        let file_out_path = file_out;
+        let mut file_out = file_out
+            .map(|file_out| {
+                let file = File::create(long_path(file_out))?;
+                Ok(CountingWriter::new(BufWriter::new(file)))
+            })
+            .transpose()
+            .map_err(|err| ErrorKind::Create.tag(err))?;
 
-        base16ct::lower::encode_string(&hash)
-    }
-}
+        let output = file_out.as_mut().map(|file_out| file_out as _);
+        let (hash, mut stats, written) = self.hash_input(BufReader::new(input), output)?;
 
-#[cfg(test)]
-mod tests {
-    use std::error::Error;
-    use std::ffi::OsString;
-    use std::fs;
-    use std::iter::zip;
-    use std::ops::Add;
+        // Devices like `/dev/stdout` cannot be synced
+        let mut sync = false;
+        if let Some(file_out) = &mut file_out {
+            file_out.flush().map_err(|err| ErrorKind::Write.tag(err))?;
+            let file = file_out.inner.get_ref();
+            let is_file = file.metadata().is_ok_and(|metadata| metadata.is_file());
+            sync = self.sync_output && is_file;
+            if sync {
+                file.sync_all().map_err(|err| ErrorKind::Write.tag(err))?;
+            }
 
-    use tempfile::NamedTempFile;
+            stats.output = report.map(|report| OutputReport {
+                bytes_written: file_out.bytes,
+                ..report.clone()
+            });
+        }
+        drop(file_out);
 
-    use super::*;
+        if let Some(file_out) = file_out_path {
+            let file_out = long_path(file_out);
+            self.verify_written(&file_out, written.as_deref().unwrap_or(&hash))?;
+            if sync {
+                sync_parent(&file_out).map_err(|err| ErrorKind::Write.tag(err))?;
+            }
+        }
 
-    struct TestEnv {
-        file_with_crlf: NamedTempFile,
-        file_with_crlf_noeof: NamedTempFile,
-        file_with_lf: NamedTempFile,
-        file_with_lf_noeof: NamedTempFile,
+        if before.is_some() && snapshot(fs::metadata(long_path(file_in))) != before {
+            let err = io::Error::other("file was modified while it was read");
+            return Err(ErrorKind::ModifiedDuringRead.tag(err));
+        }
 
-        normalized_file_with_crlf: NamedTempFile,
-        normalized_file_with_crlf_noeof: NamedTempFile,
-        normalized_file_with_lf: NamedTempFile,
-        normalized_file_with_lf_noeof: NamedTempFile,
+        Ok((hash, stats))
     }
 
-    impl TestEnv {
-        fn new() -> Result<Self, std::io::Error> {
-            let mut file_with_crlf = NamedTempFile::new()?;
-            let mut file_with_crlf_noeof = NamedTempFile::new()?;
-            let mut file_with_lf = NamedTempFile::new()?;
-            let mut file_with_lf_noeof = NamedTempFile::new()?;
-
-            let normalized_file_with_crlf_noeof = NamedTempFile::new()?;
+    /// Collect statistics about a text file, without hashing it.
+    ///
+    /// The [`Stats`] are the same as those returned by [`Hasher::try_hash_file_with_stats`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::Hasher;
+    ///
+    /// let stats = Hasher::new().analyze_file("input.txt").unwrap();
+    /// if stats.crlf > 0 {
+    ///     println!("input.txt has {} CRLF line endings", stats.crlf);
+    /// }
+    /// ```
+    pub fn analyze_file(&self, file_in: impl AsRef<Path>) -> io::Result<Stats> {
+        let file_in = open_input(file_in.as_ref())?;
+        self.analyze_reader(BufReader::new(file_in))
+    }
+
+    /// Collect statistics about text read from `input`, without hashing it.
+    ///
+    /// This works exactly like [`Hasher::analyze_file`], for text that is not read from a file,
+    /// like decoded content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Hasher;
+    ///
+    /// let stats = Hasher::new().analyze_reader("A\r\nB\n".as_bytes()).unwrap();
+    /// assert!(stats.is_mixed_eol());
+    /// ```
+    pub fn analyze_reader(&self, input: impl BufRead) -> io::Result<Stats> {
+        let mut stats = Stats::default();
+
+        for line in self.line_changes_from(input) {
+            stats.add(&line?);
+        }
+
+        Ok(stats)
+    }
+
+    /// Feed the normalized content of `input` into a `digest` that is owned by the caller.
+    ///
+    /// The normalized bytes are exactly those that [`Hasher::hash_file`] hashes, so finalizing a
+    /// fresh digest of the [`Hasher::algorithm`] after this yields the same hash. The `digest` is not
+    /// finalized, which allows to bind the content into a larger structure whose digest already
+    /// absorbed other data, like a header.
+    ///
+    /// Returns the [`Stats`] of `input`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::digest::Digest;
+    /// use normalized_hash::Hasher;
+    /// use sha2::Sha256;
+    ///
+    /// let mut digest = Sha256::new();
+    /// digest.update(b"header\0");
+    /// Hasher::new().update_digest("A B\r\nC D\r\n".as_bytes(), &mut digest).unwrap();
+    /// let signed = digest.finalize();
+    /// ```
+    pub fn update_digest<D: digest::Update>(
+        &self,
+        input: impl Read,
+        digest: &mut D,
+    ) -> io::Result<Stats> {
+        let lines = self.line_changes_from(BufReader::new(input));
+        self.digest_lines(lines, digest, None, None)
+            .map(|(stats, _)| stats)
+    }
+
+    /// Create hash from text read from `input`, regardless of line endings.
+    ///
+    /// This applies exactly the same normalization as [`Hasher::hash_file`] and yields the same
+    /// hash for the same content, for text that is not read from a file, like a response body.
+    /// Optionally, the normalized text is written to `output`, which can be any writer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Hasher;
+    ///
+    /// let mut output = Vec::new();
+    /// let hash = Hasher::new()
+    ///     .hash_reader("A B\r\nC D\r\n".as_bytes(), Some(&mut output))
+    ///     .unwrap();
+    /// assert_eq!(output, b"A B\nC D\n");
+    /// ```
+    pub fn hash_reader(
+        &self,
+        input: impl BufRead,
+        mut output: Option<impl Write>,
+    ) -> io::Result<String> {
+        let output = output.as_mut().map(|output| output as _);
+        self.hash_input(input, output).map(|(hash, _, _)| hash)
+    }
+
+    /// Create hash from a string, regardless of line endings.
+    ///
+    /// This yields the same hash as [`Hasher::hash_file`] for a file with the same content,
+    /// which is handy for small snippets and tests.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Hasher;
+    ///
+    /// let hasher = Hasher::new();
+    /// assert_eq!(hasher.hash_string("A B\r\nC D\r\n"), hasher.hash_string("A B\nC D\n"));
+    /// ```
+    pub fn hash_string(&self, input: &str) -> String {
+        // Reading valid UTF-8 from memory cannot fail
+        self.hash_reader(input.as_bytes(), None::<io::Sink>)
+            .expect("hashing a string does not fail")
+    }
+
+    /// Create hash from bytes, regardless of line endings.
+    ///
+    /// Like [`Hasher::hash_string`], but fails with [`ErrorKind::Decode`] if `input` is not
+    /// valid UTF-8, just like [`Hasher::hash_file`] does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::{ErrorKind, Hasher};
+    ///
+    /// let hasher = Hasher::new();
+    /// assert_eq!(hasher.hash_bytes(b"A B\n").unwrap(), hasher.hash_string("A B\n"));
+    ///
+    /// let err = hasher.hash_bytes(b"\xff\n").unwrap_err();
+    /// assert_eq!(ErrorKind::of(&err), Some(ErrorKind::Decode));
+    /// ```
+    pub fn hash_bytes(&self, input: &[u8]) -> io::Result<String> {
+        self.hash_reader(input, None::<io::Sink>)
+    }
+
+    /// Hash `input`, writing the normalized lines to `output`, if given.
+    ///
+    /// Returns the hash, the statistics and, if the output differs in line endings, the hash of
+    /// the written content.
+    fn hash_input<B: BufRead>(
+        &self,
+        input: B,
+        output: Option<&mut dyn Write>,
+    ) -> io::Result<(String, Stats, Option<String>)> {
+        // Without an output, its style does not matter
+        let (style, input) = match output {
+            Some(_) => self.scan_style(input)?,
+            None => (None, style::unscanned(input)),
+        };
+        self.hash_lines(self.line_changes_from(input), output, style)
+    }
+
+    /// Normalize a stream of text, writing the normalized form to `output` while hashing it.
+    ///
+    /// Each normalized line is written as soon as it is read, so this can be used as a stage in
+    /// a pipeline. Only with [`OutputEol::MatchInput`], the first [`STYLE_SCAN_LIMIT`] bytes are
+    /// read before anything is written. Errors while writing are tagged as [`ErrorKind::Write`] and keep their
+    /// [`io::ErrorKind`], so a closed pipe can be told apart by [`io::ErrorKind::BrokenPipe`].
+    ///
+    /// Returns the hash of the normalized content and the [`Stats`] of `input`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Hasher;
+    ///
+    /// let mut output = Vec::new();
+    /// let (hash, _) = Hasher::new().filter("A B\r\nC D\r\n".as_bytes(), &mut output).unwrap();
+    /// assert_eq!(output, b"A B\nC D\n");
+    /// ```
+    pub fn filter(
+        &self,
+        input: impl BufRead,
+        mut output: impl Write,
+    ) -> io::Result<(String, Stats)> {
+        self.hash_input(input, Some(&mut output))
+            .map(|(hash, stats, _)| (hash, stats))
+    }
+
+    /// Normalize a text file in place.
+    ///
+    /// The normalized content is first written to a temporary file in the same directory, which
+    /// then atomically replaces `file`. This way, the file is never left in a half-written state.
+    /// The permissions of the original file are preserved.
+    ///
+    /// Returns the hash of the normalized content.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::Hasher;
+    ///
+    /// let hash = Hasher::new().normalize_in_place("input.txt").unwrap();
+    /// ```
+    pub fn normalize_in_place(&self, file: impl AsRef<Path>) -> io::Result<String> {
+        let file = file.as_ref();
+        self.write_normalized(file, file).map(|(hash, _)| hash)
+    }
+
+    /// Write the normalized form of a text file to another file.
+    ///
+    /// Like with [`Hasher::normalize_in_place`], `file_out` is replaced atomically and gets the
+    /// permissions of `file_in`. Missing parent directories of `file_out` are created.
+    ///
+    /// Returns the [`Stats`] of `file_in`, where [`Stats::is_changed`] tells if `file_out`
+    /// differs from `file_in`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::Hasher;
+    ///
+    /// let stats = Hasher::new().normalize_file("input.txt", "output.txt").unwrap();
+    /// if !stats.is_changed() {
+    ///     println!("input.txt was already normalized");
+    /// }
+    /// ```
+    pub fn normalize_file(
+        &self,
+        file_in: impl AsRef<Path>,
+        file_out: impl AsRef<Path>,
+    ) -> io::Result<Stats> {
+        self.write_normalized(file_in.as_ref(), file_out.as_ref())
+            .map(|(_, stats)| stats)
+    }
+
+    /// Write the normalized form of `file_in` atomically to `file_out`, which may be the same.
+    fn write_normalized(&self, file_in: &Path, file_out: &Path) -> io::Result<(String, Stats)> {
+        let (style, input) = self.scan_style(open_input(file_in)?)?;
+        let lines = self.line_changes_from(BufReader::new(input));
+
+        let file_out = long_path(file_out);
+        let dir = match file_out.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let write_error = |err| ErrorKind::Write.tag(err);
+        let create_error = |err| ErrorKind::Create.tag(err);
+        fs::create_dir_all(dir).map_err(create_error)?;
+        let mut temp = NamedTempFile::new_in(dir).map_err(create_error)?;
+        let report = OutputReport::before_writing(&file_out);
+
+        let (hash, mut stats, written, bytes_written) = {
+            let mut writer = CountingWriter::new(BufWriter::new(temp.as_file_mut()));
+            let (hash, stats, written) = self.hash_lines(lines, Some(&mut writer), style)?;
+            writer.flush().map_err(write_error)?;
+            (hash, stats, written, writer.bytes)
+        };
+        stats.output = Some(OutputReport {
+            bytes_written,
+            ..report
+        });
+        if self.sync_output {
+            temp.as_file().sync_all().map_err(write_error)?;
+        }
+        self.verify_written(temp.path(), written.as_deref().unwrap_or(&hash))?;
+
+        let permissions = fs::metadata(long_path(file_in))
+            .map_err(write_error)?
+            .permissions();
+        temp.as_file()
+            .set_permissions(permissions)
+            .map_err(write_error)?;
+        temp.persist(&file_out)
+            .map_err(|err| write_error(err.error))?;
+        if self.sync_output {
+            sync_parent(&file_out).map_err(write_error)?;
+        }
+
+        Ok((hash, stats))
+    }
+
+    /// Check that the content of a written output file has the given hash, with `verify_output`.
+    fn verify_written(&self, path: &Path, hash: &str) -> io::Result<()> {
+        if !self.verify_output {
+            return Ok(());
+        }
+
+        #[cfg(test)]
+        if let Some(before_verify) = self.before_verify {
+            before_verify(path);
+        }
+
+        let mut hasher = self.algorithm.digest();
+        File::open(path)
+            .and_then(|mut file| io::copy(&mut file, &mut hasher))
+            .map_err(|err| ErrorKind::Write.tag(err))?;
+
+        if hasher.finalize_hex() != hash {
+            let err = io::Error::other("written output does not match the computed hash");
+            return Err(ErrorKind::OutputMismatch.tag(err));
+        }
+
+        Ok(())
+    }
+
+    /// Check if a text file is already in normalized form.
+    ///
+    /// This is the case if normalization would not change a single byte of the file, which means
+    /// the normalized output would be identical to the input. With [`OutputEol::MatchInput`],
+    /// a file whose line endings are all of its predominant style can be normalized.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::Hasher;
+    ///
+    /// if !Hasher::new().is_normalized("input.txt").unwrap() {
+    ///     println!("input.txt is not normalized");
+    /// }
+    /// ```
+    pub fn is_normalized(&self, file_in: impl AsRef<Path>) -> io::Result<bool> {
+        self.first_unnormalized_line(file_in)
+            .map(|line| line.is_none())
+    }
+
+    /// Find the first line of a text file that is not in normalized form.
+    ///
+    /// This is the first line whose content or line ending would be changed in the normalized
+    /// output, see [`Hasher::is_normalized`]. Returns its line number, starting at 1, or `None`
+    /// if the file is normalized.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::Hasher;
+    ///
+    /// if let Some(line) = Hasher::new().first_unnormalized_line("input.txt").unwrap() {
+    ///     println!("input.txt is not normalized from line {}", line);
+    /// }
+    /// ```
+    pub fn first_unnormalized_line(&self, file_in: impl AsRef<Path>) -> io::Result<Option<usize>> {
+        let (style, input) = self.scan_style(open_input(file_in.as_ref())?)?;
+
+        for line in self.line_changes_from(BufReader::new(input)) {
+            let line = line?;
+            let eol = written_eol(&line, &self.eol, style);
+            if line.original != line.normalized || line.original_eol != eol {
+                return Ok(Some(line.number));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Hash many text files in parallel, reporting the results in the order of `files`.
+    ///
+    /// The files are hashed like with [`Hasher::try_hash_file_timed`] by up to `jobs` threads, or
+    /// by one thread per CPU if `jobs` is 0. With a `jobs` of 1, the files are hashed one after
+    /// the other on the current thread. Each thread only has a single file open at a time.
+    ///
+    /// Binary files, as detected by [`is_binary`], are skipped and reported as `Ok(None)`, like
+    /// when walking directory trees. Only regular files are probed, since other files like named
+    /// pipes cannot be read twice. An error only affects the file it occurred in.
+    ///
+    /// `each` is called on the current thread with the index of each file in `files` and its
+    /// result, in the order of `files`, regardless of the order in which the hashing finishes.
+    /// Results that finish early are buffered until all previous results are reported. If `each`
+    /// returns [`ControlFlow::Break`], no further files are hashed and the value is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::ops::ControlFlow;
+    /// use normalized_hash::Hasher;
+    ///
+    /// let files = ["a.txt", "b.txt", "c.txt"];
+    /// Hasher::new().hash_many(&files, 0, |index, result| {
+    ///     match result {
+    ///         Ok(Some((hash, _, _))) => println!("{}  {}", hash, files[index]),
+    ///         Ok(None) => println!("skipping binary file {}", files[index]),
+    ///         Err(err) => return ControlFlow::Break(err),
+    ///     }
+    ///     ControlFlow::Continue(())
+    /// });
+    /// ```
+    pub fn hash_many<P, B>(
+        &self,
+        files: &[P],
+        jobs: usize,
+        each: impl FnMut(usize, io::Result<Option<(String, Stats, Timing)>>) -> ControlFlow<B>,
+    ) -> ControlFlow<B>
+    where
+        P: AsRef<Path> + Sync,
+    {
+        self.hash_many_in(files, jobs, true, each)
+    }
+
+    /// Hash many text files in parallel, reporting each result as soon as it is finished.
+    ///
+    /// This works exactly like [`Hasher::hash_many`], but `each` is called in the order in which
+    /// the hashing finishes, so no results are buffered. The index passed to `each` tells which
+    /// file a result belongs to. With a `jobs` of 1, this is still the order of `files`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::ops::ControlFlow;
+    /// use normalized_hash::Hasher;
+    ///
+    /// let files = ["a.txt", "b.txt", "c.txt"];
+    /// Hasher::new().hash_many_unordered(&files, 0, |index, result| {
+    ///     if let Ok(Some((hash, _, _))) = result {
+    ///         println!("{}  {}", hash, files[index]);
+    ///     }
+    ///     ControlFlow::<()>::Continue(())
+    /// });
+    /// ```
+    pub fn hash_many_unordered<P, B>(
+        &self,
+        files: &[P],
+        jobs: usize,
+        each: impl FnMut(usize, io::Result<Option<(String, Stats, Timing)>>) -> ControlFlow<B>,
+    ) -> ControlFlow<B>
+    where
+        P: AsRef<Path> + Sync,
+    {
+        self.hash_many_in(files, jobs, false, each)
+    }
+
+    /// Hash all text files in the directory tree below `root`.
+    ///
+    /// The tree is walked with the default options of [`Walker`], so symbolic links are skipped.
+    /// Returns the path of each file relative to `root` with its hash, sorted like by
+    /// [`Walker::walk`], so the result is the same regardless of the file system or the order in
+    /// which the files were created. If `root` is a file, it is returned by its file name. The
+    /// files are hashed in parallel like with [`Hasher::hash_many`], binary files are skipped.
+    /// Fails on the first file that cannot be hashed, or with [`HashError::Read`] for `root` if
+    /// the tree cannot be walked.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::Hasher;
+    ///
+    /// for (path, hash) in Hasher::new().hash_dir("src")? {
+    ///     println!("{}  {}", hash, path.display());
+    /// }
+    /// # Ok::<(), normalized_hash::HashError>(())
+    /// ```
+    pub fn hash_dir(&self, root: impl AsRef<Path>) -> Result<Vec<(PathBuf, String)>, HashError> {
+        self.hash_walk(&Walker::new(root))
+    }
+
+    /// Hash all text files found by `walker`.
+    ///
+    /// This works exactly like [`Hasher::hash_dir`], but with the options of `walker`, like
+    /// exclude patterns or following symbolic links.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::{Hasher, Walker};
+    ///
+    /// let walker = Walker::new("src").exclude("*.log").follow_symlinks(true);
+    /// let hashes = Hasher::new().hash_walk(&walker)?;
+    /// # Ok::<(), normalized_hash::HashError>(())
+    /// ```
+    pub fn hash_walk(&self, walker: &Walker) -> Result<Vec<(PathBuf, String)>, HashError> {
+        let root = walker.root();
+        let files = walker
+            .walk()
+            .map_err(|err| HashError::new(err, root, None))?;
+
+        let mut hashes = Vec::with_capacity(files.len());
+        let result = self.hash_many(&files, 0, |index, result| {
+            let file = &files[index];
+            match result {
+                Ok(Some((hash, _, _))) => {
+                    let relative = match file.strip_prefix(root) {
+                        Ok(relative) if !relative.as_os_str().is_empty() => relative,
+                        _ => file.file_name().map_or(file.as_path(), Path::new),
+                    };
+                    hashes.push((relative.to_path_buf(), hash));
+                }
+                Ok(None) => {}
+                Err(err) => return ControlFlow::Break(HashError::new(err, file, None)),
+            }
+            ControlFlow::Continue(())
+        });
+
+        match result {
+            ControlFlow::Continue(()) => Ok(hashes),
+            ControlFlow::Break(err) => Err(err),
+        }
+    }
+
+    /// Hash many text files in parallel, reporting the results in the order of `files` if
+    /// `ordered` is set, otherwise as they finish.
+    fn hash_many_in<P, B>(
+        &self,
+        files: &[P],
+        jobs: usize,
+        ordered: bool,
+        mut each: impl FnMut(usize, io::Result<Option<(String, Stats, Timing)>>) -> ControlFlow<B>,
+    ) -> ControlFlow<B>
+    where
+        P: AsRef<Path> + Sync,
+    {
+        let hash = |file: &P| {
+            // The content of a link that is not followed does not matter
+            let followed = unfollowed_link(file.as_ref(), self.symlink_input)?.is_none();
+            let is_file = fs::metadata(long_path(file)).is_ok_and(|metadata| metadata.is_file());
+            if followed && is_file && is_binary(file)? {
+                return Ok(None);
+            }
+            self.try_hash_file_timed(file, None::<&Path>).map(Some)
+        };
+
+        let pool = match jobs {
+            1 => None,
+            _ => ThreadPoolBuilder::new().num_threads(jobs).build().ok(),
+        };
+
+        // Without a thread pool, which can only fail to build if no threads can be spawned,
+        // the files are hashed serially
+        let Some(pool) = pool else {
+            for (index, file) in files.iter().enumerate() {
+                each(index, hash(file))?;
+            }
+            return ControlFlow::Continue(());
+        };
+
+        let cancelled = AtomicBool::new(false);
+        let (sender, receiver) = mpsc::channel();
+
+        pool.in_place_scope_fifo(|scope| {
+            for (index, file) in files.iter().enumerate() {
+                let sender = sender.clone();
+                let (cancelled, hash) = (&cancelled, &hash);
+                scope.spawn_fifo(move |_| {
+                    if !cancelled.load(Ordering::Relaxed) {
+                        let _ = sender.send((index, hash(file)));
+                    }
+                });
+            }
+            drop(sender);
+
+            let mut finished = BTreeMap::new();
+            let mut next = 0;
+            for (index, result) in receiver {
+                if !ordered {
+                    if let ControlFlow::Break(value) = each(index, result) {
+                        cancelled.store(true, Ordering::Relaxed);
+                        return ControlFlow::Break(value);
+                    }
+                    continue;
+                }
+
+                finished.insert(index, result);
+                while let Some(result) = finished.remove(&next) {
+                    if let ControlFlow::Break(value) = each(next, result) {
+                        cancelled.store(true, Ordering::Relaxed);
+                        return ControlFlow::Break(value);
+                    }
+                    next += 1;
+                }
+            }
+
+            ControlFlow::Continue(())
+        })
+    }
+
+    /// Hash normalized lines, writing them to `output` with the line ending `style`, if given.
+    ///
+    /// Returns the hash, the statistics and, with a `style`, the hash of the written content.
+    fn hash_lines<B: BufRead>(
+        &self,
+        lines: LineChanges<'_, B>,
+        output: Option<&mut dyn Write>,
+        style: Option<&str>,
+    ) -> io::Result<(String, Stats, Option<String>)> {
+        let mut hasher = self.algorithm.digest();
+        let (stats, written) = self.digest_lines(lines, &mut hasher, output, style)?;
+
+        Ok((hasher.finalize_hex(), stats, written))
+    }
+
+    /// Feed normalized lines into `digest`, writing them to `output` with the line ending
+    /// `style`, if given.
+    ///
+    /// Returns the statistics and, with a `style`, the hash of the written content.
+    fn digest_lines<B: BufRead, D: digest::Update>(
+        &self,
+        lines: LineChanges<'_, B>,
+        digest: &mut D,
+        mut output: Option<&mut dyn Write>,
+        style: Option<&str>,
+    ) -> io::Result<(Stats, Option<String>)> {
+        let mut written = style.map(|_| self.algorithm.digest());
+        let mut stats = Stats::default();
+
+        for line in lines {
+            let line = line?;
+            stats.add(&line);
+
+            digest.update(line.normalized.as_bytes());
+            digest.update(line.normalized_eol.as_bytes());
+
+            let eol = written_eol(&line, &self.eol, style);
+            if let Some(written) = &mut written {
+                written.update(line.normalized.as_bytes());
+                written.update(eol.as_bytes());
+            }
+
+            if let Some(output) = &mut output {
+                output
+                    .write_all(line.normalized.as_bytes())
+                    .and_then(|()| output.write_all(eol.as_bytes()))
+                    .map_err(|err| ErrorKind::Write.tag(err))?;
+            }
+        }
+
+        let written = written.map(AnyDigest::finalize_hex);
+
+        Ok((stats, written))
+    }
+
+    /// Read a text file linewise and normalize each line.
+    ///
+    /// The returned iterator yields the lines of `file_in` as they are used for hashing, that is,
+    /// without any line ending and with all configured per-line transformations applied. The eol
+    /// sequence and the `no_eof` option do not affect the individual lines.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::Hasher;
+    ///
+    /// for line in Hasher::new().normalized_lines("input.txt").unwrap() {
+    ///     println!("{}", line.unwrap());
+    /// }
+    /// ```
+    pub fn normalized_lines(
+        &self,
+        file_in: impl AsRef<Path>,
+    ) -> io::Result<NormalizedLines<'_, BufReader<File>>> {
+        let file_in = open_input(file_in.as_ref())?;
+        Ok(self.normalized_lines_from(BufReader::new(file_in)))
+    }
+
+    /// Read text from `input` linewise and normalize each line.
+    ///
+    /// This works exactly like [`Hasher::normalized_lines`], for text that is not read from a
+    /// file, like a download.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Hasher;
+    ///
+    /// let lines = Hasher::new()
+    ///     .normalized_lines_from("A B\r\nC D\r\n".as_bytes())
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(lines, ["A B", "C D"]);
+    /// ```
+    pub fn normalized_lines_from<B: BufRead>(&self, input: B) -> NormalizedLines<'_, B> {
+        NormalizedLines {
+            hasher: self,
+            lines: input.lines(),
+            ahead: None,
+            continued: false,
+        }
+    }
+
+    /// Read text from `input` linewise and normalize each line, together with its position.
+    ///
+    /// Like [`Hasher::normalized_lines_from`], but each line comes with the [`Span`] of the
+    /// original line in `input`, so that a normalized line can be traced back to the raw bytes it
+    /// was made of. Lines that are dropped by normalization are yielded as empty lines with their
+    /// span, unless [`NormalizedSpans::skip_dropped`] is set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Hasher;
+    ///
+    /// let input = "A B\r\nC D\r\n";
+    /// let hasher = Hasher::new().ignore_whitespaces(true);
+    /// let lines = hasher
+    ///     .normalized_lines_with_spans(input.as_bytes())
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    ///
+    /// let (line, span) = &lines[1];
+    /// assert_eq!(line, "CD");
+    /// assert_eq!((span.orig_offset, span.orig_len, span.line_no), (5, 3, 2));
+    /// ```
+    pub fn normalized_lines_with_spans<B: BufRead>(&self, input: B) -> NormalizedSpans<'_, B> {
+        NormalizedSpans {
+            hasher: self,
+            input,
+            offset: 0,
+            line_no: 0,
+            skip_dropped: false,
+        }
+    }
+
+    /// Read a text file linewise and report each line before and after normalization.
+    ///
+    /// In contrast to [`Hasher::normalized_lines`], the returned iterator also yields the
+    /// original line endings and the eol sequence that is appended to each normalized line. The
+    /// concatenation of all normalized lines and their eol sequences is exactly the content that
+    /// is hashed. For an empty input, a single empty line is reported if an eol sequence is
+    /// appended to it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::Hasher;
+    ///
+    /// for line in Hasher::new().line_changes("input.txt").unwrap() {
+    ///     let line = line.unwrap();
+    ///     if line.is_changed() {
+    ///         println!("Line {} is changed by normalization", line.number);
+    ///     }
+    /// }
+    /// ```
+    pub fn line_changes(
+        &self,
+        file_in: impl AsRef<Path>,
+    ) -> io::Result<LineChanges<'_, BufReader<File>>> {
+        let file_in = open_input(file_in.as_ref())?;
+        Ok(self.line_changes_from(BufReader::new(file_in)))
+    }
+
+    fn line_changes_from<B: BufRead>(&self, input: B) -> LineChanges<'_, B> {
+        LineChanges {
+            hasher: self,
+            input,
+            number: 0,
+            pending: VecDeque::new(),
+            continued: false,
+            finished: false,
+        }
+    }
+
+    /// Normalize a single line as it is hashed.
+    ///
+    /// All per-line transformations of the configured options are applied, like redacting
+    /// matches with `redact` and removing whitespaces with `ignore_whitespaces`. If a line is not changed, it is borrowed instead of
+    /// copied. Returns `None` if the line is dropped from the hashed content, which none of the
+    /// current options do.
+    ///
+    /// The line is expected without its line ending. Line endings are not in the scope of this
+    /// function: the eol sequence is appended to each normalized line for hashing, except for the
+    /// last line with `no_eof`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use normalized_hash::Hasher;
+    ///
+    /// let hasher = Hasher::new().ignore_whitespaces(true);
+    /// assert_eq!(hasher.normalize_line("A B"), Some(Cow::Owned(String::from("AB"))));
+    /// assert_eq!(hasher.normalize_line("AB"), Some(Cow::Borrowed("AB")));
+    /// ```
+    pub fn normalize_line<'a>(&self, line: &'a str) -> Option<Cow<'a, str>> {
+        let mut line = Cow::Borrowed(line);
+        for (pattern, replacement) in &self.redactions {
+            if let Cow::Owned(redacted) = pattern.replace_all(&line, replacement.as_str()) {
+                line = Cow::Owned(redacted);
+            }
+        }
+
+        if self.ignore_whitespaces && line.contains(char::is_whitespace) {
+            Some(Cow::Owned(line.replace(char::is_whitespace, "")))
+        } else {
+            Some(line)
+        }
+    }
+
+    /// Trim a non-blank line of a paragraph where it is joined, see [`Hasher::paragraph_mode`].
+    ///
+    /// The line is `continued` from the previous line and `continues` with the next one. Returns
+    /// the trimmed line and if it is joined with the next line.
+    fn paragraph_part<'a>(
+        &self,
+        line: &'a str,
+        continued: bool,
+        continues: bool,
+    ) -> (&'a str, bool) {
+        let hard_break = self.hard_breaks && (line.ends_with("  ") || line.ends_with('\\'));
+        let joined = continues && !hard_break;
+
+        let line = if continued { line.trim_start() } else { line };
+        let line = if joined { line.trim_end() } else { line };
+        (line, joined)
+    }
+
+    /// Separator of joined lines within a paragraph, which is removed with `ignore_whitespaces`.
+    fn joiner(&self) -> &'static str {
+        if self.ignore_whitespaces {
+            ""
+        } else {
+            " "
+        }
+    }
+
+    /// Apply [`Hasher::normalize_line`] to an owned line, which is reused if it is not changed.
+    fn normalize_owned(&self, line: String) -> Option<String> {
+        let changed = match self.normalize_line(&line)? {
+            Cow::Borrowed(_) => None,
+            Cow::Owned(normalized) => Some(normalized),
+        };
+        Some(changed.unwrap_or(line))
+    }
+}
+
+/// Line ending that is written after a normalized line, `style` instead of the `eol` if given.
+///
+/// With `no_eof`, the last line has no line ending in any style. Lines that are joined within a
+/// paragraph keep their separator.
+fn written_eol<'a>(line: &LineChange<'a>, eol: &str, style: Option<&'a str>) -> &'a str {
+    match style {
+        Some(style) if !line.normalized_eol.is_empty() && line.normalized_eol == eol => style,
+        _ => line.normalized_eol,
+    }
+}
+
+/// Check if a line separates paragraphs, see [`Hasher::paragraph_mode`].
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// Sync the directory that contains `file`, so that creating or renaming it is durable.
+///
+/// Only Unix needs and supports this, elsewhere this does nothing.
+fn sync_parent(file: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        let dir = match file.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        File::open(dir)?.sync_all()?;
+    }
+
+    #[cfg(not(unix))]
+    let _ = file;
+
+    Ok(())
+}
+
+/// Size and modification time of a file, `None` if it is no regular file.
+///
+/// Other files, like pipes, are not checked for modifications, since reading them changes them.
+fn snapshot(metadata: io::Result<Metadata>) -> Option<(u64, Option<SystemTime>)> {
+    metadata
+        .ok()
+        .filter(Metadata::is_file)
+        .map(|metadata| (metadata.len(), metadata.modified().ok()))
+}
+
+/// Iterator over normalized lines, created by [`Hasher::normalized_lines`].
+pub struct NormalizedLines<'a, B> {
+    hasher: &'a Hasher,
+    lines: Lines<B>,
+
+    /// Line that was read ahead, with `paragraph_mode`.
+    ahead: Option<io::Result<String>>,
+
+    /// Whether the previous line is joined with the next one, with `paragraph_mode`.
+    continued: bool,
+}
+
+impl<B: BufRead> NormalizedLines<'_, B> {
+    /// Read the lines up to the next hard break or the end of the paragraph and join them.
+    fn next_paragraph(&mut self) -> Option<io::Result<String>> {
+        let mut paragraph = String::new();
+        let mut started = false;
+
+        loop {
+            let line = match self.ahead.take().or_else(|| self.lines.next()) {
+                Some(Ok(line)) => line,
+                Some(Err(err)) => return Some(Err(ErrorKind::tag_read(err))),
+                None => return started.then_some(Ok(paragraph)),
+            };
+            if is_blank(&line) {
+                continue;
+            }
+
+            // A read error ends the paragraph before it is reported
+            self.ahead = self.lines.next();
+            let continues = matches!(&self.ahead, Some(Ok(next)) if !is_blank(next));
+            let (part, joined) = self.hasher.paragraph_part(&line, self.continued, continues);
+            self.continued = continues;
+
+            if let Some(normalized) = self.hasher.normalize_line(part) {
+                paragraph.push_str(&normalized);
+            }
+            started = true;
+
+            if !joined {
+                return Some(Ok(paragraph));
+            }
+            paragraph.push_str(self.hasher.joiner());
+        }
+    }
+}
+
+impl<B: BufRead> Iterator for NormalizedLines<'_, B> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.hasher.paragraph_mode {
+            return self.next_paragraph();
+        }
+
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(err) => return Some(Err(ErrorKind::tag_read(err))),
+            };
+
+            if let Some(normalized) = self.hasher.normalize_owned(line) {
+                return Some(Ok(normalized));
+            }
+        }
+    }
+}
+
+/// Position of an original line in the input, created by [`Hasher::normalized_lines_with_spans`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the start of the line in the input.
+    pub orig_offset: u64,
+
+    /// Length of the line in bytes, without its line ending.
+    pub orig_len: u64,
+
+    /// Line number in the input, starting at 1.
+    pub line_no: u64,
+}
+
+/// Iterator over normalized lines and their spans, created by
+/// [`Hasher::normalized_lines_with_spans`].
+pub struct NormalizedSpans<'a, B> {
+    hasher: &'a Hasher,
+    input: B,
+    offset: u64,
+    line_no: u64,
+    skip_dropped: bool,
+}
+
+impl<B> NormalizedSpans<'_, B> {
+    /// Skip lines that are dropped by normalization, instead of yielding them as empty lines.
+    ///
+    /// Line numbers and offsets of the following lines are not affected.
+    pub fn skip_dropped(mut self, skip_dropped: bool) -> Self {
+        self.skip_dropped = skip_dropped;
+        self
+    }
+}
+
+impl<B: BufRead> Iterator for NormalizedSpans<'_, B> {
+    type Item = io::Result<(String, Span)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut line = String::new();
+            let bytes = match self.input.read_line(&mut line) {
+                Ok(0) => return None,
+                Ok(bytes) => bytes as u64,
+                Err(err) => return Some(Err(ErrorKind::tag_read(err))),
+            };
+
+            let eol = split_eol(&mut line);
+            self.line_no += 1;
+            let span = Span {
+                orig_offset: self.offset,
+                orig_len: bytes - eol.len() as u64,
+                line_no: self.line_no,
+            };
+            self.offset += bytes;
+
+            match self.hasher.normalize_owned(line) {
+                Some(normalized) => return Some(Ok((normalized, span))),
+                None if self.skip_dropped => continue,
+                None => return Some(Ok((String::new(), span))),
+            }
+        }
+    }
+}
+
+/// A single line, before and after normalization, created by [`Hasher::line_changes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineChange<'a> {
+    /// Line number in the input, starting at 1.
+    pub number: usize,
+
+    /// Line content as read from the input, without line ending.
+    pub original: String,
+
+    /// Line ending as read from the input, empty if the last line has none.
+    pub original_eol: &'static str,
+
+    /// Line content after normalization, without eol sequence.
+    pub normalized: String,
+
+    /// Eol sequence appended to the normalized line, empty for the last line with `no_eof`.
+    ///
+    /// Also empty for a line that is dropped, see [`Hasher::normalize_line`].
+    pub normalized_eol: &'a str,
+}
+
+impl LineChange<'_> {
+    /// Check if normalization changes this line or its line ending.
+    pub fn is_changed(&self) -> bool {
+        self.original != self.normalized || self.original_eol != self.normalized_eol
+    }
+}
+
+/// Iterator over line changes, created by [`Hasher::line_changes`].
+pub struct LineChanges<'a, B> {
+    hasher: &'a Hasher,
+    input: B,
+    number: usize,
+    pending: VecDeque<(String, &'static str)>,
+
+    /// Whether the previous line is joined with the next one, with `paragraph_mode`.
+    continued: bool,
+    finished: bool,
+}
+
+impl<B: BufRead> LineChanges<'_, B> {
+    fn read_line(&mut self) -> io::Result<Option<(String, &'static str)>> {
+        if let Some(line) = self.pending.pop_front() {
+            return Ok(Some(line));
+        }
+
+        self.read_input()
+    }
+
+    /// Look at the line `index` lines after the current one, reading ahead as far as needed.
+    fn peek(&mut self, index: usize) -> io::Result<Option<&str>> {
+        while self.pending.len() <= index {
+            match self.read_input()? {
+                Some(line) => self.pending.push_back(line),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(&self.pending[index].0))
+    }
+
+    /// Check if a non-blank line follows the current one, so that its paragraph is not the last.
+    fn has_paragraph_ahead(&mut self) -> io::Result<bool> {
+        for index in 0.. {
+            match self.peek(index)? {
+                Some(line) if !is_blank(line) => return Ok(true),
+                Some(_) => {}
+                None => break,
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn read_input(&mut self) -> io::Result<Option<(String, &'static str)>> {
+        let mut line = String::new();
+        let bytes = self
+            .input
+            .read_line(&mut line)
+            .map_err(ErrorKind::tag_read)?;
+        if bytes == 0 {
+            return Ok(None);
+        }
+
+        if let Some(progress) = &self.hasher.progress {
+            progress(bytes as u64);
+        }
+
+        let eol = split_eol(&mut line);
+        Ok(Some((line, eol)))
+    }
+}
+
+/// Remove the line ending from a line as read with [`BufRead::read_line`] and return it.
+fn split_eol(line: &mut String) -> &'static str {
+    let eol = if line.ends_with("\r\n") {
+        "\r\n"
+    } else if line.ends_with('\n') {
+        "\n"
+    } else {
+        ""
+    };
+    line.truncate(line.len() - eol.len());
+
+    eol
+}
+
+impl<'a, B: BufRead> LineChanges<'a, B> {
+    /// Normalize the current line and choose the eol sequence that follows it.
+    ///
+    /// Returns `None` if the line is dropped, like blank lines with `paragraph_mode`.
+    fn normalize(&mut self, original: &str) -> io::Result<Option<(String, &'a str)>> {
+        let hasher = self.hasher;
+
+        if !hasher.paragraph_mode {
+            // Look ahead to find out if this is the last line
+            let is_last_line = self.peek(0)?.is_none();
+            let eol = if is_last_line && hasher.no_eof {
+                ""
+            } else {
+                hasher.eol.as_str()
+            };
+            let normalized = hasher.normalize_line(original);
+            return Ok(normalized.map(|normalized| (normalized.into_owned(), eol)));
+        }
+
+        if is_blank(original) {
+            return Ok(None);
+        }
+
+        let continues = self.peek(0)?.is_some_and(|next| !is_blank(next));
+        let (part, joined) = hasher.paragraph_part(original, self.continued, continues);
+        self.continued = continues;
+
+        let is_last_line = !continues && !self.has_paragraph_ahead()?;
+        let eol = if joined {
+            hasher.joiner()
+        } else if is_last_line && hasher.no_eof {
+            ""
+        } else {
+            hasher.eol.as_str()
+        };
+        let normalized = hasher.normalize_line(part);
+        Ok(normalized.map(|normalized| (normalized.into_owned(), eol)))
+    }
+}
+
+impl<'a, B: BufRead> Iterator for LineChanges<'a, B> {
+    type Item = io::Result<LineChange<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let line = self.read_line().and_then(|line| {
+            let Some((original, original_eol)) = line else {
+                return Ok(None);
+            };
+            let normalized = self.normalize(&original)?;
+            Ok(Some((original, original_eol, normalized)))
+        });
+
+        let (original, original_eol, normalized) = match line {
+            Ok(Some(line)) => line,
+
+            Ok(None) => {
+                self.finished = true;
+
+                // An empty input still results in a single eol
+                if self.number > 0 || self.hasher.no_eof {
+                    return None;
+                }
+
+                let normalized = self.hasher.normalize_line("");
+                let eol = self.hasher.eol.as_str();
+                (
+                    String::new(),
+                    "",
+                    normalized.map(|normalized| (normalized.into_owned(), eol)),
+                )
+            }
+
+            Err(err) => {
+                self.finished = true;
+                return Some(Err(err));
+            }
+        };
+
+        self.number += 1;
+
+        // Dropped lines contribute nothing to the hash, not even an eol sequence
+        let (normalized, normalized_eol) = normalized.unwrap_or((String::new(), ""));
+
+        Some(Ok(LineChange {
+            number: self.number,
+            original,
+            original_eol,
+            normalized,
+            normalized_eol,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::ffi::OsString;
+    use std::fs;
+    use std::iter::zip;
+    use std::ops::Add;
+    use std::path::PathBuf;
+
+    use sha2::{Digest, Sha256};
+    use tempfile::NamedTempFile;
+
+    use super::*;
+
+    struct TestEnv {
+        file_with_crlf: NamedTempFile,
+        file_with_crlf_noeof: NamedTempFile,
+        file_with_lf: NamedTempFile,
+        file_with_lf_noeof: NamedTempFile,
+
+        normalized_file_with_crlf: NamedTempFile,
+        normalized_file_with_crlf_noeof: NamedTempFile,
+        normalized_file_with_lf: NamedTempFile,
+        normalized_file_with_lf_noeof: NamedTempFile,
+    }
+
+    impl TestEnv {
+        fn new() -> Result<Self, std::io::Error> {
+            let mut file_with_crlf = NamedTempFile::new()?;
+            let mut file_with_crlf_noeof = NamedTempFile::new()?;
+            let mut file_with_lf = NamedTempFile::new()?;
+            let mut file_with_lf_noeof = NamedTempFile::new()?;
+
+            let normalized_file_with_crlf_noeof = NamedTempFile::new()?;
             let normalized_file_with_crlf = NamedTempFile::new()?;
             let normalized_file_with_lf_noeof = NamedTempFile::new()?;
             let normalized_file_with_lf = NamedTempFile::new()?;
 
-            let content = vec!["A B", "C D"];
+            let content = ["A B", "C D"];
+
+            file_with_crlf.write_all(content.join("\r\n").add("\r\n").as_bytes())?;
+            file_with_crlf_noeof.write_all(content.join("\r\n").as_bytes())?;
+            file_with_lf.write_all(content.join("\n").add("\n").as_bytes())?;
+            file_with_lf_noeof.write_all(content.join("\n").as_bytes())?;
+
+            Ok(TestEnv {
+                file_with_crlf,
+                file_with_crlf_noeof,
+                file_with_lf,
+                file_with_lf_noeof,
+
+                normalized_file_with_crlf,
+                normalized_file_with_crlf_noeof,
+                normalized_file_with_lf,
+                normalized_file_with_lf_noeof,
+            })
+        }
+
+        fn get_input_files(&self) -> Vec<&NamedTempFile> {
+            vec![
+                &self.file_with_crlf,
+                &self.file_with_crlf_noeof,
+                &self.file_with_lf,
+                &self.file_with_lf_noeof,
+            ]
+        }
+
+        fn get_output_files(&self) -> Vec<&NamedTempFile> {
+            vec![
+                &self.normalized_file_with_crlf,
+                &self.normalized_file_with_crlf_noeof,
+                &self.normalized_file_with_lf,
+                &self.normalized_file_with_lf_noeof,
+            ]
+        }
+
+        fn hash_files(&self, hasher: &Hasher) -> Result<(String, String), Box<dyn Error>> {
+            let mut hash_check = None;
+            let mut content_check = None;
+
+            for (file_in, file_out) in zip(self.get_input_files(), self.get_output_files()) {
+                let hash = hasher.hash_file(file_in, Some(file_out)).unwrap();
+
+                if hash_check.is_none() {
+                    hash_check = Some(hash.clone());
+                    content_check = Some(fs::read_to_string(file_out)?)
+                }
+
+                if let (Some(hash_check), Some(content_check)) = (&hash_check, &content_check) {
+                    assert_eq!(&hash, hash_check, "Hashes don't match");
+                    assert_eq!(
+                        &fs::read_to_string(file_out)?,
+                        content_check,
+                        "Normalized files don't match"
+                    );
+                }
+            }
+
+            let (Some(hash_check), Some(content_check)) = (hash_check, content_check) else {
+                unreachable!()
+            };
+
+            Ok((hash_check, content_check))
+        }
+    }
+
+    #[test]
+    fn check_empty_file() -> Result<(), Box<dyn Error>> {
+        let file = NamedTempFile::new()?;
+
+        // Sanity check between hasher versions
+
+        // Completely empty file
+        let hash_expected = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        let hash_actual = Hasher::new()
+            .eol("")
+            .hash_file(&file, None::<OsString>)
+            .unwrap();
+        assert_eq!(hash_actual, hash_expected);
+
+        // Empty file ending in LF
+        let hash_expected = "01ba4719c80b6fe911b091a7c05124b64eeece964e09c058ef8f9805daca546b";
+        let hash_actual = Hasher::new().hash_file(&file, None::<OsString>).unwrap();
+        assert_eq!(hash_actual, hash_expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_empty_file_algorithms() -> Result<(), Box<dyn Error>> {
+        let file = NamedTempFile::new()?;
+
+        // Known answers for the completely empty file and the empty file ending in LF
+        let cases = [
+            (
+                Algorithm::Sha256,
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+                "01ba4719c80b6fe911b091a7c05124b64eeece964e09c058ef8f9805daca546b",
+            ),
+            (
+                Algorithm::Sha512,
+                "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+                 47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+                "be688838ca8686e5c90689bf2ab585cef1137c999b48c70b92f67a5c34dc1569\
+                 7b5d11c982ed6d71be1e1e7f7b4e0733884aa97c3f7a339a8ed03577cf74be09",
+            ),
+            (
+                Algorithm::Blake3,
+                "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+                "295192ea1ec8566d563b1a7587e5f0198580cdbd043842f5090a4c197c20c67a",
+            ),
+        ];
+
+        for (algorithm, hash_empty, hash_lf) in cases {
+            let hasher = Hasher::new().algorithm(algorithm);
+            assert_eq!(hasher.hash_file(&file, None::<OsString>)?, hash_lf);
+            assert_eq!(hash_lf.len(), algorithm.hex_len());
+
+            let hasher = hasher.eol("");
+            assert_eq!(hasher.hash_file(&file, None::<OsString>)?, hash_empty);
+        }
+
+        // Written outputs are verified with the same algorithm
+        let dir = tempfile::tempdir()?;
+        let hasher = Hasher::new()
+            .algorithm(Algorithm::Sha512)
+            .output_eol(OutputEol::MatchInput)
+            .verify_output(true);
+        let file_in = dir.path().join("in.txt");
+        fs::write(&file_in, "A B\r\nC D\r\n")?;
+        let hash = hasher.hash_file(&file_in, Some(dir.path().join("out.txt")))?;
+        assert_eq!(
+            hash,
+            Hasher::new()
+                .algorithm(Algorithm::Sha512)
+                .hash_string("A B\nC D\n")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_default_options() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let (_, normalized_content) = test_env.hash_files(&Hasher::new())?;
+
+        assert_eq!(
+            fs::read_to_string(&test_env.file_with_lf)?,
+            normalized_content,
+            "Normalized files do not have LF"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_with_custom_eol() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let (_, normalized_content) = test_env.hash_files(&Hasher::new().eol("\r\n"))?;
+
+        assert_eq!(
+            fs::read_to_string(&test_env.file_with_crlf)?,
+            normalized_content,
+            "Normalized files do not have CRLF"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_without_eof() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let (_, normalized_content) = test_env.hash_files(&Hasher::new().no_eof(true))?;
+
+        assert_eq!(
+            fs::read_to_string(&test_env.file_with_lf_noeof)?,
+            normalized_content,
+            "Normalized files do not have LF without EOF"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_normalized_lines() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let hasher = Hasher::new().ignore_whitespaces(true);
+
+        for file_in in test_env.get_input_files() {
+            let lines = hasher
+                .normalized_lines(file_in)?
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(lines, ["AB", "CD"]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_normalize_line() -> Result<(), Box<dyn Error>> {
+        let lines = ["A B", " C\tD ", "", "EF"];
+
+        for hasher in [Hasher::new(), Hasher::new().ignore_whitespaces(true)] {
+            assert!(matches!(
+                hasher.normalize_line("EF"),
+                Some(Cow::Borrowed("EF"))
+            ));
+
+            // Assembling the normalized lines gives exactly the hashed content
+            let mut content = String::new();
+            for line in lines {
+                content.push_str(&hasher.normalize_line(line).unwrap());
+                content.push_str(&hasher.eol);
+            }
+            let expected = base16ct::lower::encode_string(&Sha256::digest(content));
+
+            let input = lines.join("\r\n");
+            let (hash, _) = hasher.filter(input.as_bytes(), io::sink())?;
+            assert_eq!(hash, expected);
+        }
+
+        let hasher = Hasher::new().ignore_whitespaces(true);
+        assert_eq!(hasher.normalize_line(" C\tD ").as_deref(), Some("CD"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_redact() -> Result<(), Box<dyn Error>> {
+        let hasher = Hasher::new().redact(Regex::new(r"key=(\w)\w*")?, "key=$1***");
+        assert_eq!(
+            hasher.normalize_line("key=abc, key=def").as_deref(),
+            Some("key=a***, key=d***")
+        );
+        assert!(matches!(
+            hasher.normalize_line("no secrets"),
+            Some(Cow::Borrowed("no secrets"))
+        ));
+
+        // Matches do not overlap, like with `Regex::replace_all`
+        let hasher = Hasher::new().redact(Regex::new("aa")?, "X");
+        assert_eq!(hasher.normalize_line("aaaaa").as_deref(), Some("XXa"));
+
+        // Each pattern sees the result of the previous ones, whitespaces are removed last
+        let hasher = Hasher::new()
+            .redact(Regex::new(r"\d+")?, "N")
+            .redact(Regex::new("N N")?, "pair")
+            .ignore_whitespaces(true);
+        assert_eq!(hasher.normalize_line("1 2 x").as_deref(), Some("pairx"));
+
+        // The hash is that of the redacted content
+        let (hash, _) = hasher.filter("1 2 x\r\n".as_bytes(), io::sink())?;
+        let (expected, _) = Hasher::new().filter("pairx\n".as_bytes(), io::sink())?;
+        assert_eq!(hash, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_paragraph_mode() -> Result<(), Box<dyn Error>> {
+        let words = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod \
+                     tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, \
+                     quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo.";
+        let wrap = |width: usize| {
+            let mut text = String::new();
+            let mut line = String::new();
+            for word in words.split(' ') {
+                if !line.is_empty() && line.len() + 1 + word.len() > width {
+                    text.push_str(&line);
+                    text.push('\n');
+                    line.clear();
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+            }
+            text + &line + "\n\nSecond paragraph.\n"
+        };
+        let (narrow, wide) = (wrap(72), wrap(100));
+        assert_ne!(narrow.lines().count(), wide.lines().count());
+
+        let hasher = Hasher::new().paragraph_mode(true);
+        let mut output = Vec::new();
+        let (hash, _) = hasher.filter(narrow.as_bytes(), &mut output)?;
+        assert_eq!(
+            String::from_utf8(output)?,
+            format!("{words}\nSecond paragraph.\n")
+        );
+        assert_eq!(hash, hasher.filter(wide.as_bytes(), io::sink())?.0);
+
+        // Without paragraph mode, the wrapping matters
+        let (hash_narrow, _) = Hasher::new().filter(narrow.as_bytes(), io::sink())?;
+        let (hash_wide, _) = Hasher::new().filter(wide.as_bytes(), io::sink())?;
+        assert_ne!(hash_narrow, hash_wide);
+
+        // Distinct paragraphs do not merge
+        let (separated, _) = hasher.filter("A\n\nB\n".as_bytes(), io::sink())?;
+        let (joined, _) = hasher.filter("A\nB\n".as_bytes(), io::sink())?;
+        assert_ne!(separated, joined);
+
+        let cases: [(Hasher, &str, &str); 5] = [
+            (
+                Hasher::new(),
+                "\n  A \n  B\nC  \n \t\n\n D\n",
+                "  A B C  \n D\n",
+            ),
+            (
+                Hasher::new().ignore_whitespaces(true),
+                "A B\nC\n\nD\n",
+                "ABC\nD\n",
+            ),
+            (Hasher::new().no_eof(true), "A\nB\n\n\n", "A B"),
+            (
+                Hasher::new().preserve_hard_breaks(true),
+                "A  \nB\\\nC\nD\n",
+                "A  \nB\\\nC D\n",
+            ),
+            (
+                Hasher::new().output_eol(OutputEol::MatchInput),
+                "A\r\nB\r\n\r\nC\r\n",
+                "A B\r\nC\r\n",
+            ),
+        ];
+        for (hasher, input, expected) in cases {
+            let hasher = hasher.paragraph_mode(true);
+            let mut output = Vec::new();
+            hasher.filter(input.as_bytes(), &mut output)?;
+            assert_eq!(String::from_utf8(output)?, expected, "{input:?}");
+
+            // Normalized lines and line changes describe the same content
+            let hashed = hasher
+                .line_changes_from(input.as_bytes())
+                .map(|line| line.map(|line| line.normalized + line.normalized_eol))
+                .collect::<Result<String, _>>()?;
+            let lines = hasher
+                .normalized_lines_from(input.as_bytes())
+                .collect::<Result<Vec<_>, _>>()?;
+            let eol = if hasher.no_eof { "" } else { "\n" };
+            assert_eq!(hashed, lines.join("\n") + eol, "{input:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_update_digest() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let hashers = [
+            Hasher::new(),
+            Hasher::new().eol("\r\n").no_eof(true),
+            Hasher::new().ignore_whitespaces(true),
+        ];
+
+        for hasher in &hashers {
+            for file_in in test_env.get_input_files() {
+                let mut digest = Sha256::new();
+                let stats = hasher.update_digest(File::open(file_in)?, &mut digest)?;
+                let hash = base16ct::lower::encode_string(&digest.finalize());
+
+                let (expected, expected_stats) =
+                    hasher.try_hash_file_with_stats(file_in, None::<PathBuf>)?;
+                assert_eq!(hash, expected);
+                assert_eq!(stats, expected_stats);
+            }
+        }
+
+        // The digest is continued, not started over
+        let mut digest = Sha256::new();
+        Digest::update(&mut digest, b"header\n");
+        Hasher::new().update_digest("A B\r\n".as_bytes(), &mut digest)?;
+        assert_eq!(digest.finalize(), Sha256::digest(b"header\nA B\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_normalized_lines_with_spans() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        fs::write(&test_env.file_with_crlf, "A B\r\n C\tD \r\n\r\nE\nF")?;
+        let input = fs::read(&test_env.file_with_crlf)?;
+
+        let hasher = Hasher::new().ignore_whitespaces(true);
+        let lines = hasher
+            .normalized_lines_with_spans(BufReader::new(File::open(&test_env.file_with_crlf)?))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let normalized = lines
+            .iter()
+            .map(|(line, _)| line.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(normalized, ["AB", "CD", "", "E", "F"]);
+
+        // Each span points at the raw bytes of its line, which normalize to the same line
+        let originals = ["A B", " C\tD ", "", "E", "F"];
+        for (number, ((line, span), original)) in zip(&lines, originals).enumerate() {
+            let start = span.orig_offset as usize;
+            let raw = &input[start..start + span.orig_len as usize];
+            assert_eq!(raw, original.as_bytes());
+            assert_eq!(span.line_no, number as u64 + 1);
+            assert_eq!(
+                hasher.normalize_line(original).as_deref(),
+                Some(line.as_str())
+            );
+        }
+        assert_eq!(lines[1].1.orig_offset, 5);
+        assert_eq!(lines[3].1.orig_offset, 14);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_line_changes() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let hasher = Hasher::new().ignore_whitespaces(true).no_eof(true);
+
+        let changes = hasher
+            .line_changes(&test_env.file_with_crlf)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(
+            changes,
+            [
+                LineChange {
+                    number: 1,
+                    original: "A B".to_string(),
+                    original_eol: "\r\n",
+                    normalized: "AB".to_string(),
+                    normalized_eol: "\n",
+                },
+                LineChange {
+                    number: 2,
+                    original: "C D".to_string(),
+                    original_eol: "\r\n",
+                    normalized: "CD".to_string(),
+                    normalized_eol: "",
+                },
+            ]
+        );
+
+        let hasher = Hasher::new();
+        let changes = hasher
+            .line_changes(&test_env.file_with_lf)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert!(changes.iter().all(|change| !change.is_changed()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_normalize_in_place() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let hasher = Hasher::new();
+
+        let file = &test_env.file_with_crlf;
+        assert!(!hasher.is_normalized(file)?);
+        assert_eq!(hasher.first_unnormalized_line(file)?, Some(1));
+        fs::write(file, "A B\nC D\r\n")?;
+        assert_eq!(hasher.first_unnormalized_line(file)?, Some(2));
+
+        let hash = hasher.normalize_in_place(file)?;
+
+        assert!(hasher.is_normalized(file)?);
+        assert_eq!(hash, hasher.hash_file(file, None::<OsString>).unwrap());
+        assert_eq!(
+            fs::read_to_string(file)?,
+            fs::read_to_string(&test_env.file_with_lf)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_output_eol() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let (mostly_crlf, lf_twin) = (dir.path().join("crlf.txt"), dir.path().join("lf.txt"));
+        fs::write(&mostly_crlf, "A B\r\nC D\r\nE F\nG H\r\nI J\n")?;
+        fs::write(&lf_twin, "A B\nC D\nE F\nG H\nI J\n")?;
+
+        let hasher = Hasher::new()
+            .output_eol(OutputEol::MatchInput)
+            .verify_output(true);
+        let lf_hash = Hasher::new().hash_file(&lf_twin, None::<OsString>).unwrap();
+        assert!(!hasher.is_normalized(&mostly_crlf)?);
+        assert!(hasher.is_normalized(&lf_twin)?);
+
+        let file_out = dir.path().join("out.txt");
+        assert_eq!(
+            hasher.try_hash_file(&mostly_crlf, Some(&file_out))?,
+            lf_hash
+        );
+        assert_eq!(fs::read(&file_out)?, b"A B\r\nC D\r\nE F\r\nG H\r\nI J\r\n");
+
+        assert_eq!(hasher.normalize_in_place(&mostly_crlf)?, lf_hash);
+        assert_eq!(
+            fs::read(&mostly_crlf)?,
+            b"A B\r\nC D\r\nE F\r\nG H\r\nI J\r\n"
+        );
+        assert!(hasher.is_normalized(&mostly_crlf)?);
+        assert!(!Hasher::new().is_normalized(&mostly_crlf)?);
+
+        // The last line has no line ending with `no_eof`, whatever the style
+        let hasher = hasher.no_eof(true);
+        let mut output = Vec::new();
+        hasher.filter(&b"A B\r\nC D\r\n"[..], &mut output)?;
+        assert_eq!(output, b"A B\r\nC D");
+
+        Ok(())
+    }
 
-            file_with_crlf.write_all(content.join("\r\n").add("\r\n").as_bytes())?;
-            file_with_crlf_noeof.write_all(content.join("\r\n").as_bytes())?;
-            file_with_lf.write_all(content.join("\n").add("\n").as_bytes())?;
-            file_with_lf_noeof.write_all(content.join("\n").as_bytes())?;
+    #[test]
+    fn check_filter() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let hasher = Hasher::new();
 
-            Ok(TestEnv {
-                file_with_crlf,
-                file_with_crlf_noeof,
-                file_with_lf,
-                file_with_lf_noeof,
+        let input = fs::read(&test_env.file_with_crlf)?;
+        let mut output = Vec::new();
+        let (hash, stats) = hasher.filter(&input[..], &mut output)?;
 
-                normalized_file_with_crlf,
-                normalized_file_with_crlf_noeof,
-                normalized_file_with_lf,
-                normalized_file_with_lf_noeof,
-            })
-        }
+        assert_eq!(
+            hash,
+            hasher
+                .hash_file(&test_env.file_with_crlf, None::<OsString>)
+                .unwrap()
+        );
+        assert_eq!(output, fs::read(&test_env.file_with_lf)?);
+        assert_eq!(stats.converted_crlf, 2);
 
-        fn get_input_files(&self) -> Vec<&NamedTempFile> {
-            vec![
-                &self.file_with_crlf,
-                &self.file_with_crlf_noeof,
-                &self.file_with_lf,
-                &self.file_with_lf_noeof,
-            ]
+        Ok(())
+    }
+
+    #[test]
+    fn check_hash_reader() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let hasher = Hasher::new().ignore_whitespaces(true).no_eof(true);
+
+        let input = fs::read(&test_env.file_with_crlf)?;
+        let hash = hasher.hash_reader(&input[..], None::<Vec<u8>>)?;
+        assert_eq!(
+            hash,
+            hasher
+                .hash_file(&test_env.file_with_crlf, None::<OsString>)
+                .unwrap()
+        );
+
+        let mut output = Vec::new();
+        assert_eq!(hasher.hash_reader(&input[..], Some(&mut output))?, hash);
+        assert_eq!(output, b"AB\nCD");
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_hash_string() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("input.txt");
+        let hashers = [
+            Hasher::new(),
+            Hasher::new().eol("\r\n"),
+            Hasher::new().no_eof(true),
+            Hasher::new().ignore_whitespaces(true),
+            Hasher::new().eol("").ignore_whitespaces(true).no_eof(true),
+            Hasher::new().paragraph_mode(true),
+        ];
+
+        // Content from a fixed pseudo-random sequence, with the edge cases up front
+        let pieces = ["", "A", " ", "\t", "ä", "\n", "\r\n", "\r"];
+        let mut contents = vec![String::new(), "A B".into(), "A\r\nB\r\n".into()];
+        let mut state = 0x2545_f491_u32;
+        for _ in 0..20 {
+            let mut content = String::new();
+            for _ in 0..state % 16 {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                content.push_str(pieces[state as usize % pieces.len()]);
+            }
+            contents.push(content);
         }
 
-        fn get_output_files(&self) -> Vec<&NamedTempFile> {
-            vec![
-                &self.normalized_file_with_crlf,
-                &self.normalized_file_with_crlf_noeof,
-                &self.normalized_file_with_lf,
-                &self.normalized_file_with_lf_noeof,
-            ]
+        for content in &contents {
+            fs::write(&file, content)?;
+            for hasher in &hashers {
+                let hash = hasher.hash_file(&file, None::<OsString>)?;
+                assert_eq!(hasher.hash_string(content), hash, "{:?}", content);
+                assert_eq!(hasher.hash_bytes(content.as_bytes())?, hash);
+            }
         }
 
-        fn hash_files(&self, hasher: &Hasher) -> Result<(String, String), Box<dyn Error>> {
-            let mut hash_check = None;
-            let mut content_check = None;
+        let hasher = Hasher::new();
+        assert_eq!(hasher.hash_string("A\r\nB"), hasher.hash_string("A\nB"));
+        let err = hasher.hash_bytes(b"A\xff\n").unwrap_err();
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::Decode));
 
-            for (file_in, file_out) in zip(self.get_input_files(), self.get_output_files()) {
-                let hash = hasher.hash_file(file_in, Some(file_out));
+        Ok(())
+    }
 
-                if hash_check.is_none() {
-                    hash_check = Some(hash.clone());
-                    content_check = Some(fs::read_to_string(file_out)?)
-                }
+    #[test]
+    fn check_output_report() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let hasher = Hasher::new();
+        let file_in = &test_env.file_with_crlf;
+        let dir = tempfile::tempdir()?;
+        let file_out = dir.path().join("output.txt");
 
-                if let (Some(hash_check), Some(content_check)) = (&hash_check, &content_check) {
-                    assert_eq!(&hash, hash_check, "Hashes don't match");
-                    assert_eq!(
-                        &fs::read_to_string(file_out)?,
-                        content_check,
-                        "Normalized files don't match"
-                    );
+        let (_, stats) = hasher.try_hash_file_with_stats(file_in, None::<&Path>)?;
+        assert_eq!(stats.output, None);
+
+        let (_, stats) = hasher.try_hash_file_with_stats(file_in, Some(&file_out))?;
+        let expected = OutputReport {
+            path: file_out.clone(),
+            bytes_written: fs::metadata(&file_out)?.len(),
+            created: true,
+            replaced: false,
+        };
+        assert_eq!(stats.output.as_ref(), Some(&expected));
+        assert_eq!(expected.bytes_written, 8);
+
+        let (_, stats) = hasher.try_hash_file_with_stats(file_in, Some(&file_out))?;
+        let expected = OutputReport {
+            created: false,
+            replaced: true,
+            ..expected
+        };
+        assert_eq!(stats.output.as_ref(), Some(&expected));
+
+        // Written atomically, where the temporary file does not count
+        let copy = dir.path().join("copy.txt");
+        let stats = hasher.normalize_file(file_in, &copy)?;
+        assert_eq!(
+            stats.output.as_ref().map(|output| output.created),
+            Some(true)
+        );
+        let stats = hasher.normalize_file(file_in, &copy)?;
+        let output = stats.output.unwrap();
+        assert_eq!((output.created, output.replaced), (false, true));
+        assert_eq!(output.path, copy);
+        assert_eq!(output.bytes_written, 8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_normalize_file() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let hasher = Hasher::new();
+
+        let stats =
+            hasher.normalize_file(&test_env.file_with_crlf, &test_env.file_with_lf_noeof)?;
+        assert!(stats.is_changed());
+        assert_eq!(
+            fs::read_to_string(&test_env.file_with_lf_noeof)?,
+            fs::read_to_string(&test_env.file_with_lf)?
+        );
+
+        let stats = hasher.normalize_file(&test_env.file_with_lf, &test_env.file_with_crlf)?;
+        assert!(!stats.is_changed());
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_progress() -> Result<(), Box<dyn Error>> {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::Arc;
+
+        let test_env = TestEnv::new()?;
+
+        let read = Arc::new(AtomicU64::new(0));
+        let hasher = Hasher::new().progress({
+            let read = Arc::clone(&read);
+            move |bytes| {
+                read.fetch_add(bytes, Ordering::Relaxed);
+            }
+        });
+
+        hasher.try_hash_file(&test_env.file_with_crlf, None::<OsString>)?;
+        assert_eq!(read.load(Ordering::Relaxed), 10);
+
+        hasher.try_hash_file(&test_env.file_with_lf_noeof, None::<OsString>)?;
+        assert_eq!(read.load(Ordering::Relaxed), 17);
+
+        Ok(())
+    }
+
+    /// Hasher that appends a line to `file` after reading its first line, the first `times`
+    /// times it is read.
+    fn appending_hasher(file: &Path, times: u64) -> Hasher {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        let file = file.to_path_buf();
+        let (appended, remaining) = (AtomicU64::new(0), AtomicU64::new(0));
+        Hasher::new().progress(move |bytes| {
+            // Without remaining bytes, a new read of the file has begun
+            if remaining.load(Ordering::Relaxed) == 0 {
+                if appended.fetch_add(1, Ordering::Relaxed) < times {
+                    let mut file = fs::OpenOptions::new().append(true).open(&file).unwrap();
+                    file.write_all(b"E F\r\n").unwrap();
                 }
+                remaining.store(fs::metadata(&file).unwrap().len(), Ordering::Relaxed);
             }
+            remaining.fetch_sub(bytes, Ordering::Relaxed);
+        })
+    }
 
-            let (Some(hash_check), Some(content_check)) = (hash_check, content_check) else {
-                unreachable!()
+    #[test]
+    fn check_modified_during_read() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let file = test_env.file_with_crlf.path();
+
+        let hasher = appending_hasher(file, 1);
+        let err = hasher.try_hash_file(file, None::<OsString>).unwrap_err();
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::ModifiedDuringRead));
+
+        // The next time, the file is left alone
+        let hash = hasher.try_hash_file(file, None::<OsString>)?;
+        assert_eq!(
+            hash,
+            Hasher::new().hash_file(file, None::<OsString>).unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_retry_on_modify() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let file = test_env.file_with_crlf.path();
+
+        // The third attempt is the first one without an appended line
+        let hasher = appending_hasher(file, 2).retry_on_modify(2);
+        let (hash, stats) = hasher.try_hash_file_with_stats(file, None::<OsString>)?;
+        let expected = Hasher::new().try_hash_file_with_stats(file, None::<OsString>)?;
+        assert_eq!(hash, expected.0);
+        assert_eq!(stats.retries, 2);
+        assert_eq!(
+            Stats {
+                retries: 0,
+                ..stats
+            },
+            expected.1
+        );
+
+        let hasher = appending_hasher(file, 2).retry_on_modify(1);
+        let err = hasher.try_hash_file(file, None::<OsString>).unwrap_err();
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::ModifiedDuringRead));
+
+        Ok(())
+    }
+
+    /// Reader that fails with an error of the given kind.
+    struct Failing(io::ErrorKind);
+
+    impl io::Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(self.0, "failing reader"))
+        }
+    }
+
+    /// Hash a reader that fails with `kind` after its first line, the first `failures` times.
+    fn hash_flaky(hasher: &Hasher, kind: io::ErrorKind, failures: u32) -> io::Result<u32> {
+        use std::io::{Cursor, Read};
+
+        let mut attempts = 0;
+        let (_, retries) = hasher.retrying(|| {
+            attempts += 1;
+            let content = Cursor::new("A B\r\nC D\r\n");
+            let input: Box<dyn Read> = if attempts <= failures {
+                Box::new(content.take(5).chain(Failing(kind)))
+            } else {
+                Box::new(content)
             };
+            hasher.hash_lines(hasher.line_changes_from(BufReader::new(input)), None, None)
+        })?;
 
-            Ok((hash_check, content_check))
+        assert_eq!(retries + 1, attempts);
+        Ok(retries)
+    }
+
+    #[test]
+    fn check_retries() {
+        let hasher = Hasher::new().retries(2).retry_delay(Duration::ZERO);
+        assert_eq!(
+            hash_flaky(&hasher, io::ErrorKind::ConnectionReset, 1).unwrap(),
+            1
+        );
+        assert_eq!(hash_flaky(&hasher, io::ErrorKind::TimedOut, 2).unwrap(), 2);
+
+        let err = hash_flaky(&hasher, io::ErrorKind::TimedOut, 3).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::Read));
+
+        let err = hash_flaky(&hasher, io::ErrorKind::PermissionDenied, 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        let err = hash_flaky(&hasher, io::ErrorKind::Other, 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+
+        let hasher = hasher.retry_if(|err| err.kind() == io::ErrorKind::Other);
+        assert_eq!(hash_flaky(&hasher, io::ErrorKind::Other, 1).unwrap(), 1);
+
+        let hasher = Hasher::new().retry_delay(Duration::ZERO);
+        let err = hash_flaky(&hasher, io::ErrorKind::ConnectionReset, 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_symlink_input() -> Result<(), Box<dyn Error>> {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("a.txt");
+        let link = dir.path().join("link.txt");
+        let broken = dir.path().join("broken.txt");
+        let target_text = dir.path().join("target.txt");
+        fs::write(&file, "A B\r\nC D\r\n")?;
+        fs::write(&target_text, "a.txt")?;
+        symlink("a.txt", &link)?;
+        symlink("missing.txt", &broken)?;
+
+        let hasher = Hasher::new();
+        assert_eq!(
+            hasher.try_hash_file(&link, None::<OsString>)?,
+            hasher.try_hash_file(&file, None::<OsString>)?
+        );
+        let err = hasher.try_hash_file(&broken, None::<OsString>).unwrap_err();
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::BrokenSymlink));
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        let details = BrokenSymlink::of(&err).unwrap();
+        assert_eq!(details.path, broken);
+        assert_eq!(details.target, Path::new("missing.txt"));
+
+        let hasher = Hasher::new().symlink_input(SymlinkInput::Error);
+        for path in [&link, &broken] {
+            let err = hasher.try_hash_file(path, None::<OsString>).unwrap_err();
+            assert_eq!(NotAFile::of(&err).unwrap().kind, FileKind::Symlink);
         }
+        assert!(hasher.try_hash_file(&file, None::<OsString>).is_ok());
+
+        let hasher = Hasher::new().symlink_input(SymlinkInput::HashTargetPath);
+        assert_eq!(
+            hasher.try_hash_file(&link, None::<OsString>)?,
+            hasher.try_hash_file(&target_text, None::<OsString>)?
+        );
+        assert!(hasher.try_hash_file(&broken, None::<OsString>).is_ok());
+
+        Ok(())
     }
 
     #[test]
-    fn check_empty_file() -> Result<(), Box<dyn Error>> {
-        let file = NamedTempFile::new()?;
+    fn check_verify_output() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let file_out = &test_env.normalized_file_with_lf;
 
-        // Sanity check between hasher versions
+        for eol in ["\n", "\r", "; "] {
+            let hasher = Hasher::new().eol(eol).no_eof(true).verify_output(true);
+            hasher.try_hash_file(&test_env.file_with_crlf, Some(file_out))?;
+            hasher.normalize_file(&test_env.file_with_crlf, file_out)?;
+        }
 
-        // Completely empty file
-        let hash_expected = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
-        let hash_actual = Hasher::new().eol("").hash_file(&file, None::<OsString>);
-        assert_eq!(hash_actual, hash_expected);
+        Ok(())
+    }
 
-        // Empty file ending in LF
-        let hash_expected = "01ba4719c80b6fe911b091a7c05124b64eeece964e09c058ef8f9805daca546b";
-        let hash_actual = Hasher::new().hash_file(&file, None::<OsString>);
-        assert_eq!(hash_actual, hash_expected);
+    #[test]
+    fn check_verify_output_mismatch() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let file_out = &test_env.normalized_file_with_lf;
+
+        let mut hasher = Hasher::new().verify_output(true);
+        hasher.before_verify = Some(|path| fs::write(path, "A B\nC X\n").unwrap());
+
+        let err = hasher
+            .try_hash_file(&test_env.file_with_crlf, Some(file_out))
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::OutputMismatch));
+
+        // The corrupt temporary file does not replace the output
+        fs::write(file_out, "original")?;
+        let err = hasher
+            .normalize_file(&test_env.file_with_crlf, file_out)
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::OutputMismatch));
+        assert_eq!(fs::read_to_string(file_out)?, "original");
 
         Ok(())
     }
 
     #[test]
-    fn check_default_options() -> Result<(), Box<dyn Error>> {
+    fn check_sync_output() -> Result<(), Box<dyn Error>> {
         let test_env = TestEnv::new()?;
-        let (_, normalized_content) = test_env.hash_files(&Hasher::new())?;
+        let dir = tempfile::tempdir()?;
+        let file_out = dir.path().join("normalized.txt");
 
+        let hasher = Hasher::new().sync_output(true);
+        let hash = hasher.try_hash_file(&test_env.file_with_crlf, Some(&file_out))?;
+        assert_eq!(fs::read_to_string(&file_out)?, "A B\nC D\n");
+
+        hasher.normalize_file(&test_env.file_with_lf_noeof, dir.path().join("a/b.txt"))?;
         assert_eq!(
-            fs::read_to_string(&test_env.file_with_lf)?,
-            normalized_content,
-            "Normalized files do not have LF"
+            fs::read_to_string(dir.path().join("a/b.txt"))?,
+            "A B\nC D\n"
         );
 
+        assert_eq!(hasher.normalize_in_place(&file_out)?, hash);
+
+        #[cfg(unix)]
+        hasher.try_hash_file(&test_env.file_with_crlf, Some("/dev/null"))?;
+
         Ok(())
     }
 
     #[test]
-    fn check_with_custom_eol() -> Result<(), Box<dyn Error>> {
+    fn check_sync_parent() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        sync_parent(&dir.path().join("file.txt"))?;
+        sync_parent(Path::new("file.txt"))?;
+
+        #[cfg(unix)]
+        assert!(sync_parent(&dir.path().join("missing/file.txt")).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_hash_many() -> Result<(), Box<dyn Error>> {
         let test_env = TestEnv::new()?;
-        let (_, normalized_content) = test_env.hash_files(&Hasher::new().eol("\r\n"))?;
+        let hasher = Hasher::new();
+
+        let files = test_env
+            .get_input_files()
+            .into_iter()
+            .map(|file| file.path().to_path_buf())
+            .chain([PathBuf::from("missing.txt")])
+            .collect::<Vec<_>>();
+
+        for jobs in [1, 4] {
+            let mut results = Vec::new();
+            let flow = hasher.hash_many(&files, jobs, |index, result| {
+                results.push((index, result.map(|hashed| hashed.map(|(hash, _, _)| hash))));
+                ControlFlow::<()>::Continue(())
+            });
 
+            assert_eq!(flow, ControlFlow::Continue(()));
+            assert_eq!(
+                results.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+                [0, 1, 2, 3, 4]
+            );
+            for (index, result) in &results[..4] {
+                assert_eq!(
+                    result.as_ref().unwrap().as_deref(),
+                    Some(
+                        hasher
+                            .hash_file(&files[*index], None::<OsString>)
+                            .unwrap()
+                            .as_str()
+                    )
+                );
+            }
+            assert!(results[4].1.is_err());
+
+            let mut reported = 0;
+            let flow = hasher.hash_many(&files, jobs, |index, _| {
+                reported += 1;
+                if index == 1 {
+                    ControlFlow::Break(index)
+                } else {
+                    ControlFlow::Continue(())
+                }
+            });
+            assert_eq!(flow, ControlFlow::Break(1));
+            assert_eq!(reported, 2);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_hash_dir() -> Result<(), Box<dyn Error>> {
+        let hasher = Hasher::new();
+        let files = [
+            ("b.txt", "B\r\n"),
+            ("a/z.txt", "Z\n"),
+            ("a.txt", "A\r\n"),
+            ("a/b/c.txt", "C"),
+            ("A.log", "log\n"),
+        ];
+
+        // The same tree, created in opposite orders
+        let mut trees = Vec::new();
+        for order in [files.to_vec(), files.iter().rev().copied().collect()] {
+            let dir = tempfile::tempdir()?;
+            for (path, content) in order {
+                let file = dir.path().join(path);
+                fs::create_dir_all(file.parent().unwrap())?;
+                fs::write(file, content)?;
+            }
+            fs::write(dir.path().join("binary.bin"), b"\0\xff")?;
+            trees.push(dir);
+        }
+
+        let hashes = hasher.hash_dir(trees[0].path())?;
+        assert_eq!(hashes, hasher.hash_dir(trees[1].path())?);
+
+        let paths: Vec<_> = hashes.iter().map(|(path, _)| path.clone()).collect();
+        let expected: Vec<_> = ["A.log", "a.txt", "a/b/c.txt", "a/z.txt", "b.txt"]
+            .into_iter()
+            .map(|path| path.split('/').collect::<PathBuf>())
+            .collect();
+        assert_eq!(paths, expected);
+        assert_eq!(hashes[1].1, hasher.hash_string("A\n"));
+        assert_eq!(hashes[2].1, hasher.hash_string("C"));
+
+        let walker = Walker::new(trees[0].path()).exclude("*.log").exclude("a/b");
+        let excluded = hasher.hash_walk(&walker)?;
+        assert_eq!(excluded.len(), 3);
+        assert_eq!(excluded[1].0, Path::new("a").join("z.txt"));
+
+        let file = trees[0].path().join("a.txt");
         assert_eq!(
-            fs::read_to_string(&test_env.file_with_crlf)?,
-            normalized_content,
-            "Normalized files do not have CRLF"
+            hasher.hash_dir(&file)?,
+            [(PathBuf::from("a.txt"), hashes[1].1.clone())]
         );
 
+        let err = hasher
+            .hash_dir(trees[0].path().join("missing"))
+            .unwrap_err();
+        assert_eq!(err.path(), trees[0].path().join("missing"));
+
         Ok(())
     }
 
+    #[cfg(unix)]
     #[test]
-    fn check_without_eof() -> Result<(), Box<dyn Error>> {
+    fn check_hash_dir_skips_symlinks() -> Result<(), Box<dyn Error>> {
+        let hasher = Hasher::new();
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join("tree"))?;
+        fs::write(dir.path().join("tree/a.txt"), "A\n")?;
+        fs::write(dir.path().join("outside.txt"), "B\n")?;
+        std::os::unix::fs::symlink("../outside.txt", dir.path().join("tree/link.txt"))?;
+
+        let tree = dir.path().join("tree");
+        let paths: Vec<_> = hasher
+            .hash_dir(&tree)?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(paths, [PathBuf::from("a.txt")]);
+
+        let walker = Walker::new(&tree).follow_symlinks(true);
+        assert_eq!(hasher.hash_walk(&walker)?.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_hash_many_unordered() -> Result<(), Box<dyn Error>> {
         let test_env = TestEnv::new()?;
-        let (_, normalized_content) = test_env.hash_files(&Hasher::new().no_eof(true))?;
+        let hasher = Hasher::new();
+
+        let files = test_env
+            .get_input_files()
+            .into_iter()
+            .map(|file| file.path().to_path_buf())
+            .chain([PathBuf::from("missing.txt")])
+            .collect::<Vec<_>>();
+
+        for jobs in [1, 4] {
+            let mut results = Vec::new();
+            let flow = hasher.hash_many_unordered(&files, jobs, |index, result| {
+                results.push((index, result.map(|hashed| hashed.map(|(hash, _, _)| hash))));
+                ControlFlow::<()>::Continue(())
+            });
+            assert_eq!(flow, ControlFlow::Continue(()));
+
+            // Each file is reported exactly once, whatever the order
+            results.sort_by_key(|(index, _)| *index);
+            assert_eq!(
+                results.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+                [0, 1, 2, 3, 4]
+            );
+            for (index, result) in &results[..4] {
+                assert_eq!(
+                    result.as_ref().unwrap().as_deref(),
+                    Some(
+                        hasher
+                            .hash_file(&files[*index], None::<OsString>)
+                            .unwrap()
+                            .as_str()
+                    )
+                );
+            }
+            assert!(results[4].1.is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_stats() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let hasher = Hasher::new();
 
+        let (hash, stats) =
+            hasher.try_hash_file_with_stats(&test_env.file_with_crlf_noeof, None::<OsString>)?;
         assert_eq!(
-            fs::read_to_string(&test_env.file_with_lf_noeof)?,
-            normalized_content,
-            "Normalized files do not have LF without EOF"
+            hash,
+            hasher
+                .hash_file(&test_env.file_with_lf, None::<OsString>)
+                .unwrap()
+        );
+        assert_eq!(stats.lines, 2);
+        assert_eq!(stats.crlf, 1);
+        assert_eq!(stats.converted_crlf, 1);
+        assert!(!stats.final_newline);
+        assert!(stats.added_final_newline);
+
+        assert_eq!(hasher.analyze_file(&test_env.file_with_crlf_noeof)?, stats);
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_try_hash_file() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let hasher = Hasher::new();
+
+        assert_eq!(
+            hasher.try_hash_file(&test_env.file_with_crlf, None::<OsString>)?,
+            hasher
+                .hash_file(&test_env.file_with_crlf, None::<OsString>)
+                .unwrap()
         );
 
+        let dir = tempfile::tempdir()?;
+        let err = hasher
+            .try_hash_file(dir.path().join("missing.txt"), None::<OsString>)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::Open));
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_hash_error() -> Result<(), Box<dyn Error>> {
+        let test_env = TestEnv::new()?;
+        let hasher = Hasher::new();
+        let dir = tempfile::tempdir()?;
+
+        let missing = dir.path().join("missing.txt");
+        let err = hasher.hash_file(&missing, None::<OsString>).unwrap_err();
+        assert!(matches!(err, HashError::Open { .. }));
+        assert_eq!(err.path(), missing);
+        assert_eq!(err.io_error().kind(), io::ErrorKind::NotFound);
+
+        // The parent of the output is a file instead of a directory
+        let file_out = test_env.file_with_crlf.path().join("out.txt");
+        let err = hasher
+            .hash_file(&test_env.file_with_lf, Some(&file_out))
+            .unwrap_err();
+        assert!(matches!(err, HashError::Create { .. }));
+        assert_eq!(err.path(), file_out);
+        assert!(err.to_string().starts_with("cannot create "));
+
+        let err = io::Error::from(err);
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::Create));
+
         Ok(())
     }
 
@@ -404,7 +3215,7 @@ mod tests {
         let hash = hasher.hash_file(
             &file_with_lf_without_spaces,
             Some(normalized_file_with_lf_without_spaces),
-        );
+        )?;
 
         assert_eq!(hash, normalized_hash, "Hashes don't match");
         assert_eq!(
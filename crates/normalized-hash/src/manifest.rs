@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use tempfile::NamedTempFile;
+
+use crate::{escape_path, long_path, unescape_path, Algorithm, ErrorKind, Hasher};
+
+/// First line of a manifest, to detect files that are no manifests or of another version.
+const HEADER: &str = "# normalized-hasher manifest, version 1";
+
+/// Prefix of the second line of a manifest, followed by the normalization options.
+const OPTIONS: &str = "# options: ";
+
+/// Hash and metadata of a single file in a [`Manifest`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Normalized hash of the file.
+    pub hash: String,
+
+    /// Size of the file in bytes.
+    pub size: u64,
+
+    /// Modification time of the file, since the Unix epoch.
+    pub modified: Duration,
+}
+
+impl ManifestEntry {
+    /// Create an entry for `file` with its already computed hash and its current metadata.
+    pub fn new(file: impl AsRef<Path>, hash: impl Into<String>) -> io::Result<Self> {
+        let (size, modified) = metadata(file.as_ref())?;
+
+        Ok(Self {
+            hash: hash.into(),
+            size,
+            modified,
+        })
+    }
+
+    /// Check if size and modification time of `file` are still the same as in this entry.
+    ///
+    /// This is how [`Manifest`] updates decide which files have to be hashed again.
+    pub fn is_current(&self, file: impl AsRef<Path>) -> bool {
+        metadata(file.as_ref()).is_ok_and(|metadata| metadata == (self.size, self.modified))
+    }
+}
+
+/// Size and modification time of a file.
+fn metadata(file: &Path) -> io::Result<(u64, Duration)> {
+    let metadata = fs::metadata(long_path(file))?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO);
+
+    Ok((metadata.len(), modified))
+}
+
+/// Normalization options of a hasher, as recorded in a manifest.
+fn options(hasher: &Hasher) -> String {
+    let eol = hasher
+        .eol
+        .bytes()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+
+    let options = format!(
+        "eol={} ignore-whitespaces={} no-eof={}",
+        eol, hasher.ignore_whitespaces, hasher.no_eof
+    );
+
+    // Only recorded if given, so manifests of line-based hashes stay valid
+    let options = if hasher.paragraph_mode {
+        format!(
+            "{} paragraph-mode=true preserve-hard-breaks={}",
+            options, hasher.hard_breaks
+        )
+    } else {
+        options
+    };
+
+    // Likewise, manifests of SHA-256 hashes stay valid
+    if hasher.algorithm != Algorithm::Sha256 {
+        format!("{} algorithm={}", options, hasher.algorithm)
+    } else {
+        options
+    }
+}
+
+/// Path of `file` relative to `root` as recorded in a manifest, with `/` as separator.
+///
+/// Names that are not valid UTF-8 are escaped with [`escape_path`]. Returns `None` if `file` is
+/// not below `root` or its path contains line breaks, so it cannot be recorded.
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+/// use normalized_hash::manifest_path;
+///
+/// let path = manifest_path("root", Path::new("root").join("a").join("b.txt"));
+/// assert_eq!(path.as_deref(), Some("a/b.txt"));
+/// ```
+pub fn manifest_path(root: impl AsRef<Path>, file: impl AsRef<Path>) -> Option<String> {
+    let relative = file.as_ref().strip_prefix(root.as_ref()).ok()?;
+
+    let mut path = Vec::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(name) => path.push(escape_path(Path::new(name))),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    let path = path.join("/");
+    (!path.is_empty() && !path.contains(['\n', '\r'])).then_some(path)
+}
+
+/// Path of the file of a manifest entry below `root`, the reverse of [`manifest_path`].
+pub fn manifest_file(root: impl AsRef<Path>, path: &str) -> PathBuf {
+    let mut file = root.as_ref().to_path_buf();
+    file.extend(path.split('/').map(unescape_path));
+    file
+}
+
+/// Normalized hashes of the files of a directory tree, with their size and modification time.
+///
+/// A manifest is a text file with a header that records the normalization options, followed by
+/// one line per file with the tab separated fields hash, size, modification time and path. The
+/// paths are relative to the root of the tree and use `/` as separator on all platforms, see
+/// [`manifest_path`], so a manifest created on one platform can be verified on another. The
+/// lines are sorted by path.
+///
+/// # Example
+///
+/// ```no_run
+/// use normalized_hash::{manifest_path, Hasher, Manifest, ManifestEntry, Walker};
+///
+/// let hasher = Hasher::new();
+/// let mut manifest = Manifest::new(&hasher);
+/// for file in Walker::new("src").walk().unwrap() {
+///     let hash = hasher.try_hash_file(&file, None::<&str>).unwrap();
+///     let path = manifest_path("src", &file).unwrap();
+///     manifest.insert(path, ManifestEntry::new(&file, hash).unwrap());
+/// }
+/// manifest.save("MANIFEST").unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Manifest {
+    options: String,
+    entries: BTreeMap<String, ManifestEntry>,
+}
+
+impl Manifest {
+    /// Create an empty manifest for hashes of `hasher`.
+    pub fn new(hasher: &Hasher) -> Self {
+        Self {
+            options: options(hasher),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Read a manifest file.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if the file is not a valid manifest.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content =
+            fs::read_to_string(long_path(path)).map_err(|err| ErrorKind::Open.tag(err))?;
+        content.parse()
+    }
+
+    /// Write the manifest to a file, which is replaced atomically.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = long_path(path);
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let write_error = |err| ErrorKind::Write.tag(err);
+
+        let mut file = NamedTempFile::new_in(dir).map_err(write_error)?;
+        {
+            let mut writer = io::BufWriter::new(file.as_file_mut());
+            write!(writer, "{}", self).map_err(write_error)?;
+            writer.flush().map_err(write_error)?;
+        }
+        file.persist(&path).map_err(|err| write_error(err.error))?;
+
+        Ok(())
+    }
+
+    /// Normalization options the hashes were created with, like `eol=0a ignore-whitespaces=false
+    /// no-eof=false`.
+    pub fn options(&self) -> &str {
+        &self.options
+    }
+
+    /// Check if the hashes were created with the same normalization options as `hasher` uses.
+    pub fn is_compatible(&self, hasher: &Hasher) -> bool {
+        self.options == options(hasher)
+    }
+
+    /// Add or replace the entry of a file, by its path from [`manifest_path`].
+    pub fn insert(&mut self, path: impl Into<String>, entry: ManifestEntry) {
+        self.entries.insert(path.into(), entry);
+    }
+
+    /// Remove the entry of a file, returning it if it was present.
+    pub fn remove(&mut self, path: &str) -> Option<ManifestEntry> {
+        self.entries.remove(path)
+    }
+
+    /// Get the entry of a file, by its path from [`manifest_path`].
+    pub fn get(&self, path: &str) -> Option<&ManifestEntry> {
+        self.entries.get(path)
+    }
+
+    /// Iterate over all entries, sorted by path.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &ManifestEntry)> {
+        self.entries
+            .iter()
+            .map(|(path, entry)| (path.as_str(), entry))
+    }
+
+    /// Number of files in the manifest.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the manifest has no files.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Parse a single line of a manifest after the header.
+fn parse_entry(line: &str) -> Option<(String, ManifestEntry)> {
+    let mut fields = line.splitn(4, '\t');
+    let hash = fields.next()?;
+    let size = fields.next()?.parse().ok()?;
+    let (seconds, nanos) = fields.next()?.split_once('.')?;
+    let modified = Duration::new(seconds.parse().ok()?, nanos.parse().ok()?);
+    let path = fields.next()?;
+
+    if Algorithm::of_hex(hash).is_none() || path.is_empty() {
+        return None;
+    }
+
+    let entry = ManifestEntry {
+        hash: hash.to_ascii_lowercase(),
+        size,
+        modified,
+    };
+
+    Some((path.to_string(), entry))
+}
+
+impl std::str::FromStr for Manifest {
+    type Err = io::Error;
+
+    fn from_str(content: &str) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut lines = content.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(invalid("not a normalized-hasher manifest".to_string()));
+        }
+
+        let Some(options) = lines.next().and_then(|line| line.strip_prefix(OPTIONS)) else {
+            return Err(invalid("line 2: missing normalization options".to_string()));
+        };
+
+        let mut entries = BTreeMap::new();
+        for (index, line) in lines.enumerate() {
+            let Some((path, entry)) = parse_entry(line) else {
+                return Err(invalid(format!("line {}: improperly formatted", index + 3)));
+            };
+            entries.insert(path, entry);
+        }
+
+        Ok(Self {
+            options: options.to_string(),
+            entries,
+        })
+    }
+}
+
+impl Display for Manifest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "{}{}", OPTIONS, self.options)?;
+
+        for (path, entry) in &self.entries {
+            writeln!(
+                f,
+                "{}\t{}\t{}.{:09}\t{}",
+                entry.hash,
+                entry.size,
+                entry.modified.as_secs(),
+                entry.modified.subsec_nanos(),
+                path
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "b62e3392d1ef3737f39339a43976186a039d53f30a95b987ccf8c5843dba94a2";
+
+    #[test]
+    fn check_manifest_path() {
+        let file = Path::new("root").join("a").join("b.txt");
+        assert_eq!(manifest_path("root", &file).as_deref(), Some("a/b.txt"));
+        assert_eq!(manifest_file("root", "a/b.txt"), file);
+
+        assert_eq!(manifest_path("root", "other/b.txt"), None);
+        assert_eq!(manifest_path("root", "root"), None);
+        assert_eq!(manifest_path("root", "root/a\nb"), None);
+    }
+
+    #[test]
+    fn check_roundtrip() {
+        let hasher = Hasher::new().no_eof(true);
+        let mut manifest = Manifest::new(&hasher);
+        let entry = ManifestEntry {
+            hash: HASH.to_string(),
+            size: 8,
+            modified: Duration::new(1693058709, 123),
+        };
+        manifest.insert("a b/c.txt", entry.clone());
+
+        let content = manifest.to_string();
+        assert_eq!(
+            content,
+            format!(
+                "{HEADER}\n# options: eol=0a ignore-whitespaces=false no-eof=true\n\
+                 {HASH}\t8\t1693058709.000000123\ta b/c.txt\n"
+            )
+        );
+
+        let parsed = content.parse::<Manifest>().unwrap();
+        assert_eq!(parsed, manifest);
+        assert!(parsed.is_compatible(&hasher));
+        assert!(!parsed.is_compatible(&Hasher::new()));
+        assert_eq!(parsed.get("a b/c.txt"), Some(&entry));
+    }
+
+    #[test]
+    fn check_parse_errors() {
+        assert!("".parse::<Manifest>().is_err());
+        assert!(HEADER.parse::<Manifest>().is_err());
+
+        let content = format!("{HEADER}\n{OPTIONS}eol=0a\n{HASH}\t8\t1\ta.txt\n");
+        let err = content.parse::<Manifest>().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "line 3: improperly formatted");
+    }
+}
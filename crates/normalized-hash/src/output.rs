@@ -0,0 +1,61 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// What was written to an output file, reported in [`Stats::output`](crate::Stats::output).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OutputReport {
+    /// Path of the output, as given.
+    pub path: PathBuf,
+
+    /// Number of bytes written to the output.
+    pub bytes_written: u64,
+
+    /// Whether the output did not exist before and was created.
+    pub created: bool,
+
+    /// Whether the output already existed and was replaced.
+    pub replaced: bool,
+}
+
+impl OutputReport {
+    /// Start a report for an output, noting if it exists before anything is written to it.
+    ///
+    /// Outputs that are written atomically are decided on by their final destination, not by the
+    /// temporary file. Also, outputs that are written again on retries are decided on before the
+    /// first attempt.
+    pub(crate) fn before_writing(path: &Path) -> Self {
+        let exists = crate::long_path(path).symlink_metadata().is_ok();
+
+        Self {
+            path: path.to_path_buf(),
+            bytes_written: 0,
+            created: !exists,
+            replaced: exists,
+        }
+    }
+}
+
+/// Writer that counts the bytes that are written through it.
+pub(crate) struct CountingWriter<W> {
+    pub inner: W,
+    pub bytes: u64,
+}
+
+impl<W> CountingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner, bytes: 0 }
+    }
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.bytes += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
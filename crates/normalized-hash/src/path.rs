@@ -0,0 +1,194 @@
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{BrokenSymlink, ErrorKind, FileKind, NotAFile};
+
+/// Convert a path into the extended-length form on Windows, like `\\?\C:\src\input.txt`.
+///
+/// Windows limits ordinary paths to 260 characters, while paths in extended-length form can be
+/// much longer. Since such paths are passed to the system verbatim, relative paths are made
+/// absolute first, resolving `.` and `..` and replacing `/` by `\`. Network shares become
+/// `\\?\UNC\server\share`. Paths that are already in extended-length or device form are
+/// returned as they are, as is a path that cannot be made absolute.
+///
+/// All path-based functions of this crate use this form for opening files, so it is only needed
+/// to access files directly. On all other platforms, the path is returned unchanged.
+///
+/// # Example
+///
+/// ```
+/// use std::fs::File;
+/// use normalized_hash::long_path;
+///
+/// let file = File::open(long_path("Cargo.toml"));
+/// ```
+#[cfg(windows)]
+pub fn long_path(path: impl AsRef<Path>) -> PathBuf {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+    use std::path::{Component, Prefix};
+
+    let path = path.as_ref();
+    let Ok(absolute) = std::path::absolute(path) else {
+        return path.to_path_buf();
+    };
+
+    let prefix: &str = match absolute.components().next() {
+        Some(Component::Prefix(prefix)) => match prefix.kind() {
+            Prefix::Disk(_) => r"\\?\",
+
+            // Only one of the two leading backslashes of `\\server\share` is kept
+            Prefix::UNC(_, _) => r"\\?\UNC",
+            _ => return absolute,
+        },
+        _ => return absolute,
+    };
+
+    let skip = if prefix.ends_with('\\') { 0 } else { 1 };
+    let wide = prefix
+        .encode_utf16()
+        .chain(absolute.as_os_str().encode_wide().skip(skip))
+        .collect::<Vec<_>>();
+
+    PathBuf::from(OsString::from_wide(&wide))
+}
+
+/// Convert a path into the extended-length form on Windows, unchanged on all other platforms.
+#[cfg(not(windows))]
+pub fn long_path(path: impl AsRef<Path>) -> PathBuf {
+    path.as_ref().to_path_buf()
+}
+
+/// How input files that are symbolic links are hashed, set with [`Hasher::symlink_input`].
+///
+/// Only the input itself is checked, links in the directories of its path are always followed.
+///
+/// [`Hasher::symlink_input`]: crate::Hasher::symlink_input
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SymlinkInput {
+    /// Hash the content of the file the link points to, like any other file.
+    #[default]
+    Follow,
+
+    /// Fail with [`NotAFile`] of kind [`FileKind::Symlink`].
+    Error,
+
+    /// Hash the target path stored in the link, as a text of a single line, instead of the
+    /// content of the target. The target does not need to exist.
+    HashTargetPath,
+}
+
+/// Target of `path` if it is a symbolic link that is not followed with `policy`.
+///
+/// Fails with [`NotAFile`] if links are an error.
+pub(crate) fn unfollowed_link(path: &Path, policy: SymlinkInput) -> io::Result<Option<PathBuf>> {
+    if policy == SymlinkInput::Follow {
+        return Ok(None);
+    }
+
+    let Ok(target) = fs::read_link(long_path(path)) else {
+        return Ok(None);
+    };
+
+    match policy {
+        SymlinkInput::Error => Err(NotAFile::error(path.to_path_buf(), FileKind::Symlink)),
+        _ => Ok(Some(target)),
+    }
+}
+
+/// Open an input file for reading, failing with [`NotAFile`] for directories and sockets.
+///
+/// Without the check, what happens when reading a directory depends on the platform. A symbolic
+/// link to a missing target fails with [`BrokenSymlink`] instead of a bare "not found".
+pub(crate) fn open_input(path: &Path) -> io::Result<File> {
+    let path_long = long_path(path);
+
+    // Files whose type is unknown are opened anyway, to report why they cannot be opened
+    if let Ok(metadata) = fs::metadata(&path_long) {
+        if metadata.is_dir() {
+            return Err(NotAFile::error(path.to_path_buf(), FileKind::Directory));
+        }
+
+        #[cfg(unix)]
+        if std::os::unix::fs::FileTypeExt::is_socket(&metadata.file_type()) {
+            return Err(NotAFile::error(path.to_path_buf(), FileKind::Socket));
+        }
+    }
+
+    File::open(&path_long).map_err(|err| {
+        if err.kind() == io::ErrorKind::NotFound {
+            if let Ok(target) = fs::read_link(&path_long) {
+                return BrokenSymlink::error(path.to_path_buf(), target);
+            }
+        }
+        ErrorKind::Open.tag(err)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(windows)]
+    #[test]
+    fn check_long_path() {
+        assert_eq!(
+            long_path(r"C:\dir\..\input.txt"),
+            Path::new(r"\\?\C:\input.txt")
+        );
+        assert_eq!(
+            long_path("C:/dir/input.txt"),
+            Path::new(r"\\?\C:\dir\input.txt")
+        );
+        assert_eq!(
+            long_path(r"\\server\share\input.txt"),
+            Path::new(r"\\?\UNC\server\share\input.txt")
+        );
+        assert_eq!(
+            long_path(r"\\?\C:\input.txt"),
+            Path::new(r"\\?\C:\input.txt")
+        );
+
+        let relative = long_path("input.txt");
+        assert!(relative.starts_with(r"\\?\"), "{}", relative.display());
+        assert!(relative.ends_with("input.txt"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn check_long_path() {
+        assert_eq!(long_path("dir/../input.txt"), Path::new("dir/../input.txt"));
+    }
+
+    #[test]
+    fn check_open_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("input.txt");
+        fs::write(&file, "A B\n").unwrap();
+        assert!(open_input(&file).is_ok());
+
+        let err = open_input(dir.path()).unwrap_err();
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::NotAFile));
+        assert_eq!(NotAFile::of(&err).unwrap().kind, FileKind::Directory);
+        assert_eq!(NotAFile::of(&err).unwrap().path, dir.path());
+
+        let err = open_input(&dir.path().join("missing.txt")).unwrap_err();
+        assert_eq!(ErrorKind::of(&err), Some(ErrorKind::Open));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_open_input_special() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("socket");
+        let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+
+        let err = open_input(&socket).unwrap_err();
+        assert_eq!(NotAFile::of(&err).unwrap().kind, FileKind::Socket);
+
+        // Devices are read like files
+        assert!(open_input(Path::new("/dev/null")).is_ok());
+    }
+}
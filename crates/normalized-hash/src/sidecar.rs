@@ -0,0 +1,158 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{escape_path, long_path, Encoding, ErrorKind, Hasher};
+
+/// Default suffix of sidecar checksum files.
+pub const SIDECAR_SUFFIX: &str = ".sha256";
+
+/// Result of verifying a file against its sidecar checksum file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SidecarStatus {
+    /// The hash matches the sidecar.
+    Ok,
+
+    /// The hash does not match the sidecar.
+    Failed,
+
+    /// There is no sidecar for the file.
+    Missing,
+}
+
+/// Path of the sidecar checksum file for `file`, which is `file` with `suffix` appended.
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+/// use normalized_hash::sidecar_path;
+///
+/// assert_eq!(sidecar_path("input.txt", ".sha256"), Path::new("input.txt.sha256"));
+/// ```
+pub fn sidecar_path(file: impl AsRef<Path>, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(file.as_ref());
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Write an already computed hash into the sidecar checksum file of `file`.
+///
+/// See [`Hasher::write_sidecar`] for the format of the sidecar.
+///
+/// # Example
+///
+/// ```no_run
+/// use normalized_hash::{store_sidecar, Hasher, SIDECAR_SUFFIX};
+///
+/// let hash = Hasher::new().hash_file("input.txt", Some("output.txt")).unwrap();
+/// store_sidecar("input.txt", &hash, SIDECAR_SUFFIX).unwrap();
+/// ```
+pub fn store_sidecar(file: impl AsRef<Path>, hash: &str, suffix: &str) -> io::Result<()> {
+    let file = file.as_ref();
+
+    let file_name = file.file_name().unwrap_or(file.as_os_str());
+    let line = format!("{}  {}\n", hash, escape_path(Path::new(file_name)));
+
+    fs::write(long_path(sidecar_path(file, suffix)), line).map_err(|err| ErrorKind::Write.tag(err))
+}
+
+impl Hasher {
+    /// Hash a file and write the result into a sidecar checksum file next to it.
+    ///
+    /// The sidecar is named like `file_in` with `suffix` appended and contains a single line in
+    /// the format of `sha256sum`, that is the hash, two spaces and the file name, escaped with
+    /// [`escape_path`] if it is not valid UTF-8. An existing sidecar is overwritten.
+    ///
+    /// Returns the hash.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::{Hasher, SIDECAR_SUFFIX};
+    ///
+    /// let hash = Hasher::new().write_sidecar("input.txt", SIDECAR_SUFFIX).unwrap();
+    /// ```
+    pub fn write_sidecar(&self, file_in: impl AsRef<Path>, suffix: &str) -> io::Result<String> {
+        let file_in = file_in.as_ref();
+        let hash = self.try_hash_file(file_in, None::<&Path>)?;
+
+        store_sidecar(file_in, &hash, suffix)?;
+
+        Ok(hash)
+    }
+
+    /// Verify a file against its sidecar checksum file.
+    ///
+    /// The first whitespace separated word of the sidecar is taken as the expected hash, so
+    /// sidecars written by `sha256sum` are accepted as well. The hash might be in any
+    /// [`Encoding`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::{Hasher, SidecarStatus, SIDECAR_SUFFIX};
+    ///
+    /// let status = Hasher::new().verify_sidecar("input.txt", SIDECAR_SUFFIX).unwrap();
+    /// assert_eq!(status, SidecarStatus::Ok);
+    /// ```
+    pub fn verify_sidecar(
+        &self,
+        file_in: impl AsRef<Path>,
+        suffix: &str,
+    ) -> io::Result<SidecarStatus> {
+        let file_in = file_in.as_ref();
+
+        let sidecar = match fs::read_to_string(long_path(sidecar_path(file_in, suffix))) {
+            Ok(sidecar) => sidecar,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(SidecarStatus::Missing),
+            Err(err) => return Err(err),
+        };
+
+        let expected = sidecar.split_whitespace().next().unwrap_or_default();
+        let hash = self.try_hash_file(file_in, None::<&Path>)?;
+
+        // The expected hash might be in any encoding
+        if Encoding::decode(expected) == Encoding::decode(&hash) {
+            Ok(SidecarStatus::Ok)
+        } else {
+            Ok(SidecarStatus::Failed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn check_sidecar_roundtrip() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let file = dir.path().join("input.txt");
+        fs::write(&file, "A B\r\nC D\r\n")?;
+
+        let hasher = Hasher::new();
+        assert_eq!(
+            hasher.verify_sidecar(&file, ".sum")?,
+            SidecarStatus::Missing
+        );
+
+        let hash = hasher.write_sidecar(&file, ".sum")?;
+
+        assert_eq!(
+            fs::read_to_string(dir.path().join("input.txt.sum"))?,
+            format!("{}  input.txt\n", hash)
+        );
+        assert_eq!(hasher.verify_sidecar(&file, ".sum")?, SidecarStatus::Ok);
+
+        fs::write(&file, "A B\r\nC D\r\nE F\r\n")?;
+        assert_eq!(hasher.verify_sidecar(&file, ".sum")?, SidecarStatus::Failed);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+use crate::{LineChange, OutputReport};
+
+/// Byte order mark, as it appears at the start of the first line of a UTF-8 file.
+const BOM: char = '\u{feff}';
+
+/// Counters about the content of a text file and what normalization changed in it.
+///
+/// Statistics are collected while hashing with [`Hasher::try_hash_file_with_stats`] or without
+/// hashing with [`Hasher::analyze_file`]. They can also be collected from the lines reported by
+/// [`Hasher::line_changes`] with [`Stats::add`].
+///
+/// [`Hasher::try_hash_file_with_stats`]: crate::Hasher::try_hash_file_with_stats
+/// [`Hasher::analyze_file`]: crate::Hasher::analyze_file
+/// [`Hasher::line_changes`]: crate::Hasher::line_changes
+///
+/// # Example
+///
+/// ```no_run
+/// use normalized_hash::Hasher;
+///
+/// let stats = Hasher::new().analyze_file("input.txt").unwrap();
+/// println!("{} lines, {} with CRLF", stats.lines, stats.crlf);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Stats {
+    /// Number of bytes read from the input.
+    pub bytes: u64,
+
+    /// Number of lines read from the input, 0 for an empty file.
+    pub lines: usize,
+
+    /// Number of lines ending with CRLF.
+    pub crlf: usize,
+
+    /// Number of lines ending with a single LF.
+    pub lf: usize,
+
+    /// Number of CR characters that are not followed by LF.
+    ///
+    /// Such characters do not end a line and are kept as part of the line content.
+    pub cr: usize,
+
+    /// Whether the input starts with a UTF-8 byte order mark.
+    ///
+    /// The byte order mark is kept as part of the first line.
+    pub bom: bool,
+
+    /// Whether the last line of the input ends with a line ending.
+    pub final_newline: bool,
+
+    /// Number of lines with whitespace at the end, before the line ending.
+    pub trailing_whitespace: usize,
+
+    /// Number of invisible characters that are easily overlooked, see [`is_invisible`].
+    pub invisible: usize,
+
+    /// Number of CRLF line endings replaced by a different eol sequence.
+    pub converted_crlf: usize,
+
+    /// Number of LF line endings replaced by a different eol sequence.
+    pub converted_lf: usize,
+
+    /// Whether an eol sequence was appended to a last line without line ending.
+    pub added_final_newline: bool,
+
+    /// Whether the line ending of the last line was removed due to `no_eof`.
+    pub removed_final_newline: bool,
+
+    /// Number of lines whose content, without line ending, was changed by normalization.
+    pub changed_lines: usize,
+
+    /// Number of times the input was read again, after a transient error with
+    /// [`Hasher::retries`] or a modification with [`Hasher::retry_on_modify`].
+    ///
+    /// [`Hasher::retries`]: crate::Hasher::retries
+    /// [`Hasher::retry_on_modify`]: crate::Hasher::retry_on_modify
+    pub retries: u32,
+
+    /// What was written to the output file, if any, like with
+    /// [`Hasher::try_hash_file_with_stats`] or [`Hasher::normalize_file`].
+    ///
+    /// [`Hasher::try_hash_file_with_stats`]: crate::Hasher::try_hash_file_with_stats
+    /// [`Hasher::normalize_file`]: crate::Hasher::normalize_file
+    pub output: Option<OutputReport>,
+}
+
+impl Stats {
+    /// Add a line, as reported by [`Hasher::line_changes`](crate::Hasher::line_changes).
+    ///
+    /// The lines have to be added in order, starting with the first one.
+    pub fn add(&mut self, line: &LineChange<'_>) {
+        // An empty input is reported as a single empty line without line ending, which cannot
+        // happen otherwise
+        if line.original.is_empty() && line.original_eol.is_empty() {
+            return;
+        }
+
+        self.bytes += (line.original.len() + line.original_eol.len()) as u64;
+
+        if line.number == 1 {
+            self.bom = line.original.starts_with(BOM);
+        }
+
+        self.lines += 1;
+        self.cr += line.original.matches('\r').count();
+        self.invisible += line
+            .original
+            .strip_prefix(BOM)
+            .unwrap_or(&line.original)
+            .chars()
+            .filter(|&c| is_invisible(c))
+            .count();
+
+        if line
+            .original
+            .trim_start_matches(BOM)
+            .ends_with(|c: char| c.is_whitespace())
+        {
+            self.trailing_whitespace += 1;
+        }
+
+        if line.original != line.normalized {
+            self.changed_lines += 1;
+        }
+
+        self.final_newline = !line.original_eol.is_empty();
+        self.added_final_newline = false;
+        self.removed_final_newline = false;
+
+        let converted = line.original_eol != line.normalized_eol;
+        match line.original_eol {
+            "\r\n" => {
+                self.crlf += 1;
+                if converted && !line.normalized_eol.is_empty() {
+                    self.converted_crlf += 1;
+                }
+            }
+            "\n" => {
+                self.lf += 1;
+                if converted && !line.normalized_eol.is_empty() {
+                    self.converted_lf += 1;
+                }
+            }
+            _ => {}
+        }
+
+        if converted && line.original_eol.is_empty() {
+            self.added_final_newline = true;
+        } else if converted && line.normalized_eol.is_empty() {
+            self.removed_final_newline = true;
+        }
+    }
+
+    /// Check if normalization changed anything, so the normalized form differs from the input.
+    pub fn is_changed(&self) -> bool {
+        self.changed_lines > 0
+            || self.converted() > 0
+            || self.added_final_newline
+            || self.removed_final_newline
+    }
+
+    /// Number of line endings that were converted to a different eol sequence.
+    pub fn converted(&self) -> usize {
+        self.converted_crlf + self.converted_lf
+    }
+
+    /// Check if the input mixes more than one style of line endings.
+    ///
+    /// Lone CR characters count as a style of their own, since other tools treat them as line
+    /// endings.
+    pub fn is_mixed_eol(&self) -> bool {
+        [self.crlf, self.lf, self.cr]
+            .iter()
+            .filter(|&&count| count > 0)
+            .count()
+            > 1
+    }
+}
+
+/// Check if a character is invisible and easily overlooked in a text file.
+///
+/// These are control characters other than tab, CR and LF, zero-width characters like the
+/// zero-width space or a byte order mark within the text, soft hyphens, non-breaking spaces and
+/// the controls of bidirectional text.
+pub fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00a0}'
+            | '\u{00ad}'
+            | '\u{200b}'..='\u{200f}'
+            | '\u{202a}'..='\u{202e}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{feff}'
+    ) || (c.is_control() && !matches!(c, '\t' | '\r' | '\n'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line<'a>(
+        number: usize,
+        original: &str,
+        original_eol: &'static str,
+        normalized_eol: &'a str,
+    ) -> LineChange<'a> {
+        LineChange {
+            number,
+            original: original.to_string(),
+            original_eol,
+            normalized: original.trim_end().to_string(),
+            normalized_eol,
+        }
+    }
+
+    #[test]
+    fn check_add() {
+        let mut stats = Stats::default();
+        stats.add(&line(1, "\u{feff}A ", "\r\n", "\n"));
+        stats.add(&line(2, "B\rC", "\n", "\n"));
+        stats.add(&line(3, "D", "", "\n"));
+
+        assert_eq!(
+            stats,
+            Stats {
+                bytes: 12,
+                lines: 3,
+                crlf: 1,
+                lf: 1,
+                cr: 1,
+                bom: true,
+                final_newline: false,
+                trailing_whitespace: 1,
+                invisible: 0,
+                converted_crlf: 1,
+                converted_lf: 0,
+                added_final_newline: true,
+                removed_final_newline: false,
+                changed_lines: 1,
+                retries: 0,
+                output: None,
+            }
+        );
+    }
+
+    #[test]
+    fn check_invisible() {
+        let mut stats = Stats::default();
+        stats.add(&line(1, "\u{feff}A\u{200b}B\u{a0}", "\n", "\n"));
+        stats.add(&line(2, "\tC\u{202e}\u{7}", "\r\n", "\n"));
+
+        assert_eq!(stats.invisible, 4);
+        assert!(stats.is_mixed_eol());
+        assert!(!is_invisible('\t'));
+    }
+
+    #[test]
+    fn check_add_empty() {
+        let mut stats = Stats::default();
+        stats.add(&line(1, "", "", "\n"));
+        assert_eq!(stats, Stats::default());
+    }
+}
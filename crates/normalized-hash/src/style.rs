@@ -0,0 +1,84 @@
+use std::io::{self, Cursor, Read};
+
+/// Number of bytes at the start of an input whose line endings decide the output style.
+///
+/// See [`OutputEol::MatchInput`].
+pub const STYLE_SCAN_LIMIT: u64 = 64 * 1024;
+
+/// Line endings of written outputs, set with [`Hasher::output_eol`](crate::Hasher::output_eol).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OutputEol {
+    /// Write the eol sequence that is hashed, so the output has exactly the hashed content.
+    #[default]
+    Hash,
+
+    /// Write the line ending that the input predominantly uses, either LF or CRLF.
+    ///
+    /// The hash is still computed with the eol sequence of the hasher, so it does not depend on
+    /// the style of the input. Only the line endings in the first [`STYLE_SCAN_LIMIT`] bytes are
+    /// counted, so streams do not need to be buffered completely. If there are none, or as many
+    /// CRLF as LF, the eol sequence of the hash is written. Lone CR characters do not end lines
+    /// and are not counted.
+    MatchInput,
+}
+
+/// Input whose first bytes were read ahead, followed by the rest of it.
+pub(crate) type Scanned<R> = io::Chain<Cursor<Vec<u8>>, R>;
+
+/// Find the line ending that dominates the first [`STYLE_SCAN_LIMIT`] bytes of `input`.
+///
+/// Returns the line ending, if any, and the input as if nothing had been read.
+pub(crate) fn dominant_eol<R: Read>(
+    mut input: R,
+) -> io::Result<(Option<&'static str>, Scanned<R>)> {
+    let mut prefix = Vec::new();
+    (&mut input)
+        .take(STYLE_SCAN_LIMIT)
+        .read_to_end(&mut prefix)?;
+
+    let crlf = prefix.windows(2).filter(|pair| pair == b"\r\n").count();
+    let lf = prefix.iter().filter(|&&byte| byte == b'\n').count() - crlf;
+    let eol = match crlf.cmp(&lf) {
+        std::cmp::Ordering::Greater => Some("\r\n"),
+        std::cmp::Ordering::Less => Some("\n"),
+        std::cmp::Ordering::Equal => None,
+    };
+
+    Ok((eol, Cursor::new(prefix).chain(input)))
+}
+
+/// Pass `input` on without reading ahead, for outputs with the hashed eol sequence.
+pub(crate) fn unscanned<R: Read>(input: R) -> Scanned<R> {
+    Cursor::new(Vec::new()).chain(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dominant(input: &[u8]) -> Option<&'static str> {
+        let (eol, mut scanned) = dominant_eol(input).unwrap();
+
+        let mut content = Vec::new();
+        scanned.read_to_end(&mut content).unwrap();
+        assert_eq!(content, input);
+
+        eol
+    }
+
+    #[test]
+    fn check_dominant_eol() {
+        assert_eq!(dominant(b"A\r\nB\r\nC\nD\r\n"), Some("\r\n"));
+        assert_eq!(dominant(b"A\nB\nC\r\n"), Some("\n"));
+        assert_eq!(dominant(b"A\r\nB\n"), None);
+        assert_eq!(dominant(b"A\rB\rC\n"), Some("\n"));
+        assert_eq!(dominant(b"no line ending"), None);
+        assert_eq!(dominant(b""), None);
+
+        // Only the start of the input is scanned, but all of it is passed on
+        let mut input = b"A\r\n".repeat(STYLE_SCAN_LIMIT as usize / 3);
+        input.extend(b"B\n".repeat(STYLE_SCAN_LIMIT as usize));
+        assert_eq!(dominant(&input), Some("\r\n"));
+    }
+}
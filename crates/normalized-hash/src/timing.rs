@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+/// Duration of hashing a file, created by [`Hasher::try_hash_file_timed`].
+///
+/// The duration covers opening, reading, hashing and writing the output file, but not the
+/// startup of a program or the traversal of directories.
+///
+/// [`Hasher::try_hash_file_timed`]: crate::Hasher::try_hash_file_timed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Timing {
+    /// Number of bytes read from the input.
+    pub bytes: u64,
+
+    /// Time it took to hash the input.
+    pub duration: Duration,
+}
+
+impl Timing {
+    /// Create a timing for `bytes` that were hashed in `duration`.
+    pub fn new(bytes: u64, duration: Duration) -> Self {
+        Self { bytes, duration }
+    }
+
+    /// Effective throughput in bytes per second, `None` if no time was measured.
+    pub fn bytes_per_second(&self) -> Option<f64> {
+        let seconds = self.duration.as_secs_f64();
+        (seconds > 0.0).then(|| self.bytes as f64 / seconds)
+    }
+}
+
+impl std::ops::Add for Timing {
+    type Output = Timing;
+
+    fn add(self, other: Timing) -> Timing {
+        Timing::new(self.bytes + other.bytes, self.duration + other.duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_bytes_per_second() {
+        let timing = Timing::new(1000, Duration::from_millis(500));
+        assert_eq!(timing.bytes_per_second(), Some(2000.0));
+
+        let total = timing + Timing::new(1000, Duration::from_millis(1500));
+        assert_eq!(total, Timing::new(2000, Duration::from_secs(2)));
+        assert_eq!(total.bytes_per_second(), Some(1000.0));
+
+        assert_eq!(Timing::default().bytes_per_second(), None);
+    }
+}
@@ -0,0 +1,188 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::path::open_input;
+use crate::{long_path, ErrorKind, Hasher};
+
+/// Writer that compares everything written to it with the content of a reader.
+///
+/// Writing fails as soon as the content differs, so the comparison can stop early.
+struct Comparison<R> {
+    reader: R,
+    buf: Vec<u8>,
+    equal: bool,
+}
+
+impl<R: Read> Comparison<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            equal: true,
+        }
+    }
+
+    /// Check if the whole content of the reader was written, without anything else.
+    fn finish(mut self) -> bool {
+        self.equal && matches!(self.reader.read(&mut [0]), Ok(0))
+    }
+}
+
+impl<R: Read> Write for Comparison<R> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.resize(buf.len(), 0);
+
+        // Unreadable content counts as different, so it is written again
+        self.equal = self.equal && self.reader.read_exact(&mut self.buf).is_ok() && self.buf == buf;
+
+        if self.equal {
+            Ok(buf.len())
+        } else {
+            Err(io::Error::other("content differs"))
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Check if `file` exists and has exactly the content that `write` produces.
+fn has_content(
+    file: &Path,
+    write: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+) -> io::Result<bool> {
+    let Ok(existing) = File::open(long_path(file)) else {
+        return Ok(false);
+    };
+
+    let mut comparison = Comparison::new(BufReader::new(existing));
+    match write(&mut comparison) {
+        Ok(()) => Ok(comparison.finish()),
+        Err(_) if !comparison.equal => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Copy a file, unless `file_out` already has the same content.
+///
+/// Like [`Hasher::update_normalized`], but for files that are not to be normalized, like binary
+/// files. Missing parent directories of `file_out` are created.
+///
+/// Returns true if `file_out` was written.
+///
+/// # Example
+///
+/// ```no_run
+/// use normalized_hash::update_copy;
+///
+/// if update_copy("logo.png", "dist/logo.png").unwrap() {
+///     println!("dist/logo.png was updated");
+/// }
+/// ```
+pub fn update_copy(file_in: impl AsRef<Path>, file_out: impl AsRef<Path>) -> io::Result<bool> {
+    let (file_in, file_out) = (file_in.as_ref(), long_path(file_out));
+
+    let mut input = open_input(file_in)?;
+    let same = has_content(&file_out, |output| io::copy(&mut input, output).map(|_| ()))?;
+    if same {
+        return Ok(false);
+    }
+
+    if let Some(dir) = file_out.parent() {
+        fs::create_dir_all(dir).map_err(|err| ErrorKind::Write.tag(err))?;
+    }
+    fs::copy(long_path(file_in), &file_out).map_err(|err| ErrorKind::Write.tag(err))?;
+
+    Ok(true)
+}
+
+impl Hasher {
+    /// Write the normalized form of a text file to another file, unless it is already there.
+    ///
+    /// `file_out` is only replaced, like with [`Hasher::normalize_file`], if its content differs
+    /// from the normalized form of `file_in`. Otherwise it is not touched at all, so its
+    /// modification time stays the same, and repeated runs only read both files.
+    ///
+    /// Returns true if `file_out` was written.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::Hasher;
+    ///
+    /// if Hasher::new().update_normalized("input.txt", "dist/input.txt").unwrap() {
+    ///     println!("dist/input.txt was updated");
+    /// }
+    /// ```
+    pub fn update_normalized(
+        &self,
+        file_in: impl AsRef<Path>,
+        file_out: impl AsRef<Path>,
+    ) -> io::Result<bool> {
+        let (file_in, file_out) = (file_in.as_ref(), file_out.as_ref());
+
+        let same = has_content(file_out, |output| {
+            let mut writer = BufWriter::new(output);
+            let (style, input) = self.scan_style(open_input(file_in)?)?;
+            let lines = self.line_changes_from(BufReader::new(input));
+            self.hash_lines(lines, Some(&mut writer), style)?;
+            writer.flush()
+        })?;
+        if same {
+            return Ok(false);
+        }
+
+        self.normalize_file(file_in, file_out)?;
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn check_update_normalized() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let file_in = dir.path().join("input.txt");
+        let file_out = dir.path().join("out/input.txt");
+        fs::write(&file_in, "A B\r\nC D\r\n")?;
+
+        let hasher = Hasher::new();
+        assert!(hasher.update_normalized(&file_in, &file_out)?);
+        assert_eq!(fs::read_to_string(&file_out)?, "A B\nC D\n");
+        assert!(!hasher.update_normalized(&file_in, &file_out)?);
+
+        fs::write(&file_out, "A B\nC D\nE F\n")?;
+        assert!(hasher.update_normalized(&file_in, &file_out)?);
+        fs::write(&file_out, "A B\n")?;
+        assert!(hasher.update_normalized(&file_in, &file_out)?);
+        assert_eq!(fs::read_to_string(&file_out)?, "A B\nC D\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_update_copy() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let file_in = dir.path().join("logo.bin");
+        let file_out = dir.path().join("out/logo.bin");
+        fs::write(&file_in, b"\0\x01\x02")?;
+
+        assert!(update_copy(&file_in, &file_out)?);
+        assert_eq!(fs::read(&file_out)?, b"\0\x01\x02");
+        assert!(!update_copy(&file_in, &file_out)?);
+
+        fs::write(&file_out, b"\0\x01")?;
+        assert!(update_copy(&file_in, &file_out)?);
+
+        Ok(())
+    }
+}
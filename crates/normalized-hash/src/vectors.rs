@@ -0,0 +1,289 @@
+//! Golden test vectors of the normalized hash.
+//!
+//! Each [`Vector`] is an input together with normalization options and the hash that the
+//! documented algorithm results in. The hashes are frozen: once published, the expected hash of
+//! a vector never changes, since that would change the hashes that users have recorded. New
+//! options get new vectors instead. [`self_test`] checks all vectors at runtime, so a deployed
+//! build can prove that it implements the algorithm.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+
+use crate::Hasher;
+
+/// Input with normalization options and its frozen hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Vector {
+    /// What the vector covers, unique among all vectors.
+    pub description: &'static str,
+
+    /// Content of the input file.
+    pub input: &'static [u8],
+
+    /// End-of-line sequence, see [`Hasher::eol`].
+    pub eol: &'static str,
+
+    /// See [`Hasher::ignore_whitespaces`].
+    pub ignore_whitespaces: bool,
+
+    /// See [`Hasher::no_eof`].
+    pub no_eof: bool,
+
+    /// Expected hash, in lowercase hex.
+    pub expected: &'static str,
+}
+
+/// All golden test vectors.
+pub const VECTORS: &[Vector] = &[
+    Vector {
+        description: "empty file",
+        input: b"",
+        eol: "\n",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "01ba4719c80b6fe911b091a7c05124b64eeece964e09c058ef8f9805daca546b",
+    },
+    Vector {
+        description: "empty file with no_eof",
+        input: b"",
+        eol: "\n",
+        ignore_whitespaces: false,
+        no_eof: true,
+        expected: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+    },
+    Vector {
+        description: "LF line endings",
+        input: b"A B\nC D\n",
+        eol: "\n",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "b62e3392d1ef3737f39339a43976186a039d53f30a95b987ccf8c5843dba94a2",
+    },
+    Vector {
+        description: "CRLF line endings",
+        input: b"A B\r\nC D\r\n",
+        eol: "\n",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "b62e3392d1ef3737f39339a43976186a039d53f30a95b987ccf8c5843dba94a2",
+    },
+    Vector {
+        description: "mixed line endings",
+        input: b"A B\r\nC D\nE F\r\n",
+        eol: "\n",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "8ed4bf77bac50090168f95ac1911f947454f0092a86b7381aa72d113585315c3",
+    },
+    Vector {
+        description: "missing final newline",
+        input: b"A B\r\nC D",
+        eol: "\n",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "b62e3392d1ef3737f39339a43976186a039d53f30a95b987ccf8c5843dba94a2",
+    },
+    Vector {
+        description: "blank lines",
+        input: b"\r\n\r\nA B\r\n\r\n",
+        eol: "\n",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "3114e77245a58cbcd776a0be89853ca4e117bc566338d3bfc60d492d470944b4",
+    },
+    Vector {
+        description: "lone CR within a line",
+        input: b"A\rB\r\n",
+        eol: "\n",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "c33b275aff9abcebf680b6c374f61899eae86b1c1dc9578d2be032261eeed8ca",
+    },
+    Vector {
+        description: "byte order mark",
+        input: b"\xef\xbb\xbfA B\r\n",
+        eol: "\n",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "6bc47bf3d5e6062a1a21cea67f82b6a38ec6eec3b9d2dece9b4de1ba88d2ed5b",
+    },
+    Vector {
+        description: "non-ASCII text",
+        input: b"Gr\xc3\xbc\xc3\x9fe\r\n",
+        eol: "\n",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "b1de61b8108f15d9913e0fa2e6371ed737fbe2be84e63a89ca8ae7a370322371",
+    },
+    Vector {
+        description: "no_eof",
+        input: b"A B\r\nC D\r\n",
+        eol: "\n",
+        ignore_whitespaces: false,
+        no_eof: true,
+        expected: "96bb38fb6386a2fa30e5de087dc1ca7f9bf2747dab4555be40ec8b19eabff28b",
+    },
+    Vector {
+        description: "no_eof without final newline",
+        input: b"A B\r\nC D",
+        eol: "\n",
+        ignore_whitespaces: false,
+        no_eof: true,
+        expected: "96bb38fb6386a2fa30e5de087dc1ca7f9bf2747dab4555be40ec8b19eabff28b",
+    },
+    Vector {
+        description: "ignore_whitespaces",
+        input: b"A B\r\n\tC  D \r\n",
+        eol: "\n",
+        ignore_whitespaces: true,
+        no_eof: false,
+        expected: "8add794044f09fda7a3b1b89f2bfef0e1993aaa5955c2208b95440d59c919d09",
+    },
+    Vector {
+        description: "eol CRLF",
+        input: b"A B\nC D\n",
+        eol: "\r\n",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "9b0197338a25019411e776a2d2cdb68a23d5a1ad7d79350aea527a3189ffe20d",
+    },
+    Vector {
+        description: "eol CR",
+        input: b"A B\nC D\n",
+        eol: "\r",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "e2c810069b9a64f423629375d6d6b1e24d3a2c4c9943c5522b22a5d6115d7572",
+    },
+    Vector {
+        description: "custom eol",
+        input: b"A B\nC D\n",
+        eol: "<br>",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "e2b8ffecab0daf15352dd15d8d8482c0802a9c0bfe1d7935590113803a669b34",
+    },
+    Vector {
+        description: "empty eol",
+        input: b"A B\r\nC D\r\n",
+        eol: "",
+        ignore_whitespaces: false,
+        no_eof: false,
+        expected: "6501592a85f51776555620e788662f750aec24b418949b2010ad247fdd3bc9ca",
+    },
+    Vector {
+        description: "all options",
+        input: b"A B\nC D\n",
+        eol: "\r\n",
+        ignore_whitespaces: true,
+        no_eof: true,
+        expected: "f9801780e2a37f9ecddc95a917fcbbf88abec795dae2f3a89d352427c8c7b0f9",
+    },
+];
+
+impl Vector {
+    /// Hasher with the options of this vector.
+    pub fn hasher(&self) -> Hasher {
+        Hasher::new()
+            .eol(self.eol)
+            .ignore_whitespaces(self.ignore_whitespaces)
+            .no_eof(self.no_eof)
+    }
+
+    /// Hash the input of this vector.
+    pub fn hash(&self) -> io::Result<String> {
+        let (hash, _) = self.hasher().filter(self.input, io::sink())?;
+        Ok(hash)
+    }
+
+    /// Check that the input of this vector results in the expected hash.
+    pub fn check(&self) -> Result<(), SelfTestFailure> {
+        let actual = self.hash().unwrap_or_else(|err| format!("error: {}", err));
+        if actual == self.expected {
+            Ok(())
+        } else {
+            Err(SelfTestFailure {
+                description: self.description,
+                expected: self.expected,
+                actual,
+            })
+        }
+    }
+}
+
+/// Vector whose input did not result in the expected hash, returned by [`self_test`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SelfTestFailure {
+    /// Description of the failed vector.
+    pub description: &'static str,
+
+    /// Expected hash of the vector.
+    pub expected: &'static str,
+
+    /// Computed hash, or the error message if the input could not be hashed.
+    pub actual: String,
+}
+
+impl Display for SelfTestFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: expected {}, got {}",
+            self.description, self.expected, self.actual
+        )
+    }
+}
+
+impl Error for SelfTestFailure {}
+
+/// Check all golden test vectors, returning the first one that fails.
+///
+/// # Example
+///
+/// ```
+/// normalized_hash::self_test().unwrap();
+/// ```
+pub fn self_test() -> Result<(), SelfTestFailure> {
+    VECTORS.iter().try_for_each(Vector::check)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn check_vectors() {
+        for vector in VECTORS {
+            assert_eq!(vector.check(), Ok(()));
+        }
+        assert_eq!(self_test(), Ok(()));
+    }
+
+    #[test]
+    fn check_descriptions_unique() {
+        let descriptions = VECTORS
+            .iter()
+            .map(|vector| vector.description)
+            .collect::<HashSet<_>>();
+        assert_eq!(descriptions.len(), VECTORS.len());
+    }
+
+    #[test]
+    fn check_failure() {
+        let vector = Vector {
+            expected: "00",
+            ..VECTORS[0]
+        };
+        let failure = vector.check().unwrap_err();
+        assert_eq!(failure.actual, VECTORS[0].expected);
+        assert_eq!(
+            failure.to_string(),
+            format!("empty file: expected 00, got {}", VECTORS[0].expected)
+        );
+    }
+}
@@ -0,0 +1,829 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::WalkBuilder;
+
+use crate::long_path;
+
+/// Set of glob patterns for paths that should be excluded.
+///
+/// The patterns follow the syntax of `.gitignore` files: A pattern without a slash, like `*.log`,
+/// matches a file or directory name at any depth, while a pattern with a slash, like
+/// `migrations/archive`, is anchored at the root. If a directory is excluded, all of its contents
+/// are excluded as well.
+#[derive(Clone)]
+pub struct Exclude {
+    matcher: Override,
+}
+
+impl Exclude {
+    /// Create new Exclude instance for paths relative to `root`.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if any pattern is not a valid glob.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Exclude;
+    ///
+    /// let exclude = Exclude::new("src", ["*.log", "migrations/archive"]).unwrap();
+    /// assert!(exclude.is_excluded("src/debug.log"));
+    /// assert!(exclude.is_excluded("src/migrations/archive/001.sql"));
+    /// assert!(!exclude.is_excluded("src/migrations/002.sql"));
+    /// ```
+    pub fn new<S: AsRef<str>>(
+        root: impl AsRef<Path>,
+        patterns: impl IntoIterator<Item = S>,
+    ) -> io::Result<Self> {
+        let invalid = |err| io::Error::new(io::ErrorKind::InvalidInput, err);
+
+        let mut builder = OverrideBuilder::new(root);
+        for pattern in patterns {
+            // Overrides are whitelists, negated patterns exclude matching paths
+            builder
+                .add(&format!("!{}", pattern.as_ref()))
+                .map_err(invalid)?;
+        }
+
+        Ok(Self {
+            matcher: builder.build().map_err(invalid)?,
+        })
+    }
+
+    /// Check if a path or any of its parent directories below the root is excluded.
+    pub fn is_excluded(&self, path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        let relative = path.strip_prefix(self.matcher.path()).unwrap_or(path);
+
+        relative
+            .ancestors()
+            .filter(|ancestor| !ancestor.as_os_str().is_empty())
+            .any(|ancestor| {
+                let is_dir = ancestor != relative || path.is_dir();
+                self.matches(ancestor, is_dir)
+            })
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        self.matcher.matched(path, is_dir).is_ignore()
+    }
+}
+
+/// Walker for directory trees, collecting all regular files in a deterministic order.
+///
+/// Other files, like named pipes, sockets or devices, are skipped, since reading from them might
+/// block forever.
+///
+/// # Defaults
+///
+/// If not overwritten by the fluent API, the following defaults are valid:
+///
+/// -   `hidden`: `true`
+///
+///     Include hidden files and directories, whose names start with a dot.
+///
+/// -   `gitignore`: `false`
+///
+///     Don't respect `.gitignore` files.
+///
+/// -   `exclude`: none
+///
+///     Don't exclude any paths.
+///
+/// -   `extension`: none
+///
+///     Include files with any extension.
+///
+/// -   `max_depth`: none
+///
+///     Descend into directories of any depth.
+///
+/// -   `follow_symlinks`: `false`
+///
+///     Don't follow symbolic links, neither to files nor to directories.
+///
+/// Directories named `.git` are always skipped.
+pub struct Walker {
+    root: PathBuf,
+    hidden: bool,
+    gitignore: bool,
+    exclude: Vec<String>,
+    extensions: Vec<OsString>,
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+}
+
+/// A path that was skipped while walking a directory tree.
+#[derive(Debug)]
+pub enum Skipped {
+    /// A symbolic link to a directory that is already being traversed, which would result in an
+    /// endless loop.
+    Loop {
+        /// The symbolic link.
+        path: PathBuf,
+
+        /// The directory the link points to.
+        ancestor: PathBuf,
+    },
+
+    /// A file that is neither a regular file nor a directory, like a named pipe, a socket or a
+    /// device. Reading from such a file might block forever.
+    NotRegular(PathBuf),
+
+    /// A file or directory that was already reached by another path via symbolic links.
+    Duplicate {
+        /// The skipped path.
+        path: PathBuf,
+
+        /// The path by which the file or directory is included.
+        original: PathBuf,
+    },
+}
+
+impl Walker {
+    /// Create new Walker instance for the directory tree below `root`, with default options.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Walker;
+    /// let walker = Walker::new("src");
+    /// ```
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            hidden: true,
+            gitignore: false,
+            exclude: Vec::new(),
+            extensions: Vec::new(),
+            max_depth: None,
+            follow_symlinks: false,
+        }
+    }
+
+    /// Include hidden files and directories.
+    ///
+    /// Files and directories are considered hidden if their names start with a dot.
+    ///
+    /// Defaults to `true`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Walker;
+    /// let walker = Walker::new("src").hidden(false);
+    /// ```
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Respect `.gitignore` files.
+    ///
+    /// With this option, files and directories that are ignored by `.gitignore` files, either in
+    /// the directory tree or in its parent directories, or by `.git/info/exclude` are skipped.
+    /// The rules are applied regardless of whether the tree is actually part of a git
+    /// repository.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Walker;
+    /// let walker = Walker::new("src").gitignore(true);
+    /// ```
+    pub fn gitignore(mut self, gitignore: bool) -> Self {
+        self.gitignore = gitignore;
+        self
+    }
+
+    /// Exclude paths matching a glob pattern, relative to the root directory.
+    ///
+    /// Can be given multiple times. See [`Exclude`] for the pattern syntax. Excluded directories
+    /// are not traversed at all.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Walker;
+    /// let walker = Walker::new("src").exclude("*.log").exclude("migrations/archive");
+    /// ```
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Only include files with the given extension, without the leading dot.
+    ///
+    /// Can be given multiple times to include files with any of the extensions. An empty
+    /// extension includes files without an extension. Extensions are compared case-insensitively
+    /// on Windows and case-sensitively on all other platforms.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Walker;
+    /// let walker = Walker::new("src").extension("sql").extension("");
+    /// ```
+    pub fn extension(mut self, extension: impl Into<OsString>) -> Self {
+        self.extensions.push(extension.into());
+        self
+    }
+
+    /// Limit the depth of the traversal.
+    ///
+    /// A depth of 1 only includes files directly in the root directory, a depth of 2 also
+    /// includes files in its subdirectories, and so on.
+    ///
+    /// Defaults to `None`, meaning no limit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Walker;
+    /// let walker = Walker::new("src").max_depth(Some(1));
+    /// ```
+    pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Follow symbolic links to files and directories.
+    ///
+    /// Links that point to a directory that is already being traversed are skipped, see
+    /// [`Walker::walk_with`]. Directories and files that can be reached by more than one path are
+    /// only included once, by the first path in traversal order. Files are identified by their
+    /// canonical path, so hard links are still included with all of their paths.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use normalized_hash::Walker;
+    /// let walker = Walker::new("src").follow_symlinks(true);
+    /// ```
+    pub fn follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Collect all regular files below the root directory.
+    ///
+    /// If the root is not a directory, it is returned as the only file. The returned paths start
+    /// with the root directory. They are sorted bytewise by their path relative to the root,
+    /// using `/` as separator on all platforms, like with [`sort_paths`], so the order is the
+    /// same regardless of the file system or the order in which the files were created.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if an exclude pattern is not a valid glob.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::Walker;
+    ///
+    /// for file in Walker::new("src").walk().unwrap() {
+    ///     println!("{}", file.display());
+    /// }
+    /// ```
+    pub fn walk(&self) -> io::Result<Vec<PathBuf>> {
+        self.walk_with(|_| {})
+    }
+
+    /// Collect all regular files below the root directory, reporting skipped paths.
+    ///
+    /// Works like [`Walker::walk`], but calls `skipped` for each path that had to be skipped to
+    /// complete the traversal, like a symbolic link loop, a named pipe or a duplicate.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use normalized_hash::{Skipped, Walker};
+    ///
+    /// let files = Walker::new("src")
+    ///     .follow_symlinks(true)
+    ///     .walk_with(|skipped| match skipped {
+    ///         Skipped::Loop { path, .. } => eprintln!("skipping loop {}", path.display()),
+    ///         Skipped::NotRegular(path) => eprintln!("skipping {}", path.display()),
+    ///         Skipped::Duplicate { path, .. } => eprintln!("skipping {}", path.display()),
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn walk_with(&self, mut skipped: impl FnMut(Skipped)) -> io::Result<Vec<PathBuf>> {
+        // Traversed in extended-length form, but reported by the paths below the given root
+        let root = long_path(&self.root);
+        let friendly = |path: PathBuf| self.friendly(&root, path);
+        let mut skipped = |entry: Skipped| {
+            skipped(match entry {
+                Skipped::Loop { path, ancestor } => Skipped::Loop {
+                    path: friendly(path),
+                    ancestor: friendly(ancestor),
+                },
+                Skipped::NotRegular(path) => Skipped::NotRegular(friendly(path)),
+                Skipped::Duplicate { path, original } => Skipped::Duplicate {
+                    path: friendly(path),
+                    original: friendly(original),
+                },
+            })
+        };
+
+        let exclude = Exclude::new(&root, &self.exclude)?;
+
+        // Directories reachable by more than one path are only traversed once
+        let follow_symlinks = self.follow_symlinks;
+        let visited = Arc::new(Mutex::new(HashMap::new()));
+        let duplicates = Arc::new(Mutex::new(Vec::new()));
+
+        let walk = WalkBuilder::new(&root)
+            .standard_filters(false)
+            .hidden(!self.hidden)
+            .parents(self.gitignore)
+            .git_ignore(self.gitignore)
+            .git_exclude(self.gitignore)
+            .require_git(false)
+            .max_depth(self.max_depth)
+            .follow_links(self.follow_symlinks)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .filter_entry({
+                let visited = Arc::clone(&visited);
+                let duplicates = Arc::clone(&duplicates);
+                move |entry| {
+                    let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+
+                    // Parents are already filtered, so only the entry itself has to be checked
+                    if entry.depth() > 0
+                        && (is_dir && entry.file_name() == ".git"
+                            || exclude.matches(entry.path(), is_dir))
+                    {
+                        return false;
+                    }
+
+                    if !follow_symlinks || !is_dir {
+                        return true;
+                    }
+
+                    let Some(id) = dir_id(entry.path()) else {
+                        return true;
+                    };
+
+                    let mut visited = visited.lock().unwrap();
+                    match visited.entry(id) {
+                        Entry::Vacant(vacant) => {
+                            vacant.insert(entry.path().to_path_buf());
+                            true
+                        }
+                        Entry::Occupied(occupied) => {
+                            duplicates.lock().unwrap().push(Skipped::Duplicate {
+                                path: entry.path().to_path_buf(),
+                                original: occupied.get().clone(),
+                            });
+                            false
+                        }
+                    }
+                }
+            })
+            .build();
+
+        let mut files = Vec::new();
+        for entry in walk {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => match into_loop(err) {
+                    Ok(loop_) => {
+                        skipped(loop_);
+                        continue;
+                    }
+                    Err(err) => return Err(into_io_error(err)),
+                },
+            };
+
+            let Some(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            // A root that is not a directory was named explicitly and is always included
+            if entry.depth() == 0 {
+                if !file_type.is_dir() {
+                    files.push(entry.into_path());
+                }
+                continue;
+            }
+
+            // Unless followed, the file type of symbolic links is not resolved
+            if file_type.is_file() {
+                if self.has_extension(entry.path()) {
+                    files.push(entry.into_path());
+                }
+            } else if !file_type.is_dir() && !file_type.is_symlink() {
+                skipped(Skipped::NotRegular(entry.into_path()));
+            }
+        }
+
+        for duplicate in duplicates.lock().unwrap().drain(..) {
+            skipped(duplicate);
+        }
+
+        let mut files = files.into_iter().map(friendly).collect::<Vec<_>>();
+        files.sort_by_cached_key(|file| sort_key(file.strip_prefix(&self.root).unwrap_or(file)));
+
+        // Files reachable by more than one path are only included once, by their first path
+        if follow_symlinks {
+            let mut originals = HashMap::new();
+            files.retain(|file| {
+                let Ok(canonical) = fs::canonicalize(long_path(file)) else {
+                    return true;
+                };
+
+                match originals.entry(canonical) {
+                    Entry::Vacant(vacant) => {
+                        vacant.insert(file.clone());
+                        true
+                    }
+                    Entry::Occupied(occupied) => {
+                        skipped(Skipped::Duplicate {
+                            path: file.clone(),
+                            original: occupied.get().clone(),
+                        });
+                        false
+                    }
+                }
+            });
+        }
+
+        Ok(files)
+    }
+
+    /// Path below the given root for a path below the traversed `root` in extended-length form.
+    /// Root directory of the tree, as given to [`Walker::new`].
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn friendly(&self, root: &Path, path: PathBuf) -> PathBuf {
+        if root == self.root {
+            return path;
+        }
+
+        match path.strip_prefix(root) {
+            Ok(relative) if relative.as_os_str().is_empty() => self.root.clone(),
+            Ok(relative) => self.root.join(relative),
+            Err(_) => path,
+        }
+    }
+
+    fn has_extension(&self, file: &Path) -> bool {
+        if self.extensions.is_empty() {
+            return true;
+        }
+
+        let extension = file.extension().unwrap_or_default();
+        self.extensions
+            .iter()
+            .any(|wanted| extension_eq(extension, wanted))
+    }
+}
+
+#[cfg(windows)]
+fn extension_eq(extension: &OsStr, wanted: &OsStr) -> bool {
+    extension.eq_ignore_ascii_case(wanted)
+}
+
+#[cfg(not(windows))]
+fn extension_eq(extension: &OsStr, wanted: &OsStr) -> bool {
+    extension == wanted
+}
+
+/// Identity of a directory, the same for all paths that lead to it.
+#[cfg(unix)]
+fn dir_id(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Identity of a directory, the same for all paths that lead to it.
+#[cfg(not(unix))]
+fn dir_id(path: &Path) -> Option<PathBuf> {
+    fs::canonicalize(path).ok()
+}
+
+/// Extract a symbolic link loop from a walk error, or return the error as is.
+fn into_loop(err: ignore::Error) -> Result<Skipped, ignore::Error> {
+    match err {
+        ignore::Error::Loop { ancestor, child } => Ok(Skipped::Loop {
+            path: child,
+            ancestor,
+        }),
+        ignore::Error::WithDepth { depth, err } => {
+            into_loop(*err).map_err(|err| ignore::Error::WithDepth {
+                depth,
+                err: Box::new(err),
+            })
+        }
+        ignore::Error::WithPath { path, err } => {
+            into_loop(*err).map_err(|err| ignore::Error::WithPath {
+                path,
+                err: Box::new(err),
+            })
+        }
+        err => Err(err),
+    }
+}
+
+/// Convert a walk error, keeping the kind of an underlying IO error and the offending path.
+fn into_io_error(err: ignore::Error) -> io::Error {
+    let kind = err.io_error().map_or(io::ErrorKind::Other, io::Error::kind);
+    io::Error::new(kind, err)
+}
+
+/// Sort key of a relative path, its components joined by `/`.
+fn sort_key(path: &Path) -> Vec<u8> {
+    let mut key = Vec::new();
+
+    for component in path.components() {
+        if !key.is_empty() {
+            key.push(b'/');
+        }
+        key.extend_from_slice(component.as_os_str().as_encoded_bytes());
+    }
+
+    key
+}
+
+/// Sort paths bytewise, using `/` as separator on all platforms.
+///
+/// This is the order of the files returned by [`Walker::walk`], for paths that were collected
+/// differently, like from several walks or from a glob pattern. Unlike the order of [`Path`],
+/// which compares component by component, `a.txt` comes before `a/b.txt`, like in the output of
+/// `sort` and in the index of git.
+///
+/// # Example
+///
+/// ```
+/// use std::path::PathBuf;
+/// use normalized_hash::sort_paths;
+///
+/// let mut paths = vec![PathBuf::from("a/b.txt"), PathBuf::from("a.txt")];
+/// sort_paths(&mut paths);
+/// assert_eq!(paths, [PathBuf::from("a.txt"), PathBuf::from("a/b.txt")]);
+/// ```
+pub fn sort_paths(paths: &mut [PathBuf]) {
+    paths.sort_by_cached_key(|path| sort_key(path));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn relative_files(walker: &Walker, root: &Path) -> io::Result<Vec<String>> {
+        Ok(walker
+            .walk()?
+            .iter()
+            .map(|file| {
+                let file = file.strip_prefix(root).unwrap();
+                file.to_string_lossy().replace('\\', "/")
+            })
+            .collect())
+    }
+
+    #[test]
+    fn check_walk_sorted() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let root = dir.path();
+
+        for file in [
+            "b.txt",
+            "a/z.txt",
+            "a.txt",
+            ".hidden/c.txt",
+            "a/b/c.txt",
+            ".git/HEAD",
+        ] {
+            let file = root.join(file);
+            fs::create_dir_all(file.parent().unwrap())?;
+            fs::write(file, "")?;
+        }
+
+        assert_eq!(
+            relative_files(&Walker::new(root), root)?,
+            [".hidden/c.txt", "a.txt", "a/b/c.txt", "a/z.txt", "b.txt"]
+        );
+
+        assert_eq!(
+            relative_files(&Walker::new(root).hidden(false), root)?,
+            ["a.txt", "a/b/c.txt", "a/z.txt", "b.txt"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_sort_paths() {
+        let mut paths = ["b.txt", "a/z.txt", "a-b.txt", "a.txt", "a/b/c.txt", "B.txt"]
+            .map(PathBuf::from)
+            .to_vec();
+
+        sort_paths(&mut paths);
+
+        assert_eq!(
+            paths,
+            ["B.txt", "a-b.txt", "a.txt", "a/b/c.txt", "a/z.txt", "b.txt"].map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn check_walk_gitignore() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let root = dir.path();
+
+        for file in ["a.txt", "a.log", "target/b.txt", "sub/c.txt"] {
+            let file = root.join(file);
+            fs::create_dir_all(file.parent().unwrap())?;
+            fs::write(file, "")?;
+        }
+        fs::write(root.join(".gitignore"), "*.log\n/target/\n")?;
+
+        assert_eq!(relative_files(&Walker::new(root), root)?.len(), 5);
+
+        assert_eq!(
+            relative_files(&Walker::new(root).gitignore(true), root)?,
+            [".gitignore", "a.txt", "sub/c.txt"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_walk_exclude() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let root = dir.path();
+
+        for file in [
+            "a.txt",
+            "a.log",
+            "sub/b.log",
+            "archive/c.txt",
+            "sub/archive/d.txt",
+        ] {
+            let file = root.join(file);
+            fs::create_dir_all(file.parent().unwrap())?;
+            fs::write(file, "")?;
+        }
+
+        assert_eq!(
+            relative_files(&Walker::new(root).exclude("*.log"), root)?,
+            ["a.txt", "archive/c.txt", "sub/archive/d.txt"]
+        );
+
+        assert_eq!(
+            relative_files(&Walker::new(root).exclude("/archive"), root)?,
+            ["a.log", "a.txt", "sub/archive/d.txt", "sub/b.log"]
+        );
+
+        assert_eq!(
+            Walker::new(root).exclude("[").walk().unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_walk_extension() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let root = dir.path();
+
+        for file in ["a.sql", "b.txt", "c.log", "sub/deploy", "sub/d.sql"] {
+            let file = root.join(file);
+            fs::create_dir_all(file.parent().unwrap())?;
+            fs::write(file, "")?;
+        }
+
+        assert_eq!(
+            relative_files(&Walker::new(root).extension("sql").extension("txt"), root)?,
+            ["a.sql", "b.txt", "sub/d.sql"]
+        );
+
+        assert_eq!(
+            relative_files(&Walker::new(root).extension(""), root)?,
+            ["sub/deploy"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_walk_max_depth() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let root = dir.path();
+
+        for file in ["a.txt", "sub/b.txt", "sub/deeper/c.txt"] {
+            let file = root.join(file);
+            fs::create_dir_all(file.parent().unwrap())?;
+            fs::write(file, "")?;
+        }
+
+        assert_eq!(
+            relative_files(&Walker::new(root).max_depth(Some(1)), root)?,
+            ["a.txt"]
+        );
+        assert_eq!(
+            relative_files(&Walker::new(root).max_depth(Some(2)), root)?,
+            ["a.txt", "sub/b.txt"]
+        );
+        assert_eq!(relative_files(&Walker::new(root), root)?.len(), 3);
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_walk_symlink_loop() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new()?;
+        let root = dir.path();
+
+        fs::create_dir_all(root.join("sub"))?;
+        fs::write(root.join("sub/a.txt"), "")?;
+        std::os::unix::fs::symlink("..", root.join("sub/up"))?;
+        std::os::unix::fs::symlink("sub/a.txt", root.join("link.txt"))?;
+
+        assert_eq!(relative_files(&Walker::new(root), root)?, ["sub/a.txt"]);
+
+        let mut loops = Vec::new();
+        let files = Walker::new(root)
+            .follow_symlinks(true)
+            .walk_with(|skipped| loops.push(skipped))?;
+        assert_eq!(files, [root.join("link.txt")]);
+        assert!(matches!(
+            loops.as_slice(),
+            [Skipped::Loop { path, .. }, Skipped::Duplicate { path: duplicate, .. }]
+                if path == &root.join("sub/up") && duplicate == &root.join("sub/a.txt")
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_walk_symlink_diamond() -> Result<(), Box<dyn Error>> {
+        use std::os::unix::fs::symlink;
+
+        let dir = TempDir::new()?;
+        let root = dir.path();
+
+        // Both branches lead to the same shared directory
+        fs::create_dir_all(root.join("shared"))?;
+        fs::write(root.join("shared/a.txt"), "")?;
+        fs::create_dir_all(root.join("left"))?;
+        fs::create_dir_all(root.join("right"))?;
+        symlink("../shared", root.join("left/shared"))?;
+        symlink("../shared", root.join("right/shared"))?;
+
+        let mut skipped = Vec::new();
+        let files = Walker::new(root)
+            .follow_symlinks(true)
+            .walk_with(|s| skipped.push(s))?;
+        assert_eq!(files, [root.join("left/shared/a.txt")]);
+        assert_eq!(skipped.len(), 2);
+        assert!(skipped.iter().all(|s| matches!(
+            s,
+            Skipped::Duplicate { original, .. } if original == &root.join("left/shared")
+        )));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_walk_not_regular() -> Result<(), Box<dyn Error>> {
+        use std::os::unix::net::UnixListener;
+
+        let dir = TempDir::new()?;
+        let root = dir.path();
+
+        fs::write(root.join("a.txt"), "")?;
+        let _listener = UnixListener::bind(root.join("socket"))?;
+
+        let mut skipped = Vec::new();
+        let files = Walker::new(root).walk_with(|s| skipped.push(s))?;
+        assert_eq!(files, [root.join("a.txt")]);
+        assert!(matches!(
+            skipped.as_slice(),
+            [Skipped::NotRegular(path)] if path == &root.join("socket")
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::path::Path;
+
+use clap::ValueEnum;
+
+/// Format of the results for CI systems, given with `--output-format`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Only the normal output
+    #[default]
+    Plain,
+
+    /// Also print workflow commands, which GitHub Actions shows as annotations
+    Github,
+}
+
+/// Severity of an annotation.
+#[derive(Clone, Copy)]
+enum Level {
+    Error,
+    Notice,
+}
+
+/// Printer of annotations on offending files, alongside the normal output.
+///
+/// Annotations are printed to stdout, or to stderr if stdout is a JSON document, which the
+/// GitHub Actions runner reads as well.
+#[derive(Clone, Copy)]
+pub struct Annotations {
+    format: OutputFormat,
+    to_stderr: bool,
+}
+
+impl Annotations {
+    /// Print annotations in the given `format`, where `json` tells if stdout is a JSON document.
+    pub fn new(format: OutputFormat, json: bool) -> Self {
+        Self {
+            format,
+            to_stderr: json,
+        }
+    }
+
+    /// Annotate `file` with an error, at the given (1-based) line number if known.
+    pub fn error(self, file: impl Display, line: Option<usize>, message: impl Display) {
+        self.annotate(Level::Error, file, line, message);
+    }
+
+    /// Annotate `file` with a notice, like for a skipped file.
+    pub fn notice(self, file: impl Display, line: Option<usize>, message: impl Display) {
+        self.annotate(Level::Notice, file, line, message);
+    }
+
+    fn annotate(
+        self,
+        level: Level,
+        file: impl Display,
+        line: Option<usize>,
+        message: impl Display,
+    ) {
+        if self.format != OutputFormat::Github {
+            return;
+        }
+
+        let command = format_command(level, &file.to_string(), line, &message.to_string());
+        if self.to_stderr {
+            eprintln!("{}", command);
+        } else {
+            println!("{}", command);
+        }
+    }
+}
+
+/// Path of `file` as annotated, without a leading `./`, like the paths within a repository.
+pub fn path(file: &Path) -> Cow<'_, str> {
+    normalized_hash::escape_path(file.strip_prefix(".").unwrap_or(file))
+}
+
+/// Format a workflow command like `::error file=a.txt,line=2::message`.
+fn format_command(level: Level, file: &str, line: Option<usize>, message: &str) -> String {
+    let level = match level {
+        Level::Error => "error",
+        Level::Notice => "notice",
+    };
+
+    let mut properties = format!("file={}", escape_property(file));
+    if let Some(line) = line {
+        properties.push_str(&format!(",line={}", line));
+    }
+
+    format!("::{} {}::{}", level, properties, escape_data(message))
+}
+
+/// Escape the message of a workflow command, so that it stays on a single line.
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property of a workflow command, where `:` and `,` are delimiters.
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_format_command() {
+        assert_eq!(
+            format_command(Level::Error, "a.txt", Some(2), "not normalized"),
+            "::error file=a.txt,line=2::not normalized"
+        );
+        assert_eq!(
+            format_command(
+                Level::Notice,
+                "C:\\a,b%.txt",
+                None,
+                "50%\r\nskipped: binary"
+            ),
+            "::notice file=C%3A\\a%2Cb%25.txt::50%25%0D%0Askipped: binary"
+        );
+    }
+}
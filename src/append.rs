@@ -0,0 +1,139 @@
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use tempfile::NamedTempFile;
+
+use crate::checksum::{self, Header};
+
+#[derive(Debug)]
+pub enum AppendError {
+    Io(io::Error),
+
+    /// An entry for the same path with a different hash exists and updating is disabled.
+    Conflict {
+        hash: String,
+    },
+
+    /// The checksum file records other normalization options.
+    Options {
+        recorded: Header,
+    },
+}
+
+impl Display for AppendError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AppendError::Io(err) => err.fmt(f),
+            AppendError::Conflict { hash } => {
+                write!(
+                    f,
+                    "an entry with a different hash ({}) already exists",
+                    hash
+                )
+            }
+            AppendError::Options { recorded } => {
+                let fields = recorded
+                    .fields()
+                    .map(|(key, value)| format!("{}={}", key, value));
+                write!(f, "it was generated with {}", fields.join(" "))
+            }
+        }
+    }
+}
+
+impl From<io::Error> for AppendError {
+    fn from(err: io::Error) -> Self {
+        AppendError::Io(err)
+    }
+}
+
+/// Add or update the entry for `path` in a checksum file.
+///
+/// An existing entry for the same path is replaced in place, further duplicates are removed. All
+/// other lines, including comments, are preserved in their original order. If there is no entry
+/// yet, a new line is appended. The checksum file is replaced atomically, so concurrent readers
+/// never see a half-written file.
+///
+/// With `update` set to false, an existing entry with a different hash results in an error.
+///
+/// A new checksum file starts with the `header` of the normalization options. Adding to a
+/// checksum file whose header records other options is an error, since its hashes could not be
+/// checked together.
+pub fn append(
+    checksum_file: &Path,
+    path: &Path,
+    hash: &str,
+    tag: bool,
+    update: bool,
+    header: &Header,
+) -> Result<(), AppendError> {
+    let checksum_file = &normalized_hash::long_path(checksum_file);
+    let content = match fs::read_to_string(checksum_file) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+
+    if let Some(Ok(recorded)) = Header::find(&content) {
+        if recorded != *header {
+            return Err(AppendError::Options { recorded });
+        }
+    }
+    let header_line = header.to_string();
+
+    let new_line = if tag {
+        checksum::format_bsd(header.algorithm, path, hash)
+    } else {
+        checksum::format_gnu(path, hash)
+    };
+
+    let mut lines = Vec::new();
+    let mut found = false;
+
+    if content.is_empty() {
+        lines.push(header_line.as_str());
+    }
+
+    for line in content.lines() {
+        let entry = match checksum::parse_line(line) {
+            Ok(Some(entry)) if entry.path == path => entry,
+            _ => {
+                lines.push(line);
+                continue;
+            }
+        };
+
+        if !update && entry.hash != hash {
+            return Err(AppendError::Conflict { hash: entry.hash });
+        }
+
+        if !found {
+            lines.push(&new_line);
+            found = true;
+        }
+    }
+
+    if !found {
+        lines.push(&new_line);
+    }
+
+    let dir = match checksum_file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    let mut file = NamedTempFile::new_in(dir)?;
+    for line in lines {
+        writeln!(file, "{}", line)?;
+    }
+
+    if let Ok(metadata) = fs::metadata(checksum_file) {
+        file.as_file().set_permissions(metadata.permissions())?;
+    }
+
+    file.persist(checksum_file).map_err(|err| err.error)?;
+
+    Ok(())
+}
@@ -0,0 +1,332 @@
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::BuildHasher;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use tempfile::NamedTempFile;
+
+use normalized_hash::{escape_path, unescape_path, Algorithm};
+
+use crate::error;
+
+/// First line of a cache file, to detect files that are no caches or of another version.
+const HEADER: &str = "# normalized-hasher cache, version 2";
+
+/// First line of cache files of version 1, which are replaced without a warning.
+const HEADER_V1: &str = "# normalized-hasher cache, version 1";
+
+/// Hash of binary files in the cache file, which are skipped instead of hashed.
+const BINARY: &str = "binary";
+
+/// Cached result of a file, valid as long as its size and modification time are unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Entry {
+    size: u64,
+    modified: Duration,
+
+    /// Normalization options the file was hashed with.
+    options: String,
+
+    /// Hash of the file, `None` if it is a binary file.
+    hash: Option<String>,
+}
+
+/// Hashes of files from a previous run, with `--cache`.
+///
+/// The cache is a text file with one line per file, with the tab separated fields hash, size,
+/// modification time, normalization options and canonical path, sorted by path. Since hashes
+/// depend on the normalization options, which may differ per directory, a cached hash is only
+/// used with the options it was created with.
+pub struct Cache {
+    path: PathBuf,
+    entries: BTreeMap<PathBuf, Entry>,
+
+    /// Whether cached hashes are used, false with `--no-cache-read`.
+    read: bool,
+
+    /// Fraction of cached hashes that are verified anyway, with `--cache-verify-sample`.
+    sample: f64,
+    random: RandomState,
+
+    changed: bool,
+}
+
+/// Canonical path, size and modification time of a file, `None` if not available.
+fn metadata(file: &Path) -> Option<(PathBuf, u64, Duration)> {
+    let path = fs::canonicalize(file).ok()?;
+    let metadata = fs::metadata(&path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+
+    Some((path, metadata.len(), modified))
+}
+
+/// Parse the lines of a cache file after the header lines.
+fn parse_entries<'a>(lines: impl Iterator<Item = &'a str>) -> Option<BTreeMap<PathBuf, Entry>> {
+    let mut entries = BTreeMap::new();
+
+    for line in lines {
+        let mut fields = line.splitn(5, '\t');
+        let hash = fields.next()?;
+        let size = fields.next()?.parse().ok()?;
+        let (seconds, nanos) = fields.next()?.split_once('.')?;
+        let modified = Duration::new(seconds.parse().ok()?, nanos.parse().ok()?);
+        let options = fields.next()?.to_string();
+        let path = unescape_path(fields.next()?);
+
+        let hash = match hash {
+            BINARY => None,
+            hash if Algorithm::of_hex(hash).is_some() => Some(hash.to_string()),
+            _ => return None,
+        };
+
+        entries.insert(
+            path,
+            Entry {
+                size,
+                modified,
+                options,
+                hash,
+            },
+        );
+    }
+
+    Some(entries)
+}
+
+impl Cache {
+    /// Read the cache file at `path`.
+    ///
+    /// A missing file results in an empty cache. A file that cannot be read or parsed results in
+    /// an empty cache as well, with a warning, so all files are hashed and the cache file is
+    /// written from scratch.
+    pub fn load(path: &Path, read: bool, sample: f64, quiet: bool) -> Self {
+        let mut cache = Self {
+            path: path.to_path_buf(),
+            entries: BTreeMap::new(),
+            read,
+            sample,
+            random: RandomState::new(),
+            changed: false,
+        };
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return cache,
+            Err(err) => {
+                if !quiet {
+                    eprintln!(
+                        "warning: cannot read cache {}: {}, hashing all files",
+                        path.display(),
+                        error::message(&err)
+                    );
+                }
+                return cache;
+            }
+        };
+
+        let mut lines = content.lines();
+        let header = lines.next();
+
+        // A cache of an earlier version is replaced, but not corrupt
+        if header == Some(HEADER_V1) {
+            return cache;
+        }
+
+        if header != Some(HEADER) {
+            if !quiet {
+                eprintln!(
+                    "warning: {} is not a valid cache, hashing all files",
+                    path.display()
+                );
+            }
+            return cache;
+        }
+
+        match parse_entries(lines) {
+            Some(entries) => cache.entries = entries,
+            None => {
+                if !quiet {
+                    eprintln!(
+                        "warning: cache {} is corrupt, hashing all files",
+                        path.display()
+                    );
+                }
+            }
+        }
+
+        cache
+    }
+
+    /// Check if the cached hash of a file should be verified, for `--cache-verify-sample`.
+    fn is_sampled(&self, path: &Path) -> bool {
+        ((self.random.hash_one(path) % 1_000_000) as f64) < self.sample * 1_000_000.0
+    }
+
+    /// Get the cached hash of a file, if its size and modification time are unchanged and it was
+    /// hashed with the normalization `options`.
+    ///
+    /// The inner value is `None` for a binary file. If the file has to be hashed, because it is
+    /// not cached, changed, cached with other options, or selected to be verified, `None` is
+    /// returned.
+    pub fn get(&self, file: &Path, options: &str) -> Option<Option<String>> {
+        if !self.read {
+            return None;
+        }
+
+        let (path, size, modified) = metadata(file)?;
+        let entry = self.entries.get(&path)?;
+
+        if entry.size != size
+            || entry.modified != modified
+            || entry.options != options
+            || self.is_sampled(&path)
+        {
+            return None;
+        }
+
+        Some(entry.hash.clone())
+    }
+
+    /// Store the hash of a file with the normalization `options`, `None` for a binary file.
+    ///
+    /// Returns true if the file was cached with the same size, modification time and options,
+    /// but a different hash, so the cache was stale.
+    pub fn insert(&mut self, file: &Path, options: &str, hash: Option<&str>) -> bool {
+        let Some((path, size, modified)) = metadata(file) else {
+            return false;
+        };
+
+        // Such paths cannot be written to the cache file
+        if path
+            .as_os_str()
+            .as_encoded_bytes()
+            .iter()
+            .any(|byte| b"\t\n\r".contains(byte))
+        {
+            return false;
+        }
+
+        let entry = Entry {
+            size,
+            modified,
+            options: options.to_string(),
+            hash: hash.map(str::to_string),
+        };
+
+        match self.entries.insert(path, entry.clone()) {
+            Some(old) if old == entry => false,
+            Some(old) => {
+                self.changed = true;
+                old.size == entry.size
+                    && old.modified == entry.modified
+                    && old.options == entry.options
+            }
+            None => {
+                self.changed = true;
+                false
+            }
+        }
+    }
+
+    /// Write the cache file, if anything changed.
+    ///
+    /// Entries of files that no longer exist are removed. The file is replaced atomically, so an
+    /// interrupted run leaves the previous cache intact.
+    pub fn save(mut self) -> io::Result<()> {
+        let before = self.entries.len();
+        self.entries.retain(|path, _| path.exists());
+
+        if !self.changed && self.entries.len() == before && self.path.exists() {
+            return Ok(());
+        }
+
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+
+        let mut file = NamedTempFile::new_in(dir)?;
+        {
+            let mut writer = io::BufWriter::new(file.as_file_mut());
+            writeln!(writer, "{}", HEADER)?;
+
+            for (path, entry) in &self.entries {
+                writeln!(
+                    writer,
+                    "{}\t{}\t{}.{:09}\t{}\t{}",
+                    entry.hash.as_deref().unwrap_or(BINARY),
+                    entry.size,
+                    entry.modified.as_secs(),
+                    entry.modified.subsec_nanos(),
+                    entry.options,
+                    escape_path(path)
+                )?;
+            }
+
+            writer.flush()?;
+        }
+
+        file.persist(&self.path).map_err(|err| err.error)?;
+
+        Ok(())
+    }
+}
+
+/// Parse the fraction of `--cache-verify-sample`, like `10%` or `2.5`.
+pub fn parse_sample(sample: &str) -> Result<f64, String> {
+    let percent = sample
+        .strip_suffix('%')
+        .unwrap_or(sample)
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| format!("invalid percentage '{}'", sample))?;
+
+    if !(0.0..=100.0).contains(&percent) {
+        return Err(format!("percentage '{}' is not between 0 and 100", sample));
+    }
+
+    Ok(percent / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_parse_entries() {
+        let hash = "b62e3392d1ef3737f39339a43976186a039d53f30a95b987ccf8c5843dba94a2";
+        let options = "eol=0a ignore-whitespaces=false no-eof=false";
+        let content = format!(
+            "{hash}\t8\t1693058709.000000123\t{options}\t/a b.txt\nbinary\t3\t5.0\t{options}\t/c\n"
+        );
+
+        let entries = parse_entries(content.lines()).unwrap();
+        assert_eq!(
+            entries[Path::new("/a b.txt")],
+            Entry {
+                size: 8,
+                modified: Duration::new(1693058709, 123),
+                options: options.to_string(),
+                hash: Some(hash.to_string()),
+            }
+        );
+        assert_eq!(entries[Path::new("/c")].hash, None);
+
+        assert!(parse_entries(["xyz\t8\t1.0\t\t/a"].into_iter()).is_none());
+        assert!(parse_entries([&format!("{hash}\t8\t1\t\t/a")[..]].into_iter()).is_none());
+        assert!(parse_entries([&format!("{hash}\t8\t1.0\t/a")[..]].into_iter()).is_none());
+    }
+
+    #[test]
+    fn check_parse_sample() {
+        assert_eq!(parse_sample("10%"), Ok(0.1));
+        assert_eq!(parse_sample("2.5"), Ok(0.025));
+        assert_eq!(parse_sample("0%"), Ok(0.0));
+        assert!(parse_sample("101%").is_err());
+        assert!(parse_sample("-1").is_err());
+        assert!(parse_sample("ten").is_err());
+    }
+}
@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::Instant;
+
+use normalized_hash::Algorithm;
+
+use crate::annotate::{self, Annotations};
+use crate::checksum::{self, Header};
+use crate::color::Palette;
+use crate::config::Directories;
+use crate::error;
+use crate::exit::ExitStatus;
+use crate::output::Output;
+use crate::summary;
+use crate::RetryArgs;
+
+/// Output options for check mode, compatible to `sha256sum`.
+pub struct CheckOptions {
+    /// Use the given normalization options, even if the checksum file records others.
+    pub override_options: bool,
+
+    /// Resolve relative paths against the current directory instead of the checksum file's.
+    pub relative_to_current_dir: bool,
+
+    /// Print a summary line after the results.
+    pub summary: bool,
+
+    /// How often a file is hashed again after a modification or a transient error.
+    pub retry: RetryArgs,
+
+    /// Don't print `OK` for successfully verified files.
+    pub quiet: bool,
+
+    /// Don't print anything, only the exit code indicates success.
+    pub status: bool,
+
+    /// Print only the result of each file, without its path.
+    pub no_filename: bool,
+
+    /// Print only the paths of failed files, without their result.
+    pub filename_only: bool,
+
+    /// Colors of the results.
+    pub palette: Palette,
+
+    /// Annotations on failed files and skipped lines, regardless of the other options.
+    pub annotations: Annotations,
+}
+
+impl CheckOptions {
+    /// Print the result line of a single file, unless suppressed by the options.
+    fn print_result(&self, out: &mut Output, path: &Path, result: &str) {
+        if self.status || (self.quiet || self.filename_only) && result == "OK" {
+            return;
+        }
+
+        let result = if result == "OK" {
+            self.palette.ok(result)
+        } else {
+            self.palette.failed(result)
+        };
+
+        if self.filename_only {
+            out.line(normalized_hash::escape_path(path));
+        } else if self.no_filename {
+            out.line(result);
+        } else {
+            out.line(format_args!(
+                "{}: {}",
+                normalized_hash::escape_path(path),
+                result
+            ));
+        }
+    }
+}
+
+/// Counters for the warnings and the summary line printed after checking all entries.
+#[derive(Default)]
+struct Summary {
+    verified: usize,
+    malformed: usize,
+    unreadable: usize,
+    mismatched: usize,
+    unsupported: usize,
+    other_algorithm: usize,
+}
+
+pub fn plural(count: usize, singular: &'static str, plural: &'static str) -> &'static str {
+    if count == 1 {
+        singular
+    } else {
+        plural
+    }
+}
+
+fn read_checksum_file(checksum_file: &Path) -> io::Result<String> {
+    if checksum_file == Path::new("-") {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        Ok(content)
+    } else {
+        fs::read_to_string(normalized_hash::long_path(checksum_file))
+    }
+}
+
+/// Verify all files listed in a checksum file.
+///
+/// Both GNU-style (`hash  path`) and BSD-style (`SHA256 (path) = hash`) lines are accepted, also
+/// mixed within the same file. If `checksum_file` is `-`, the list is read from stdin. Relative
+/// paths are resolved against the directory of the checksum file, or against the current
+/// directory for stdin and with `--relative-to-current-dir`.
+///
+/// If the checksum file has a header, the files are checked with the recorded normalization
+/// options, which must not conflict with the given ones, unless `--override-options` is given.
+/// The given options that headers don't record, like `--paragraph-mode`, apply on top.
+/// Otherwise, each file is checked with the options of the configuration files of its directory,
+/// and BSD-style lines with the algorithm they name, unless an algorithm is given.
+pub fn run(
+    dirs: &mut Directories,
+    out: &mut Output,
+    checksum_file: &Path,
+    options: &CheckOptions,
+) -> ExitStatus {
+    let is_stdin = checksum_file == Path::new("-");
+    let name = if is_stdin {
+        "standard input".to_string()
+    } else {
+        checksum_file.display().to_string()
+    };
+
+    let content = match read_checksum_file(checksum_file) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("error: cannot read {}: {}", name, err);
+            return ExitStatus::Io;
+        }
+    };
+
+    let recorded = match Header::find(&content) {
+        Some(Ok(header)) if !options.override_options => {
+            let conflicts = dirs.options(0).conflicts(&header);
+            if let Some((given, recorded)) = conflicts.first() {
+                eprintln!(
+                    "error: {} was generated with {}, but {} is given, use --override-options to check with it anyway",
+                    name, recorded, given
+                );
+                return ExitStatus::Usage;
+            }
+            Some(options.retry.apply(dirs.options(0).with_header(&header)))
+        }
+        Some(Err(message)) if !options.override_options => {
+            eprintln!("error: {}: {}", name, message);
+            return ExitStatus::Malformed;
+        }
+        _ => None,
+    };
+    let mut hashers = HashMap::new();
+
+    let base = match checksum_file.parent() {
+        Some(parent) if !options.relative_to_current_dir && !is_stdin => Some(parent),
+        _ => None,
+    };
+
+    let mut summary = Summary::default();
+    let mut entries = 0;
+    let start = Instant::now();
+
+    // Lines of the checksum file can only be annotated if it is a file
+    let skipped = |line_number: usize, message: &str| {
+        if !is_stdin {
+            let file = annotate::path(checksum_file);
+            let message = format_args!("{}, skipping", message);
+            options
+                .annotations
+                .notice(file, Some(line_number + 1), message);
+        }
+    };
+
+    for (line_number, line) in content.lines().enumerate() {
+        let entry = match checksum::parse_line(line) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => continue,
+            Err(()) => {
+                skipped(line_number, "improperly formatted checksum line");
+                summary.malformed += 1;
+                continue;
+            }
+        };
+
+        if is_stdin && entry.path == Path::new("-") {
+            eprintln!(
+                "error: {}:{}: cannot read file content from standard input while reading the checksum list from it",
+                name,
+                line_number + 1
+            );
+            return ExitStatus::Usage;
+        }
+
+        // Results are printed with the path as listed, like by `sha256sum`
+        let file = match base {
+            Some(base) if entry.path.is_relative() => base.join(&entry.path),
+            _ => entry.path.clone(),
+        };
+
+        let hasher = match &recorded {
+            Some(hasher) => hasher,
+            None => {
+                let index = match dirs.index(&file) {
+                    Ok(index) => index,
+                    Err(status) => return status,
+                };
+                let normalization = dirs.options(index);
+
+                // Unless an algorithm is chosen, BSD lines are checked with the one they name
+                let named = entry.algorithm.as_deref().map(str::parse::<Algorithm>);
+                let algorithm = match named {
+                    Some(Ok(named)) if !normalization.given.contains(&"algorithm") => named,
+                    _ => normalization.algorithm.into(),
+                };
+                hashers.entry((index, algorithm)).or_insert_with(|| {
+                    options
+                        .retry
+                        .apply(normalization.hasher().algorithm(algorithm))
+                })
+            }
+        };
+
+        // Lines of other algorithms cannot be checked with the selected one
+        let algorithm = hasher.digest_algorithm();
+        if let Some(named) = &entry.algorithm {
+            let selected = checksum::tag(algorithm);
+            let messages = match named.parse::<Algorithm>() {
+                Ok(named) if named == algorithm => None,
+                Ok(_) => {
+                    summary.other_algorithm += 1;
+                    let selection = if recorded.is_some() {
+                        "header"
+                    } else {
+                        "--algorithm or configuration"
+                    };
+                    Some((
+                        format!(
+                            "{} line skipped, checking with {} ({})",
+                            named, selected, selection
+                        ),
+                        format!("{} line, but checking with {}", named, selected),
+                    ))
+                }
+                Err(_) => {
+                    summary.unsupported += 1;
+                    let message = format!("unsupported algorithm {}", named);
+                    Some((message.clone(), message))
+                }
+            };
+            if let Some((message, notice)) = messages {
+                if !options.status {
+                    eprintln!("error: {}:{}: {}", name, line_number + 1, message);
+                }
+                skipped(line_number, &notice);
+                continue;
+            }
+        }
+
+        if entry.hash.len() != algorithm.hex_len() {
+            skipped(line_number, "improperly formatted checksum line");
+            summary.malformed += 1;
+            continue;
+        }
+
+        entries += 1;
+
+        match hasher.try_hash_file(&file, None::<&Path>) {
+            Ok(hash) if hash == entry.hash => {
+                options.print_result(out, &entry.path, "OK");
+                summary.verified += 1;
+            }
+            Ok(_) => {
+                options.print_result(out, &entry.path, "FAILED");
+                let message = "computed checksum did NOT match";
+                options
+                    .annotations
+                    .error(annotate::path(&file), None, message);
+                summary.mismatched += 1;
+            }
+            Err(err) => {
+                let message = error::describe(&err, &file, None);
+                if !options.status {
+                    eprintln!("error: {}", message);
+                }
+                options.print_result(out, &entry.path, "FAILED open or read");
+                options
+                    .annotations
+                    .error(annotate::path(&file), None, message);
+                summary.unreadable += 1;
+            }
+        }
+    }
+
+    if entries == 0 && summary.unsupported == 0 && summary.other_algorithm == 0 {
+        if !options.status {
+            eprintln!(
+                "error: {}: no properly formatted checksum lines found",
+                name
+            );
+        }
+        return ExitStatus::Malformed;
+    }
+
+    let Summary {
+        verified,
+        malformed,
+        unreadable,
+        mismatched,
+        unsupported,
+        other_algorithm,
+    } = summary;
+
+    if !options.status {
+        if malformed > 0 {
+            eprintln!(
+                "WARNING: {} {} improperly formatted",
+                malformed,
+                plural(malformed, "line is", "lines are")
+            );
+        }
+
+        if unsupported > 0 {
+            eprintln!(
+                "WARNING: {} {} an unsupported algorithm",
+                unsupported,
+                plural(unsupported, "line uses", "lines use")
+            );
+        }
+
+        if other_algorithm > 0 {
+            eprintln!(
+                "WARNING: {} {} another algorithm than the selected one",
+                other_algorithm,
+                plural(other_algorithm, "line uses", "lines use")
+            );
+        }
+
+        if unreadable > 0 {
+            eprintln!(
+                "WARNING: {} listed {} could not be read",
+                unreadable,
+                plural(unreadable, "file", "files")
+            );
+        }
+
+        if mismatched > 0 {
+            eprintln!(
+                "WARNING: {} computed {} did NOT match",
+                mismatched,
+                plural(mismatched, "checksum", "checksums")
+            );
+        }
+    }
+
+    if options.summary && !options.status && !options.filename_only {
+        let counts = [
+            (verified, "OK"),
+            (mismatched, "failed"),
+            (unreadable, "missing"),
+            (malformed + unsupported + other_algorithm, "skipped"),
+        ];
+        out.line(summary::line(&counts, start.elapsed()));
+    }
+
+    if unreadable > 0 {
+        ExitStatus::Io
+    } else if unsupported > 0 || other_algorithm > 0 {
+        ExitStatus::Malformed
+    } else if mismatched > 0 {
+        ExitStatus::Mismatch
+    } else {
+        ExitStatus::Success
+    }
+}
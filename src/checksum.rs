@@ -0,0 +1,461 @@
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::path::{Component, Path, PathBuf};
+
+use normalized_hash::{escape_path, unescape_path, Algorithm, Encoding, Hasher};
+
+/// Prefix of the comment line that records the options a checksum file was generated with.
+const HEADER: &str = "# normalized-hasher:";
+
+/// Version of the header line, to recognize headers of later versions.
+const HEADER_FORMAT: &str = "v1";
+
+/// Names of line endings in the header, other sequences are written as hex bytes like `0x0a`.
+const EOL_NAMES: [(&str, &str); 3] = [("lf", "\n"), ("crlf", "\r\n"), ("cr", "\r")];
+
+/// Normalization options recorded in the header of a generated checksum file.
+///
+/// The header is a comment line like `# normalized-hasher: eol=lf ignore_whitespaces=false
+/// no_eof=false algorithm=sha256 format=v1`, so other tools ignore it. Unknown fields are
+/// ignored for compatibility with later versions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub eol: String,
+    pub ignore_whitespaces: bool,
+    pub no_eof: bool,
+    pub algorithm: Algorithm,
+}
+
+/// Name of the hash algorithm, as used in checksum lines, like `SHA256`.
+pub fn tag(algorithm: Algorithm) -> String {
+    algorithm.name().to_ascii_uppercase()
+}
+
+impl Header {
+    /// Hasher with the recorded options.
+    pub fn hasher(&self) -> Hasher {
+        Hasher::new()
+            .eol(&self.eol)
+            .ignore_whitespaces(self.ignore_whitespaces)
+            .no_eof(self.no_eof)
+            .algorithm(self.algorithm)
+    }
+
+    /// Options as fields of the header, like `("eol", "lf")`.
+    pub fn fields(&self) -> [(&'static str, String); 4] {
+        let eol = match EOL_NAMES.iter().find(|(_, eol)| *eol == self.eol) {
+            Some((name, _)) => name.to_string(),
+            None => {
+                let bytes = self.eol.bytes().map(|byte| format!("{:02x}", byte));
+                format!("0x{}", bytes.collect::<String>())
+            }
+        };
+
+        [
+            ("eol", eol),
+            ("ignore_whitespaces", self.ignore_whitespaces.to_string()),
+            ("no_eof", self.no_eof.to_string()),
+            ("algorithm", self.algorithm.to_string()),
+        ]
+    }
+
+    /// Parse a header line, `None` if the line is no header.
+    pub fn parse(line: &str) -> Option<Result<Self, String>> {
+        let fields = line
+            .strip_suffix('\r')
+            .unwrap_or(line)
+            .strip_prefix(HEADER)?;
+        Some(Self::parse_fields(fields))
+    }
+
+    fn parse_fields(fields: &str) -> Result<Self, String> {
+        let (mut eol, mut ignore_whitespaces, mut no_eof) = (None, None, None);
+        let mut algorithm = Algorithm::Sha256;
+        let mut format = None;
+
+        let boolean = |value: &str| match value {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(format!("invalid value '{}' in header", value)),
+        };
+
+        for field in fields.split_whitespace() {
+            let Some((key, value)) = field.split_once('=') else {
+                return Err(format!("invalid field '{}' in header", field));
+            };
+
+            match key {
+                "eol" => eol = Some(parse_eol(value)?),
+                "ignore_whitespaces" => ignore_whitespaces = Some(boolean(value)?),
+                "no_eof" => no_eof = Some(boolean(value)?),
+                "algorithm" => {
+                    algorithm = value
+                        .parse()
+                        .map_err(|_| format!("unsupported algorithm {} in header", value))?;
+                }
+                "format" => format = Some(value),
+                _ => {}
+            }
+        }
+
+        if format != Some(HEADER_FORMAT) {
+            return Err(format!(
+                "unsupported header format {}",
+                format.unwrap_or("(missing)")
+            ));
+        }
+
+        match (eol, ignore_whitespaces, no_eof) {
+            (Some(eol), Some(ignore_whitespaces), Some(no_eof)) => Ok(Self {
+                eol,
+                ignore_whitespaces,
+                no_eof,
+                algorithm,
+            }),
+            _ => Err("incomplete header".to_string()),
+        }
+    }
+
+    /// Find the header among the lines before the first checksum line.
+    pub fn find(content: &str) -> Option<Result<Self, String>> {
+        content
+            .lines()
+            .take_while(|line| matches!(parse_line(line), Ok(None)))
+            .find_map(Self::parse)
+    }
+}
+
+impl Display for Header {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", HEADER)?;
+        for (key, value) in self.fields() {
+            write!(f, " {}={}", key, value)?;
+        }
+        write!(f, " format={}", HEADER_FORMAT)
+    }
+}
+
+/// Parse the line ending of a header, a name like `lf` or hex bytes like `0x0d0a`.
+fn parse_eol(value: &str) -> Result<String, String> {
+    if let Some((_, eol)) = EOL_NAMES.iter().find(|(name, _)| *name == value) {
+        return Ok(eol.to_string());
+    }
+
+    let invalid = || format!("invalid eol '{}' in header", value);
+    let hex = value.strip_prefix("0x").ok_or_else(invalid)?;
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+/// Whether `path` contains a line break, which would split its checksum line.
+pub fn has_line_break(path: &Path) -> bool {
+    path.as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        .any(|byte| matches!(byte, b'\n' | b'\r'))
+}
+
+/// Path of `file` as listed in `checksum_file`, relative to the directory of the checksum file.
+///
+/// `--check` resolves relative paths against the directory of the checksum file, so files given
+/// relative to the current directory are listed relative to that directory instead. Absolute
+/// paths, standard input and files without a common ancestor are listed as given.
+pub fn listed_path(checksum_file: &Path, file: &Path) -> PathBuf {
+    let base = checksum_file.parent().unwrap_or(Path::new(""));
+    if file.is_absolute() || file == Path::new("-") || base.as_os_str().is_empty() {
+        return file.to_path_buf();
+    }
+
+    let cwd = env::current_dir().unwrap_or_default();
+    let (base, target) = (lexical(&cwd.join(base)), lexical(&cwd.join(file)));
+    let common = base
+        .components()
+        .zip(target.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == 0 {
+        return file.to_path_buf();
+    }
+
+    let mut listed = PathBuf::new();
+    for _ in base.components().skip(common) {
+        listed.push("..");
+    }
+    listed.extend(target.components().skip(common));
+    listed
+}
+
+/// Resolve `.` and `..` in `path` without looking at the file system.
+fn lexical(path: &Path) -> PathBuf {
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+    resolved
+}
+
+/// Format a GNU-style checksum line, like `hash  path`.
+pub fn format_gnu(path: &Path, hash: &str) -> String {
+    format!("{}  {}", hash, escape_path(path))
+}
+
+/// Format a BSD-style checksum line, like `SHA256 (path) = hash`.
+///
+/// The path is printed verbatim, even if it contains parentheses. Parsers have to split on the
+/// last occurrence of `) = `, which is unambiguous since the hash never contains it.
+pub fn format_bsd(algorithm: Algorithm, path: &Path, hash: &str) -> String {
+    format!("{} ({}) = {}", tag(algorithm), escape_path(path), hash)
+}
+
+/// A single entry of a checksum file.
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    /// Algorithm named in a BSD-style line, `None` for GNU-style lines.
+    pub algorithm: Option<String>,
+
+    /// Expected hash, in lowercase hex.
+    ///
+    /// Hashes in other encodings are converted to hex, unknown encodings are kept as is.
+    pub hash: String,
+
+    /// Path of the file to check, as written in the checksum file.
+    pub path: PathBuf,
+}
+
+/// Decode a hash of any algorithm in any encoding into lowercase hex.
+fn decode_hash(hash: &str) -> Option<String> {
+    Encoding::decode_pasted(hash)
+        .ok()
+        .map(|digest| Encoding::Hex.encode(&digest))
+}
+
+/// Parse a GNU-style line, like `hash  path` or `hash *path`.
+///
+/// A single space before the path is accepted as well, as written by hand or by other tools.
+fn parse_gnu(line: &str) -> Option<Entry> {
+    let (hash, path) = line.split_once(' ')?;
+    let path = path.strip_prefix([' ', '*']).unwrap_or(path);
+
+    let hash = decode_hash(hash)?;
+    if path.is_empty() {
+        return None;
+    }
+
+    Some(Entry {
+        algorithm: None,
+        hash,
+        path: unescape_path(path),
+    })
+}
+
+/// Parse a BSD-style line, like `SHA256 (path) = hash`.
+fn parse_bsd(line: &str) -> Option<Entry> {
+    let (algorithm, rest) = line.split_once(" (")?;
+    let (path, hash) = rest.rsplit_once(") = ")?;
+
+    let is_algorithm = !algorithm.is_empty()
+        && algorithm
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+
+    // The hash length depends on the algorithm, it is checked when verifying
+    let is_hex = hash.chars().all(|c| c.is_ascii_hexdigit());
+    let hash = match decode_hash(hash) {
+        Some(hash) => hash,
+        None if is_hex => hash.to_ascii_lowercase(),
+        None => return None,
+    };
+
+    if !is_algorithm || path.is_empty() {
+        return None;
+    }
+
+    Some(Entry {
+        algorithm: Some(algorithm.to_string()),
+        hash,
+        path: unescape_path(path),
+    })
+}
+
+/// Parse a single line of a checksum file, detecting GNU or BSD style automatically.
+///
+/// Returns `Ok(None)` for blank lines and comments and `Err(())` for malformed lines.
+pub fn parse_line(line: &str) -> Result<Option<Entry>, ()> {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+
+    if line.trim().is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    match parse_gnu(line).or_else(|| parse_bsd(line)) {
+        Some(entry) => Ok(Some(entry)),
+        None => Err(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "b62e3392d1ef3737f39339a43976186a039d53f30a95b987ccf8c5843dba94a2";
+
+    #[test]
+    fn parse_gnu_lines() {
+        let expected = Entry {
+            algorithm: None,
+            hash: HASH.to_string(),
+            path: PathBuf::from("dir/input.txt"),
+        };
+
+        assert_eq!(
+            parse_line(&format!("{HASH}  dir/input.txt")),
+            Ok(Some(expected))
+        );
+        assert_eq!(
+            parse_line(&format!("{} *dir/input.txt\r", HASH.to_uppercase()))
+                .unwrap()
+                .unwrap()
+                .hash,
+            HASH
+        );
+        assert_eq!(
+            parse_line(&format!("{HASH} dir/input.txt")),
+            parse_line(&format!("{HASH}  dir/input.txt"))
+        );
+        assert_eq!(
+            parse_line("sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=  empty.txt")
+                .unwrap()
+                .unwrap()
+                .hash,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn parse_bsd_lines() {
+        let path = Path::new("input (copy) = 1).txt");
+        let entry = parse_line(&format_bsd(Algorithm::Sha256, path, HASH))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            entry,
+            Entry {
+                algorithm: Some(String::from("SHA256")),
+                hash: HASH.to_string(),
+                path: path.to_path_buf(),
+            }
+        );
+    }
+
+    #[test]
+    fn listed_paths() {
+        let cases = [
+            ("SUMS", "dist/a.tar", "dist/a.tar"),
+            ("dist/SUMS", "dist/a.tar", "a.tar"),
+            ("dist/SUMS", "./dist/sub/../a.tar", "a.tar"),
+            ("dist/SUMS", "src/main.rs", "../src/main.rs"),
+            ("out/sums/SUMS", "a.txt", "../../a.txt"),
+            ("dist/SUMS", "-", "-"),
+        ];
+
+        for (checksum_file, file, listed) in cases {
+            assert_eq!(
+                listed_path(Path::new(checksum_file), Path::new(file)),
+                Path::new(listed),
+                "{file} in {checksum_file}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_ignored_and_malformed_lines() {
+        assert_eq!(parse_line(""), Ok(None));
+        assert_eq!(parse_line("  "), Ok(None));
+        assert_eq!(parse_line("# comment"), Ok(None));
+
+        assert_eq!(parse_line("garbage"), Err(()));
+        assert_eq!(parse_line(&format!("{}  input.txt", &HASH[1..])), Err(()));
+        assert_eq!(parse_line(&format!("{HASH}  ")), Err(()));
+        assert_eq!(parse_line("SHA256 (input.txt) = xyz"), Err(()));
+    }
+
+    #[test]
+    fn header_round_trip() {
+        for eol in ["\n", "\r\n", "\r", "", "; "] {
+            let header = Header {
+                eol: eol.to_string(),
+                ignore_whitespaces: true,
+                no_eof: false,
+                algorithm: Algorithm::Sha256,
+            };
+            assert_eq!(Header::parse(&header.to_string()), Some(Ok(header)));
+        }
+
+        let mut header = Header {
+            eol: "\r\n".to_string(),
+            ignore_whitespaces: false,
+            no_eof: true,
+            algorithm: Algorithm::Sha256,
+        };
+        assert_eq!(
+            header.to_string(),
+            "# normalized-hasher: eol=crlf ignore_whitespaces=false no_eof=true algorithm=sha256 format=v1"
+        );
+
+        header.algorithm = Algorithm::Sha512;
+        assert_eq!(
+            header.to_string(),
+            "# normalized-hasher: eol=crlf ignore_whitespaces=false no_eof=true algorithm=sha512 format=v1"
+        );
+        assert_eq!(Header::parse(&header.to_string()), Some(Ok(header)));
+    }
+
+    #[test]
+    fn header_errors() {
+        assert_eq!(Header::parse("# comment"), None);
+        assert!(Header::parse("# normalized-hasher: eol=lf format=v1")
+            .unwrap()
+            .is_err());
+        assert!(Header::parse(
+            "# normalized-hasher: eol=lf ignore_whitespaces=false no_eof=false format=v2"
+        )
+        .unwrap()
+        .is_err());
+        assert!(Header::parse(
+            "# normalized-hasher: eol=lf ignore_whitespaces=false no_eof=false algorithm=md5 format=v1"
+        )
+        .unwrap()
+        .is_err());
+        assert!(Header::parse(
+            "# normalized-hasher: eol=0xzz ignore_whitespaces=false no_eof=false format=v1"
+        )
+        .unwrap()
+        .is_err());
+    }
+
+    #[test]
+    fn header_before_first_entry() {
+        let header = "# normalized-hasher: eol=cr ignore_whitespaces=false no_eof=false format=v1";
+
+        let content = format!("# generated\n{header}\n{HASH}  a.txt\n");
+        assert_eq!(Header::find(&content).unwrap().unwrap().eol, "\r");
+
+        let content = format!("{HASH}  a.txt\n{header}\n");
+        assert_eq!(Header::find(&content), None);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::exit::ExitStatus;
+
+/// Destination of `--copy` and source of `--from-clipboard`, behind a trait so that it can be
+/// replaced in tests.
+pub trait Clipboard {
+    /// Replace the content of the clipboard with `text`.
+    fn set_text(&mut self, text: &str) -> Result<(), String>;
+
+    /// Get the text in the clipboard, which is an error if it holds no text at all.
+    fn get_text(&mut self) -> Result<String, String>;
+}
+
+/// The clipboard of the desktop system.
+#[cfg(feature = "clipboard")]
+pub struct SystemClipboard(arboard::Clipboard);
+
+#[cfg(feature = "clipboard")]
+impl SystemClipboard {
+    /// Connect to the clipboard, which fails on systems without a desktop session.
+    pub fn new() -> Result<Self, String> {
+        arboard::Clipboard::new()
+            .map(Self)
+            .map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(feature = "clipboard")]
+impl Clipboard for SystemClipboard {
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        self.0.set_text(text).map_err(|err| err.to_string())
+    }
+
+    fn get_text(&mut self) -> Result<String, String> {
+        self.0.get_text().map_err(|err| match err {
+            arboard::Error::ContentNotAvailable => String::from("the clipboard contains no text"),
+            err => err.to_string(),
+        })
+    }
+}
+
+/// Placeholder if the binary was built without the `clipboard` feature.
+#[cfg(not(feature = "clipboard"))]
+pub struct SystemClipboard(std::convert::Infallible);
+
+#[cfg(not(feature = "clipboard"))]
+impl SystemClipboard {
+    pub fn new() -> Result<Self, String> {
+        Err(String::from(
+            "this build does not support the clipboard, enable the feature `clipboard`",
+        ))
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+impl Clipboard for SystemClipboard {
+    fn set_text(&mut self, _text: &str) -> Result<(), String> {
+        match self.0 {}
+    }
+
+    fn get_text(&mut self) -> Result<String, String> {
+        match self.0 {}
+    }
+}
+
+/// Copy `text` to the clipboard, printing an error if that is not possible.
+pub fn copy(clipboard: Result<impl Clipboard, String>, text: &str) -> ExitStatus {
+    match clipboard.and_then(|mut clipboard| clipboard.set_text(text)) {
+        Ok(()) => ExitStatus::Success,
+        Err(err) => {
+            eprintln!("error: cannot copy to clipboard: {}", err);
+            ExitStatus::Io
+        }
+    }
+}
+
+/// Get the text in the clipboard, printing an error if there is none.
+///
+/// An empty clipboard is an error as well, since its hash is never what was meant to be checked.
+pub fn paste(clipboard: Result<impl Clipboard, String>) -> Result<String, ExitStatus> {
+    let text = clipboard
+        .and_then(|mut clipboard| clipboard.get_text())
+        .and_then(|text| {
+            if text.is_empty() {
+                Err(String::from("the clipboard is empty"))
+            } else {
+                Ok(text)
+            }
+        });
+
+    text.map_err(|err| {
+        eprintln!("error: cannot read from clipboard: {}", err);
+        ExitStatus::Io
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StubClipboard<'a>(&'a mut Option<String>);
+
+    impl Clipboard for StubClipboard<'_> {
+        fn set_text(&mut self, text: &str) -> Result<(), String> {
+            *self.0 = Some(text.to_string());
+            Ok(())
+        }
+
+        fn get_text(&mut self) -> Result<String, String> {
+            self.0
+                .clone()
+                .ok_or_else(|| String::from("the clipboard contains no text"))
+        }
+    }
+
+    #[test]
+    fn check_copy() {
+        let mut content = None;
+
+        let status = copy(Ok(StubClipboard(&mut content)), "abc");
+        assert_eq!(status, ExitStatus::Success);
+        assert_eq!(content.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn check_copy_unavailable() {
+        let status = copy(Err::<StubClipboard, _>(String::from("no display")), "abc");
+        assert_eq!(status, ExitStatus::Io);
+    }
+
+    #[test]
+    fn check_paste() {
+        let mut content = Some(String::from("A B\r\nC D"));
+        assert_eq!(
+            paste(Ok(StubClipboard(&mut content))).as_deref(),
+            Ok("A B\r\nC D")
+        );
+
+        let mut empty = Some(String::new());
+        assert_eq!(paste(Ok(StubClipboard(&mut empty))), Err(ExitStatus::Io));
+
+        let mut image = None;
+        assert_eq!(paste(Ok(StubClipboard(&mut image))), Err(ExitStatus::Io));
+
+        let headless = Err::<StubClipboard, _>(String::from("no display"));
+        assert_eq!(paste(headless), Err(ExitStatus::Io));
+    }
+}
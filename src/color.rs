@@ -0,0 +1,109 @@
+use std::env;
+use std::fmt::{self, Display, Formatter};
+
+use anstyle::{AnsiColor, Style};
+use clap::ValueEnum;
+
+/// When to colorize results, given with `--color`.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum ColorChoice {
+    /// Colorize if printing to a terminal and `NO_COLOR` is not set
+    #[default]
+    Auto,
+
+    /// Always colorize
+    Always,
+
+    /// Never colorize
+    Never,
+}
+
+/// Colors for the results of check, verify and compare modes.
+#[derive(Clone, Copy)]
+pub struct Palette {
+    enabled: bool,
+}
+
+impl Palette {
+    /// Decide whether to colorize, where `terminal` tells if the output goes to a terminal.
+    pub fn new(choice: ColorChoice, terminal: bool) -> Self {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+        let enabled = match choice {
+            ColorChoice::Auto => terminal && !no_color,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        };
+
+        Self { enabled }
+    }
+
+    /// Paint a successful result, like `OK`, in green.
+    pub fn ok<T: Display>(self, text: T) -> Painted<T> {
+        self.paint(text, AnsiColor::Green)
+    }
+
+    /// Paint a failed result, like `FAILED`, in red.
+    pub fn failed<T: Display>(self, text: T) -> Painted<T> {
+        self.paint(text, AnsiColor::Red)
+    }
+
+    /// Paint a missing result, like `MISSING`, in yellow.
+    pub fn missing<T: Display>(self, text: T) -> Painted<T> {
+        self.paint(text, AnsiColor::Yellow)
+    }
+
+    fn paint<T: Display>(self, text: T, color: AnsiColor) -> Painted<T> {
+        let style = if self.enabled {
+            Style::new().fg_color(Some(color.into()))
+        } else {
+            Style::new()
+        };
+
+        Painted { text, style }
+    }
+}
+
+/// Text that is printed with a style, if colors are enabled.
+pub struct Painted<T> {
+    text: T,
+    style: Style,
+}
+
+impl<T: Display> Display for Painted<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            self.style.render(),
+            self.text,
+            self.style.render_reset()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_paint() {
+        let palette = Palette::new(ColorChoice::Always, false);
+        assert_eq!(palette.ok("OK").to_string(), "\x1b[32mOK\x1b[0m");
+        assert_eq!(
+            palette.failed("FAILED").to_string(),
+            "\x1b[31mFAILED\x1b[0m"
+        );
+        assert_eq!(
+            palette.missing("MISSING").to_string(),
+            "\x1b[33mMISSING\x1b[0m"
+        );
+
+        let palette = Palette::new(ColorChoice::Never, true);
+        assert_eq!(palette.ok("OK").to_string(), "OK");
+        assert_eq!(palette.failed("FAILED").to_string(), "FAILED");
+
+        let palette = Palette::new(ColorChoice::Auto, false);
+        assert_eq!(palette.ok("OK").to_string(), "OK");
+    }
+}
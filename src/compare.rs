@@ -0,0 +1,264 @@
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::{ArgGroup, Args, ValueHint};
+
+use normalized_hash::Hasher;
+
+use crate::color::Palette;
+use crate::exit::ExitStatus;
+use crate::git;
+use crate::http::{self, Request};
+use crate::Select;
+
+/// Largest file with an expected hash that is downloaded with `--expected-url`.
+const EXPECTED_LIMIT: u64 = 64 * 1024;
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("remote").args(["url", "expected_url"])))]
+pub struct CompareArgs {
+    /// First file to compare
+    #[arg(value_hint = ValueHint::FilePath)]
+    file_a: OsString,
+
+    /// Second file to compare
+    #[arg(
+        value_hint = ValueHint::FilePath,
+        required_unless_present_any = ["remote", "git"],
+        conflicts_with_all = ["remote", "git"]
+    )]
+    file_b: Option<OsString>,
+
+    /// Compare with the first file as committed at the revision REV, like `HEAD`
+    ///
+    /// The file is read from the git repository that contains it. Both versions are normalized
+    /// alike, so only changes of the normalized content are reported.
+    #[arg(long, value_name = "REV", conflicts_with = "remote")]
+    git: Option<String>,
+
+    /// Compare with the content downloaded from URL instead of a second file
+    ///
+    /// The content is normalized while it is downloaded, like a second file. Redirects are
+    /// followed, any response but a success is an error.
+    #[arg(long, value_name = "URL", value_hint = ValueHint::Url)]
+    url: Option<String>,
+
+    /// Compare the hash of the first file with the hash downloaded from URL
+    ///
+    /// The downloaded file has to start with the expected hash, in any encoding of
+    /// `--expected`, so checksum files with a single line like `HASH  FILE` work as well.
+    #[arg(
+        long,
+        value_name = "URL",
+        value_hint = ValueHint::Url,
+        conflicts_with = "url"
+    )]
+    expected_url: Option<String>,
+
+    /// Send an additional header with the downloads, like `Authorization: Bearer TOKEN`
+    #[arg(
+        long,
+        value_name = "HEADER",
+        value_parser = http::parse_header,
+        requires = "remote"
+    )]
+    header: Vec<(String, String)>,
+
+    /// Give up on downloads without a response after SECONDS
+    #[arg(long, value_name = "SECONDS", default_value_t = 30)]
+    timeout: u64,
+}
+
+/// Result of comparing the normalized lines of two files.
+#[derive(Debug, PartialEq)]
+pub enum Comparison {
+    Equal,
+
+    /// The files differ, starting with the given (1-based) line number.
+    Different(usize),
+}
+
+/// Compare two sequences of normalized lines.
+pub fn compare(
+    mut lines_a: impl Iterator<Item = io::Result<String>>,
+    mut lines_b: impl Iterator<Item = io::Result<String>>,
+) -> io::Result<Comparison> {
+    let mut line_number = 1;
+    loop {
+        match (lines_a.next().transpose()?, lines_b.next().transpose()?) {
+            (None, None) => return Ok(Comparison::Equal),
+            (Some(line_a), Some(line_b)) if line_a == line_b => line_number += 1,
+            _ => return Ok(Comparison::Different(line_number)),
+        }
+    }
+}
+
+/// Print the result of comparing `a` and `b`.
+fn report(
+    a: impl Display,
+    b: impl Display,
+    result: io::Result<Comparison>,
+    palette: Palette,
+) -> ExitStatus {
+    match result {
+        Ok(Comparison::Equal) => {
+            println!("{} and {} are {}", a, b, palette.ok("equal"));
+            ExitStatus::Success
+        }
+
+        Ok(Comparison::Different(line_number)) => {
+            println!(
+                "{} and {} {} in line {}",
+                a,
+                b,
+                palette.failed("differ"),
+                line_number
+            );
+            ExitStatus::Mismatch
+        }
+
+        Err(err) => {
+            eprintln!("error: cannot compare {} and {}: {}", a, b, err);
+            ExitStatus::Io
+        }
+    }
+}
+
+/// Download `url`, printing an error naming the URL if that is not possible.
+fn fetch(url: &str, request: &Request) -> Result<Box<dyn io::BufRead>, ExitStatus> {
+    http::fetch(url, request).map_err(|err| {
+        eprintln!("error: cannot download {}: {}", url, err);
+        ExitStatus::Io
+    })
+}
+
+/// Compare the hash of `file` with the expected hash downloaded from `url`.
+fn compare_expected(
+    hasher: &Hasher,
+    file: &Path,
+    url: &str,
+    request: &Request,
+    palette: Palette,
+) -> ExitStatus {
+    let reader = match fetch(url, request) {
+        Ok(reader) => reader,
+        Err(status) => return status,
+    };
+
+    let mut content = String::new();
+    if let Err(err) = reader.take(EXPECTED_LIMIT).read_to_string(&mut content) {
+        eprintln!("error: cannot download {}: {}", url, err);
+        return ExitStatus::Io;
+    }
+
+    let expected = match crate::parse_expected(content.split_whitespace().next().unwrap_or("")) {
+        Ok(expected) => expected,
+        Err(err) => {
+            eprintln!("error: {} contains no hash: {}", url, err);
+            return ExitStatus::Malformed;
+        }
+    };
+
+    match hasher.try_hash_file(file, None::<&Path>) {
+        Ok(hash) if hash == expected => {
+            println!(
+                "{} {} the hash from {}",
+                file.display(),
+                palette.ok("matches"),
+                url
+            );
+            ExitStatus::Success
+        }
+        Ok(hash) => {
+            println!(
+                "{} {} the hash from {}",
+                file.display(),
+                palette.failed("does not match"),
+                url
+            );
+            println!("expected: {}", expected);
+            println!("actual:   {}", hash);
+            ExitStatus::Mismatch
+        }
+        Err(err) => {
+            eprintln!("error: cannot hash {}: {}", file.display(), err);
+            ExitStatus::Io
+        }
+    }
+}
+
+/// Compare a file with another one, a revision in git or a download.
+///
+/// Each file is normalized with the options of its directory, and revisions and downloads with
+/// those of the first file.
+pub fn run(select: Select, args: &CompareArgs, palette: Palette) -> ExitStatus {
+    let paths = [Some(&args.file_a), args.file_b.as_ref()];
+    let paths = paths
+        .into_iter()
+        .flatten()
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    let hashers = match select(&paths.iter().collect::<Vec<_>>()) {
+        Ok(hashers) => hashers,
+        Err(status) => return status,
+    };
+    let hasher = hashers.of(0);
+
+    let file_a = Path::new(&args.file_a);
+    let request = Request {
+        headers: &args.header,
+        timeout: Duration::from_secs(args.timeout),
+    };
+
+    if let Some(url) = &args.expected_url {
+        return compare_expected(hasher, file_a, url, &request, palette);
+    }
+
+    if let Some(rev) = &args.git {
+        let blob = match git::read_blob(file_a, rev) {
+            Ok(blob) => blob,
+            Err(err) => {
+                eprintln!(
+                    "error: cannot read {} at {}: {}",
+                    file_a.display(),
+                    rev,
+                    err
+                );
+                return ExitStatus::Io;
+            }
+        };
+
+        let result = hasher.normalized_lines(file_a).and_then(|lines_a| {
+            compare(
+                lines_a,
+                hasher.normalized_lines_from(blob.content.as_slice()),
+            )
+        });
+        return report(file_a.display(), blob.label, result, palette);
+    }
+
+    if let Some(url) = &args.url {
+        let lines_a = match hasher.normalized_lines(file_a) {
+            Ok(lines) => lines,
+            Err(err) => return report(file_a.display(), url, Err(err), palette),
+        };
+        let reader = match fetch(url, &request) {
+            Ok(reader) => reader,
+            Err(status) => return status,
+        };
+
+        let result = compare(lines_a, hasher.normalized_lines_from(reader));
+        return report(file_a.display(), url, result, palette);
+    }
+
+    // Guaranteed by clap, since the second file is required without a URL
+    let file_b = Path::new(args.file_b.as_deref().unwrap());
+    let result = hasher
+        .normalized_lines(file_a)
+        .and_then(|lines_a| compare(lines_a, hashers.of(1).normalized_lines(file_b)?));
+
+    report(file_a.display(), file_b.display(), result, palette)
+}
@@ -0,0 +1,22 @@
+use std::io;
+
+use clap::{Args, CommandFactory};
+use clap_complete::Shell;
+
+use crate::exit::ExitStatus;
+use crate::Cli;
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    /// Shell to generate the completion script for
+    shell: Shell,
+}
+
+/// Print the completion script for a shell to stdout.
+pub fn run(args: &CompletionsArgs) -> ExitStatus {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(args.shell, &mut command, name, &mut io::stdout());
+
+    ExitStatus::Success
+}
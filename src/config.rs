@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Command, CommandFactory};
+
+use normalized_hash::{Algorithm, Config};
+
+use crate::environment;
+use crate::error;
+use crate::exit::ExitStatus;
+use crate::output::Output;
+use crate::{Cli, HashAlgorithm, NormalizationArgs};
+
+/// Keys of configuration files, with the ids of the command line arguments they set.
+const SETTINGS: [(&str, &str); 7] = [
+    ("eol", "eol"),
+    ("ignore-whitespaces", "ignore_whitespaces"),
+    ("no-eof", "no_eof"),
+    ("algorithm", "algorithm"),
+    ("exclude", "exclude"),
+    ("no-hidden", "no_hidden"),
+    ("no-ignore", "no_ignore"),
+];
+
+/// Where a configuration file was found.
+#[derive(Clone, Copy)]
+enum Scope {
+    /// In the platform configuration directory.
+    User,
+
+    /// In the current directory or one of its parents.
+    Project,
+
+    /// Given with `--config`.
+    Explicit,
+}
+
+/// Loaded configuration file.
+#[derive(Clone)]
+pub struct Layer {
+    scope: Scope,
+    path: PathBuf,
+    config: Config,
+}
+
+impl Layer {
+    /// Describe where a setting of this file comes from, for `--show-config`.
+    fn describe(&self) -> String {
+        let scope = match self.scope {
+            Scope::User => "user config",
+            Scope::Project => "project config",
+            Scope::Explicit => "config",
+        };
+        format!("{} {}", scope, self.path.display())
+    }
+}
+
+/// Value of a setting, `None` if the configuration file does not give it.
+fn value(config: &Config, key: &str) -> Option<toml::Value> {
+    let string = |value: &Option<String>| value.clone().map(toml::Value::String);
+    let boolean = |value: Option<bool>| value.map(toml::Value::Boolean);
+
+    match key {
+        "eol" => string(&config.eol),
+        "ignore-whitespaces" => boolean(config.ignore_whitespaces),
+        "no-eof" => boolean(config.no_eof),
+        "algorithm" => config
+            .algorithm
+            .map(|algorithm| toml::Value::String(algorithm.name().to_string())),
+        "exclude" => config.exclude.clone().map(|patterns| {
+            toml::Value::Array(patterns.into_iter().map(toml::Value::String).collect())
+        }),
+        "no-hidden" => boolean(config.no_hidden),
+        "no-ignore" => boolean(config.no_ignore),
+        _ => unreachable!("unknown setting {}", key),
+    }
+}
+
+/// Format a value as in a configuration file, with control characters escaped.
+fn format(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(string) => {
+            let mut quoted = String::from('"');
+            for c in string.chars() {
+                match c {
+                    '"' => quoted.push_str("\\\""),
+                    '\\' => quoted.push_str("\\\\"),
+                    '\n' => quoted.push_str("\\n"),
+                    '\r' => quoted.push_str("\\r"),
+                    '\t' => quoted.push_str("\\t"),
+                    c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+                    c => quoted.push(c),
+                }
+            }
+            quoted.push('"');
+            quoted
+        }
+        toml::Value::Array(values) => {
+            let values = values.iter().map(format).collect::<Vec<_>>();
+            format!("[{}]", values.join(", "))
+        }
+        value => value.to_string(),
+    }
+}
+
+/// Path of the user configuration file, like `~/.config/normalized-hasher/config.toml`.
+fn user_config() -> Option<PathBuf> {
+    #[cfg(windows)]
+    let dir = env::var_os("APPDATA").map(PathBuf::from);
+
+    #[cfg(not(windows))]
+    let dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+
+    Some(dir?.join("normalized-hasher").join("config.toml"))
+}
+
+/// Read a configuration file, printing an error if it is unreadable or invalid.
+fn read(scope: Scope, path: PathBuf) -> Result<Layer, ExitStatus> {
+    match Config::load(&path) {
+        Ok(config) => Ok(Layer {
+            scope,
+            path,
+            config,
+        }),
+        Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+            let message = format_args!("invalid configuration {}: {}", path.display(), err);
+            error::fatal("usage", Some(&path), message);
+            Err(ExitStatus::Usage)
+        }
+        Err(err) => {
+            let message = format_args!(
+                "cannot read configuration {}: {}",
+                path.display(),
+                error::message(&err)
+            );
+            error::fatal("io", Some(&path), message);
+            Err(ExitStatus::Io)
+        }
+    }
+}
+
+/// Load the configuration files that apply to `dir`, from lowest to highest precedence.
+///
+/// With `--config`, only the given file is read. Otherwise, the user configuration file and all
+/// `.normalized-hasher.toml` files in `dir` and its parents up to the repository root are read,
+/// if they exist, with nearer files taking precedence. With `--no-config`, no files are read at
+/// all.
+pub fn load(dir: &Path, explicit: Option<&Path>, disabled: bool) -> Result<Vec<Layer>, ExitStatus> {
+    if disabled {
+        return Ok(Vec::new());
+    }
+
+    if let Some(path) = explicit {
+        return Ok(vec![read(Scope::Explicit, path.to_path_buf())?]);
+    }
+
+    let mut layers = Vec::new();
+    if let Some(path) = user_config().filter(|path| path.is_file()) {
+        layers.push(read(Scope::User, path)?);
+    }
+    for path in Config::find_all(dir) {
+        layers.push(read(Scope::Project, path)?);
+    }
+
+    Ok(layers)
+}
+
+/// Absolute path of the directory of `file`, where its configuration files are searched.
+///
+/// Standard input and files without a directory are in the current directory.
+pub fn directory_of(file: &Path) -> PathBuf {
+    let dir = match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    std::path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf())
+}
+
+/// Normalization options of input files, with the configuration files of their directories.
+///
+/// Each file gets the settings of all `.normalized-hasher.toml` files from the repository root
+/// down to its directory, so subtrees can have their own settings. Flags and environment
+/// variables still take precedence. Files whose directories have the same configuration files
+/// share their options, the first options are those of the current directory.
+pub struct Directories {
+    explicit: Option<PathBuf>,
+    disabled: bool,
+
+    /// Configuration files of the directories that were looked up.
+    found: HashMap<PathBuf, Vec<PathBuf>>,
+
+    /// Configuration files of each entry in `options`.
+    chains: Vec<Vec<PathBuf>>,
+    options: Vec<NormalizationArgs>,
+}
+
+impl Directories {
+    /// Start with the options of `cli`, which were parsed with the configuration files of the
+    /// current directory.
+    pub fn new(cli: &Cli) -> Self {
+        let cwd = env::current_dir().unwrap_or_default();
+        Self {
+            explicit: cli.config.clone(),
+            disabled: cli.no_config,
+            found: HashMap::new(),
+            chains: vec![Config::find_all(cwd)],
+            options: vec![cli.normalization.clone()],
+        }
+    }
+
+    /// Index of the options of `file`, to be passed to [`Directories::options`].
+    ///
+    /// A new configuration file that is invalid is an error.
+    pub fn index(&mut self, file: &Path) -> Result<usize, ExitStatus> {
+        if self.disabled || self.explicit.is_some() {
+            return Ok(0);
+        }
+
+        let dir = directory_of(file);
+        let chain = self
+            .found
+            .entry(dir.clone())
+            .or_insert_with(|| Config::find_all(&dir));
+        if let Some(index) = self.chains.iter().position(|known| known == chain) {
+            return Ok(index);
+        }
+        let chain = chain.clone();
+
+        let layers = load(&dir, None, false)?;
+        let (mut cli, matches) = crate::parse_with(&layers)?;
+        cli.normalization.given = given(&matches, &layers);
+
+        self.chains.push(chain);
+        self.options.push(cli.normalization);
+        Ok(self.options.len() - 1)
+    }
+
+    /// Options at an index returned by [`Directories::index`].
+    pub fn options(&self, index: usize) -> &NormalizationArgs {
+        &self.options[index]
+    }
+}
+
+/// Set the value of an argument of the command and all its subcommands as default value.
+fn set_default(mut command: Command, id: &str, value: &toml::Value) -> Command {
+    if command.get_arguments().any(|arg| arg.get_id() == id) {
+        command = command.mut_arg(id, |arg| match value {
+            toml::Value::String(string) => arg.default_value(string.clone()),
+            toml::Value::Array(values) => arg.default_values(
+                values
+                    .iter()
+                    .filter_map(|value| value.as_str().map(str::to_string)),
+            ),
+            value => arg.default_value(value.to_string()),
+        });
+    }
+
+    let names = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect::<Vec<_>>();
+    for name in names {
+        command = command.mut_subcommand(name, |subcommand| set_default(subcommand, id, value));
+    }
+
+    command
+}
+
+/// Use the settings of the configuration files as default values of the command line arguments.
+///
+/// Thus, flags given on the command line take precedence over all configuration files, and
+/// later files over earlier ones.
+pub fn apply(mut command: Command, layers: &[Layer]) -> Command {
+    for (key, id) in SETTINGS {
+        if let Some(value) = layers
+            .iter()
+            .rev()
+            .find_map(|layer| value(&layer.config, key))
+        {
+            command = set_default(command, id, &value);
+        }
+    }
+
+    command
+}
+
+/// Effective value of each setting, with a description of where it comes from.
+fn effective(matches: &ArgMatches, layers: &[Layer]) -> Vec<(&'static str, toml::Value, String)> {
+    SETTINGS
+        .iter()
+        .map(|&(key, id)| {
+            let value = match key {
+                "eol" => {
+                    toml::Value::String(matches.get_one::<String>(id).cloned().unwrap_or_default())
+                }
+                "algorithm" => {
+                    let algorithm = matches.get_one::<HashAlgorithm>(id).copied();
+                    let name = algorithm.map_or("", |algorithm| Algorithm::from(algorithm).name());
+                    toml::Value::String(name.to_string())
+                }
+                "exclude" => toml::Value::Array(
+                    matches
+                        .get_many::<String>(id)
+                        .into_iter()
+                        .flatten()
+                        .map(|pattern| toml::Value::String(pattern.clone()))
+                        .collect(),
+                ),
+                _ => toml::Value::Boolean(matches.get_flag(id)),
+            };
+
+            let source = match matches.value_source(id) {
+                Some(ValueSource::CommandLine) => "command line".to_string(),
+                Some(ValueSource::EnvVariable) => {
+                    format!("environment {}", environment::variable(id))
+                }
+                _ => layers
+                    .iter()
+                    .rev()
+                    .find(|layer| self::value(&layer.config, key).is_some())
+                    .map_or_else(|| "default".to_string(), Layer::describe),
+            };
+
+            (key, value, source)
+        })
+        .collect()
+}
+
+/// Ids of the normalization options that are given by a flag, an environment variable or a
+/// configuration file, instead of being at their built-in default.
+pub fn given(matches: &ArgMatches, layers: &[Layer]) -> Vec<&'static str> {
+    effective(matches, layers)
+        .into_iter()
+        .filter(|(key, _, source)| {
+            matches!(*key, "eol" | "ignore-whitespaces" | "no-eof" | "algorithm")
+                && source != "default"
+        })
+        .filter_map(|(key, _, _)| SETTINGS.iter().find(|(name, _)| *name == key))
+        .map(|(_, id)| *id)
+        .collect()
+}
+
+/// Print the effective settings and where they come from, for `--show-config`.
+pub fn show(out: &mut Output, matches: &ArgMatches, layers: &[Layer]) {
+    for (key, value, source) in effective(matches, layers) {
+        out.line(format_args!("{} = {}  # {}", key, format(&value), source));
+    }
+}
+
+/// Write the effective settings into a configuration file, for `--write-config`.
+///
+/// Each setting is preceded by a comment with the help of its flag. An existing file is only
+/// replaced with `force`.
+pub fn write(path: &Path, force: bool, matches: &ArgMatches, layers: &[Layer]) -> ExitStatus {
+    let command = Cli::command();
+
+    let mut content = String::from("# Settings of normalized-hasher, written by --write-config\n");
+    for (key, value, _) in effective(matches, layers) {
+        let id = SETTINGS
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, id)| *id);
+        let help = command
+            .get_arguments()
+            .find(|arg| Some(arg.get_id().as_str()) == id)
+            .and_then(|arg| arg.get_help())
+            .map(|help| help.to_string())
+            .unwrap_or_default();
+        content.push_str(&format!("\n# {}\n{} = {}\n", help, key, format(&value)));
+    }
+
+    let file = if force {
+        File::create(path)
+    } else {
+        OpenOptions::new().write(true).create_new(true).open(path)
+    };
+
+    let result = file.and_then(|mut file| file.write_all(content.as_bytes()));
+    match result {
+        Ok(()) => ExitStatus::Success,
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+            let message = format_args!(
+                "{} already exists, use --force to overwrite it",
+                path.display()
+            );
+            error::fatal("usage", Some(path), message);
+            ExitStatus::Usage
+        }
+        Err(err) => {
+            let message = format_args!("cannot write {}: {}", path.display(), error::message(&err));
+            error::fatal("write", Some(path), message);
+            ExitStatus::Io
+        }
+    }
+}
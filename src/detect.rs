@@ -0,0 +1,147 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use normalized_hash::{Hasher, Stats, TextEncoding};
+
+use crate::check::plural;
+use crate::error;
+use crate::exit::ExitStatus;
+use crate::output::{self, Output};
+
+/// Line endings of a text file, as written in the JSON output.
+#[derive(Serialize)]
+struct LineEndings {
+    /// Most common style, `CRLF`, `LF` or `CR`, missing if there are no line endings.
+    dominant: Option<&'static str>,
+
+    /// Whether more than one style is used.
+    mixed: bool,
+
+    crlf: usize,
+    lf: usize,
+    cr: usize,
+}
+
+impl LineEndings {
+    fn new(stats: &Stats) -> Self {
+        let styles = [("CRLF", stats.crlf), ("LF", stats.lf), ("CR", stats.cr)];
+
+        // Ties are decided in the order above
+        let dominant = styles
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .filter(|(_, count)| *count > 0)
+            .map(|(style, _)| *style);
+
+        Self {
+            dominant,
+            mixed: stats.is_mixed_eol(),
+            crlf: stats.crlf,
+            lf: stats.lf,
+            cr: stats.cr,
+        }
+    }
+}
+
+/// Findings about a file, as written in the JSON output.
+#[derive(Serialize)]
+struct Findings {
+    path: String,
+    encoding: String,
+    bom: bool,
+
+    /// Line endings, missing for binary files like the following fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line_endings: Option<LineEndings>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    final_newline: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    invisible_characters: Option<usize>,
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// Print what a file looks like, without hashing it, with `--detect`.
+pub fn run(hasher: &Hasher, out: &mut Output, file: &Path, json: bool) -> ExitStatus {
+    let result = normalized_hash::detect_encoding(file).and_then(|detection| {
+        let stats = detection
+            .text
+            .as_deref()
+            .map(|text| hasher.analyze_reader(text.as_bytes()))
+            .transpose()?;
+        Ok((detection.encoding, detection.bom, stats))
+    });
+
+    let (encoding, bom, stats) = match result {
+        Ok(findings) => findings,
+        Err(err) => {
+            let message = error::describe(&err, file, None);
+            error::fatal(error::kind(&err), Some(file), message);
+            return ExitStatus::Io;
+        }
+    };
+
+    let findings = Findings {
+        path: normalized_hash::escape_path(file).into_owned(),
+        encoding: encoding.to_string(),
+        bom,
+        line_endings: stats.as_ref().map(LineEndings::new),
+        final_newline: stats
+            .as_ref()
+            .map(|stats| stats.lines == 0 || stats.final_newline),
+        invisible_characters: stats.as_ref().map(|stats| stats.invisible),
+    };
+
+    if json {
+        out.line(output::json(&findings, true));
+        return ExitStatus::Success;
+    }
+
+    out.line(format_args!("{}:", file.display()));
+    out.line(format_args!(
+        "  encoding:        {}{}",
+        encoding,
+        if matches!(encoding, TextEncoding::Windows1252) {
+            " (likely)"
+        } else {
+            ""
+        }
+    ));
+    out.line(format_args!("  byte order mark: {}", yes_no(bom)));
+
+    let (Some(line_endings), Some(final_newline), Some(invisible)) = (
+        findings.line_endings,
+        findings.final_newline,
+        findings.invisible_characters,
+    ) else {
+        return ExitStatus::Success;
+    };
+
+    let style = match (line_endings.dominant, line_endings.mixed) {
+        (None, _) => "none".to_string(),
+        (Some(dominant), false) => dominant.to_string(),
+        (Some(dominant), true) => format!("mixed, mostly {}", dominant),
+    };
+    out.line(format_args!(
+        "  line endings:    {} ({} CRLF, {} LF, {} CR)",
+        style, line_endings.crlf, line_endings.lf, line_endings.cr
+    ));
+    out.line(format_args!("  final newline:   {}", yes_no(final_newline)));
+    out.line(format_args!(
+        "  invisible:       {} {}",
+        invisible,
+        plural(invisible, "character", "characters")
+    ));
+
+    ExitStatus::Success
+}
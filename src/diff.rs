@@ -0,0 +1,95 @@
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueHint};
+use similar::TextDiff;
+
+use normalized_hash::Hasher;
+
+use crate::exit::ExitStatus;
+use crate::Select;
+
+#[derive(Args)]
+pub struct DiffArgs {
+    /// Original file
+    #[arg(value_hint = ValueHint::FilePath)]
+    file_a: OsString,
+
+    /// Modified file
+    #[arg(value_hint = ValueHint::FilePath)]
+    file_b: OsString,
+
+    /// Number of context lines around each change
+    #[arg(long, value_name = "N", default_value_t = 3)]
+    context: usize,
+}
+
+fn read_lines(hasher: &Hasher, file: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    hasher.normalized_lines(file)?.collect()
+}
+
+/// Create a unified diff of the normalized lines of two files, each normalized with its hasher.
+///
+/// Returns `None` if there are no differences after normalization.
+fn diff(
+    hashers: [&Hasher; 2],
+    file_a: &Path,
+    file_b: &Path,
+    context: usize,
+) -> io::Result<Option<String>> {
+    let lines_a = read_lines(hashers[0], file_a)?;
+    let lines_b = read_lines(hashers[1], file_b)?;
+
+    if lines_a == lines_b {
+        return Ok(None);
+    }
+
+    let lines_a = lines_a.iter().map(String::as_str).collect::<Vec<_>>();
+    let lines_b = lines_b.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let diff = TextDiff::from_slices(&lines_a, &lines_b)
+        .unified_diff()
+        .context_radius(context)
+        .header(&file_a.to_string_lossy(), &file_b.to_string_lossy())
+        .to_string();
+
+    Ok(Some(diff))
+}
+
+/// Diff two files, each normalized with the options of its directory.
+///
+/// The exit status follows diff(1): 0 if the files are equal, 1 if they differ and 2 on trouble.
+pub fn run(select: Select, args: &DiffArgs) -> ExitStatus {
+    let (file_a, file_b) = (PathBuf::from(&args.file_a), PathBuf::from(&args.file_b));
+    // Like diff(1), any trouble exits with 2
+    let hashers = match select(&[&file_a, &file_b]) {
+        Ok(hashers) => hashers,
+        Err(_) => return ExitStatus::Usage,
+    };
+
+    match diff(
+        [hashers.of(0), hashers.of(1)],
+        &file_a,
+        &file_b,
+        args.context,
+    ) {
+        Ok(None) => ExitStatus::Success,
+
+        Ok(Some(diff)) => {
+            // Ignore errors from closed pipes, the diff is only informational at this point
+            let _ = io::stdout().write_all(diff.as_bytes());
+            ExitStatus::Mismatch
+        }
+
+        Err(err) => {
+            eprintln!(
+                "error: cannot diff {} and {}: {}",
+                file_a.display(),
+                file_b.display(),
+                err
+            );
+            ExitStatus::Usage
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use std::io;
+use std::path::Path;
+
+/// Extract the plain text of the .docx or .odt document `file`, one line per paragraph.
+///
+/// The kind of the document is detected from its content, not from its extension. Only the text
+/// of the document body is extracted, without images, comments or footnotes. Tabs and line
+/// breaks within paragraphs are kept as tab and line feed characters.
+#[cfg(feature = "doc")]
+pub fn extract(file: &Path) -> io::Result<String> {
+    use std::fs::File;
+    use std::io::{BufReader, Read, Seek};
+
+    use zip::result::ZipError;
+    use zip::ZipArchive;
+
+    /// Signature of OLE compound files, which hold encrypted and legacy Office documents.
+    const OLE_SIGNATURE: [u8; 8] = [0xd0, 0xcf, 0x11, 0xe0, 0xa1, 0xb1, 0x1a, 0xe1];
+
+    fn invalid(message: impl Into<String>) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, message.into())
+    }
+
+    let mut input = BufReader::new(File::open(normalized_hash::long_path(file))?);
+    let mut signature = [0; 8];
+    let read = input.read(&mut signature)?;
+    if signature[..read] == OLE_SIGNATURE {
+        return Err(invalid(
+            "the document is encrypted or in the legacy .doc format",
+        ));
+    }
+    input.rewind()?;
+
+    let mut archive = ZipArchive::new(input).map_err(|err| match err {
+        ZipError::Io(err) => err,
+        _ => invalid("it is neither a .docx nor an .odt document"),
+    })?;
+    let open_error = |name: &str, err: ZipError| match err {
+        ZipError::Io(err) => err,
+        ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED) => {
+            invalid("the document is encrypted")
+        }
+        err => invalid(format!("cannot read {}: {}", name, err)),
+    };
+
+    if archive.index_for_name("word/document.xml").is_some() {
+        let name = "word/document.xml";
+        let body = archive.by_name(name).map_err(|err| open_error(name, err))?;
+        return text_of(BufReader::new(body), &DOCX).map_err(|err| xml_error(name, err));
+    }
+
+    let mut mimetype = String::new();
+    if let Ok(entry) = archive.by_name("mimetype") {
+        entry.take(256).read_to_string(&mut mimetype)?;
+    }
+    match mimetype.trim() {
+        "application/vnd.oasis.opendocument.text" => {}
+        "" => return Err(invalid("it is neither a .docx nor an .odt document")),
+        other => return Err(invalid(format!("unsupported document type {}", other))),
+    }
+
+    if let Ok(mut manifest) = archive.by_name("META-INF/manifest.xml") {
+        let mut content = String::new();
+        manifest.read_to_string(&mut content)?;
+        if content.contains("encryption-data") {
+            return Err(invalid("the document is encrypted"));
+        }
+    }
+
+    let name = "content.xml";
+    let body = archive.by_name(name).map_err(|err| open_error(name, err))?;
+    text_of(BufReader::new(body), &ODT).map_err(|err| xml_error(name, err))
+}
+
+/// Placeholder if the binary was built without the `doc` feature.
+#[cfg(not(feature = "doc"))]
+pub fn extract(_file: &Path) -> io::Result<String> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "this build does not support documents, enable the feature `doc`",
+    ))
+}
+
+/// Names of the XML elements of a document format that make up its text.
+#[cfg(feature = "doc")]
+struct Format {
+    /// Element that contains the text of the document, `None` for the root element.
+    body: Option<&'static str>,
+
+    /// Elements whose end terminates a line.
+    paragraphs: &'static [&'static str],
+
+    /// Element whose character content is text, `None` if all character content is.
+    text: Option<&'static str>,
+
+    /// Elements that stand for a tab character.
+    tabs: &'static [&'static str],
+
+    /// Elements that stand for a line break within a paragraph.
+    breaks: &'static [&'static str],
+
+    /// Element that stands for spaces, as many as given by the attribute `text:c`.
+    spaces: Option<&'static str>,
+
+    /// Elements whose content is not part of the text, like comments.
+    skipped: &'static [&'static str],
+}
+
+/// WordprocessingML of .docx documents, in `word/document.xml`.
+#[cfg(feature = "doc")]
+const DOCX: Format = Format {
+    body: None,
+    paragraphs: &["w:p"],
+    text: Some("w:t"),
+    tabs: &["w:tab"],
+    breaks: &["w:br", "w:cr"],
+    spaces: None,
+    // The fallback repeats the content of text boxes for older readers
+    skipped: &["mc:Fallback"],
+};
+
+/// OpenDocument text of .odt documents, in `content.xml`.
+#[cfg(feature = "doc")]
+const ODT: Format = Format {
+    body: Some("office:text"),
+    paragraphs: &["text:p", "text:h"],
+    text: None,
+    tabs: &["text:tab"],
+    breaks: &["text:line-break"],
+    spaces: Some("text:s"),
+    skipped: &["office:annotation", "text:note", "svg:title", "svg:desc"],
+};
+
+#[cfg(feature = "doc")]
+fn xml_error(name: &str, err: quick_xml::Error) -> io::Error {
+    match err {
+        quick_xml::Error::Io(err) => io::Error::new(err.kind(), err.to_string()),
+        err => io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid XML in {}: {}", name, err),
+        ),
+    }
+}
+
+/// Collect the text of the XML document `input`, one line per paragraph.
+#[cfg(feature = "doc")]
+fn text_of(input: impl io::BufRead, format: &Format) -> Result<String, quick_xml::Error> {
+    use quick_xml::escape::{resolve_xml_entity, EscapeError};
+    use quick_xml::events::Event;
+    use quick_xml::{Reader, XmlVersion};
+
+    let mut reader = Reader::from_reader(input);
+    let mut buf = Vec::new();
+
+    let mut text = String::new();
+    let mut line = String::new();
+    let mut in_body = format.body.is_none();
+    let mut paragraphs = 0;
+    let mut in_text = false;
+    let mut skipped = 0;
+
+    let mut end_line = |line: &mut String| {
+        text.push_str(line);
+        text.push('\n');
+        line.clear();
+    };
+
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        let collecting =
+            in_body && paragraphs > 0 && skipped == 0 && (in_text || format.text.is_none());
+
+        match event {
+            Event::Eof => break,
+
+            Event::Start(ref element) | Event::Empty(ref element) => {
+                let name = element.name();
+                let name = name.as_ref();
+                let empty = matches!(event, Event::Empty(_));
+
+                if skipped > 0 || format.skipped.contains(&name) {
+                    skipped += usize::from(!empty && format.skipped.contains(&name));
+                } else if Some(name) == format.body {
+                    in_body = !empty;
+                } else if in_body && format.paragraphs.contains(&name) {
+                    // Paragraphs nested in text boxes end the text before them
+                    if !line.is_empty() || empty {
+                        end_line(&mut line);
+                    }
+                    paragraphs += usize::from(!empty);
+                } else if in_body && Some(name) == format.text {
+                    in_text = !empty;
+                } else if in_body && paragraphs > 0 {
+                    if format.tabs.contains(&name) {
+                        line.push('\t');
+                    } else if format.breaks.contains(&name) {
+                        line.push('\n');
+                    } else if Some(name) == format.spaces {
+                        let count = element
+                            .try_get_attribute("text:c")?
+                            .and_then(|count| count.normalized_value(XmlVersion::Implicit1_0).ok())
+                            .and_then(|count| count.parse().ok())
+                            .unwrap_or(1);
+                        line.extend(std::iter::repeat_n(' ', count));
+                    }
+                }
+            }
+
+            Event::End(ref element) => {
+                let name = element.name();
+                let name = name.as_ref();
+
+                if skipped > 0 {
+                    skipped -= usize::from(format.skipped.contains(&name));
+                } else if Some(name) == format.body {
+                    in_body = false;
+                } else if format.paragraphs.contains(&name) && paragraphs > 0 {
+                    paragraphs -= 1;
+                    end_line(&mut line);
+                } else if Some(name) == format.text {
+                    in_text = false;
+                }
+            }
+
+            Event::Text(content) if collecting => line.push_str(&content.xml10_content()),
+            Event::CData(content) if collecting => line.push_str(&content.xml10_content()),
+
+            Event::GeneralRef(reference) if collecting => match reference.resolve_char_ref()? {
+                Some(char) => line.push(char),
+                None => {
+                    let name = reference.xml10_content();
+                    let Some(resolved) = resolve_xml_entity(&name) else {
+                        let range = 0..name.len();
+                        let err = EscapeError::UnrecognizedEntity(range, name.into_owned());
+                        return Err(err.into());
+                    };
+                    line.push_str(resolved);
+                }
+            },
+
+            _ => {}
+        }
+
+        buf.clear();
+    }
+
+    Ok(text)
+}
+
+#[cfg(all(test, feature = "doc"))]
+mod tests {
+    use super::*;
+
+    fn docx(body: &str) -> String {
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}</w:body></w:document>"#,
+            body
+        );
+        text_of(xml.as_bytes(), &DOCX).unwrap()
+    }
+
+    fn odt(body: &str) -> String {
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"><office:automatic-styles><text:p>style</text:p></office:automatic-styles><office:body><office:text>{}</office:text></office:body></office:document-content>"#,
+            body
+        );
+        text_of(xml.as_bytes(), &ODT).unwrap()
+    }
+
+    #[test]
+    fn check_docx_text() {
+        assert_eq!(
+            docx(
+                "<w:p><w:r><w:t>SELECT</w:t></w:r><w:r><w:t xml:space=\"preserve\"> 1;</w:t></w:r></w:p>\
+                 <w:p/>\
+                 <w:p><w:r><w:tab/><w:t>a &amp; b&#160;c</w:t><w:br/><w:t>d</w:t></w:r></w:p>\
+                 <w:p><w:r><w:delText>deleted</w:delText><w:instrText>PAGE</w:instrText></w:r></w:p>"
+            ),
+            "SELECT 1;\n\n\ta & b\u{a0}c\nd\n\n"
+        );
+
+        // Text boxes are only read once, not again from their fallback
+        assert_eq!(
+            docx(
+                "<w:p><w:r><w:t>before</w:t></w:r><w:r><mc:AlternateContent xmlns:mc=\"mc\">\
+                 <mc:Choice><w:txbxContent><w:p><w:r><w:t>box</w:t></w:r></w:p></w:txbxContent></mc:Choice>\
+                 <mc:Fallback><w:txbxContent><w:p><w:r><w:t>box</w:t></w:r></w:p></w:txbxContent></mc:Fallback>\
+                 </mc:AlternateContent></w:r><w:r><w:t>after</w:t></w:r></w:p>"
+            ),
+            "before\nbox\nafter\n"
+        );
+    }
+
+    #[test]
+    fn check_odt_text() {
+        assert_eq!(
+            odt(
+                "<text:h>Title</text:h>\
+                 <text:p>a<text:s/>b<text:s text:c=\"3\"/>c<text:tab/>d<text:line-break/>e</text:p>\
+                 <text:p/>\
+                 <text:p>x<office:annotation><text:p>comment</text:p></office:annotation>y</text:p>"
+            ),
+            "Title\na b   c\td\ne\n\nxy\n"
+        );
+    }
+}
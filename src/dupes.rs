@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueHint};
+use serde::Serialize;
+
+use crate::check::plural;
+use crate::error::{self, ErrorInfo};
+use crate::exit::ExitStatus;
+use crate::input::{self, InputOptions, Inputs};
+use crate::output::{self, Output};
+use crate::Select;
+
+#[derive(Args)]
+pub struct DupesArgs {
+    /// Files to be compared, directories with `--recursive`
+    #[arg(required = true, value_hint = ValueHint::AnyPath)]
+    files: Vec<OsString>,
+
+    /// Compare all files in the given directories and their subdirectories
+    ///
+    /// Binary files are skipped with a note on stderr. The same rules apply as for hashing with
+    /// `--recursive`.
+    #[arg(short, long)]
+    recursive: bool,
+
+    /// Skip hidden files and directories with `--recursive`
+    #[arg(long, requires = "recursive")]
+    no_hidden: bool,
+
+    /// Don't respect `.gitignore` files with `--recursive`
+    #[arg(long, requires = "recursive")]
+    no_ignore: bool,
+
+    /// Exclude files matching a glob pattern
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Descend at most N directory levels with `--recursive`
+    #[arg(long, value_name = "N", requires = "recursive")]
+    max_depth: Option<usize>,
+
+    /// Follow symbolic links with `--recursive`
+    #[arg(long, requires = "recursive")]
+    follow_symlinks: bool,
+
+    /// Skip files smaller than BYTES, like empty files that are trivially equal
+    #[arg(long, value_name = "BYTES", default_value_t = 0)]
+    min_size: u64,
+
+    /// Exit with code 1 if any duplicates are found
+    #[arg(long)]
+    fail_if_dupes: bool,
+
+    /// Print the groups as JSON document
+    #[arg(long)]
+    json: bool,
+}
+
+/// Files with the same normalized hash, as written in the JSON output.
+#[derive(Serialize)]
+struct Group {
+    hash: String,
+
+    /// Paths of the files, sorted.
+    files: Vec<String>,
+}
+
+/// File that could not be read, as written in the JSON output.
+#[derive(Serialize)]
+struct ErrorEntry {
+    path: String,
+    error: ErrorInfo,
+}
+
+/// Duplicates as written in the JSON output.
+#[derive(Serialize)]
+struct Duplicates {
+    groups: Vec<Group>,
+
+    /// Files that could not be read.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<ErrorEntry>,
+}
+
+/// Collect the files to be compared, skipping files smaller than `--min-size`.
+fn collect(args: &DupesArgs) -> Result<Vec<PathBuf>, ExitStatus> {
+    let options = InputOptions {
+        recursive: args.recursive,
+        hidden: !args.no_hidden,
+        gitignore: !args.no_ignore,
+        exclude: args.exclude.clone(),
+        extensions: Vec::new(),
+        max_depth: args.max_depth,
+        follow_symlinks: args.follow_symlinks,
+        allow_empty_glob: false,
+        verbose: false,
+        quiet: false,
+        verb: "hash",
+    };
+
+    let mut files = Vec::new();
+    for file in &args.files {
+        match input::collect(Path::new(file), &options)? {
+            Inputs::Single(file) => files.push(file),
+            Inputs::Batch(batch) => files.extend(batch),
+        }
+    }
+
+    // A file given by several paths is not a duplicate of itself
+    let mut files = input::dedup(files, false);
+
+    // Files whose size is unknown are kept, so errors are reported while hashing
+    files
+        .retain(|file| fs::metadata(file).map_or(true, |metadata| metadata.len() >= args.min_size));
+
+    Ok(files)
+}
+
+/// Group files by their normalized hash and print the groups with more than one file.
+///
+/// Each file is hashed with the options of its directory.
+pub fn run(select: Select, out: &mut Output, args: &DupesArgs) -> ExitStatus {
+    let files = match collect(args) {
+        Ok(files) => files,
+        Err(status) => return status,
+    };
+    let hashers = match select(&files.iter().collect::<Vec<_>>()) {
+        Ok(hashers) => hashers,
+        Err(status) => return status,
+    };
+
+    let mut hashes = files.iter().map(|_| None).collect::<Vec<_>>();
+    for (options, hasher) in hashers.hashers.iter().enumerate() {
+        let group = hashers.files_of(options, 0..files.len());
+        let group_files = group.iter().map(|&index| &files[index]).collect::<Vec<_>>();
+        let _ = hasher.hash_many(&group_files, 0, |index, result| {
+            hashes[group[index]] = Some(result.map(|hashed| hashed.map(|(hash, _, _)| hash)));
+            ControlFlow::<()>::Continue(())
+        });
+    }
+
+    // Groups in the order of their first file, so related groups stay close together
+    let mut groups = Vec::<(String, Vec<PathBuf>)>::new();
+    let mut indices = HashMap::new();
+    let mut errors = Vec::new();
+
+    for (file, result) in files.iter().zip(hashes) {
+        let Some(result) = result else {
+            continue;
+        };
+        match result {
+            Ok(Some(hash)) => {
+                let index = *indices.entry(hash.clone()).or_insert_with(|| {
+                    groups.push((hash, Vec::new()));
+                    groups.len() - 1
+                });
+                groups[index].1.push(file.clone());
+            }
+            Ok(None) => eprintln!("note: skipping binary file {}", file.display()),
+            Err(err) => {
+                if !args.json {
+                    eprintln!("warning: {}, skipping", error::describe(&err, file, None));
+                }
+                errors.push(ErrorEntry {
+                    path: normalized_hash::escape_path(file).into_owned(),
+                    error: ErrorInfo::new(&err),
+                });
+            }
+        }
+    }
+
+    for (_, files) in &mut groups {
+        // A file given twice is not a duplicate of itself
+        normalized_hash::sort_paths(files);
+        files.dedup();
+    }
+    groups.retain(|(_, files)| files.len() > 1);
+
+    let found = !groups.is_empty();
+    let status = if errors.is_empty() {
+        ExitStatus::Success
+    } else {
+        ExitStatus::Incomplete
+    };
+
+    if args.json {
+        let duplicates = Duplicates {
+            groups: groups
+                .into_iter()
+                .map(|(hash, files)| Group {
+                    hash,
+                    files: files
+                        .iter()
+                        .map(|file| normalized_hash::escape_path(file).into_owned())
+                        .collect(),
+                })
+                .collect(),
+            errors,
+        };
+
+        out.line(output::json(&duplicates, true));
+    } else {
+        for (index, (hash, files)) in groups.iter().enumerate() {
+            if index > 0 {
+                println!();
+            }
+            println!(
+                "{} ({} {})",
+                hash,
+                files.len(),
+                plural(files.len(), "file", "files")
+            );
+            for file in files {
+                println!("  {}", file.display());
+            }
+        }
+    }
+
+    if found && args.fail_if_dupes {
+        status.max(ExitStatus::Mismatch)
+    } else {
+        status
+    }
+}
@@ -0,0 +1,71 @@
+use std::ffi::OsStr;
+
+use clap::builder::TypedValueParser;
+use clap::error::ErrorKind;
+use clap::{Arg, ArgAction, Command};
+
+/// Prefix of the environment variables that set flags, like `NORMALIZED_HASHER_NO_EOF`.
+const PREFIX: &str = "NORMALIZED_HASHER_";
+
+/// Values of environment variables that enable a flag, compared case-insensitively.
+const TRUE: [&str; 3] = ["1", "true", "yes"];
+
+/// Values of environment variables that disable a flag, compared case-insensitively.
+const FALSE: [&str; 3] = ["0", "false", "no"];
+
+/// Name of the environment variable of an argument, like `NORMALIZED_HASHER_EOL` for `eol`.
+pub fn variable(id: &str) -> String {
+    format!("{}{}", PREFIX, id.to_uppercase())
+}
+
+/// Parser of flags, whose value only comes from environment variables or configuration files.
+#[derive(Clone)]
+struct FlagParser;
+
+impl TypedValueParser for FlagParser {
+    type Value = bool;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<bool, clap::Error> {
+        let text = value.to_string_lossy();
+        if TRUE.iter().any(|word| text.eq_ignore_ascii_case(word)) {
+            return Ok(true);
+        }
+        if FALSE.iter().any(|word| text.eq_ignore_ascii_case(word)) {
+            return Ok(false);
+        }
+
+        let name = arg.map_or_else(|| "flag".to_string(), |arg| variable(arg.get_id().as_str()));
+        let message = format!(
+            "invalid value '{}' for {}, expected one of 1, true, yes, 0, false or no",
+            text, name
+        );
+        Err(cmd.clone().error(ErrorKind::InvalidValue, message))
+    }
+}
+
+/// Let every option and flag of the command be set by an environment variable.
+///
+/// The variable is named after the argument, like `NORMALIZED_HASHER_IGNORE_WHITESPACES` for
+/// `--ignore-whitespaces`. Flags on the command line take precedence. Flags are enabled by `1`,
+/// `true` or `yes` and disabled by `0`, `false` or `no`, in any case. Positional arguments and
+/// counted flags like `--verbose` have no variable, and neither have the arguments of
+/// subcommands, except for global options.
+pub fn apply(command: Command) -> Command {
+    command.mut_args(|arg| {
+        if arg.is_positional() {
+            return arg;
+        }
+
+        let name = variable(arg.get_id().as_str());
+        match arg.get_action() {
+            ArgAction::SetTrue => arg.env(name).hide_env_values(true).value_parser(FlagParser),
+            ArgAction::Set | ArgAction::Append => arg.env(name).hide_env_values(true),
+            _ => arg,
+        }
+    })
+}
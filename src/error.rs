@@ -0,0 +1,147 @@
+use std::fmt::Display;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Serialize;
+
+use normalized_hash::{BrokenSymlink, ErrorKind, NotAFile};
+
+use crate::output;
+
+/// Whether fatal errors are printed as JSON lines, enabled with `--json`.
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Kind of an error, as written in the JSON output.
+///
+/// The kinds of errors from processing a file correspond to [`ErrorKind`] of the library.
+/// Errors that are not related to a single file have the kind `usage` for invalid arguments and
+/// `io` for everything else.
+pub fn kind(err: &io::Error) -> &'static str {
+    match ErrorKind::of(err) {
+        Some(ErrorKind::Open) => "open",
+        Some(ErrorKind::NotAFile) => "not_a_file",
+        Some(ErrorKind::BrokenSymlink) => "broken_symlink",
+        Some(ErrorKind::Read) => "read",
+        Some(ErrorKind::Decode) => "decode",
+        Some(ErrorKind::Create) => "create",
+        Some(ErrorKind::Write) => "write",
+        Some(ErrorKind::ModifiedDuringRead) => "modified",
+        Some(ErrorKind::OutputMismatch) => "verify",
+        _ => "io",
+    }
+}
+
+/// Error as written in the JSON output, both in report entries and for fatal errors.
+#[derive(Serialize)]
+pub struct ErrorInfo {
+    pub kind: &'static str,
+    pub message: String,
+}
+
+impl ErrorInfo {
+    pub fn new(err: &io::Error) -> Self {
+        Self {
+            kind: kind(err),
+            message: message(err),
+        }
+    }
+}
+
+/// Message of an error, without the `(os error N)` suffix added by the standard library.
+pub fn message(err: &io::Error) -> String {
+    let message = err.to_string();
+
+    // The suffix is also present if the OS error is wrapped, like by the library
+    let code = message
+        .strip_suffix(')')
+        .and_then(|message| message.rsplit_once(" (os error "));
+
+    match code {
+        Some((message, code)) if code.parse::<i32>().is_ok() => message.to_string(),
+        _ => message,
+    }
+}
+
+/// Describe an error from hashing `file`, like `cannot open 'FILE': REASON`.
+///
+/// Errors while writing refer to `file_out` if given, since only output files are written.
+pub fn describe(err: &io::Error, file: &Path, file_out: Option<&Path>) -> String {
+    match ErrorKind::of(err) {
+        Some(ErrorKind::Open) => format!("cannot open '{}': {}", file.display(), message(err)),
+        Some(ErrorKind::NotAFile) => match NotAFile::of(err) {
+            Some(not_a_file) => {
+                format!("'{}' is a {}, not a file", file.display(), not_a_file.kind)
+            }
+            None => format!("'{}' is not a file", file.display()),
+        },
+        Some(ErrorKind::BrokenSymlink) => match BrokenSymlink::of(err) {
+            Some(broken) => format!(
+                "cannot open '{}': it is a symbolic link to '{}', which does not exist",
+                file.display(),
+                broken.target.display()
+            ),
+            None => format!("cannot open '{}': {}", file.display(), message(err)),
+        },
+        Some(ErrorKind::Decode) => {
+            format!(
+                "cannot read '{}': it is not valid UTF-8 text",
+                file.display()
+            )
+        }
+        Some(ErrorKind::Create | ErrorKind::Write) => {
+            let file = file_out.unwrap_or(file);
+            format!("cannot write '{}': {}", file.display(), message(err))
+        }
+        Some(ErrorKind::OutputMismatch) => {
+            let file = file_out.unwrap_or(file);
+            format!(
+                "self-check of '{}' failed: the written content does not have the computed hash",
+                file.display()
+            )
+        }
+        Some(ErrorKind::ModifiedDuringRead) => {
+            format!(
+                "cannot hash '{}': it was modified while it was read",
+                file.display()
+            )
+        }
+        _ => format!("cannot read '{}': {}", file.display(), message(err)),
+    }
+}
+
+/// Fatal error, printed as a single JSON line.
+#[derive(Serialize)]
+struct Fatal {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+
+    error: ErrorInfo,
+}
+
+/// Print fatal errors as JSON lines from now on.
+pub fn print_json(enabled: bool) {
+    JSON.store(enabled, Ordering::Relaxed);
+}
+
+/// Print an error that aborts the run to stderr.
+///
+/// The line is `error: MESSAGE` or, with `--json`, a JSON object with the fields `path`, if the
+/// error is related to a single file, and `error`, an object with the fields `kind` and
+/// `message`.
+pub fn fatal(kind: &'static str, path: Option<&Path>, message: impl Display) {
+    if !JSON.load(Ordering::Relaxed) {
+        eprintln!("error: {}", message);
+        return;
+    }
+
+    let fatal = Fatal {
+        path: path.map(|path| normalized_hash::escape_path(path).into_owned()),
+        error: ErrorInfo {
+            kind,
+            message: message.to_string(),
+        },
+    };
+
+    eprintln!("{}", output::json(&fatal, false));
+}
@@ -0,0 +1,85 @@
+use std::process::ExitCode;
+
+/// Description of all exit codes, shown in the help output.
+pub const EXIT_STATUS_HELP: &str = "\
+Exit status:
+  0  Success, all files verified, equal or normalized
+  1  At least one hash mismatch or difference
+  2  Invalid usage or arguments
+  3  At least one file could not be read or written
+  4  Malformed checksum input
+  5  Completed with errors, unreadable files were skipped
+
+If several failures occur, the first matching one is used in the order 2, 3, 5, 4, 1.";
+
+/// Description of the exit codes of the `diff` subcommand, which follow diff(1).
+pub const DIFF_EXIT_STATUS_HELP: &str = "\
+Exit status:
+  0  The files are equal after normalization
+  1  The files differ
+  2  Trouble, like invalid arguments or a file that could not be read
+
+Like diff(1), files that cannot be read exit with 2 instead of 3.";
+
+/// Outcome of a run, mapped to the documented exit codes.
+///
+/// The variants are ordered by precedence, so that the outcome of a run with several failures is
+/// the maximum of all individual outcomes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExitStatus {
+    /// Success, all files verified, equal or normalized.
+    #[default]
+    Success,
+
+    /// At least one hash mismatch or difference.
+    Mismatch,
+
+    /// Malformed checksum input.
+    Malformed,
+
+    /// Completed with errors, unreadable files were skipped.
+    Incomplete,
+
+    /// At least one file could not be read or written.
+    Io,
+
+    /// Invalid usage or arguments.
+    ///
+    /// The `diff` subcommand also uses it for files that cannot be read, like diff(1).
+    Usage,
+}
+
+impl ExitStatus {
+    pub fn code(self) -> u8 {
+        match self {
+            ExitStatus::Success => 0,
+            ExitStatus::Mismatch => 1,
+            ExitStatus::Usage => 2,
+            ExitStatus::Io => 3,
+            ExitStatus::Malformed => 4,
+            ExitStatus::Incomplete => 5,
+        }
+    }
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_precedence() {
+        use ExitStatus::*;
+
+        assert_eq!(Success.max(Mismatch), Mismatch);
+        assert_eq!(Mismatch.max(Malformed), Malformed);
+        assert_eq!(Malformed.max(Incomplete), Incomplete);
+        assert_eq!(Incomplete.max(Io), Io);
+        assert_eq!(Io.max(Usage), Usage);
+    }
+}
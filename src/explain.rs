@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueHint};
+use similar::TextDiff;
+
+use normalized_hash::{Hasher, LineChange};
+
+use crate::error;
+use crate::exit::ExitStatus;
+use crate::Select;
+
+#[derive(Args)]
+pub struct ExplainArgs {
+    /// File to be explained
+    #[arg(value_hint = ValueHint::FilePath)]
+    file_in: OsString,
+
+    /// Number of context lines around each change
+    #[arg(long, value_name = "N", default_value_t = 3)]
+    context: usize,
+}
+
+/// Human readable name of a line ending.
+fn eol_name(eol: &str) -> String {
+    match eol {
+        "" => "none".to_string(),
+        "\n" => "LF".to_string(),
+        "\r\n" => "CRLF".to_string(),
+        "\r" => "CR".to_string(),
+        eol => format!("{:?}", eol),
+    }
+}
+
+/// Render a line with its line ending made visible.
+fn render(content: &str, eol: &str) -> String {
+    format!("{}{}", content, eol.escape_debug())
+}
+
+/// Summarize all changes in a single line, like `3 lines: 3 CRLF→LF, 1 whitespace`.
+fn summarize(changes: &[LineChange]) -> String {
+    let mut eol_changes = BTreeMap::new();
+    let mut content_changes = 0;
+
+    for change in changes {
+        if change.original_eol != change.normalized_eol {
+            let key = (
+                eol_name(change.original_eol),
+                eol_name(change.normalized_eol),
+            );
+            *eol_changes.entry(key).or_insert(0) += 1;
+        }
+
+        if change.original != change.normalized {
+            content_changes += 1;
+        }
+    }
+
+    let mut summary = eol_changes
+        .into_iter()
+        .map(|((from, to), count)| format!("{} {}→{}", count, from, to))
+        .collect::<Vec<_>>();
+
+    if content_changes > 0 {
+        summary.push(format!("{} whitespace", content_changes));
+    }
+
+    let lines = if changes.len() == 1 { "line" } else { "lines" };
+
+    format!("{} {}: {}", changes.len(), lines, summary.join(", "))
+}
+
+/// Create a unified diff between the original and the normalized lines of a file, followed by a
+/// summary line.
+///
+/// Returns `None` if normalization does not change the file.
+fn explain(hasher: &Hasher, file_in: &Path, context: usize) -> io::Result<Option<String>> {
+    let changes = hasher
+        .line_changes(file_in)?
+        .collect::<io::Result<Vec<_>>>()?;
+
+    if !changes.iter().any(LineChange::is_changed) {
+        return Ok(None);
+    }
+
+    let original = changes
+        .iter()
+        .filter(|change| !change.original.is_empty() || !change.original_eol.is_empty())
+        .map(|change| render(&change.original, change.original_eol))
+        .collect::<Vec<_>>();
+    let normalized = changes
+        .iter()
+        .map(|change| render(&change.normalized, change.normalized_eol))
+        .collect::<Vec<_>>();
+
+    let original = original.iter().map(String::as_str).collect::<Vec<_>>();
+    let normalized = normalized.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let file_name = file_in.to_string_lossy();
+    let diff = TextDiff::from_slices(&original, &normalized)
+        .unified_diff()
+        .context_radius(context)
+        .header(&file_name, &format!("{} (normalized)", file_name))
+        .to_string();
+
+    Ok(Some(format!("{}{}\n", diff, summarize(&changes))))
+}
+
+/// Explain the changes of normalizing a file with the options of its directory.
+pub fn run(select: Select, args: &ExplainArgs) -> ExitStatus {
+    let file_in = PathBuf::from(&args.file_in);
+    let hashers = match select(&[&file_in]) {
+        Ok(hashers) => hashers,
+        Err(status) => return status,
+    };
+    let file_in = file_in.as_path();
+
+    match explain(hashers.of(0), file_in, args.context) {
+        Ok(None) => ExitStatus::Success,
+
+        Ok(Some(explanation)) => {
+            let _ = io::stdout().write_all(explanation.as_bytes());
+            ExitStatus::Mismatch
+        }
+
+        Err(err) => {
+            eprintln!("error: {}", error::describe(&err, file_in, None));
+            ExitStatus::Io
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::fs::{self, File};
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+
+use normalized_hash::{ErrorKind, Hasher};
+
+use crate::error;
+use crate::exit::ExitStatus;
+use crate::verbose;
+
+/// Stdout as a file of its own, so its buffer is not shared with other output.
+///
+/// After the reader of stdout exited, unwritten content must not stay in the buffer of
+/// [`io::stdout`], since flushing it at exit would fail again.
+fn stdout_file() -> io::Result<File> {
+    #[cfg(unix)]
+    let handle = std::os::fd::AsFd::as_fd(&io::stdout()).try_clone_to_owned();
+    #[cfg(windows)]
+    let handle = std::os::windows::io::AsHandle::as_handle(&io::stdout()).try_clone_to_owned();
+
+    handle.map(File::from)
+}
+
+/// Normalize stdin to stdout and print the hash at the end, with `--filter`.
+///
+/// The hash goes to stderr, or into `hash_file` if given, so stdout only carries the normalized
+/// content. If the reader of stdout exits early, the rest of stdin is not read and no hash is
+/// printed, since it would only cover part of the input.
+pub fn run(
+    hasher: &Hasher,
+    hash_file: Option<&Path>,
+    verbose: u8,
+    display: &dyn Fn(&str) -> String,
+) -> ExitStatus {
+    // Each line is passed on as soon as it is normalized
+    let result = stdout_file().and_then(|stdout| {
+        let mut stdout = LineWriter::new(stdout);
+        let hashed = hasher.filter(io::stdin().lock(), &mut stdout);
+        match hashed {
+            Ok(hashed) => stdout.flush().map(|()| Ok(hashed)),
+            Err(err) => Ok(Err(err)),
+        }
+    });
+
+    let (hash, stats) = match result {
+        Ok(Ok(hashed)) => hashed,
+        Ok(Err(err)) | Err(err) if err.kind() == io::ErrorKind::BrokenPipe => {
+            return ExitStatus::Io
+        }
+        Ok(Err(err)) if ErrorKind::of(&err) != Some(ErrorKind::Write) => {
+            let message = error::describe(&err, Path::new("-"), None);
+            error::fatal(error::kind(&err), None, message);
+            return ExitStatus::Io;
+        }
+        Ok(Err(err)) | Err(err) => {
+            let message = format_args!("cannot write standard output: {}", error::message(&err));
+            error::fatal("write", None, message);
+            return ExitStatus::Io;
+        }
+    };
+
+    verbose::report(Path::new("-"), &stats, verbose);
+
+    let line = format!("{}\n", display(&hash));
+    match hash_file {
+        Some(hash_file) => {
+            if let Err(err) = fs::write(hash_file, line) {
+                let message = format_args!("cannot write {}: {}", hash_file.display(), err);
+                error::fatal(error::kind(&err), Some(hash_file), message);
+                return ExitStatus::Io;
+            }
+        }
+        None => eprint!("{}", line),
+    }
+
+    ExitStatus::Success
+}
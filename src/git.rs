@@ -0,0 +1,70 @@
+use std::io;
+use std::path::Path;
+
+/// Content of a file at a revision of a git repository.
+pub struct Blob {
+    /// Name of the file at the revision, like `HEAD:src/main.rs`.
+    pub label: String,
+
+    pub content: Vec<u8>,
+}
+
+/// Read `file` at the revision `rev`, like `HEAD`, of the repository that contains it.
+///
+/// The file does not need to exist in the working copy, but its directory does. The content is
+/// read as it was committed, without applying filters like `core.autocrlf`.
+#[cfg(feature = "git")]
+pub fn read_blob(file: &Path, rev: &str) -> io::Result<Blob> {
+    use git2::{ErrorCode, ObjectType, Repository};
+
+    let absolute = std::path::absolute(file)?;
+    let (Some(dir), Some(name)) = (absolute.parent(), absolute.file_name()) else {
+        return Err(io::Error::other("it is not a file"));
+    };
+
+    let repo = Repository::discover(dir).map_err(|err| io::Error::other(err.message()))?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| io::Error::other("the repository has no working copy"))?
+        .canonicalize()?;
+    let path = dir.canonicalize()?.join(name);
+    let path = path
+        .strip_prefix(&workdir)
+        .map_err(|_| io::Error::other("it is not in the working copy of the repository"))?;
+    let label = format!("{}:{}", rev, path.to_string_lossy().replace('\\', "/"));
+
+    let tree = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_tree())
+        .map_err(|err| io::Error::other(format!("unknown revision: {}", err.message())))?;
+
+    let entry = match tree.get_path(path) {
+        Ok(entry) => entry,
+        Err(err) if err.code() == ErrorCode::NotFound => {
+            let message = "it is not tracked";
+            return Err(io::Error::new(io::ErrorKind::NotFound, message));
+        }
+        Err(err) => return Err(io::Error::other(err.message())),
+    };
+    if entry.kind() != Some(ObjectType::Blob) {
+        return Err(io::Error::other("it is not a file"));
+    }
+
+    let blob = repo
+        .find_blob(entry.id())
+        .map_err(|err| io::Error::other(err.message()))?;
+
+    Ok(Blob {
+        label,
+        content: blob.content().to_vec(),
+    })
+}
+
+/// Placeholder if the binary was built without the `git` feature.
+#[cfg(not(feature = "git"))]
+pub fn read_blob(_file: &Path, _rev: &str) -> io::Result<Blob> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "this build does not support git repositories, enable the feature `git`",
+    ))
+}
@@ -0,0 +1,80 @@
+use std::io::BufRead;
+use std::time::Duration;
+
+/// Parse a header of `--header`, like `Authorization: Bearer TOKEN`.
+pub fn parse_header(header: &str) -> Result<(String, String), String> {
+    match header.split_once(':') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(String::from("expected a header like 'NAME: VALUE'")),
+    }
+}
+
+/// Options of a download, given on the command line.
+#[cfg_attr(not(feature = "http"), allow(dead_code))]
+pub struct Request<'a> {
+    pub headers: &'a [(String, String)],
+    pub timeout: Duration,
+}
+
+/// Start downloading `url`, following redirects.
+///
+/// Only a successful response is returned, any other status is an error. The error message
+/// describes the problem, but does not name the URL.
+#[cfg(feature = "http")]
+pub fn fetch(url: &str, request: &Request) -> Result<Box<dyn BufRead>, String> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(request.timeout))
+        .build()
+        .into();
+
+    let response = request
+        .headers
+        .iter()
+        .fold(agent.get(url), |get, (name, value)| get.header(name, value))
+        .call()
+        .map_err(|err| match err {
+            ureq::Error::StatusCode(status) => format!("the server responded with {}", status),
+            ureq::Error::Timeout(_) => {
+                let seconds = request.timeout.as_secs();
+                let unit = crate::check::plural(seconds as usize, "second", "seconds");
+                format!("no response within {} {}", seconds, unit)
+            }
+            err => err.to_string(),
+        })?;
+
+    Ok(Box::new(std::io::BufReader::new(
+        response.into_body().into_reader(),
+    )))
+}
+
+/// Placeholder if the binary was built without the `http` feature.
+#[cfg(not(feature = "http"))]
+pub fn fetch(_url: &str, _request: &Request) -> Result<Box<dyn BufRead>, String> {
+    Err(String::from(
+        "this build does not support downloads, enable the feature `http`",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_parse_header() {
+        assert_eq!(
+            parse_header("Authorization: Bearer abc:def"),
+            Ok((
+                String::from("Authorization"),
+                String::from("Bearer abc:def")
+            ))
+        );
+        assert_eq!(
+            parse_header("X-Empty:"),
+            Ok((String::from("X-Empty"), String::new()))
+        );
+        assert!(parse_header("Authorization").is_err());
+        assert!(parse_header(": value").is_err());
+    }
+}
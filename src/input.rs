@@ -0,0 +1,400 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use normalized_hash::{long_path, Exclude, Skipped, Walker};
+
+use crate::error;
+use crate::exit::ExitStatus;
+
+/// Options that control how FILE_IN is turned into a list of files.
+pub struct InputOptions {
+    pub recursive: bool,
+    pub hidden: bool,
+    pub gitignore: bool,
+    pub exclude: Vec<String>,
+    pub extensions: Vec<OsString>,
+    pub max_depth: Option<usize>,
+    pub follow_symlinks: bool,
+    pub allow_empty_glob: bool,
+    pub verbose: bool,
+    pub quiet: bool,
+
+    /// What is done with the files, like `hash`, to suggest `--recursive` for a directory.
+    pub verb: &'static str,
+}
+
+/// Files to be hashed.
+pub enum Inputs {
+    /// A single file, named explicitly.
+    Single(PathBuf),
+
+    /// Any number of files, resulting from a directory or a glob pattern.
+    Batch(Vec<PathBuf>),
+}
+
+/// Check if a path contains glob metacharacters.
+fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+/// Expand a glob pattern into a sorted list of matching paths.
+fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>, ExitStatus> {
+    let paths = match glob::glob(pattern) {
+        Ok(paths) => paths,
+        Err(err) => {
+            let message = format_args!("invalid pattern {}: {}", pattern, err);
+            error::fatal("usage", None, message);
+            return Err(ExitStatus::Usage);
+        }
+    };
+
+    let mut matches = Vec::new();
+    for path in paths {
+        match path {
+            Ok(path) => matches.push(path),
+            Err(err) => {
+                error::fatal(
+                    "io",
+                    None,
+                    format_args!("cannot expand {}: {}", pattern, err),
+                );
+                return Err(ExitStatus::Io);
+            }
+        }
+    }
+
+    normalized_hash::sort_paths(&mut matches);
+
+    Ok(matches)
+}
+
+/// Exclude patterns for paths relative to the current directory.
+fn exclude(options: &InputOptions) -> Result<Exclude, ExitStatus> {
+    Exclude::new("", &options.exclude).map_err(|err| {
+        error::fatal(
+            "usage",
+            None,
+            format_args!("invalid exclude pattern: {}", err),
+        );
+        ExitStatus::Usage
+    })
+}
+
+fn skip_not_regular(path: &Path, options: &InputOptions) {
+    if options.verbose {
+        eprintln!(
+            "note: skipping {}, it is not a regular file",
+            path.display()
+        );
+    }
+}
+
+fn walk(root: &Path, options: &InputOptions) -> Result<Vec<PathBuf>, ExitStatus> {
+    let walker = Walker::new(root)
+        .hidden(options.hidden)
+        .gitignore(options.gitignore)
+        .max_depth(options.max_depth)
+        .follow_symlinks(options.follow_symlinks);
+
+    let walker = options
+        .exclude
+        .iter()
+        .fold(walker, |walker, pattern| walker.exclude(pattern));
+
+    options
+        .extensions
+        .iter()
+        .fold(walker, |walker, extension| walker.extension(extension))
+        .walk_with(|skipped| match skipped {
+            Skipped::Loop { path, ancestor } => {
+                if !options.quiet {
+                    eprintln!(
+                        "warning: skipping {}, it links to its ancestor {}",
+                        path.display(),
+                        ancestor.display()
+                    );
+                }
+            }
+            Skipped::NotRegular(path) => skip_not_regular(&path, options),
+            Skipped::Duplicate { path, original } => {
+                if options.verbose {
+                    eprintln!(
+                        "note: skipping {}, it is the same as {}",
+                        path.display(),
+                        original.display()
+                    );
+                }
+            }
+        })
+        .map_err(|err| {
+            let message = format_args!("cannot read directory {}: {}", root.display(), err);
+            error::fatal(error::kind(&err), Some(root), message);
+            ExitStatus::Io
+        })
+}
+
+/// Collect the files to be hashed for FILE_IN.
+///
+/// A path that does not exist literally but contains glob metacharacters is expanded as a glob
+/// pattern, `**` matches any number of directories. Directories are traversed with
+/// `--recursive`, otherwise they are an error if named explicitly and skipped if matched by a
+/// pattern. Ignore rules only apply to the traversal of directories, an explicitly named file or
+/// a file matched by a pattern is always included. Exclude patterns apply to traversed
+/// directories, relative to each one, and to files matched by a pattern, but not to an
+/// explicitly named file. Extension filters only apply to traversed directories. Files that are
+/// not regular files, like named pipes, are skipped unless named explicitly.
+pub fn collect(file_in: &Path, options: &InputOptions) -> Result<Inputs, ExitStatus> {
+    let exclude = exclude(options)?;
+
+    let pattern = file_in
+        .to_str()
+        .filter(|pattern| is_glob(pattern) && !long_path(file_in).exists());
+
+    let Some(pattern) = pattern else {
+        if options.recursive {
+            return walk(file_in, options).map(Inputs::Batch);
+        }
+
+        if long_path(file_in).is_dir() {
+            let message = format_args!(
+                "{} is a directory, use --recursive to {} all files in it",
+                file_in.display(),
+                options.verb
+            );
+            error::fatal("usage", Some(file_in), message);
+            return Err(ExitStatus::Usage);
+        }
+
+        return Ok(Inputs::Single(file_in.to_path_buf()));
+    };
+
+    let matches = expand_glob(pattern)?;
+
+    if matches.is_empty() && !options.allow_empty_glob {
+        error::fatal(
+            "io",
+            None,
+            format_args!("pattern {} matches no files", pattern),
+        );
+        return Err(ExitStatus::Io);
+    }
+
+    let mut files = Vec::new();
+    for path in matches {
+        if exclude.is_excluded(&path) {
+            continue;
+        }
+
+        if path.is_file() {
+            files.push(path);
+        } else if !path.is_dir() {
+            skip_not_regular(&path, options);
+        } else if options.recursive {
+            files.extend(walk(&path, options)?);
+        }
+    }
+
+    // Files of different matched directories might be interleaved with matched files
+    normalized_hash::sort_paths(&mut files);
+
+    Ok(Inputs::Batch(files))
+}
+
+/// Collect the files of all explicitly given paths, in the given order.
+///
+/// Each path is collected like with [`collect`], so glob patterns are expanded and directories
+/// are traversed with `--recursive`.
+pub fn collect_all(files_in: &[&Path], options: &InputOptions) -> Result<Inputs, ExitStatus> {
+    let mut files = Vec::new();
+    for file_in in files_in {
+        match collect(file_in, options)? {
+            Inputs::Single(file) => files.push(file),
+            Inputs::Batch(batch) => files.extend(batch),
+        }
+    }
+
+    Ok(Inputs::Batch(files))
+}
+
+fn read_list(list: &Path) -> io::Result<Vec<u8>> {
+    if list == Path::new("-") {
+        let mut content = Vec::new();
+        io::stdin().read_to_end(&mut content)?;
+        Ok(content)
+    } else {
+        fs::read(list)
+    }
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Parse a list of paths, separated by newlines or by NUL bytes.
+///
+/// In newline mode, a trailing carriage return is removed from each line, and blank lines and
+/// lines starting with `#` are ignored. In NUL mode, only empty entries are ignored, so that
+/// any path is possible.
+fn parse_list(content: &[u8], null: bool) -> Vec<PathBuf> {
+    if null {
+        return content
+            .split(|&b| b == 0)
+            .filter(|entry| !entry.is_empty())
+            .map(path_from_bytes)
+            .collect();
+    }
+
+    content
+        .split(|&b| b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .filter(|line| !line.iter().all(u8::is_ascii_whitespace) && !line.starts_with(b"#"))
+        .map(path_from_bytes)
+        .collect()
+}
+
+/// Collect the files to be hashed from a list in a file, or from stdin if `list` is `-`.
+///
+/// The files are kept in the order of the list. Relative paths are resolved against the current
+/// directory. Exclude patterns apply to all paths, directories are traversed with `--recursive`.
+pub fn collect_list(list: &Path, null: bool, options: &InputOptions) -> Result<Inputs, ExitStatus> {
+    let exclude = exclude(options)?;
+
+    let content = match read_list(list) {
+        Ok(content) => content,
+        Err(err) => {
+            let message = format_args!("cannot read {}: {}", list.display(), err);
+            error::fatal(error::kind(&err), Some(list), message);
+            return Err(ExitStatus::Io);
+        }
+    };
+
+    let mut files = Vec::new();
+    for path in parse_list(&content, null) {
+        if exclude.is_excluded(&path) {
+            continue;
+        }
+
+        if options.recursive && path.is_dir() {
+            files.extend(walk(&path, options)?);
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(Inputs::Batch(files))
+}
+
+/// Identity of a file, the same for all paths that lead to it.
+#[derive(PartialEq, Eq, Hash)]
+enum FileId {
+    /// Canonical path, with all symbolic links and `.` and `..` components resolved.
+    Canonical(PathBuf),
+
+    /// Device and inode, for files that exist but whose path cannot be canonicalized.
+    Inode(u64, u64),
+
+    /// Path as given, for files that do not exist.
+    Literal(PathBuf),
+}
+
+#[cfg(unix)]
+fn inode(path: &Path) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = fs::metadata(long_path(path)).ok()?;
+    Some(FileId::Inode(metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn inode(_path: &Path) -> Option<FileId> {
+    None
+}
+
+fn file_id(path: &Path) -> FileId {
+    match fs::canonicalize(long_path(path)) {
+        Ok(canonical) => FileId::Canonical(canonical),
+        Err(_) => inode(path).unwrap_or_else(|| FileId::Literal(path.to_path_buf())),
+    }
+}
+
+/// Remove files that are given more than once, keeping the first path by which each is given.
+///
+/// Different paths of the same file, like `./a.txt` and `a.txt` or a symbolic link and its
+/// target, are recognized as well. With `verbose`, a note is printed for each removed path.
+pub fn dedup(files: Vec<PathBuf>, verbose: bool) -> Vec<PathBuf> {
+    let mut originals = HashMap::new();
+
+    files
+        .into_iter()
+        .filter(|file| match originals.entry(file_id(file)) {
+            Entry::Vacant(vacant) => {
+                vacant.insert(file.clone());
+                true
+            }
+            Entry::Occupied(occupied) => {
+                if verbose {
+                    eprintln!(
+                        "note: skipping {}, it is the same file as {}",
+                        file.display(),
+                        occupied.get().display()
+                    );
+                }
+                false
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_parse_list() {
+        let content = b"a.txt\r\n\n# comment\n  \nsub/b c.txt\n";
+        assert_eq!(
+            parse_list(content, false),
+            [PathBuf::from("a.txt"), PathBuf::from("sub/b c.txt")]
+        );
+
+        let content = b"a.txt\0# not a comment\0\0line\nbreak\0";
+        assert_eq!(
+            parse_list(content, true),
+            [
+                PathBuf::from("a.txt"),
+                PathBuf::from("# not a comment"),
+                PathBuf::from("line\nbreak")
+            ]
+        );
+    }
+
+    #[test]
+    fn check_dedup() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        fs::write(&file, "").unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        let missing = dir.path().join("missing.txt");
+
+        let files = vec![
+            file.clone(),
+            missing.clone(),
+            dir.path().join(".").join("a.txt"),
+            dir.path().join("sub").join("..").join("a.txt"),
+            missing.clone(),
+        ];
+        assert_eq!(dedup(files, false), [file, missing]);
+    }
+}
@@ -0,0 +1,99 @@
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use normalized_hash::Hasher;
+
+use crate::error;
+use crate::exit::ExitStatus;
+use crate::pause;
+
+/// Check if the program was started without arguments from an interactive console.
+///
+/// This is the case if it is double-clicked in a file manager, where a usage error would only
+/// flash up in a console that is closed right away.
+pub fn is_wanted() -> bool {
+    env::args_os().len() == 1 && io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Clean up a path as entered or dropped into the console.
+///
+/// Surrounding whitespace and the line ending are removed. Paths dropped into a console are
+/// wrapped in double quotes on Windows if they contain spaces, and in single quotes by some
+/// terminals elsewhere, so a single pair of surrounding quotes is removed as well.
+pub fn clean_path(input: &str) -> PathBuf {
+    let path = input.trim();
+
+    let unquoted = ['"', '\'']
+        .iter()
+        .find_map(|&quote| path.strip_prefix(quote)?.strip_suffix(quote));
+
+    PathBuf::from(unquoted.unwrap_or(path))
+}
+
+fn hash(path: &Path) -> ExitStatus {
+    match Hasher::new().try_hash_file(path, None::<&Path>) {
+        Ok(hash) => {
+            println!("{}  {}", hash, path.display());
+            ExitStatus::Success
+        }
+        Err(err) => {
+            eprintln!("error: {}", error::describe(&err, path, None));
+            ExitStatus::Io
+        }
+    }
+}
+
+/// Prompt for a file, hash it with default options and wait before exiting.
+pub fn run() -> ExitStatus {
+    print!("Enter the path of the file to hash: ");
+    let _ = io::stdout().flush();
+
+    let mut input = String::new();
+    let status = match io::stdin().read_line(&mut input) {
+        // Nothing to wait for if stdin was closed
+        Ok(0) => return ExitStatus::Usage,
+        Ok(_) if input.trim().is_empty() => {
+            eprintln!("error: no file given");
+            ExitStatus::Usage
+        }
+        Ok(_) => hash(&clean_path(&input)),
+        Err(err) => {
+            eprintln!(
+                "error: cannot read standard input: {}",
+                error::message(&err)
+            );
+            return ExitStatus::Io;
+        }
+    };
+
+    pause::wait();
+
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_clean_path() {
+        assert_eq!(clean_path("input.txt\n"), Path::new("input.txt"));
+        assert_eq!(clean_path("  input.txt \r\n"), Path::new("input.txt"));
+        assert_eq!(
+            clean_path("\"C:\\My Files\\input.txt\"\r\n"),
+            Path::new("C:\\My Files\\input.txt")
+        );
+        assert_eq!(
+            clean_path("\"C:\\My Files\\input.txt\" \r\n"),
+            Path::new("C:\\My Files\\input.txt")
+        );
+        assert_eq!(
+            clean_path("'/home/me/my file.txt' "),
+            Path::new("/home/me/my file.txt")
+        );
+        assert_eq!(clean_path("\" spaced \""), Path::new(" spaced "));
+        assert_eq!(clean_path("\"unbalanced"), Path::new("\"unbalanced"));
+        assert_eq!(clean_path("\""), Path::new("\""));
+    }
+}
@@ -0,0 +1,142 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueHint};
+
+use crate::annotate::{self, Annotations, OutputFormat};
+use crate::error;
+use crate::exit::ExitStatus;
+use crate::input::{self, InputOptions, Inputs};
+use crate::Select;
+
+#[derive(Args)]
+pub struct LintArgs {
+    /// Files to be checked, directories with `--recursive`
+    #[arg(required = true, value_hint = ValueHint::AnyPath)]
+    files: Vec<OsString>,
+
+    /// Check all files in the given directories and their subdirectories
+    ///
+    /// Binary files are skipped with a note on stderr. The same rules apply as for hashing with
+    /// `--recursive`.
+    #[arg(short, long)]
+    recursive: bool,
+
+    /// Skip hidden files and directories with `--recursive`
+    #[arg(long, requires = "recursive")]
+    no_hidden: bool,
+
+    /// Don't respect `.gitignore` files with `--recursive`
+    #[arg(long, requires = "recursive")]
+    no_ignore: bool,
+
+    /// Exclude files matching a glob pattern
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Descend at most N directory levels with `--recursive`
+    #[arg(long, value_name = "N", requires = "recursive")]
+    max_depth: Option<usize>,
+
+    /// Follow symbolic links with `--recursive`
+    #[arg(long, requires = "recursive")]
+    follow_symlinks: bool,
+
+    /// Rewrite non-normalized files in place
+    ///
+    /// Each offending file is atomically replaced by its normalized form.
+    #[arg(long)]
+    fix: bool,
+
+    /// Terminate each listed file with a NUL byte instead of a newline
+    #[arg(short = 'z', long)]
+    print0: bool,
+}
+
+/// Collect the files to be checked.
+fn collect(args: &LintArgs) -> Result<Vec<PathBuf>, ExitStatus> {
+    let options = InputOptions {
+        recursive: args.recursive,
+        hidden: !args.no_hidden,
+        gitignore: !args.no_ignore,
+        exclude: args.exclude.clone(),
+        extensions: Vec::new(),
+        max_depth: args.max_depth,
+        follow_symlinks: args.follow_symlinks,
+        allow_empty_glob: false,
+        verbose: false,
+        quiet: false,
+        verb: "lint",
+    };
+
+    let mut files = Vec::new();
+    for file in &args.files {
+        match input::collect(Path::new(file), &options)? {
+            Inputs::Single(file) => files.push(file),
+            Inputs::Batch(batch) => files.extend(batch),
+        }
+    }
+
+    Ok(files)
+}
+
+/// Check that files are normalized, each with the options of its directory.
+pub fn run(select: Select, args: &LintArgs, format: OutputFormat) -> ExitStatus {
+    let files = match collect(args) {
+        Ok(files) => files,
+        Err(status) => return status,
+    };
+    let hashers = match select(&files.iter().collect::<Vec<_>>()) {
+        Ok(hashers) => hashers,
+        Err(status) => return status,
+    };
+
+    let annotations = Annotations::new(format, false);
+    let mut status = ExitStatus::Success;
+
+    for (index, file) in files.iter().enumerate() {
+        let file = file.as_path();
+        let path = annotate::path(file);
+        let hasher = hashers.of(index);
+
+        let line = normalized_hash::is_binary(file).and_then(|binary| {
+            (!binary)
+                .then(|| hasher.first_unnormalized_line(file))
+                .transpose()
+        });
+        let line = match line {
+            Ok(None) => {
+                eprintln!("note: skipping binary file {}", file.display());
+                continue;
+            }
+            Ok(Some(None)) => continue,
+            Ok(Some(Some(line))) => {
+                status = status.max(ExitStatus::Mismatch);
+                line
+            }
+            Err(err) => {
+                let message = error::describe(&err, file, None);
+                eprintln!("error: {}", message);
+                annotations.error(&path, None, message);
+                status = status.max(ExitStatus::Io);
+                continue;
+            }
+        };
+
+        print!(
+            "{}{}",
+            file.display(),
+            if args.print0 { '\0' } else { '\n' }
+        );
+        annotations.error(&path, Some(line), "file is not normalized");
+
+        if args.fix {
+            if let Err(err) = hasher.normalize_in_place(file) {
+                eprintln!("error: cannot fix {}", error::describe(&err, file, None));
+                status = status.max(ExitStatus::Io);
+            }
+        }
+    }
+
+    status
+}
@@ -68,20 +68,101 @@
 //!
 //! ## Usage
 //!
+//!
 //! ```text
-//! Usage: normalized-hasher [OPTIONS] <FILE_IN> [FILE_OUT]
+//! Usage: normalized-hasher [OPTIONS] [FILE_IN] [FILE]...
+//!        normalized-hasher [OPTIONS] [FILE_IN] [FILE]... <COMMAND>
+//!
+//! Commands:
+//!   compare      Check if two files are equal after normalization
+//!   diff         Show a unified diff of two files after normalization
+//!   explain      Show what normalization would change in a file
+//!   lint         List files that are not in normalized form
+//!   stats        Survey line endings and whitespace of files, without hashing them
+//!   dupes        Find files with the same content after normalization
+//!   normalize    Convert files to their normalized form, without hashing them
+//!   manifest     Record the hashes of a directory tree in a manifest and verify them later
+//!   verify-dir   Check if two directory trees are equal after normalization
+//!   completions  Print a shell completion script to stdout
+//!   help         Print this message or the help of the given subcommand(s)
 //!
 //! Arguments:
-//!   <FILE_IN>
-//!           File to be hashed
+//!   [FILE_IN]
+//!           File to be hashed, directory with `--recursive`, or checksum file with `--check`
+//!
+//!           With `-`, standard input is hashed. If FILE_IN does not exist but contains one of the glob metacharacters `*`, `?` or `[`, it is expanded as a glob pattern, where `**` matches any number of directories. All matching files are hashed in sorted order, like with `--recursive`.
+//!
+//!   [FILE]...
+//!           More files to be hashed, after FILE_IN
 //!
-//!   [FILE_OUT]
-//!           Optional file path to write normalized input into
+//!           With three or more paths, all of them are hashed in the given order and printed with their names, like the files matched by a glob pattern. A file that cannot be read is reported and the remaining files are still hashed. The normalized files can be written with `--output-dir`. A single FILE is FILE_OUT, like with `--output-file`, unless `--recursive`, `--output-dir`, `--watch`, `--verify-sidecar` or `--verify-xattr` is given. To hash exactly two files otherwise, pass a glob pattern instead.
 //!
 //! Options:
+//!       --text <TEXT>
+//!           Hash TEXT instead of a file, as if it was the content of a file
+//!
+//!           Can be given multiple times, where each text is hashed as an input of its own and printed and recorded as `<text:1>`, `<text:2>` and so on. The text is normalized like a file, including the eol sequence appended to its last line, unless `--no-eof` is given. Texts may start with `-`, like `--text '-- comment'`.
+//!
+//!           [env: NORMALIZED_HASHER_TEXT]
+//!
+//!       --text-join
+//!           Hash all texts of `--text` as lines of a single input
+//!
+//!           [env: NORMALIZED_HASHER_TEXT_JOIN]
+//!
+//!       --from-clipboard
+//!           Hash the text in the clipboard instead of a file, as if it was the content of a file
+//!
+//!           The text is printed and recorded as `<clipboard>`. Combine it with `--expected` to verify a snippet copied from a document. It is an error if the clipboard is empty, holds no text or cannot be accessed, like on systems without a desktop session.
+//!
+//!           [env: NORMALIZED_HASHER_FROM_CLIPBOARD]
+//!
+//!   -c, --check
+//!           Read checksums from FILE_IN and verify them
+//!
+//!           Each line of the checksum file has to be either in GNU style (`HASH  FILE`) or in BSD style (`SHA256 (FILE) = HASH`). Blank lines and lines starting with `#` are ignored. For each listed file, `FILE: OK` or `FILE: FAILED` is printed. Exits with 1 if any file could not be verified. If FILE_IN is `-`, the checksums are read from stdin. Relative paths are resolved against the directory of the checksum file, or against the current directory if the checksums are read from stdin or `--relative-to-current-dir` is given.
+//!
+//!           If the checksum file starts with a header that records the normalization options, as written to `--output` and `--append` files, the files are checked with these options. It is an error if other options are given by flags, environment variables or configuration files, unless `--override-options` is given. Options that are not recorded, like `--paragraph-mode` and `--redact`, are applied as given.
+//!
+//!           [env: NORMALIZED_HASHER_CHECK]
+//!
+//!       --override-options
+//!           Check with the given normalization options, even if the checksum file records others
+//!
+//!           [env: NORMALIZED_HASHER_OVERRIDE_OPTIONS]
+//!
+//!       --relative-to-current-dir
+//!           Resolve relative paths in the checksum file against the current directory
+//!
+//!           By default, they are resolved against the directory of the checksum file. With this flag, they are resolved like by `sha256sum`. The results are printed with the paths as listed.
+//!
+//!           [env: NORMALIZED_HASHER_RELATIVE_TO_CURRENT_DIR]
+//!
+//!       --no-summary
+//!           Don't print a summary line at the end of a run with multiple files or `--check`
+//!
+//!           The summary counts the hashed, skipped and unreadable files and is printed to stderr, or with `--check`, the verified, failed, missing and skipped entries, printed with the results.
+//!
+//!           [env: NORMALIZED_HASHER_NO_SUMMARY]
+//!
+//!   -q, --quiet
+//!           Print only the result, without notes, warnings or progress bar
+//!
+//!           With `--check`, only the OK lines of successfully verified files are omitted, like with `sha256sum`. Otherwise, only hashes are printed, `--expected` only prints `OK` or `MISMATCH`, and notes, warnings and the progress bar on stderr are suppressed. Errors that abort the run are still printed. The exit code is not affected. Cannot be combined with `--verbose`.
+//!
+//!           [env: NORMALIZED_HASHER_QUIET]
+//!
+//!       --status
+//!           Don't output anything, the exit code shows success
+//!
+//!           [env: NORMALIZED_HASHER_STATUS]
+//!
 //!       --eol <EOL>
 //!           End-of-line sequence, will be appended to each normalized line for hashing
 //!
+//!           The names `lf`, `crlf` and `cr` can be used instead of the control characters.
+//!
+//!           [env: NORMALIZED_HASHER_EOL]
 //!           [default: "\n"]
 //!
 //!       --ignore-whitespaces
@@ -89,112 +170,4920 @@
 //!
 //!           This will remove all whitespaces from the input file when generating the hash.
 //!
+//!           [env: NORMALIZED_HASHER_IGNORE_WHITESPACES]
+//!
+//!       --redact <PATTERN=>REPLACEMENT>
+//!           Replace all matches of a regular expression in each line before hashing
+//!
+//!           Unlike leaving out whole lines, the line still counts with the replacement instead of the sensitive part. The replacement may refer to capture groups like `$1`. Can be given multiple times, the patterns are applied in the given order, each to the result of the previous ones. Normalized outputs contain the redacted lines. The patterns are not recorded in checksum headers, so they have to be given again to `--check`, which applies them on top of the options recorded in a header.
+//!
+//!           [env: NORMALIZED_HASHER_REDACT]
+//!
 //!       --no-eof
 //!           Skip last end-of-line on end-of-file
 //!
 //!           With this flag, no trailing EOL will be appended at the end of the file.
 //!
-//!   -h, --help
-//!           Print help (see a summary with '-h')
+//!           [env: NORMALIZED_HASHER_NO_EOF]
 //!
-//!   -V, --version
-//!           Print version
-//! ```
+//!   -a, --algorithm <ALGORITHM>
+//!           Digest algorithm of the hashes
 //!
-//! ### Flags
+//!           SHA-256 is the default, so hashes stay the same as with earlier versions. The algorithm is recorded in checksum headers and named in `--tag` lines, and `--check` only verifies hashes of the selected algorithm.
 //!
-//! -   `--eol`
+//!           [env: NORMALIZED_HASHER_ALGORITHM]
+//!           [default: sha256]
 //!
-//!     With the `--eol` flag you can change the end-of-line sequence that will be
-//!     appended to each normalized line to generate the hash. This can be useful
-//!     if you explicitly want CRLF endings, for example.
+//!           Possible values:
+//!           - sha256: SHA-256, with 64 hex digits
+//!           - sha512: SHA-512, with 128 hex digits
+//!           - blake3: BLAKE3, with 64 hex digits like SHA-256, so bare hashes need `--algorithm blake3`
 //!
-//!     Please note that you need to escape control characters properly in your
-//!     shell. For Bash, you can type:
+//!       --paragraph-mode
+//!           Hash paragraphs instead of lines, so rewrapping text keeps the hash
 //!
-//!     ```shell
-//!     normalized-hasher --eol $'\r\n' input.txt output.txt
-//!     ```
+//!           Paragraphs are separated by blank lines. Within a paragraph, line breaks and the whitespaces around them are replaced by single spaces, before `--ignore-whitespaces` applies. The paragraphs are joined with a single EOL sequence for hashing and in normalized outputs. The flag is not recorded in checksum headers, so it has to be given again to `--check`, which applies it on top of the options recorded in a header.
 //!
-//! -   `--ignore-whitespaces`
+//!           [env: NORMALIZED_HASHER_PARAGRAPH_MODE]
 //!
-//!     In some extreme cases, you might want to ignore all whitespaces in a file.
-//!     With the `--ignore-whitespaces` flag, all whitespaces are removed prior to
-//!     generate the hash.
+//!       --preserve-hard-breaks
+//!           Keep line breaks after two trailing spaces or a backslash with `--paragraph-mode`
 //!
-//! -   `--no-eof`
+//!           [env: NORMALIZED_HASHER_PRESERVE_HARD_BREAKS]
 //!
-//!     With the `--no-eof` flag you can avoid appending the EOL sequence at the
-//!     end of the file. This is for use cases where such trailing EOL is not
-//!     desireable, like in Windows files. In contrast to UNIX files which usually
-//!     end with a final LF, Windows files do not usually end with an additional
-//!     CRLF.
+//!       --keep-style
+//!           Write normalized files with the line ending that each input predominantly uses
 //!
-//! ## Examples
+//!           CRLF files stay CRLF and LF files stay LF, while the hash is still computed with the eol sequence, so it is the same on every platform. Applies to FILE_OUT, `--output-dir`, `--filter` and the `normalize` subcommand. Only the first 64 KiB of each input are counted. Without line endings there, or with as many CRLF as LF, the eol sequence is written.
 //!
-//! Simple example with default options, without writing an output file:
+//!           [env: NORMALIZED_HASHER_KEEP_STYLE]
 //!
-//! ```shell
-//! normalized-hasher input.txt
-//! ```
+//!       --config <FILE>
+//!           Read the settings from FILE instead of discovering configuration files
 //!
-//! More complex example, with writing output:
+//!           Without this flag, the user configuration file `normalized-hasher/config.toml` in the platform configuration directory and all `.normalized-hasher.toml` files from the repository root down to the directory of each input file are read, if they exist. Nearer files take precedence over farther ones, and all of them over the user configuration file. Flags on the command line take precedence over all configuration files.
 //!
-//! ```shell
-//! normalized-hasher --eol $'\r\n' --no-eof input.txt output.txt
-//! ```
-
-use std::ffi::OsString;
-
-use clap::Parser;
-
-use normalized_hash::Hasher;
-
-#[derive(Parser)]
-#[clap(author, version, about, long_about = None)]
-struct Cli {
-    /// File to be hashed
-    file_in: OsString,
-
-    /// Optional file path to write normalized input into
-    file_out: Option<OsString>,
-
-    /// End-of-line sequence, will be appended to each normalized line for hashing
-    #[arg(long, default_value = "\n")]
-    eol: String,
-
-    /// Ignore all whitespaces
-    ///
-    /// This will remove all whitespaces from the input file when generating the hash.
-    #[arg(long)]
-    ignore_whitespaces: bool,
-
-    /// Skip last end-of-line on end-of-file
-    ///
-    /// With this flag, no trailing EOL will be appended at the end of the file.
-    #[arg(long)]
-    no_eof: bool,
-}
-
-fn main() {
-    let cli = Cli::parse();
-
-    println!(
-        "{}",
-        Hasher::new()
-            .eol(cli.eol)
-            .no_eof(cli.no_eof)
-            .hash_file(cli.file_in, cli.file_out)
-    );
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn verify_app() {
-        use clap::CommandFactory;
-        Cli::command().debug_assert()
+//!           [env: NORMALIZED_HASHER_CONFIG]
+//!
+//!       --no-config
+//!           Don't read any configuration files
+//!
+//!           [env: NORMALIZED_HASHER_NO_CONFIG]
+//!
+//!       --show-config [<PATH>]
+//!           Print the effective settings and where they come from, without hashing anything
+//!
+//!           The settings are printed in the format of a configuration file, each followed by a comment naming its source, which is `default`, `command line` or a configuration file. With PATH, the settings that apply to the file PATH are printed, instead of those of the current directory.
+//!
+//!           [env: NORMALIZED_HASHER_SHOW_CONFIG]
+//!
+//!       --self-test
+//!           Check the golden test vectors of the hash algorithm, without hashing anything
+//!
+//!           Each vector is a frozen input with normalization options and its expected hash. PASS or FAIL is printed for each one, and any failure exits with code 1, so a deployed binary can prove that it implements the documented algorithm.
+//!
+//!           [env: NORMALIZED_HASHER_SELF_TEST]
+//!
+//!       --write-config <FILE>
+//!           Write the effective settings into a configuration FILE, without hashing anything
+//!
+//!           The settings of flags, environment variables and configuration files are resolved and written with a comment each, so running with FILE as configuration file behaves the same as this run. An existing FILE is only replaced with `--force`.
+//!
+//!           [env: NORMALIZED_HASHER_WRITE_CONFIG]
+//!
+//!       --force
+//!           Overwrite FILE_OUT or the FILE of `--write-config` without asking if it exists
+//!
+//!           [env: NORMALIZED_HASHER_FORCE]
+//!
+//!       --self-check
+//!           Read FILE_OUT back after writing it and check that it has the computed hash
+//!
+//!           Catches output files that are not written as intended, at the cost of reading them once more. The files of `--output-dir` are checked before they replace existing files. A mismatch is an error. Only applies when writing FILE_OUT or `--output-dir`, except for standard input.
+//!
+//!           [env: NORMALIZED_HASHER_SELF_CHECK]
+//!
+//!       --sync
+//!           Flush FILE_OUT to the storage device before reporting success
+//!
+//!           The file and, on Unix, its directory are synced, so a power loss right after a successful run cannot leave an empty or missing file behind. This waits for the storage device and can make writing many small files considerably slower, so it is off by default. Only applies when writing FILE_OUT or `--output-dir`, except for standard input.
+//!
+//!           [env: NORMALIZED_HASHER_SYNC]
+//!
+//!       --detect
+//!           Print what FILE_IN looks like instead of hashing it
+//!
+//!           Reports the guessed encoding, `UTF-8`, `UTF-16LE`, `UTF-16BE`, likely `Windows-1252` or `binary`, whether the file starts with a byte order mark, its dominant line ending and whether it mixes different ones, whether it ends with a newline, and the number of invisible characters like zero-width spaces or control characters. With `--json`, the findings are printed as JSON document.
+//!
+//!           [env: NORMALIZED_HASHER_DETECT]
+//!
+//!       --filter
+//!           Normalize stdin to stdout and print the hash to stderr at the end
+//!
+//!           Works as a stage in a pipeline, like `generate | normalized-hasher --filter | gzip`, where each line is passed on as soon as it is read. If the reader of stdout exits early, the rest of stdin is not read, no hash is printed, and the exit code is 3.
+//!
+//!           [env: NORMALIZED_HASHER_FILTER]
+//!
+//!       --hash-file <FILE>
+//!           Write the hash of `--filter` into FILE instead of stderr
+//!
+//!           [env: NORMALIZED_HASHER_HASH_FILE]
+//!
+//!       --files-from <FILE>
+//!           Hash all files listed in FILE instead of FILE_IN, or from stdin if FILE is `-`
+//!
+//!           The list contains one path per line, blank lines and lines starting with `#` are ignored. The files are hashed in the order of the list, like with `--recursive`.
+//!
+//!           [env: NORMALIZED_HASHER_FILES_FROM]
+//!
+//!   -0, --null
+//!           Paths in the `--files-from` list are separated by NUL bytes instead of newlines
+//!
+//!           This is the format of `find -print0`. Only empty entries are ignored.
+//!
+//!           [env: NORMALIZED_HASHER_NULL]
+//!
+//!   -r, --recursive
+//!           Hash all files in the directory FILE_IN and its subdirectories
+//!
+//!           For each file, a line like `HASH  FILE` is printed, sorted by path. Binary files are skipped with a note on stderr. Symbolic links are not followed, unless `--follow-symlinks` is given. Hidden files and directories are included, unless `--no-hidden` is given. Files ignored by `.gitignore` files are skipped, unless `--no-ignore` is given. `.git` directories are always skipped.
+//!
+//!           [env: NORMALIZED_HASHER_RECURSIVE]
+//!
+//!       --no-hidden
+//!           Skip hidden files and directories with `--recursive`
+//!
+//!           [env: NORMALIZED_HASHER_NO_HIDDEN]
+//!
+//!       --no-ignore
+//!           Don't respect `.gitignore` files with `--recursive`
+//!
+//!           [env: NORMALIZED_HASHER_NO_IGNORE]
+//!
+//!       --exclude <GLOB>
+//!           Exclude files matching a glob pattern
+//!
+//!           The pattern follows the `.gitignore` syntax and is matched against the path relative to the directory given to `--recursive`, or against the path of a file matched by a glob pattern in FILE_IN or listed with `--files-from`. Can be given multiple times. An explicitly named FILE_IN is never excluded.
+//!
+//!           [env: NORMALIZED_HASHER_EXCLUDE]
+//!
+//!       --max-depth <N>
+//!           Descend at most N directory levels with `--recursive`
+//!
+//!           With a depth of 1, only the files directly in FILE_IN are hashed.
+//!
+//!           [env: NORMALIZED_HASHER_MAX_DEPTH]
+//!
+//!       --follow-symlinks
+//!           Follow symbolic links with `--recursive`
+//!
+//!           Links to directories that are already being traversed are skipped with a warning. Directories and files that can be reached by more than one path are only hashed once.
+//!
+//!           [env: NORMALIZED_HASHER_FOLLOW_SYMLINKS]
+//!
+//!       --no-follow-input-symlinks
+//!           Fail on input files that are symbolic links, instead of hashing the files they point to
+//!
+//!           Applies to FILE_IN, to files matched by a glob pattern and to files listed with `--files-from`. A link whose target does not exist is always reported as such.
+//!
+//!           [env: NORMALIZED_HASHER_NO_FOLLOW_INPUT_SYMLINKS]
+//!
+//!       --no-dedup
+//!           Hash files that are given more than once every time
+//!
+//!           By default, a file that is given by several paths, like `./a.txt` and `a.txt`, a symbolic link and its target, or an overlapping glob pattern and `--files-from` list, is only hashed once, by the path it is given first.
+//!
+//!           [env: NORMALIZED_HASHER_NO_DEDUP]
+//!
+//!       --ext <EXT>
+//!           Only hash files with this extension with `--recursive`
+//!
+//!           The extension is given without the leading dot. Can be given multiple times. An empty extension selects files without an extension. Extensions are compared case-insensitively on Windows and case-sensitively elsewhere. Explicitly named files and files matched by a glob pattern are not filtered.
+//!
+//!           [env: NORMALIZED_HASHER_EXT]
+//!
+//!       --no-ext
+//!           Only hash files without an extension with `--recursive`, like `--ext ''`
+//!
+//!           [env: NORMALIZED_HASHER_NO_EXT]
+//!
+//!       --fail-fast
+//!           Abort on the first file that cannot be read with multiple files
+//!
+//!           By default, such files are skipped with a warning and the run completes with exit code 5.
+//!
+//!           [env: NORMALIZED_HASHER_FAIL_FAST]
+//!
+//!       --retry-on-modify <N>
+//!           Hash a file up to N more times if it is modified while it is read
+//!
+//!           The size and modification time of each file are compared before and after reading it, so a file that is still being written is not recorded with the hash of a half-written file. A file that is still modified after N retries cannot be hashed, which with multiple files is reported like an unreadable file.
+//!
+//!           [env: NORMALIZED_HASHER_RETRY_ON_MODIFY]
+//!           [default: 0]
+//!
+//!       --retries <N>
+//!           Hash a file up to N more times after a transient error while reading it
+//!
+//!           Network filesystems occasionally fail in the middle of reading a file. Such a file is opened again and hashed from scratch after `--retry-delay`, which doubles with each retry. Interrupted reads, reset connections and timeouts are transient, and so are the errors given with `--retry-on-errno`. Missing files and denied permissions fail right away.
+//!
+//!           [env: NORMALIZED_HASHER_RETRIES]
+//!           [default: 0]
+//!
+//!       --retry-delay <MS>
+//!           Wait MS milliseconds before the first retry with `--retries`
+//!
+//!           [env: NORMALIZED_HASHER_RETRY_DELAY]
+//!           [default: 100]
+//!
+//!       --retry-on-errno <ERRNO>
+//!           Also retry errors with this OS error number with `--retries`, like 5 for EIO on Linux
+//!
+//!           Can be given multiple times.
+//!
+//!           [env: NORMALIZED_HASHER_RETRY_ON_ERRNO]
+//!
+//!   -j, --jobs <N>
+//!           Hash up to N files at the same time with multiple files
+//!
+//!           Defaults to the number of CPUs, `-j 1` hashes one file after the other. The results are printed in the same order either way. With `--time`, the total is followed by the elapsed wall time, which shows the speedup.
+//!
+//!           [env: NORMALIZED_HASHER_JOBS]
+//!
+//!       --cache <FILE>
+//!           Reuse the hashes of unchanged files from FILE with multiple files
+//!
+//!           FILE stores the hash, size and modification time of each hashed file. If the size and modification time of a file are unchanged, its hash is taken from FILE without reading the file. Otherwise, the file is hashed and FILE is updated. FILE is created if it does not exist and is replaced atomically. If it cannot be read, all files are hashed, with a warning. Cached hashes are only used with the same normalization options. Files whose hash is taken from FILE are not checked for mixed line endings, so this cannot be combined with `--deny-mixed-eol`. Only applies when hashing multiple files.
+//!
+//!           [env: NORMALIZED_HASHER_CACHE]
+//!
+//!       --no-cache-read
+//!           Hash all files with `--cache`, but still update the cache file
+//!
+//!           [env: NORMALIZED_HASHER_NO_CACHE_READ]
+//!
+//!       --cache-verify-sample <PERCENT>
+//!           Hash a random fraction of the files anyway with `--cache`, like `5%`
+//!
+//!           A file whose hash differs from its cached hash, despite the same size and modification time, is reported with a warning and updated in the cache.
+//!
+//!           [env: NORMALIZED_HASHER_CACHE_VERIFY_SAMPLE]
+//!
+//!       --allow-empty-glob
+//!           Don't fail if a glob pattern in FILE_IN matches no files
+//!
+//!           [env: NORMALIZED_HASHER_ALLOW_EMPTY_GLOB]
+//!
+//!   -v, --verbose...
+//!           Report what normalization did and print notes about skipped files
+//!
+//!           For each hashed file, a note on stderr reports the number of lines read, the number of converted CRLF line endings, whether the file starts with a byte order mark, which is kept, and whether it ends with a newline. Given twice, a second note counts the line endings found and converted, and the changes to the final newline and to line contents.
+//!
+//!           With multiple files, files that are neither regular files nor directories, like named pipes, sockets or devices, are skipped, since reading from them might block forever. With this flag, a note is printed for each of them, and for each duplicate skipped due to `--follow-symlinks` or given more than once.
+//!
+//!       --time
+//!           Report how long hashing took and the effective throughput
+//!
+//!           After the results, the duration and throughput of hashing each file are printed to stderr, followed by the total and the elapsed wall time with multiple files. Only opening, reading and hashing the files is measured, not the startup or the traversal of directories. With `--json`, the figures are included in the document instead, as `time` objects with the fields `milliseconds` and `bytes_per_second`.
+//!
+//!           [env: NORMALIZED_HASHER_TIME]
+//!
+//!       --no-warn-mixed-eol
+//!           Don't warn about files that mix line endings
+//!
+//!           By default, a warning on stderr lists the counts of each style if a hashed file mixes CRLF, LF and lone CR line endings, like after a partial paste. The hash is not affected.
+//!
+//!           [env: NORMALIZED_HASHER_NO_WARN_MIXED_EOL]
+//!
+//!       --deny-mixed-eol
+//!           Fail if a hashed file mixes line endings
+//!
+//!           The warning about mixed line endings becomes an error, and the run exits with code 1 after hashing all files, like for a mismatch.
+//!
+//!           [env: NORMALIZED_HASHER_DENY_MIXED_EOL]
+//!
+//!       --pause
+//!           Wait for Enter before exiting, to keep the console window open
+//!
+//!           After the results or errors are printed, `Press Enter to close...` is printed to stderr and a line is read from stdin. This is only done if stdin is a terminal, so the flag never blocks scripts. Also applies if the arguments are invalid.
+//!
+//!           [env: NORMALIZED_HASHER_PAUSE]
+//!
+//!       --watch
+//!           Keep running and hash FILE_IN again whenever it changes
+//!
+//!           The hash is printed right away and after each change, in lines like `TIMESTAMP  HASH  FILE` with the time in UTC. Changes in quick succession, like an editor writing a file twice, only print one line. A file that is replaced by renaming another file over it, like editors do to save atomically, is still watched afterwards. Multiple files can be watched with a glob pattern. Stops with exit code 0 on Ctrl-C.
+//!
+//!           [env: NORMALIZED_HASHER_WATCH]
+//!
+//!       --no-progress
+//!           Don't show a progress bar
+//!
+//!           By default, a progress bar is shown on stderr while hashing, if stderr is a terminal. For a single file, it shows the bytes read, for multiple files the number of hashed files.
+//!
+//!           [env: NORMALIZED_HASHER_NO_PROGRESS]
+//!
+//!       --no-tty-warning
+//!           Don't warn before reading standard input from a terminal
+//!
+//!           If FILE_IN is `-` and standard input is an interactive terminal, a note on stderr tells that typed input is awaited, since the program seems to hang otherwise. `--filter` even refuses to read from a terminal. With this flag, standard input is read without either.
+//!
+//!           [env: NORMALIZED_HASHER_NO_TTY_WARNING]
+//!
+//!       --expected <HASH>
+//!           Verify the hash against an expected value
+//!
+//!           Instead of printing the hash, compare it to the given value and print `OK` or `MISMATCH` together with both values, which go to stderr on a mismatch. The expected value can be in any encoding of `--format`. Whitespace, quotes, invisible characters like zero-width spaces, an algorithm prefix like `SHA-256:` and the case of hex values are ignored, so hashes can be pasted from documents. Exits with 1 on mismatch. FILE_OUT is written in either case.
+//!
+//!           [env: NORMALIZED_HASHER_EXPECTED]
+//!           [aliases: expect]
+//!
+//!       --tag
+//!           Create a BSD-style checksum line
+//!
+//!           Instead of the bare hash, print a line like `SHA256 (FILE_IN) = HASH`.
+//!
+//!           [env: NORMALIZED_HASHER_TAG]
+//!
+//!       --with-filename
+//!           Create a GNU-style checksum line
+//!
+//!           Instead of the bare hash, print a line like `HASH  FILE_IN`, as printed for each file when hashing multiple files. As for multiple files, it is an error if its name contains a line break, which would split the line, unless `--print0` is given.
+//!
+//!           [env: NORMALIZED_HASHER_WITH_FILENAME]
+//!
+//!       --copy
+//!           Copy the printed hash to the clipboard
+//!
+//!           The hash is printed as usual and copied in the encoding of `--format`, without the file name of `--tag`. Only a single file can be hashed, it is an error to use `--copy` with multiple files. Fails with exit code 3 if no clipboard is available, like on systems without a desktop session.
+//!
+//!           [env: NORMALIZED_HASHER_COPY]
+//!
+//!       --template <TEMPLATE>
+//!           Print each hash into a text snippet instead of the checksum line
+//!
+//!           TEMPLATE is the text itself, or `@FILE` to read it from FILE. The placeholders `{hash}`, `{file}`, `{filename}`, `{algorithm}`, `{date}`, `{lines}` and `{size}` are replaced by the hash in the encoding of `--format`, the printed path, its last component, `SHA256`, the current date in UTC like `2023-08-26`, the number of lines and the size in bytes. Write `{{` and `}}` for literal braces. With multiple files, the template is printed once per file. With `--copy`, the filled in template is copied instead of the bare hash.
+//!
+//!           [env: NORMALIZED_HASHER_TEMPLATE]
+//!
+//!       --qr
+//!           Print the hash as a QR code
+//!
+//!           The QR code is printed with half blocks after the hash and encodes a self-describing line like `normalized-hasher: eol=lf ignore_whitespaces=false no_eof=false algorithm=sha256 format=v1 hash=HASH`, with the hash in the encoding of `--format`, so a scanned code tells how to verify it. Light modules are drawn as blocks, for terminals with a dark background. Only a single file can be hashed.
+//!
+//!           [env: NORMALIZED_HASHER_QR]
+//!
+//!       --qr-out <FILE>
+//!           Write the QR code of `--qr` as PNG image into FILE
+//!
+//!           The hash is printed as usual, the QR code is only printed as well if `--qr` is given.
+//!
+//!           [env: NORMALIZED_HASHER_QR_OUT]
+//!
+//!       --qr-level <LEVEL>
+//!           Error correction level of QR codes
+//!
+//!           Higher levels can still be scanned if parts of the code are damaged, but need larger codes.
+//!
+//!           [env: NORMALIZED_HASHER_QR_LEVEL]
+//!           [default: medium]
+//!
+//!           Possible values:
+//!           - low:      Recover about 7% of the code
+//!           - medium:   Recover about 15% of the code
+//!           - quartile: Recover about 25% of the code
+//!           - high:     Recover about 30% of the code, for prints that might get damaged
+//!
+//!       --output-file <FILE_OUT>
+//!           Write the normalized form of FILE_IN into FILE_OUT
+//!
+//!           FILE_OUT can also be given as the only path after FILE_IN. An existing FILE_OUT is only replaced with `--force`. In an interactive console, it is asked whether to overwrite it instead.
+//!
+//!           [env: NORMALIZED_HASHER_OUTPUT_FILE]
+//!
+//!       --output-dir <DIR>
+//!           Write the normalized form of each file into DIR
+//!
+//!           Each file keeps its path relative to the `--relative-to` directory if given, otherwise to the common ancestor of the inputs, so with `--recursive src`, the file `src/a/b.txt` is written to `DIR/a/b.txt`. Subdirectories are created as needed. Outputs outside of DIR and outputs that would overwrite any input are refused before anything is written. Binary files are not copied.
+//!
+//!           [env: NORMALIZED_HASHER_OUTPUT_DIR]
+//!
+//!       --relative-to <DIR>
+//!           Print and record paths relative to this directory
+//!
+//!           Applies to the printed output, to `--append` and to `--json` and `--csv` reports. It is an error if a file is not inside of DIR, unless `--allow-outside` is given. Paths are resolved lexically, without following symlinks.
+//!
+//!           [env: NORMALIZED_HASHER_RELATIVE_TO]
+//!
+//!       --allow-outside
+//!           Use absolute paths for files outside of the `--relative-to` directory
+//!
+//!           [env: NORMALIZED_HASHER_ALLOW_OUTSIDE]
+//!
+//!       --stdin-name <LABEL>
+//!           Print and record standard input as LABEL instead of `-`
+//!
+//!           Applies to the checksum lines of `--tag`, to `--append` and to `--json` and `--csv` reports, but not to the hash itself. It is an error if FILE_IN is not `-`.
+//!
+//!           [env: NORMALIZED_HASHER_STDIN_NAME]
+//!
+//!       --git <REV>
+//!           Hash FILE_IN as committed at the revision REV, like `HEAD`, instead of the working copy
+//!
+//!           The file is read from the git repository that contains it, so the hash tells if the normalized content changed since the commit. It is an error if the file is not tracked at that revision.
+//!
+//!           [env: NORMALIZED_HASHER_GIT]
+//!
+//!       --doc
+//!           Hash the text of FILE_IN, a .docx or .odt document
+//!
+//!           The text of the document body is extracted with one line per paragraph, and normalized like the content of a text file. Line breaks within paragraphs start new lines as well. Images, comments and footnotes are ignored. With `--ignore-whitespaces`, non-breaking spaces of word processors are removed like all other whitespaces. Encrypted documents, legacy .doc files and other formats are an error.
+//!
+//!           [env: NORMALIZED_HASHER_DOC]
+//!
+//!       --no-filename
+//!           Print only the hashes, without file names
+//!
+//!           Prints one bare hash per file in input order, also when hashing multiple files. With `--check`, only the result of each file is printed.
+//!
+//!           [env: NORMALIZED_HASHER_NO_FILENAME]
+//!
+//!       --filename-only
+//!           Print only the file names, without hashes
+//!
+//!           Prints the path of each hashed file in input order. With `--check`, only the paths of failed files are printed.
+//!
+//!           [env: NORMALIZED_HASHER_FILENAME_ONLY]
+//!
+//!   -z, --print0
+//!           Terminate each output record with a NUL byte instead of a newline
+//!
+//!           Makes the output safe to split with `xargs -0` or `read -d ''`, even if file names contain newlines, which are an error in checksum lines otherwise. Composes with `--files-from FILE -0`.
+//!
+//!           [env: NORMALIZED_HASHER_PRINT0]
+//!
+//!       --strict-names
+//!           Fail on file names that are not valid UTF-8, instead of escaping them
+//!
+//!           By default, bytes of such names are printed and recorded as `%` followed by two hex digits, like `caf%E9.txt`, which `--check` reads back into the original name.
+//!
+//!           [env: NORMALIZED_HASHER_STRICT_NAMES]
+//!
+//!       --format <FORMAT>
+//!           Encoding of printed hashes
+//!
+//!           Applies to all printed hashes, also with `--json` and `--csv`. Checksum and sidecar files are always written in hex. Expected hashes, in `--expected`, `--check` and `--verify-sidecar`, are accepted in any of these encodings. `sri` and `multihash` already name the algorithm and cannot be combined with `--tag`.
+//!
+//!           [env: NORMALIZED_HASHER_FORMAT]
+//!           [default: hex]
+//!
+//!           Possible values:
+//!           - hex:       Lowercase hex
+//!           - HEX:       Uppercase hex
+//!           - base64:    Base64 with padding
+//!           - sri:       Subresource Integrity, like `sha256-BASE64`
+//!           - multihash: Multihash in hex, like `1220HEX`
+//!
+//!       --json
+//!           Print the results as JSON document
+//!
+//!           The document contains an array `entries` with an object per file, with the fields `path`, `hash`, `algorithm`, `bytes` and `changed`, or `path`, `algorithm` and `error` if the file could not be hashed. The `error` is an object with the fields `kind`, which is `open`, `read`, `decode`, `create` or `write`, and `message`. The object `summary` contains the counters `hashed`, `changed`, `skipped` and `errors`. Warnings are still printed to stderr. Errors that abort the run are printed to stderr as a JSON line with the fields `path`, if the error concerns a single file, and `error`, whose `kind` can also be `usage`, `io`, `conflict` or `mixed-eol`.
+//!
+//!           [env: NORMALIZED_HASHER_JSON]
+//!
+//!       --json-lines
+//!           Print each result as a JSON line as soon as it is available
+//!
+//!           Instead of a single document, each entry of `--json` is printed as an object of its own on a single line, with the additional field `type` set to `entry`, as soon as the file is hashed. The last line is the summary, with the `type` set to `summary`. Thus, results can be consumed while the run is going on, and all finished results are kept if it is aborted. Only the summary is kept in memory.
+//!
+//!           [env: NORMALIZED_HASHER_JSON_LINES]
+//!
+//!       --unordered
+//!           Print the JSON lines as the files finish, instead of in the order of the files
+//!
+//!           Each entry gets the field `seq` with the index of its file in the usual order, starting at 0. Results of the cache are printed first.
+//!
+//!           [env: NORMALIZED_HASHER_UNORDERED]
+//!
+//!       --csv
+//!           Print the results as CSV
+//!
+//!           A header row is followed by one row per hashed file, with the columns `path`, `algorithm`, `hash`, `size`, `lines` and `changed`. Records are terminated by CRLF.
+//!
+//!           [env: NORMALIZED_HASHER_CSV]
+//!
+//!       --csv-delimiter <CHAR>
+//!           Field delimiter for `--csv`
+//!
+//!           [env: NORMALIZED_HASHER_CSV_DELIMITER]
+//!           [default: ,]
+//!
+//!   -o, --output <FILE>
+//!           Write the results to FILE instead of stdout
+//!
+//!           Applies to the checksum lines, the `--check` results and the `--json` and `--csv` reports, while errors and warnings are still printed to stderr. Checksum lines list the paths relative to the directory of FILE, where `--check` resolves them. FILE is replaced atomically once all results are written. If writing fails, or the run fails with exit code 2 or 3, FILE is left untouched. The checksum file given to `--append` is updated independently and has to be a different file.
+//!
+//!           [env: NORMALIZED_HASHER_OUTPUT]
+//!
+//!       --append <CHECKSUM_FILE>
+//!           Add the hash to a checksum file
+//!
+//!           An existing entry for FILE_IN is updated, otherwise a new line is appended. All other lines, including comments, are preserved. The checksum file is replaced atomically. The path of FILE_IN is listed relative to the directory of the checksum file.
+//!
+//!           [env: NORMALIZED_HASHER_APPEND]
+//!
+//!       --no-update
+//!           Fail instead of updating an existing entry with a different hash
+//!
+//!           [env: NORMALIZED_HASHER_NO_UPDATE]
+//!
+//!       --write-sidecar
+//!           Write the hash into a sidecar checksum file next to FILE_IN
+//!
+//!           The sidecar is named like FILE_IN with the sidecar suffix appended.
+//!
+//!           [env: NORMALIZED_HASHER_WRITE_SIDECAR]
+//!
+//!       --verify-sidecar
+//!           Verify FILE_IN against its sidecar checksum file
+//!
+//!           Prints `FILE: OK`, `FILE: FAILED` or `FILE: MISSING`. Exits with 1 if the file could not be verified.
+//!
+//!           [env: NORMALIZED_HASHER_VERIFY_SIDECAR]
+//!
+//!       --sidecar-suffix <SUFFIX>
+//!           Suffix of sidecar checksum files
+//!
+//!           Defaults to `.sha256`, or to the name of another `--algorithm`, like `.sha512`.
+//!
+//!           [env: NORMALIZED_HASHER_SIDECAR_SUFFIX]
+//!
+//!       --xattr
+//!           Store the hash in the extended attribute `user.normalized_hash.sha256` of FILE_IN
+//!
+//!           The attribute also records the normalization options, so that `--verify-xattr` can tell a changed file from a hash of other options. With another `--algorithm`, the attribute is named after it, like `user.normalized_hash.sha512`. Needs a file system with extended attributes.
+//!
+//!           [env: NORMALIZED_HASHER_XATTR]
+//!
+//!       --verify-xattr
+//!           Verify FILE_IN against the hash in its extended attribute
+//!
+//!           Prints `FILE: OK`, `FILE: FAILED` or `FILE: UNSET`. If the attribute was stored with other normalization options, the result is `FAILED` and the stored options are printed. Exits with 1 if the hash differs and with 3 if the attribute is not set.
+//!
+//!           [env: NORMALIZED_HASHER_VERIFY_XATTR]
+//!
+//!       --color <WHEN>
+//!           When to colorize the results of `--check`, `--verify-sidecar`, `--expected` and `compare`
+//!
+//!           With `auto`, colors are only used if stdout is a terminal and the environment variable `NO_COLOR` is not set. Reports of `--json` and `--csv` are never colorized.
+//!
+//!           [env: NORMALIZED_HASHER_COLOR]
+//!           [default: auto]
+//!
+//!           Possible values:
+//!           - auto:   Colorize if printing to a terminal and `NO_COLOR` is not set
+//!           - always: Always colorize
+//!           - never:  Never colorize
+//!
+//!       --output-format <FORMAT>
+//!           Also print the results of `lint`, `--check`, `manifest verify` and `verify-dir` for CI
+//!
+//!           With `github`, each offending file gets an error annotation and each skipped file a notice, as workflow commands that GitHub Actions shows on the changed lines. They are printed alongside the normal output, regardless of `--quiet` and `--status`, and to stderr if stdout is a JSON document.
+//!
+//!           [env: NORMALIZED_HASHER_OUTPUT_FORMAT]
+//!           [default: plain]
+//!
+//!           Possible values:
+//!           - plain:  Only the normal output
+//!           - github: Also print workflow commands, which GitHub Actions shows as annotations
+//!
+//!   -h, --help
+//!           Print help (see a summary with '-h')
+//!
+//!   -V, --version
+//!           Print version
+//!
+//! Exit status:
+//!   0  Success, all files verified, equal or normalized
+//!   1  At least one hash mismatch or difference
+//!   2  Invalid usage or arguments
+//!   3  At least one file could not be read or written
+//!   4  Malformed checksum input
+//!   5  Completed with errors, unreadable files were skipped
+//!
+//! If several failures occur, the first matching one is used in the order 2, 3, 5, 4, 1.
+//! ```
+//!
+//! ### Interactive Use
+//!
+//! When `normalized-hasher` is started without any arguments from an
+//! interactive console, like by double-clicking it in the Windows Explorer, it
+//! asks for the file to hash instead of failing with a usage error:
+//!
+//! ```text
+//! Enter the path of the file to hash:
+//! ```
+//!
+//! The path can be typed or dropped into the console, surrounding quotes as
+//! added by the Windows console are removed. The file is hashed with the
+//! default options, and the result stays visible until Enter is pressed. Without
+//! an interactive console, like in scripts, a missing argument is still a usage
+//! error.
+//!
+//! ### Output Order
+//!
+//! Whenever multiple files are hashed, the results are printed in a fixed
+//! order, so that checksum lists of different runs can be diffed without
+//! spurious changes:
+//!
+//! -   Files given on the command line or listed with `--files-from` keep their
+//!     order.
+//! -   Files found with `--recursive` or a glob pattern are sorted bytewise by
+//!     their path, with `/` as separator on all platforms. Thus, `a.txt` comes
+//!     before `a/b.txt`, and `B.txt` before `a.txt`, like with `LC_ALL=C sort`.
+//!
+//! The order does not depend on the file system, on the order in which files
+//! were created, on the platform or on the number of `--jobs`.
+//!
+//! ### Long Paths on Windows
+//!
+//! Windows limits ordinary paths to 260 characters, which deeply nested
+//! project directories exceed easily. `normalized-hasher` opens all files in
+//! the extended-length form, like `\\?\C:\src\input.txt`, so such paths can
+//! be hashed, written and traversed with `--recursive` anyway. Paths are still
+//! printed as they were given, relative paths stay relative.
+//!
+//! ### File Names That Are Not Valid UTF-8
+//!
+//! File names are bytes on Unix, and old archives often contain names in
+//! encodings like Latin-1, which are not valid UTF-8. Such files are hashed
+//! like any other file. Whenever their names are printed or recorded, in
+//! checksum lines, manifests, sidecar files and `--json` and `--csv` reports,
+//! each byte that is not part of valid UTF-8 is written as `%` followed by two
+//! hex digits, like `caf%E9.txt`. A `%` that would be mistaken for such an
+//! escape is written as `%25`, all other names stay as they are. `--check` and
+//! `manifest verify` read the escapes back into the original bytes, so the
+//! files are found again. With `--strict-names`, such names are an error
+//! instead.
+//!
+//! ### Environment Variables
+//!
+//! Every option and flag of the main command can also be set by an environment
+//! variable, which is named after the flag with the prefix `NORMALIZED_HASHER_`,
+//! like `NORMALIZED_HASHER_EOL` for `--eol` or `NORMALIZED_HASHER_NO_EOF` for
+//! `--no-eof`. The name of each variable is shown in `--help`. This is useful
+//! where only the environment of an invocation can be changed, like in some CI
+//! systems:
+//!
+//! ```shell
+//! NORMALIZED_HASHER_EOL=crlf NORMALIZED_HASHER_NO_EOF=yes normalized-hasher input.txt
+//! ```
+//!
+//! Flags are enabled by `1`, `true` or `yes` and disabled by `0`, `false` or
+//! `no`, in any case. Any other value is an error that names the variable.
+//! Environment variables take precedence over configuration files, and flags
+//! on the command line take precedence over environment variables. Positional
+//! arguments, `--verbose` and the flags of subcommands have no variables, but
+//! the global flags like `--eol` apply to subcommands as well.
+//!
+//! ### Flags
+//!
+//! -   `--eol`
+//!
+//!     With the `--eol` flag you can change the end-of-line sequence that will be
+//!     appended to each normalized line to generate the hash. This can be useful
+//!     if you explicitly want CRLF endings, for example.
+//!
+//!     Please note that you need to escape control characters properly in your
+//!     shell. For Bash, you can type:
+//!
+//!     ```shell
+//!     normalized-hasher --eol $'\r\n' input.txt --output-file output.txt
+//!     ```
+//!
+//!     The common line endings can also be given by name, as `lf`, `crlf` or
+//!     `cr`:
+//!
+//!     ```shell
+//!     normalized-hasher --eol crlf input.txt --output-file output.txt
+//!     ```
+//!
+//! -   `--ignore-whitespaces`
+//!
+//!     In some extreme cases, you might want to ignore all whitespaces in a file.
+//!     With the `--ignore-whitespaces` flag, all whitespaces are removed prior to
+//!     generate the hash.
+//!
+//! -   `--redact`
+//!
+//!     To share the hash of a configuration file without its secrets, the
+//!     sensitive part of a line can be replaced with a fixed placeholder, so the
+//!     line itself and everything around it still count. `--redact` takes a
+//!     regular expression and a replacement, separated by `=>`, and replaces all
+//!     matches in each line:
+//!
+//!     ```shell
+//!     normalized-hasher --redact 'password=.*=>password=<redacted>' db.conf
+//!     ```
+//!
+//!     The replacement may refer to capture groups like `$1`. The flag can be
+//!     given multiple times, the patterns are applied in the given order, each
+//!     to the result of the previous ones, and before `--ignore-whitespaces`.
+//!     Written output files contain the redacted lines, so they hash to the same
+//!     value. The patterns are not recorded in the header of checksum files and
+//!     have to be given again to `--check`, which applies them on top of the
+//!     options recorded in the header:
+//!
+//!     ```shell
+//!     normalized-hasher --redact 'password=.*=>password=<redacted>' -r conf -o SUMS
+//!     normalized-hasher --redact 'password=.*=>password=<redacted>' --check SUMS
+//!     ```
+//!
+//! -   `--paragraph-mode`, `--preserve-hard-breaks`
+//!
+//!     Prose like documentation is often rewrapped to another width, which
+//!     changes every line break but not the text. With `--paragraph-mode`,
+//!     paragraphs are hashed instead of lines, so the hash stays the same:
+//!
+//!     ```shell
+//!     normalized-hasher --paragraph-mode README.md
+//!     ```
+//!
+//!     Paragraphs are separated by blank lines, which only contain whitespaces.
+//!     Within a paragraph, each line break and the whitespaces around it are
+//!     replaced by a single space, before `--ignore-whitespaces` applies. The
+//!     paragraphs are joined with a single EOL sequence, both for hashing and in
+//!     written output files, which thus contain one paragraph per line. Other
+//!     per-line options like `--redact` still apply to each line before it is
+//!     joined.
+//!
+//!     Markdown ends a line with a hard break if it ends with two spaces or a
+//!     backslash. With `--preserve-hard-breaks`, such lines are kept unchanged
+//!     and end with the EOL sequence instead of being joined with the next line.
+//!
+//!     Like `--redact`, the flags are not recorded in the header of checksum
+//!     files and have to be given again to `--check`, which applies them on top
+//!     of the options recorded in the header.
+//!
+//! -   `--no-eof`
+//!
+//!     With the `--no-eof` flag you can avoid appending the EOL sequence at the
+//!     end of the file. This is for use cases where such trailing EOL is not
+//!     desireable, like in Windows files. In contrast to UNIX files which usually
+//!     end with a final LF, Windows files do not usually end with an additional
+//!     CRLF.
+//!
+//! -   `--algorithm`
+//!
+//!     Hashes are SHA-256 by default, so they stay the same as with earlier
+//!     versions. With `--algorithm` (or `-a`), SHA-512 or BLAKE3 is used
+//!     instead, for policies requiring another digest:
+//!
+//!     ```shell
+//!     normalized-hasher --algorithm sha512 input.txt
+//!     ```
+//!
+//!     Only the digest changes, the content is normalized just the same. The
+//!     algorithm is recorded in the header of checksum files, so `--check`
+//!     verifies them without giving it again, and `--tag` names it on each line,
+//!     like `SHA512 (input.txt) = ...`, which is enough for `--check` to verify
+//!     the line with SHA-512. Lines with hashes of another algorithm than the
+//!     selected or recorded one are skipped. `--expected`, sidecar files
+//!     (`.sha512`) and the cache use the selected algorithm as well, and
+//!     `--format` encodes its digest, like `sha512-...` in SRI.
+//!
+//!     BLAKE3 hashes have 64 hex digits like SHA-256 hashes, so a bare hash
+//!     cannot tell them apart. `--expected` and GNU-style lines of checksum
+//!     files without a header are taken as SHA-256, a BLAKE3 hash has to be
+//!     checked with `--algorithm blake3`:
+//!
+//!     ```shell
+//!     normalized-hasher --algorithm blake3 --check B3SUMS
+//!     ```
+//!
+//! -   `--keep-style`
+//!
+//!     To fix a file in place without changing its native style, `--keep-style`
+//!     writes normalized files with the line ending that the input predominantly
+//!     uses. A mostly CRLF file with a few stray LF lines comes out uniformly
+//!     CRLF, while its hash is still computed with the EOL sequence and thus
+//!     equals that of its LF twin. This applies to FILE_OUT, `--output-dir`,
+//!     `--filter` and the `normalize` subcommand, where files that consistently
+//!     use their style are left unchanged:
+//!
+//!     ```shell
+//!     normalized-hasher normalize --in-place --keep-style -r src/
+//!     ```
+//!
+//!     Only the line endings in the first 64 KiB of each input are counted, so
+//!     streams are not buffered. If there are none, or as many CRLF as LF, the
+//!     EOL sequence is written as without the flag.
+//!
+//! -   `--expected`
+//!
+//!     To verify a file against a hash from a specification document, pass the
+//!     recorded hash with the `--expected` flag, or its alias `--expect`. Instead
+//!     of the plain hash, the result `OK` or `MISMATCH` is printed, together with
+//!     the expected and the actual hash, which are printed to stderr on a
+//!     mismatch. The expected hash can be in any encoding of `--format`.
+//!     Whitespace, quotes, invisible characters like soft hyphens and zero-width
+//!     spaces, an algorithm prefix like `SHA-256:` and the case of hex values are
+//!     ignored, so hashes can be pasted from documents and web pages. A value that
+//!     is no hash of the selected `--algorithm` is rejected with a description of
+//!     what was found, like `found 63 hex digits`, before anything is hashed. On
+//!     a mismatch, the exit code is 1, while a malformed expected value is
+//!     rejected as a usage error with exit code 2. A given `FILE_OUT` is written
+//!     in either case:
+//!
+//!     ```shell
+//!     normalized-hasher --expected sha256:b62e3392... input.txt
+//!     normalized-hasher --expect b62e3392... spec.sql --output-file normalized.sql
+//!     ```
+//!
+//! -   `--tag`, `--with-filename`
+//!
+//!     With the `--tag` flag, a BSD-style checksum line like
+//!     `SHA256 (input.txt) = b62e3392...` is printed instead of the bare hash.
+//!     The file name is printed verbatim, even if it contains parentheses. With
+//!     `--with-filename`, a GNU-style line like `b62e3392...  input.txt` is
+//!     printed, as for each file when hashing multiple files. Both can be
+//!     verified with `--check`:
+//!
+//!     ```shell
+//!     normalized-hasher --with-filename input.txt >> SHA256SUMS
+//!     ```
+//!
+//!     Names with line breaks would split the line, so they are an error
+//!     unless `--print0` is given, for a single file as for checksum lines of
+//!     multiple files or `--recursive`. Lines appended with `--append` always
+//!     end with a newline, so such names cannot be appended.
+//!
+//! -   `--check`
+//!
+//!     With the `--check` flag, `FILE_IN` is read as a checksum file and every
+//!     listed file is verified with the normalization rules, printing `FILE: OK`
+//!     or `FILE: FAILED` per entry. Lines can be in GNU style (`HASH  FILE`, also
+//!     with a single space) or in BSD style (`SHA256 (FILE) = HASH`), even mixed
+//!     within the same file, which makes the output of `--tag` directly
+//!     verifiable. Blank lines and comments starting with `#` are ignored, other
+//!     malformed lines are reported with a warning. BSD lines are checked with
+//!     the algorithm they name, unless `--algorithm` or the header selects one,
+//!     then lines naming another algorithm are skipped with an error like
+//!     `SHA512 line skipped, checking with SHA256 (header)`. Lines naming
+//!     an unsupported algorithm are reported as errors as well, and both result
+//!     in exit code 4. If any file fails, the exit code is 1.
+//!     Files that cannot be read, like missing ones, are printed as `FILE: FAILED
+//!     open or read` with the reason on stderr, and the exit code is 3. With
+//!     `--quiet`, OK lines are omitted, and with `--status`, nothing is printed
+//!     at all. Relative paths are resolved against the directory of the checksum
+//!     file, so a checksum file stored next to the files it lists can be checked
+//!     from anywhere. If the checksum file is `-`, the list is read from stdin
+//!     and the paths are resolved against the current directory:
+//!
+//!     ```shell
+//!     normalized-hasher --check dist/SHA256SUMS
+//!     curl https://example.com/SHA256SUMS | normalized-hasher --check --quiet -
+//!     ```
+//!
+//!     Checksum files written with `--output` or `--append` list the paths
+//!     relative to their own directory, so `-r dist -o dist/SHA256SUMS` lists
+//!     `a.txt` instead of `dist/a.txt`. With
+//!     `--relative-to-current-dir`, paths are always resolved against the
+//!     current directory, like by `sha256sum`, for checksum files written by
+//!     other tools that list paths relative to another directory than their
+//!     own:
+//!
+//!     ```shell
+//!     normalized-hasher --check --relative-to-current-dir dist/SHA256SUMS
+//!     ```
+//!
+//!     Checksum files written with `--output` or `--append` start with a
+//!     comment that records the normalization options they were generated
+//!     with, like:
+//!
+//!     ```text
+//!     # normalized-hasher: eol=lf ignore_whitespaces=false no_eof=true algorithm=sha256 format=v1
+//!     ```
+//!
+//!     `--check` uses these options instead of the given ones, so a checksum
+//!     file is verified correctly without repeating its flags. It is an error
+//!     if other options are given by flags, environment variables or
+//!     configuration files, unless `--override-options` is given, which checks
+//!     with the given options anyway. `--paragraph-mode` and `--redact` are not
+//!     recorded, so they are applied as given on top of the recorded options.
+//!     Checksum files without this comment are checked with the given options,
+//!     as before.
+//!
+//! -   `--append`
+//!
+//!     To maintain a growing checksum file, use `--append` with the path of the
+//!     checksum file. An existing entry for the same file is updated with the new
+//!     hash, otherwise a new line is appended, in BSD style if `--tag` is given.
+//!     All other lines, including comments, keep their order. The checksum file
+//!     is replaced atomically. A new checksum file starts with a comment that
+//!     records the normalization options, see `--check`, and adding to a
+//!     checksum file that records other options is an error. The path of the
+//!     file is listed relative to the directory of the checksum file. To catch
+//!     accidental double builds, `--no-update` fails instead of changing an
+//!     existing entry with a different hash:
+//!
+//!     ```shell
+//!     normalized-hasher --append SHA256SUMS --no-update dist/app.sql
+//!     ```
+//!
+//! -   `--write-sidecar`, `--verify-sidecar`
+//!
+//!     Instead of maintaining a central checksum file, the hash can be stored in a
+//!     sidecar file next to the input. With `--write-sidecar`, the hash is written
+//!     to `FILE_IN.sha256` in the format of `sha256sum`. With `--verify-sidecar`,
+//!     `FILE_IN` is verified against its sidecar, printing `OK`, `FAILED` or
+//!     `MISSING`, with exit code 1 in the latter two cases. The suffix can be
+//!     changed with `--sidecar-suffix`:
+//!
+//!     ```shell
+//!     normalized-hasher --write-sidecar --sidecar-suffix .sum input.txt
+//!     normalized-hasher --verify-sidecar --sidecar-suffix .sum input.txt
+//!     ```
+//!
+//! -   `--xattr`, `--verify-xattr`
+//!
+//!     On file systems with extended attributes, the hash can also be attached to
+//!     the file itself. With `--xattr`, it is stored in the attribute
+//!     `user.normalized_hash.sha256`, along with the normalization options, like
+//!     `v1 eol=0a ignore-whitespaces=false no-eof=false HASH`. With
+//!     `--verify-xattr`, `FILE_IN` is hashed again and compared with the
+//!     attribute, printing `OK`, `FAILED` or `UNSET`, with exit code 1 or 3 in
+//!     the latter two cases. If the attribute was stored with other options, the
+//!     result is `FAILED` and the stored options are printed:
+//!
+//!     ```shell
+//!     normalized-hasher --xattr --recursive artifacts
+//!     normalized-hasher --verify-xattr --recursive artifacts
+//!     ```
+//!
+//!     The attributes need the feature `xattr`, which is enabled by default. On
+//!     file systems without extended attributes, an error is printed.
+//!
+//! -   `--recursive`
+//!
+//!     With `-r` or `--recursive`, `FILE_IN` is a directory and all files in it
+//!     and its subdirectories are hashed. For each file, a line `HASH  FILE` is
+//!     printed, sorted by path, so the output can be verified with `--check`
+//!     later on. Binary files, which contain a NUL byte within their first 8000
+//!     bytes, are skipped with a note on stderr. Other files that might block
+//!     forever, like named pipes, sockets or devices, are skipped as well, with a
+//!     note if `--verbose` is given. An explicitly named pipe is read as usual,
+//!     which includes paths like `/dev/stdin`. Symbolic links are only followed
+//!     with `--follow-symlinks`, links to a directory that is already being
+//!     traversed are skipped with a warning. Directories and files that can be
+//!     reached by more than one path are only hashed once, by the first path in
+//!     sorted order, so each file appears only once in the output. The depth of the traversal can be
+//!     limited with `--max-depth`, where 1 only hashes the files directly in the
+//!     directory. Hidden files and directories are included by default and can be
+//!     skipped with `--no-hidden`. Files ignored by `.gitignore` files, also
+//!     those in parent directories, are skipped unless `--no-ignore` is given,
+//!     and `.git` directories are always skipped. These rules only apply while
+//!     traversing directories, a file that is named explicitly or matched by a
+//!     glob pattern is always hashed. Passing a directory without `--recursive`
+//!     is an error, and so is a directory or socket listed in a checksum file
+//!     or with `--files-from`, which is reported as `'DIR' is a directory, not
+//!     a file` on all platforms:
+//!
+//!     ```shell
+//!     normalized-hasher -r --no-hidden src/ > SHA256SUMS
+//!     ```
+//!
+//! -   Multiple files
+//!
+//!     With three or more paths, all paths are hashed in the given order,
+//!     printing one `HASH  FILE` line per file. So a glob pattern expanded by the
+//!     shell hashes all matching files in a single run. A file that cannot be
+//!     read is reported on stderr, the remaining files are still hashed and the
+//!     exit code is non-zero. The normalized files can be written with
+//!     `--output-dir`:
+//!
+//!     ```shell
+//!     normalized-hasher migrations/*.sql
+//!     normalized-hasher --output-dir normalized/ a.sql b.sql c.sql
+//!     ```
+//!
+//!     Two paths are `FILE_IN` and `FILE_OUT`, as in earlier versions, unless
+//!     `--recursive`, `--output-dir`, `--watch`, `--verify-sidecar` or
+//!     `--verify-xattr` is given. `FILE_OUT` can also be given with
+//!     `--output-file`. An existing `FILE_OUT` is never overwritten without
+//!     `--force` or confirmation, so a shell glob that matches two files fails
+//!     instead. To hash exactly two files, quote a glob pattern matching them or
+//!     list them with `--files-from`.
+//!
+//! -   Glob patterns
+//!
+//!     Not all shells expand glob patterns, most notably `cmd.exe` on Windows. So
+//!     if `FILE_IN` does not exist, but contains one of the metacharacters `*`,
+//!     `?` or `[`, it is expanded internally, where `**` matches any number of
+//!     directories. All matching files are hashed in sorted order, printing one
+//!     `HASH  FILE` line per file, like with `--recursive`. Matching directories
+//!     are only traversed with `--recursive`. A pattern that matches no files is
+//!     an error, unless `--allow-empty-glob` is given:
+//!
+//!     ```shell
+//!     normalized-hasher "migrations/**/*.sql"
+//!     ```
+//!
+//! -   `--exclude`
+//!
+//!     Independent of `.gitignore` files, paths can be excluded with `--exclude`,
+//!     which can be given multiple times. The patterns follow the `.gitignore`
+//!     syntax: A pattern without a slash, like `*.log`, matches names at any
+//!     depth, while a pattern with a slash, like `migrations/archive`, is
+//!     anchored. Patterns are matched against the path relative to the directory
+//!     given to `--recursive`, or against the path of a file matched by a glob
+//!     pattern or listed with `--files-from`. Excluded directories are not
+//!     traversed at all. Excludes apply in addition to `.gitignore` files, also
+//!     with `--no-ignore`. An explicitly named file is never excluded:
+//!
+//!     ```shell
+//!     normalized-hasher -r --exclude '*.log' --exclude migrations/archive src/
+//!     ```
+//!
+//! -   `--ext`, `--no-ext`
+//!
+//!     As a lightweight alternative to glob patterns, `--ext` restricts
+//!     `--recursive` to files with the given extension, without the leading dot.
+//!     It can be given multiple times. Files without an extension are selected
+//!     with `--no-ext` or `--ext ''`. Extensions are compared case-insensitively
+//!     on Windows and case-sensitively on all other platforms. Explicitly named
+//!     files and files matched by a glob pattern are not filtered:
+//!
+//!     ```shell
+//!     normalized-hasher -r --ext sql --no-ext scripts/
+//!     ```
+//!
+//! -   `--fail-fast`
+//!
+//!     When hashing multiple files, with `--recursive` or a glob pattern, a file
+//!     that cannot be read does not abort the whole run. Instead, it is skipped
+//!     with a warning on stderr, the number of skipped files is reported at the
+//!     end and the run completes with exit code 5. With `--fail-fast`, the run is
+//!     aborted on the first such file with exit code 3:
+//!
+//!     ```shell
+//!     normalized-hasher -r --fail-fast /mnt/share/
+//!     ```
+//!
+//! -   `--retry-on-modify`
+//!
+//!     If a generator is still writing a file while it is hashed, the hash is
+//!     that of a half-written file. To notice this, the size and modification
+//!     time of each file are compared before and after reading it. A file that
+//!     was modified is an error, or with multiple files, skipped with a warning
+//!     like an unreadable file. With `--retry-on-modify N`, such a file is
+//!     hashed up to N more times before giving up:
+//!
+//!     ```shell
+//!     normalized-hasher --retry-on-modify 3 build/schema.sql
+//!     ```
+//!
+//! -   `--retries`, `--retry-delay`, `--retry-on-errno`
+//!
+//!     Network filesystems like SMB mounts occasionally fail in the middle of
+//!     reading a file, which would end a long batch run just before it is
+//!     done. With `--retries N`, a file that fails with a transient error is
+//!     opened again and hashed from scratch, up to N more times. The first
+//!     retry waits for `--retry-delay` milliseconds, 100 by default, and the
+//!     delay doubles with each further retry of the same file. Interrupted
+//!     reads, reset connections and timeouts are transient, further errors can
+//!     be added by their OS error number with `--retry-on-errno`, like 5 for
+//!     `EIO` on Linux. Missing files and denied permissions always fail right
+//!     away. With `--verbose`, a note tells how often a file was read again:
+//!
+//!     ```shell
+//!     normalized-hasher -r --retries 3 --retry-on-errno 5 /mnt/share/
+//!     ```
+//!
+//! -   `--files-from`
+//!
+//!     If the files to be hashed are already known, for example by a build
+//!     system, they can be passed as a list with `--files-from` instead of
+//!     `FILE_IN`, or on stdin with `--files-from -`. The list contains one path
+//!     per line, blank lines and lines starting with `#` are ignored. With `-0` or
+//!     `--null`, paths are separated by NUL bytes instead, like the output of
+//!     `find -print0`, so that any path is possible. The files are hashed in the
+//!     order of the list, relative paths are resolved against the current
+//!     directory. Exclude patterns apply to all paths, and directories are
+//!     traversed with `--recursive`. Files that cannot be read are skipped like
+//!     with `--recursive`:
+//!
+//!     ```shell
+//!     find . -name '*.sql' -print0 | normalized-hasher --files-from - -0
+//!     ```
+//!
+//! -   `--no-follow-input-symlinks`
+//!
+//!     An input file that is a symbolic link is hashed like the file it points
+//!     to, without notice. Where it matters what was actually hashed, like for
+//!     evidence of a release, `--no-follow-input-symlinks` makes such inputs an
+//!     error instead, reported as `'FILE' is a symbolic link, not a file`. This
+//!     applies to `FILE_IN`, to files matched by a glob pattern and to files
+//!     listed with `--files-from`, and cannot be combined with
+//!     `--follow-symlinks`. A link whose target does not exist is always
+//!     reported with its target, like `cannot open 'FILE': it is a symbolic
+//!     link to 'TARGET', which does not exist`, with the kind `broken_symlink`
+//!     in `--json` reports.
+//!
+//! -   `--no-dedup`
+//!
+//!     When a glob pattern, a directory and a `--files-from` list overlap, the
+//!     same file can be given by several paths, like `./a.txt` and `a.txt` or
+//!     a symbolic link and its target. Such a file is only hashed once, by the
+//!     path it is given first, so checksum lists and summaries do not contain
+//!     it twice. Paths are compared after resolving symbolic links, or by
+//!     device and inode if that fails. With `--verbose`, a note names each
+//!     skipped path. `--no-dedup` hashes the file once per path instead.
+//!
+//! -   `--json`
+//!
+//!     For further processing, `--json` prints the results as a single JSON
+//!     document, for a single file as well as for multiple files. Warnings are
+//!     still printed to stderr, so stdout only contains the document:
+//!
+//!     ```json
+//!     {
+//!       "entries": [
+//!         {
+//!           "path": "src/a.sql",
+//!           "hash": "b62e3392d1ef3737f39339a43976186a039d53f30a95b987ccf8c5843dba94a2",
+//!           "algorithm": "SHA256",
+//!           "bytes": 10,
+//!           "lines": 2,
+//!           "changed": true
+//!         },
+//!         {
+//!           "path": "src/b.sql",
+//!           "algorithm": "SHA256",
+//!           "error": "stream did not contain valid UTF-8"
+//!         }
+//!       ],
+//!       "summary": {
+//!         "hashed": 1,
+//!         "changed": 1,
+//!         "skipped": 0,
+//!         "errors": 1,
+//!         "elapsed_milliseconds": 2.41
+//!       }
+//!     }
+//!     ```
+//!
+//!     Each entry contains the `path` as given or found, the `algorithm`, and
+//!     either the `hash`, the size of the original file in `bytes`, its number of
+//!     `lines` and whether normalization `changed` its content, or an `error`
+//!     message. `lines` and `changed` are missing for files that cannot be read
+//!     twice, like named pipes. The
+//!     `summary` counts the hashed files, how many of them were changed, the
+//!     skipped binary files and the errors.
+//!
+//! -   `--json-lines`, `--unordered`
+//!
+//!     For very large runs, `--json-lines` prints each entry of `--json` as a
+//!     JSON object on a line of its own, as soon as the file is hashed, so the
+//!     results can be consumed while the run is still going on, and everything
+//!     finished so far is kept if it is aborted. Each entry is marked with
+//!     `"type":"entry"`, and the last line is the summary, marked with
+//!     `"type":"summary"`:
+//!
+//!     ```shell
+//!     normalized-hasher -r --json-lines src/ | jq -c 'select(.type == "entry")'
+//!     ```
+//!
+//!     The entries are printed in the order of the files, like with all other
+//!     outputs. With `--unordered`, they are printed as soon as each file is
+//!     finished instead, and each entry gets the field `seq` with the index of
+//!     its file in the usual order.
+//!
+//! -   `--csv`
+//!
+//!     For spreadsheet applications, `--csv` prints a header row and one row per
+//!     hashed file, with the columns `path`, `algorithm`, `hash`, `size`, `lines`
+//!     and `changed`, the latter two as described for `--json`. Fields are quoted
+//!     as needed and records are terminated by CRLF. Files that could not be
+//!     hashed are only reported on stderr. The delimiter can be changed with
+//!     `--csv-delimiter`:
+//!
+//!     ```shell
+//!     normalized-hasher -r --csv --csv-delimiter ';' src/ > hashes.csv
+//!     ```
+//!
+//! -   `--format`
+//!
+//!     Hashes are printed in lowercase hex by default. With `--format`, another
+//!     encoding can be chosen: `HEX` for uppercase hex, `base64`, `sri` for
+//!     Subresource Integrity values like `sha256-BASE64`, or `multihash` for
+//!     multihashes in hex like `1220HEX`. The format applies to all printed
+//!     hashes, also with `--json` and `--csv`, while checksum and sidecar files
+//!     are always written in hex. When verifying with `--expected`, `--check` or
+//!     `--verify-sidecar`, all encodings are detected automatically. `sri` and
+//!     `multihash` already name the algorithm and cannot be combined with
+//!     `--tag`:
+//!
+//!     ```shell
+//!     normalized-hasher --format sri dist/app.js
+//!     ```
+//!
+//! -   `-z`, `--print0`
+//!
+//!     Every output record is terminated by a NUL byte instead of a newline.
+//!     This makes the hash listing safe to process with `xargs -0` or
+//!     `read -d ''`, even if file names contain newlines. Together with
+//!     `--files-from FILE -0`, a whole pipeline works on NUL-separated names:
+//!
+//!     ```shell
+//!     find . -name '*.txt' -print0 | normalized-hasher --files-from - -0 -z
+//!     ```
+//!
+//! -   `--strict-names`
+//!
+//!     File names that are not valid UTF-8 are an error, exiting with code 2
+//!     before anything is hashed, instead of being escaped like `caf%E9.txt`.
+//!     See [File Names That Are Not Valid UTF-8](#file-names-that-are-not-valid-utf-8).
+//!
+//! -   `--no-filename`, `--filename-only`
+//!
+//!     By default, multiple files are listed with both hash and file name. With
+//!     `--no-filename`, only the bare hashes are printed, one per line in input
+//!     order. With `--filename-only`, only the paths of the hashed files are
+//!     printed. In check mode, `--no-filename` prints only the result of each
+//!     file, while `--filename-only` prints only the paths of failed files:
+//!
+//!     ```shell
+//!     normalized-hasher --check --filename-only SHA256SUMS
+//!     ```
+//!
+//! -   `--relative-to`
+//!
+//!     Paths are printed as given on the command line. With `--relative-to DIR`,
+//!     they are printed and recorded relative to `DIR` instead, so that the
+//!     output does not depend on the local directory layout. This applies to the
+//!     checksum lines, to `--append` and to the `--json` and `--csv` reports.
+//!     Files outside of `DIR` are an error, unless `--allow-outside` is given, in
+//!     which case their absolute path is used:
+//!
+//!     ```shell
+//!     normalized-hasher --relative-to "$PWD/dist" -r "$PWD/dist" > SHA256SUMS
+//!     ```
+//!
+//! -   `--stdin-name`
+//!
+//!     With `-` as `FILE_IN`, standard input is hashed, and it is printed and
+//!     recorded as `-`. With `--stdin-name LABEL`, the given label is used
+//!     instead, like for content that is generated on the fly. This applies to
+//!     the checksum lines of `--tag`, to `--append` and to the `--json` and
+//!     `--csv` reports, while the hash stays the same. It is an error if
+//!     `FILE_IN` is not `-`, and sidecars cannot be written for standard input:
+//!
+//!     ```shell
+//!     generate-schema | normalized-hasher --tag --stdin-name generated/install.sql -
+//!     ```
+//!
+//! -   `--text`, `--text-join`
+//!
+//!     For quick experiments and snippets pasted from a chat, the text to hash
+//!     can be given on the command line with `--text TEXT`, without creating a
+//!     file. It is normalized exactly like the content of a file, so
+//!     `--text 'A'` has the same hash as a file that contains `A`, and the eol
+//!     sequence is appended to the last line unless `--no-eof` is given. The
+//!     flag can be repeated, where each text is hashed on its own and printed
+//!     and recorded as `<text:1>`, `<text:2>` and so on, including in `--tag`
+//!     lines and the `--json` and `--csv` reports. With `--text-join`, all texts
+//!     are hashed as the lines of a single input instead:
+//!
+//!     ```shell
+//!     normalized-hasher --text 'SELECT 1;' --text 'SELECT 2;'
+//!     normalized-hasher --text-join --text 'SELECT 1;' --text 'SELECT 2;'
+//!     ```
+//!
+//!     Texts may start with `-`, like `--text '-- comment'`. In the Windows
+//!     command prompt, where single quotes do not group words, use double
+//!     quotes like `--text "SELECT 1;"`.
+//!
+//! -   `--git`
+//!
+//!     With `--git REV`, `FILE_IN` is hashed as it was committed at the revision
+//!     `REV`, like `HEAD` or a tag, instead of the working copy. The file is read
+//!     from the git repository that contains it, without applying filters like
+//!     `core.autocrlf`. It is an error if the file is not tracked at that
+//!     revision. Together with `--expected` or a plain hash of the working copy,
+//!     this tells if the normalized content changed since the commit:
+//!
+//!     ```shell
+//!     normalized-hasher --git v1.2.0 install.sql
+//!     ```
+//!
+//!     Git repositories can be disabled at build time by building without the
+//!     default feature `git`, which is also needed by `compare --git`.
+//!
+//! -   `--doc`
+//!
+//!     When the specification itself is a Word or LibreOffice document, `--doc`
+//!     hashes the text of a .docx or .odt file instead of its bytes. Each
+//!     paragraph of the document body becomes a line, line breaks within a
+//!     paragraph start new lines as well, and images, comments and footnotes
+//!     are ignored. The text is normalized like any other input, so a code
+//!     listing in a document has the same hash as the listing in a text file.
+//!     Word processors like to replace spaces with non-breaking spaces, which
+//!     `--ignore-whitespaces` removes like all other whitespaces:
+//!
+//!     ```shell
+//!     normalized-hasher --doc --ignore-whitespaces specification.docx
+//!     ```
+//!
+//!     The format is detected from the content, not from the file extension.
+//!     Encrypted documents, legacy .doc files and other formats are refused
+//!     with exit code 3. Documents need the default feature `doc`.
+//!
+//! -   `--output`
+//!
+//!     With `-o FILE` or `--output FILE`, the results are written to `FILE`
+//!     instead of stdout, while errors and warnings are still printed to stderr.
+//!     This works for checksum lines, `--check` results and the `--json` and
+//!     `--csv` reports. Checksum lines are preceded by a comment that records
+//!     the normalization options, see `--check`, and list the paths relative to
+//!     the directory of `FILE`, so `--check` finds them. The file is replaced
+//!     atomically once all results are written, and left untouched if the run
+//!     fails:
+//!
+//!     ```shell
+//!     normalized-hasher -r dist -o SHA256SUMS
+//!     ```
+//!
+//! -   `--force`
+//!
+//!     An existing `FILE_OUT` of `--output-file` is never truncated silently. In
+//!     an interactive console, `normalized-hasher` asks on stderr whether to
+//!     overwrite it:
+//!
+//!     ```text
+//!     overwrite 'output.txt'? [y/N]
+//!     ```
+//!
+//!     Anything but `y` or `yes` aborts without touching the file. Without an
+//!     interactive console, like in scripts, an existing `FILE_OUT` is an
+//!     error. With `--force`, it is overwritten without asking:
+//!
+//!     ```shell
+//!     normalized-hasher --force input.txt --output-file output.txt
+//!     ```
+//!
+//! -   `--self-check`
+//!
+//!     For extra assurance, `--self-check` reads `FILE_OUT` back after writing
+//!     it and checks that its content has the computed hash, which catches
+//!     output that did not end up on disk as written. The files of
+//!     `--output-dir` are checked before they replace existing files. A
+//!     mismatch is an error with exit code 3, or with multiple files, skipped
+//!     with a warning. The cost is a second read of each output file:
+//!
+//!     ```shell
+//!     normalized-hasher --self-check input.txt --output-file output.txt
+//!     ```
+//!
+//! -   `--sync`
+//!
+//!     When the normalized copy is the artifact that is shipped, a power loss
+//!     right after a successful run must not leave an empty file behind. With
+//!     `--sync`, `FILE_OUT` and the files of `--output-dir` are flushed to the
+//!     storage device before they replace existing files, and on Unix, their
+//!     directory is synced afterwards, so the rename is durable as well. This
+//!     waits for the storage device on every file, which can make writing many
+//!     small files considerably slower, so it is off by default:
+//!
+//!     ```shell
+//!     normalized-hasher --sync --output-dir release/ -r src/
+//!     ```
+//!
+//! -   `--copy`
+//!
+//!     With `--copy`, the hash of a single file is copied to the clipboard in
+//!     addition to printing it, ready to be pasted into a document. Only the
+//!     hash is copied, in the encoding given with `--format`. With multiple
+//!     files, `--copy` is refused. If no clipboard is available, like on a
+//!     server without a desktop session, an error is printed and the exit code
+//!     is 3:
+//!
+//!     ```shell
+//!     normalized-hasher --copy input.txt
+//!     ```
+//!
+//!     The other way around, `--from-clipboard` hashes the text in the clipboard
+//!     instead of a file, like a snippet copied from a specification. The text is
+//!     normalized like the content of a file, so line endings of the source do
+//!     not matter, and it is printed and recorded as `<clipboard>`. Together
+//!     with `--expected`, this verifies the snippet in a single command. An
+//!     empty clipboard, a clipboard without text and a missing clipboard are
+//!     errors with exit code 3:
+//!
+//!     ```shell
+//!     normalized-hasher --from-clipboard --expected b62e3392d1ef3737f39339a43976186a039d53f30a95b987ccf8c5843dba94a2
+//!     ```
+//!
+//!     The clipboard support can be disabled at build time by building without
+//!     the default feature `clipboard`.
+//!
+//! -   `--template`
+//!
+//!     When the hash goes into a standard sentence of a document, `--template`
+//!     prints that sentence instead of the hash. The placeholders `{hash}`,
+//!     `{file}`, `{filename}`, `{algorithm}`, `{date}`, `{lines}` and `{size}`
+//!     are filled in with the hash in the encoding of `--format`, the printed
+//!     path, its last component, `SHA256`, the current date in UTC, the number of
+//!     lines and the size in bytes. `{{` and `}}` stand for literal braces. A
+//!     template starting with `@` is read from the named file. With multiple
+//!     files, one filled in template is printed per file, and with `--copy`, the
+//!     filled in template is copied:
+//!
+//!     ```shell
+//!     normalized-hasher --copy \
+//!         --template 'File {filename}, SHA-256: {hash}, generated on {date}.' \
+//!         install.sql
+//!     ```
+//!
+//!     An unknown placeholder is rejected with a list of the known ones.
+//!
+//! -   `--qr`
+//!
+//!     For signed paper documents, `--qr` prints the hash of a single file as a
+//!     QR code of half blocks, and `--qr-out` writes the code as PNG image to be
+//!     placed in the document. The code encodes a line like
+//!     `normalized-hasher: eol=lf ignore_whitespaces=false no_eof=false
+//!     algorithm=sha256 format=v1 hash=HASH`, so whoever scans it knows the
+//!     options to verify the hash with, instead of retyping 64 hex characters.
+//!     With `--qr-level low`, `medium`, `quartile` or `high`, the error
+//!     correction of the code is chosen, higher levels survive more damage of
+//!     the print:
+//!
+//!     ```shell
+//!     normalized-hasher --qr-out spec-hash.png --qr-level high spec.txt
+//!     ```
+//!
+//!     QR codes need the default feature `qr`.
+//!
+//! -   `--color`
+//!
+//!     The results of `--check`, `--verify-sidecar`, `--expected` and `compare`
+//!     are colorized when printed to a terminal: successful results in green,
+//!     failed results in red and missing sidecar files in yellow. With
+//!     `--color always` or `--color never`, colors can be forced or disabled.
+//!     Setting the environment variable `NO_COLOR` disables colors as well,
+//!     unless `--color always` is given:
+//!
+//!     ```shell
+//!     normalized-hasher --check SHA256SUMS --color always | less -R
+//!     ```
+//!
+//! -   `--output-format`
+//!
+//!     In GitHub Actions, failures are easily buried in the log. With
+//!     `--output-format github`, the results of `lint`, `--check`,
+//!     `manifest verify` and `verify-dir` are also printed as workflow commands,
+//!     which GitHub shows as annotations directly on the files of a pull
+//!     request:
+//!
+//!     ```shell
+//!     normalized-hasher --output-format github lint docs/*.md
+//!     ```
+//!
+//!     Each offending file gets an error, at the first line that differs where
+//!     it is known, like the first line that is not normalized for `lint`:
+//!
+//!     ```text
+//!     docs/setup.md
+//!     ::error file=docs/setup.md,line=2::file is not normalized
+//!     ```
+//!
+//!     Skipped files and lines of checksum files get a notice. The annotations
+//!     are printed alongside the normal output, regardless of `--quiet` and
+//!     `--status`. If stdout is a JSON document, like with `verify-dir --json`,
+//!     they are printed to stderr instead, so the document stays intact. The
+//!     default `--output-format plain` prints no annotations.
+//!
+//! -   Errors with `--json`
+//!
+//!     With `--json`, files that cannot be hashed are listed with an `error`
+//!     object, whose `kind` tells what went wrong: the file could not be opened
+//!     (`open`), read (`read`), is not valid UTF-8 (`decode`), or an output
+//!     file could not be created (`create`) or written (`write`). Errors that
+//!     abort the run are printed to stderr as a single JSON line instead of a
+//!     sentence:
+//!
+//!     ```json
+//!     {"path":"dir","error":{"kind":"usage","message":"dir is a directory, use --recursive to hash all files in it"}}
+//!     ```
+//!
+//!     The kinds of these errors also include `usage` for invalid arguments,
+//!     `conflict` for a refused update with `--no-update`, `mixed-eol` for a
+//!     file denied by `--deny-mixed-eol` and `io` for all other failures.
+//!     Errors in parsing the command line are still printed as text.
+//!
+//! -   `--no-progress`
+//!
+//!     While hashing, a progress bar is shown on stderr, with the bytes read
+//!     for a single file and the number of hashed files for multiple files,
+//!     together with the throughput and the estimated remaining time. It is
+//!     only shown if stderr is a terminal, so it never ends up in redirected
+//!     output. With `--no-progress`, it is disabled completely.
+//!
+//! -   `--no-tty-warning`
+//!
+//!     Reading standard input with `-` while it is an interactive terminal
+//!     prints a note that typed input is awaited, together with the keys that
+//!     finish it, `Ctrl-D` or `Ctrl-Z` and Enter on Windows. `--filter`, which
+//!     reads standard input without being told to, refuses to wait for typed
+//!     input with a usage error instead. With `--no-tty-warning`, standard input
+//!     is read from a terminal without a note, like in scripts that are run in
+//!     a terminal on purpose. Piped or redirected input is never affected.
+//!
+//! -   `--verbose`
+//!
+//!     To find out why a hash differs from what you expected, `-v` or
+//!     `--verbose` reports on stderr what normalization did to each file: the
+//!     number of lines read, the number of converted CRLF line endings, whether
+//!     the file starts with a byte order mark, which is kept as it is, and
+//!     whether it already ended with a newline. With `-vv`, a second note counts
+//!     the line endings that were found and converted, whether a final newline
+//!     was added or removed, and how many lines were changed otherwise:
+//!
+//!     ```shell
+//!     normalized-hasher -v input.txt
+//!     ```
+//!
+//!     ```text
+//!     note: input.txt: 4 lines read, 2 CRLF line endings converted, no byte order mark, ends with a newline
+//!     ```
+//!
+//! -   `--quiet`
+//!
+//!     In scripts, `-q` or `--quiet` reduces the output to the result itself:
+//!     the hash, or only `OK` or `MISMATCH` with `--expected`. Notes and
+//!     warnings on stderr, like those about skipped binary or unreadable files,
+//!     and the progress bar are suppressed, while errors that abort the run are
+//!     still printed. The exit code is the same as without `--quiet`. In check
+//!     mode, `--quiet` keeps its meaning from `sha256sum` and only omits the OK
+//!     lines. Since it contradicts `--verbose`, combining both is an error:
+//!
+//!     ```shell
+//!     hash=$(normalized-hasher -q input.txt)
+//!     ```
+//!
+//! -   `--no-summary`
+//!
+//!     At the end of a run with multiple files, a summary line with the
+//!     number of hashed, skipped and unreadable files and the elapsed time is
+//!     printed to stderr. In check mode, the line counts the verified, failed,
+//!     missing and skipped entries and follows the results on stdout. The
+//!     counts agree with the exit code, so a non-zero number of failed or
+//!     unreadable entries always means a non-zero exit code. The line is
+//!     omitted with `--no-summary` or `--status`, and when hashing also with
+//!     `--quiet`. With `--json`, the elapsed time is the field
+//!     `elapsed_milliseconds` of the summary instead:
+//!
+//!     ```text
+//!     summary: 41 hashed, 2 skipped, 0 unreadable, 0.35 s
+//!     summary: 40 OK, 1 failed, 0 missing, 0 skipped, 0.36 s
+//!     ```
+//!
+//! -   `--time`
+//!
+//!     For tracking the performance, `--time` reports how long hashing each
+//!     file took and the effective throughput, printed to stderr after the
+//!     results. With multiple files, a line with the total follows. Only
+//!     opening, reading and hashing the files is measured, so unlike `time`,
+//!     the figures do not include the startup of the program or the traversal
+//!     of directories:
+//!
+//!     ```shell
+//!     normalized-hasher --time -r dist/
+//!     ```
+//!
+//!     ```text
+//!     time: dist/a.sql: 1.52 ms, 84.3 MB/s
+//!     time: dist/b.sql: 0.87 ms, 91.0 MB/s
+//!     time: total: 2 files, 2.39 ms, 86.7 MB/s
+//!     ```
+//!
+//!     With `--json`, the figures are part of the document instead, as `time`
+//!     objects in each entry and in the summary, with the raw duration in
+//!     `milliseconds` and the throughput in `bytes_per_second`.
+//!
+//! -   `--detect`
+//!
+//!     When a file cannot be hashed or its hash differs for no visible reason,
+//!     `--detect` prints what the file looks like instead of hashing it: the
+//!     guessed encoding, which is `UTF-8`, `UTF-16LE`, `UTF-16BE`, likely
+//!     `Windows-1252` or `binary`, whether it starts with a byte order mark,
+//!     its dominant line ending and whether it mixes different ones, whether it
+//!     ends with a newline, and the number of invisible characters like
+//!     zero-width spaces, non-breaking spaces or control characters:
+//!
+//!     ```shell
+//!     normalized-hasher --detect suspicious.txt
+//!     ```
+//!
+//!     ```text
+//!     suspicious.txt:
+//!       encoding:        UTF-16LE
+//!       byte order mark: yes
+//!       line endings:    mixed, mostly LF (3 CRLF, 120 LF, 0 CR)
+//!       final newline:   no
+//!       invisible:       2 characters
+//!     ```
+//!
+//!     Only UTF-8 files can be hashed, the other encodings are detected to tell
+//!     what is wrong with a file. With `--json`, the findings are printed as a
+//!     JSON document.
+//!
+//! -   `--no-warn-mixed-eol`, `--deny-mixed-eol`
+//!
+//!     A file where most lines end with LF but a few in the middle with CRLF,
+//!     like after a partial paste, has the same hash as a consistent one, but
+//!     it might break other tools. Therefore, a warning on stderr lists the
+//!     counts of each style if a hashed file mixes CRLF, LF and lone CR line
+//!     endings:
+//!
+//!     ```text
+//!     warning: input.txt mixes line endings (3 CRLF, 120 LF)
+//!     ```
+//!
+//!     The warning is suppressed with `--no-warn-mixed-eol`. For CI, it can be
+//!     turned into an error with `--deny-mixed-eol`, which still hashes all
+//!     files, but exits with code 1 if any of them mixes line endings.
+//!
+//! -   `--pause`
+//!
+//!     When `normalized-hasher` is run from a batch file by double-clicking it,
+//!     the console window closes as soon as it is done. With `--pause`, it
+//!     prints `Press Enter to close...` after the results and waits for Enter.
+//!     This also applies on errors, even for invalid arguments, since that is
+//!     when the message is needed the most. It only waits if stdin is a
+//!     terminal, so scripts that pass the flag by accident don't hang:
+//!
+//!     ```bat
+//!     normalized-hasher --pause "%~1"
+//!     ```
+//!
+//! -   `--watch`
+//!
+//!     While editing a file that has to match a published hash, `--watch`
+//!     keeps running and prints the hash again whenever the file changes,
+//!     with the time in UTC in front:
+//!
+//!     ```
+//!     $ normalized-hasher --watch spec.txt
+//!     2023-08-26T14:05:09Z  b62e3392d1ef3737f39339a43976186a039d53f30a95b987ccf8c5843dba94a2  spec.txt
+//!     2023-08-26T14:06:41Z  8ed4bf77bac50090168f95ac1911f947454f0092a86b7381aa72d113585315c3  spec.txt
+//!     ```
+//!
+//!     Editors often write a file more than once when saving, so a line is
+//!     only printed once the file did not change for a short moment. Files
+//!     that are replaced by renaming a temporary file over them, which is how
+//!     many editors save atomically, are still watched afterwards. Press
+//!     Ctrl-C to stop, which exits with code 0. This needs the feature
+//!     `watch`, which is enabled by default.
+//!
+//! -   `--jobs`
+//!
+//!     With multiple files, from `--recursive`, a glob pattern or
+//!     `--files-from`, up to one file per CPU is hashed at the same time. The
+//!     number can be set with `-j`, where `-j 1` hashes one file after the
+//!     other. The output is the same either way, in the same order, so
+//!     checksum files of both can be diffed:
+//!
+//!     ```
+//!     normalized-hasher -j 4 --time -r docs/ > SHA256SUMS
+//!     ```
+//!
+//!     With `--time`, the total is followed by the elapsed wall time, which is
+//!     shorter than the sum of all files when hashing in parallel.
+//!
+//! -   `--cache`
+//!
+//!     Hashing a large tree that hardly changes, like every night, reads the
+//!     same content over and over again. With `--cache FILE`, the hash, size
+//!     and modification time of each file are stored in `FILE`, and the next
+//!     run takes the hash of each file whose size and modification time are
+//!     unchanged from there, without reading the file:
+//!
+//!     ```shell
+//!     normalized-hasher --cache ~/.cache/docs.hashes -r docs/ > SHA256SUMS
+//!     ```
+//!
+//!     `FILE` is a small text file that is created if it does not exist and
+//!     replaced atomically at the end of the run. A cache that cannot be read
+//!     is not fatal, all files are hashed then, with a warning, and the cache
+//!     is written from scratch. Since hashes depend on the normalization
+//!     options, a cache is only used with the options it was created with.
+//!
+//!     With `--no-cache-read`, all files are hashed, but the cache is still
+//!     updated. As a safety net against files that were changed without
+//!     updating the modification time, `--cache-verify-sample 5%` hashes a
+//!     random fraction of the cached files anyway, and warns about each file
+//!     whose hash changed. With `-v`, a note tells which hashes were taken
+//!     from the cache. Files with cached hashes are not checked for mixed line
+//!     endings, so `--cache` cannot be combined with `--deny-mixed-eol`. The
+//!     cache only applies when hashing multiple files.
+//!
+//! -   `--output-dir`
+//!
+//!     `--output-file` only takes the normalized form of a single file. With
+//!     `--output-dir DIR`, the normalized form of every hashed file is written
+//!     into `DIR`, keeping its path relative to the common ancestor of the
+//!     inputs, or to the `--relative-to` directory if given:
+//!
+//!     ```shell
+//!     normalized-hasher --output-dir dist/ -r docs/
+//!     ```
+//!
+//!     This writes `docs/guide/intro.md` to `dist/guide/intro.md`, creating
+//!     subdirectories as needed. All output paths are checked before anything
+//!     is written: files outside of the `--relative-to` directory, which would
+//!     end up outside of `DIR`, and outputs that would overwrite any of the
+//!     inputs are refused with exit code 2. Binary files are skipped and not
+//!     copied.
+//!
+//! -   `--filter`
+//!
+//!     To use the normalizer as a stage in a pipeline, `--filter` reads stdin,
+//!     writes the normalized content to stdout and prints the hash to stderr
+//!     once the input ends:
+//!
+//!     ```shell
+//!     generate-schema | normalized-hasher --filter --hash-file schema.sha256 | gzip > schema.sql.gz
+//!     ```
+//!
+//!     With `--hash-file FILE`, the hash is written into `FILE` instead of
+//!     stderr. Each line is passed on as soon as it is read, so slow producers
+//!     don't stall the pipeline. If the next stage exits early, like `head`,
+//!     the rest of the input is not read, no hash is printed, since it would
+//!     only cover part of the input, and the exit code is 3, without an error
+//!     message. `--filter` cannot be combined with `FILE_IN` or `FILE_OUT`.
+//!
+//! -   `--config`, `--no-config`, `--show-config`, `--write-config`
+//!
+//!     To keep the normalization policy in one place instead of repeating it in
+//!     every script, put it into a `.normalized-hasher.toml` in the root of your
+//!     project. The keys are the names of the flags:
+//!
+//!     ```toml
+//!     eol = "crlf"
+//!     ignore-whitespaces = false
+//!     no-eof = false
+//!     algorithm = "sha256"
+//!     exclude = ["*.min.js", "vendor/**"]
+//!     no-hidden = true
+//!     no-ignore = false
+//!     ```
+//!
+//!     Like with `.editorconfig`, all `.normalized-hasher.toml` files from the
+//!     root of the Git repository down to the directory of each input file are
+//!     read, or up to the root of the filesystem outside of repositories. Nearer
+//!     files override the settings of farther ones, so a vendored subtree can
+//!     keep CRLF while the rest of the repository is strict LF:
+//!
+//!     ```text
+//!     repo/.normalized-hasher.toml         no-eof = true
+//!     repo/vendor/.normalized-hasher.toml  eol = "crlf"
+//!     ```
+//!
+//!     Files in `repo/vendor` are hashed with CRLF and without a final EOL, all
+//!     other files with LF and without a final EOL. This applies to single
+//!     files, recursive mode, `--check` of checksum files without a header and
+//!     the files of all subcommands, like `lint -r` and `manifest`. A checksum
+//!     file written with `--output` or `--append` only gets a header if all its
+//!     files share the same options. The settings that control walking
+//!     directories, like `exclude`, use the settings of the current directory.
+//!
+//!     A user configuration file `normalized-hasher/config.toml` in the
+//!     platform configuration directory is read as well, which is
+//!     `$XDG_CONFIG_HOME` or `~/.config` on Unix and `%APPDATA%` on Windows.
+//!     Settings of the project files take precedence over those of the user
+//!     file, and environment variables and flags on the command line take
+//!     precedence over all of them. The `exclude` patterns of a flag replace those of
+//!     the configuration files. Since a flag cannot be negated on the command
+//!     line, a setting that is enabled in a configuration file can only be
+//!     disabled by its environment variable, like `NORMALIZED_HASHER_NO_EOF=0`,
+//!     or with `--no-config`.
+//!
+//!     With `--config FILE`, only `FILE` is read and nothing is discovered.
+//!     With `--no-config`, no configuration files are read at all. An invalid
+//!     configuration file, including unknown keys, is an error with exit
+//!     code 2.
+//!
+//!     With `--show-config`, the effective settings are printed in the format
+//!     of a configuration file, each with a comment naming where it comes from,
+//!     and nothing is hashed:
+//!
+//!     ```text
+//!     eol = "\r\n"  # project config /home/user/project/.normalized-hasher.toml
+//!     ignore-whitespaces = false  # default
+//!     no-eof = true  # command line
+//!     algorithm = "sha256"  # default
+//!     exclude = ["*.min.js", "vendor/**"]  # project config /home/user/project/.normalized-hasher.toml
+//!     no-hidden = true  # project config /home/user/project/.normalized-hasher.toml
+//!     no-ignore = false  # default
+//!     ```
+//!
+//!     With a path like `--show-config vendor/schema.sql`, the settings that
+//!     apply to this file are printed instead of those of the current directory.
+//!
+//!     Once the flags produce the expected hashes, `--write-config FILE` writes
+//!     the effective settings into `FILE` instead of hashing anything, with a
+//!     comment for each setting. Flags, environment variables and other
+//!     configuration files are all resolved, so running without flags and with
+//!     `FILE` as configuration file behaves the same. An existing `FILE` is
+//!     only replaced with `--force`:
+//!
+//!     ```shell
+//!     normalized-hasher --eol crlf --no-eof -r src --write-config .normalized-hasher.toml
+//!     ```
+//!
+//! -   `--self-test`
+//!
+//!     To prove in a validation report that a deployed binary implements the
+//!     documented algorithm, `--self-test` checks the golden test vectors of
+//!     the library, without hashing any files. Each vector is a frozen input,
+//!     like an empty file, CRLF line endings or a byte order mark, together
+//!     with normalization options and its expected hash. `PASS` or `FAIL` is
+//!     printed for each vector, and any failure exits with code 1:
+//!
+//!     ```text
+//!     PASS  empty file
+//!     PASS  empty file with no_eof
+//!     PASS  LF line endings
+//!     ...
+//!     all 18 test vectors passed
+//!     ```
+//!
+//!     The expected hashes never change once published, new options get new
+//!     vectors instead.
+//!
+//! ### Subcommands
+//!
+//! -   `compare`
+//!
+//!     To check if two files have the same content after normalization, like a
+//!     customer's copy and your own, use the `compare` subcommand. All
+//!     normalization flags apply to both files identically. If the files differ,
+//!     the number of the first differing normalized line is reported and the exit
+//!     code is 1:
+//!
+//!     ```shell
+//!     normalized-hasher compare --ignore-whitespaces ours.sql theirs.sql
+//!     ```
+//!
+//!     The second file can also be a reference on a web server. With `--url`, its
+//!     content is normalized while it is downloaded and compared like a file.
+//!     With `--expected-url`, the downloaded file has to contain the expected
+//!     hash instead, like a checksum file with a single line. Redirects are
+//!     followed, while any response but a success, a timeout after `--timeout`
+//!     seconds or a TLS error is reported with the URL and exits with code 3.
+//!     Headers like an authentication token are added with `--header`:
+//!
+//!     ```shell
+//!     normalized-hasher compare local.sql --url https://repo.internal/canonical/install.sql
+//!     normalized-hasher compare local.sql --header "Authorization: Bearer $TOKEN" \
+//!         --expected-url https://repo.internal/canonical/install.sql.sha256
+//!     ```
+//!
+//!     Downloads can be disabled at build time by building without the default
+//!     feature `http`.
+//!
+//!     The second file can also be the committed version of the first one. With
+//!     `--git REV`, the file is read at the revision `REV`, like `HEAD`, from the
+//!     git repository that contains it, so only changes of the normalized
+//!     content are reported, like `input.sql and HEAD:input.sql differ in line
+//!     3`. Paths that are not tracked at the revision are an error with exit
+//!     code 3:
+//!
+//!     ```shell
+//!     normalized-hasher compare --git HEAD install.sql
+//!     ```
+//!
+//! -   `diff`
+//!
+//!     To see where two files differ after normalization, the `diff` subcommand
+//!     prints a unified diff of their normalized lines, so that line-ending and
+//!     whitespace noise is already factored out. Use `--context` to change the
+//!     number of context lines. Like with `diff`, the exit code is 0 if the files
+//!     are equal, 1 if they differ and 2 on trouble, also if a file cannot be
+//!     read:
+//!
+//!     ```shell
+//!     normalized-hasher diff --context 1 ours.sql theirs.sql
+//!     ```
+//!
+//! -   `explain`
+//!
+//!     Before trusting a hash, you might want to see what the normalization does
+//!     to a file. The `explain` subcommand prints a unified diff between the
+//!     original and the normalized lines, with line endings made visible, followed
+//!     by a summary like `187 lines: 187 CRLF→LF, 3 whitespace`. For a file that
+//!     is already normalized, nothing is printed and the exit code is 0,
+//!     otherwise it is 1:
+//!
+//!     ```shell
+//!     normalized-hasher explain input.txt
+//!     ```
+//!
+//! -   `lint`
+//!
+//!     As a pre-commit or CI gate, the `lint` subcommand lists all given files
+//!     that are not in normalized form, one per line, without computing any
+//!     hashes. The exit code is 1 if any offenders were found and 0 otherwise.
+//!     With `--fix`, the offenders are additionally replaced atomically by their
+//!     normalized form, the exit code stays 1 to signal the change:
+//!
+//!     ```shell
+//!     normalized-hasher lint --fix *.sql
+//!     ```
+//!
+//!     With `-z`, the offenders are terminated by NUL bytes instead of newlines.
+//!     With `--recursive`, whole directories are checked, with the same rules as
+//!     for hashing, including `--exclude`, `--no-hidden` and `--no-ignore`.
+//!     Binary files are skipped with a note on stderr:
+//!
+//!     ```shell
+//!     normalized-hasher lint -r --exclude '*.min.js' src/
+//!     ```
+//!
+//! -   `stats`
+//!
+//!     Before switching a repository to normalized hashing, the `stats`
+//!     subcommand surveys its files without computing any hashes. For each
+//!     file, a table row counts the lines, the lines ending with CRLF and with
+//!     LF, lone CR characters that do not end a line and lines with trailing
+//!     whitespace, and tells if the file starts with a byte order mark and if
+//!     it misses a final newline. With more than one file, a row with the
+//!     totals follows, which is all that is printed with `--summary-only`:
+//!
+//!     ```shell
+//!     normalized-hasher stats -r legacy/
+//!     ```
+//!
+//!     ```text
+//!        LINES     CRLF       LF       CR TRAILING   BOM NO-NEWLINE  FILE
+//!            3        2        0        0        1    no        yes  legacy/a.sql
+//!            3        0        3        1        1   yes         no  legacy/sub/b.txt
+//!            6        2        3        1        2     1          1  total (2 files)
+//!     ```
+//!
+//!     Directories are traversed with `-r`, following the same rules as for
+//!     hashing, including `--exclude`, `--no-hidden`, `--no-ignore`,
+//!     `--max-depth` and `--follow-symlinks`. Binary files are skipped with a
+//!     note. With `--json`, the survey is printed as a JSON document with the
+//!     fields `files`, omitted with `--summary-only`, and `total`, plus `errors`
+//!     for files that could not be read, which result in exit code 5.
+//!
+//! -   `dupes`
+//!
+//!     Copies of the same file tend to spread across a repository, often
+//!     differing only in their line endings. The `dupes` subcommand hashes all
+//!     given files and prints each group of files with the same normalized
+//!     hash, starting with the hash and the number of files, followed by the
+//!     sorted paths:
+//!
+//!     ```shell
+//!     normalized-hasher dupes -r --min-size 1 project/
+//!     ```
+//!
+//!     ```text
+//!     c6faba4228728a4b882ed1cf2e8e1d351106434c70fb0f74a6afec0f7b3d17ac (2 files)
+//!       project/alpha/schema.sql
+//!       project/beta/schema.sql
+//!
+//!     1cda4cca3718db5cf3533cbd0a573011ddcd82e1ee5f1214d65f58fc4e59ee4b (2 files)
+//!       project/alpha/seed.sql
+//!       project/gamma/seed-copy.sql
+//!     ```
+//!
+//!     Files without duplicates are not printed. With `--min-size`, files
+//!     smaller than the given number of bytes are skipped, like empty files,
+//!     which are all equal. Directories are traversed with `-r`, following the
+//!     same rules as for `stats`. With `--json`, the groups are printed as a
+//!     JSON document with an array `groups` of objects with the fields `hash`
+//!     and `files`, plus `errors` for files that could not be read, which
+//!     result in exit code 5. Finding duplicates is not an error, unless
+//!     `--fail-if-dupes` is given, which exits with code 1 then.
+//!
+//! -   `normalize`
+//!
+//!     Instead of only hashing the normalized form of files, the `normalize`
+//!     subcommand writes it, using the same normalization flags as for
+//!     hashing. It prints for each file whether it was changed:
+//!
+//!     ```shell
+//!     normalized-hasher normalize --in-place -r docs/
+//!     ```
+//!
+//!     ```text
+//!     docs/intro.md: normalized
+//!     docs/usage.md: unchanged
+//!     ```
+//!
+//!     With `--in-place`, files that are not in normalized form are atomically
+//!     replaced, and `--backup` keeps a copy of each rewritten file with the
+//!     suffix `.orig` or the one given with `--backup-suffix`. With
+//!     `--output-dir DIR`, the normalized files are written to `DIR` instead,
+//!     keeping their paths relative to the common ancestor of the inputs, like
+//!     with `--output-dir` when hashing. With `--dry-run`, only the
+//!     files that would change are listed, exiting with code 1 if there are
+//!     any. Directories are traversed with `-r`, following the same rules as
+//!     for `stats`, and binary files are skipped with a note. Files that
+//!     cannot be read are skipped with a warning and result in exit code 5.
+//!
+//!     For packaging, `--mirror SRC DST` reproduces the whole tree `SRC` in
+//!     `DST` with every text file normalized, and binary files copied
+//!     verbatim, or skipped with `--skip-binary`:
+//!
+//!     ```shell
+//!     normalized-hasher normalize --mirror src/ dist/
+//!     ```
+//!
+//!     ```text
+//!     dist/docs/intro.md: changed
+//!     dist/img/logo.png: copied
+//!     1 changed, 12 unchanged, 1 copied, 0 skipped
+//!     ```
+//!
+//!     Only files in `DST` whose content would change are written, so repeated
+//!     runs are fast and leave modification times alone, which keeps tools like
+//!     `rsync` happy. Nothing in `DST` is ever deleted.
+//!
+//! -   `manifest`
+//!
+//!     To pin a whole directory tree, `manifest generate` hashes all of its
+//!     files and records them in a manifest, which `manifest verify` checks
+//!     later:
+//!
+//!     ```shell
+//!     normalized-hasher manifest generate release/ -o release.manifest
+//!     normalized-hasher manifest verify release/ release.manifest
+//!     ```
+//!
+//!     ```text
+//!     docs/intro.md: OK
+//!     docs/setup.md: CHANGED
+//!     img/logo.svg: MISSING
+//!     notes.txt: EXTRA
+//!     1 OK, 1 changed, 1 missing, 1 extra
+//!     ```
+//!
+//!     The manifest is a text file that records the normalization options,
+//!     followed by the hash, size, modification time and path of each file.
+//!     Paths are relative to the root of the tree and use `/` as separator,
+//!     so a manifest generated on Linux verifies on Windows. Without `-o`, the
+//!     manifest is printed to stdout. Verifying with other normalization flags
+//!     than the manifest was generated with is refused. Any changed, missing
+//!     or extra file results in exit code 1, an unreadable file in exit code 3
+//!     and an invalid manifest in exit code 4.
+//!
+//!     `manifest update` only hashes files again whose size or modification
+//!     time changed, adds new files and removes missing ones, and prints each
+//!     change followed by a summary. The tree is walked with the same rules as
+//!     for `--recursive`, including `--exclude`, `--no-hidden`, `--no-ignore`,
+//!     `--max-depth` and `--follow-symlinks`. Binary files are skipped.
+//!
+//! -   `verify-dir`
+//!
+//!     Before and after a migration, `verify-dir <A> <B>` checks that two
+//!     directory trees have the same files with the same normalized content.
+//!     Files are paired by their path relative to each root, and each path is
+//!     reported as identical, different with the first differing line, or only
+//!     present in one of the trees:
+//!
+//!     ```shell
+//!     normalized-hasher verify-dir old-export/ new-export/
+//!     ```
+//!
+//!     ```text
+//!     README.md: only in B
+//!     notes.txt: only in A
+//!     sql/schema.sql: identical
+//!     sql/seed.sql: different in line 2
+//!     summary: 1 identical, 1 different, 1 only in A, 1 only in B, 0 skipped, 0 unreadable, 1.13 ms
+//!     ```
+//!
+//!     Both files of a pair are read line by line side by side, so even huge
+//!     trees are never loaded into memory. The trees are walked with the same
+//!     rules as for `manifest`, binary files are skipped. With `--json`, the
+//!     results and the summary are printed as JSON document instead. Any
+//!     difference results in exit code 1, a file that cannot be read in exit
+//!     code 3.
+//!
+//! -   `completions`
+//!
+//!     `completions <SHELL>` prints a completion script for `bash`, `zsh`,
+//!     `fish`, `powershell` or `elvish` to stdout, which completes flags,
+//!     subcommands, file paths and the values of flags like `--format` and
+//!     `--eol`. Redirect it to where your shell loads completions from, for
+//!     example:
+//!
+//!     ```shell
+//!     normalized-hasher completions bash > ~/.local/share/bash-completion/completions/normalized-hasher
+//!     normalized-hasher completions fish > ~/.config/fish/completions/normalized-hasher.fish
+//!     ```
+//!
+//! ## Exit Status
+//!
+//! The exit code of `normalized-hasher` is stable and can be used in scripts:
+//!
+//! | Code | Meaning                                                |
+//! |------|--------------------------------------------------------|
+//! | 0    | Success, all files verified, equal or normalized       |
+//! | 1    | At least one hash mismatch or difference               |
+//! | 2    | Invalid usage or arguments                             |
+//! | 3    | At least one file could not be read or written         |
+//! | 4    | Malformed checksum input                               |
+//! | 5    | Completed with errors, unreadable files were skipped   |
+//!
+//! If several failures occur in a single run, the first matching code is used
+//! in the order 2, 3, 5, 4, 1. For example, a check run with one missing file and
+//! one mismatch exits with 3.
+//!
+//! The `diff` subcommand follows the exit codes of `diff` instead, so it exits
+//! with 2 for files that cannot be read.
+//!
+//! ## Examples
+//!
+//! Simple example with default options, without writing an output file:
+//!
+//! ```shell
+//! normalized-hasher input.txt
+//! ```
+//!
+//! More complex example, with writing output:
+//!
+//! ```shell
+//! normalized-hasher --eol $'\r\n' --no-eof input.txt --output-file output.txt
+//! ```
+
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io::{self, Write};
+use std::iter;
+use std::num::NonZeroUsize;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use clap::builder::{PossibleValue, StringValueParser, TypedValueParser};
+use clap::{
+    ArgAction, ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum,
+    ValueHint,
+};
+
+use normalized_hash::regex::Regex;
+use normalized_hash::{
+    Algorithm, Encoding, Hasher, OutputEol, SidecarStatus, Stats, SymlinkInput, Timing,
+};
+
+use crate::annotate::{Annotations, OutputFormat};
+use crate::append::AppendError;
+use crate::cache::Cache;
+use crate::clipboard::SystemClipboard;
+use crate::color::{ColorChoice, Palette};
+use crate::exit::{ExitStatus, DIFF_EXIT_STATUS_HELP, EXIT_STATUS_HELP};
+use crate::input::Inputs;
+use crate::output::Output;
+use crate::prompt::Terminal;
+use crate::qr::QrLevel;
+use crate::relative::RelativeTo;
+use crate::report::Report;
+use crate::template::Template;
+use crate::tty::SystemStdin;
+
+mod annotate;
+mod append;
+mod cache;
+mod check;
+mod checksum;
+mod clipboard;
+mod color;
+mod compare;
+mod completions;
+mod config;
+mod detect;
+mod diff;
+mod document;
+mod dupes;
+mod environment;
+mod error;
+mod exit;
+mod explain;
+mod filter;
+mod git;
+mod http;
+mod input;
+mod interactive;
+mod lint;
+mod manifest;
+mod normalize;
+mod outdir;
+mod output;
+mod pause;
+mod progress;
+mod prompt;
+mod qr;
+mod relative;
+mod report;
+mod self_test;
+mod stats;
+mod summary;
+mod template;
+mod text;
+mod timing;
+mod tty;
+mod verbose;
+mod verify_dir;
+mod watch;
+mod xattr;
+
+#[derive(Parser)]
+#[clap(author, version, about, long_about = None)]
+#[command(subcommand_negates_reqs = true, after_help = EXIT_STATUS_HELP)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// File to be hashed, directory with `--recursive`, or checksum file with `--check`
+    ///
+    /// With `-`, standard input is hashed. If FILE_IN does not exist but contains one of the glob metacharacters `*`, `?` or `[`, it
+    /// is expanded as a glob pattern, where `**` matches any number of directories. All matching
+    /// files are hashed in sorted order, like with `--recursive`.
+    #[arg(
+        required_unless_present_any = [
+            "files_from", "filter", "text", "from_clipboard", "show_config", "write_config",
+            "self_test",
+        ],
+        value_hint = ValueHint::AnyPath
+    )]
+    file_in: Option<OsString>,
+
+    /// More files to be hashed, after FILE_IN
+    ///
+    /// With three or more paths, all of them are hashed in the given order and printed with
+    /// their names, like the files matched by a glob pattern. A file that cannot be read is
+    /// reported and the remaining files are still hashed. The normalized files can be written
+    /// with `--output-dir`. A single FILE is FILE_OUT, like with `--output-file`, unless
+    /// `--recursive`, `--output-dir`, `--watch`, `--verify-sidecar` or `--verify-xattr` is given.
+    /// To hash exactly two files otherwise, pass a glob pattern instead.
+    #[arg(
+        value_name = "FILE",
+        value_hint = ValueHint::AnyPath,
+        conflicts_with_all = [
+            "output_file", "check", "detect", "filter", "files_from", "text", "from_clipboard",
+            "stdin_name", "git", "doc",
+        ]
+    )]
+    files: Vec<OsString>,
+
+    /// Hash TEXT instead of a file, as if it was the content of a file
+    ///
+    /// Can be given multiple times, where each text is hashed as an input of its own and printed
+    /// and recorded as `<text:1>`, `<text:2>` and so on. The text is normalized like a file,
+    /// including the eol sequence appended to its last line, unless `--no-eof` is given. Texts
+    /// may start with `-`, like `--text '-- comment'`.
+    #[arg(
+        long,
+        value_name = "TEXT",
+        allow_hyphen_values = true,
+        conflicts_with_all = [
+            "file_in", "files_from", "filter", "check", "detect", "watch", "git", "output_dir",
+            "cache", "append", "write_sidecar", "verify_sidecar", "xattr", "verify_xattr",
+            "stdin_name", "relative_to",
+        ]
+    )]
+    text: Vec<String>,
+
+    /// Hash all texts of `--text` as lines of a single input
+    #[arg(long, requires = "text")]
+    text_join: bool,
+
+    /// Hash the text in the clipboard instead of a file, as if it was the content of a file
+    ///
+    /// The text is printed and recorded as `<clipboard>`. Combine it with `--expected` to verify
+    /// a snippet copied from a document. It is an error if the clipboard is empty, holds no text
+    /// or cannot be accessed, like on systems without a desktop session.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "file_in", "files_from", "filter", "text", "check", "detect", "watch", "git",
+            "output_dir", "cache", "append", "write_sidecar", "verify_sidecar", "xattr",
+            "verify_xattr", "stdin_name", "relative_to",
+        ]
+    )]
+    from_clipboard: bool,
+
+    /// Read checksums from FILE_IN and verify them
+    ///
+    /// Each line of the checksum file has to be either in GNU style (`HASH  FILE`) or in BSD
+    /// style (`SHA256 (FILE) = HASH`). Blank lines and lines starting with `#` are ignored. For
+    /// each listed file, `FILE: OK` or `FILE: FAILED` is printed. Exits with 1 if any file could
+    /// not be verified. If FILE_IN is `-`, the checksums are read from stdin. Relative paths are
+    /// resolved against the directory of the checksum file, or against the current directory if
+    /// the checksums are read from stdin or `--relative-to-current-dir` is given.
+    ///
+    /// If the checksum file starts with a header that records the normalization options, as
+    /// written to `--output` and `--append` files, the files are checked with these options. It
+    /// is an error if other options are given by flags, environment variables or configuration
+    /// files, unless `--override-options` is given. Options that are not recorded, like
+    /// `--paragraph-mode` and `--redact`, are applied as given.
+    #[arg(short, long, conflicts_with_all = ["output_file", "expected", "tag", "append"])]
+    check: bool,
+
+    /// Check with the given normalization options, even if the checksum file records others
+    #[arg(long, requires = "check")]
+    override_options: bool,
+
+    /// Resolve relative paths in the checksum file against the current directory
+    ///
+    /// By default, they are resolved against the directory of the checksum file. With this flag,
+    /// they are resolved like by `sha256sum`. The results are printed with the paths as listed.
+    #[arg(long, requires = "check")]
+    relative_to_current_dir: bool,
+
+    /// Don't print a summary line at the end of a run with multiple files or `--check`
+    ///
+    /// The summary counts the hashed, skipped and unreadable files and is printed to stderr, or
+    /// with `--check`, the verified, failed, missing and skipped entries, printed with the
+    /// results.
+    #[arg(long)]
+    no_summary: bool,
+
+    /// Print only the result, without notes, warnings or progress bar
+    ///
+    /// With `--check`, only the OK lines of successfully verified files are omitted, like with
+    /// `sha256sum`. Otherwise, only hashes are printed, `--expected` only prints `OK` or
+    /// `MISMATCH`, and notes, warnings and the progress bar on stderr are suppressed. Errors that
+    /// abort the run are still printed. The exit code is not affected. Cannot be combined with
+    /// `--verbose`.
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Don't output anything, the exit code shows success
+    #[arg(long, requires = "check")]
+    status: bool,
+
+    #[command(flatten)]
+    normalization: NormalizationArgs,
+
+    /// Read the settings from FILE instead of discovering configuration files
+    ///
+    /// Without this flag, the user configuration file `normalized-hasher/config.toml` in the
+    /// platform configuration directory and all `.normalized-hasher.toml` files from the
+    /// repository root down to the directory of each input file are read, if they exist.
+    /// Nearer files take precedence over farther ones, and all of them over the user
+    /// configuration file. Flags on the command line take precedence over all configuration
+    /// files.
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath, global = true)]
+    config: Option<PathBuf>,
+
+    /// Don't read any configuration files
+    #[arg(long, conflicts_with = "config", global = true)]
+    no_config: bool,
+
+    /// Print the effective settings and where they come from, without hashing anything
+    ///
+    /// The settings are printed in the format of a configuration file, each followed by a
+    /// comment naming its source, which is `default`, `command line` or a configuration file.
+    /// With PATH, the settings that apply to the file PATH are printed, instead of those of the
+    /// current directory.
+    #[arg(long, value_name = "PATH", num_args = 0..=1, value_hint = ValueHint::AnyPath)]
+    show_config: Option<Option<PathBuf>>,
+
+    /// Check the golden test vectors of the hash algorithm, without hashing anything
+    ///
+    /// Each vector is a frozen input with normalization options and its expected hash. PASS or
+    /// FAIL is printed for each one, and any failure exits with code 1, so a deployed binary can
+    /// prove that it implements the documented algorithm.
+    #[arg(long, conflicts_with_all = ["show_config", "write_config"])]
+    self_test: bool,
+
+    /// Write the effective settings into a configuration FILE, without hashing anything
+    ///
+    /// The settings of flags, environment variables and configuration files are resolved and
+    /// written with a comment each, so running with FILE as configuration file behaves the same
+    /// as this run. An existing FILE is only replaced with `--force`.
+    #[arg(
+        long,
+        value_name = "FILE",
+        value_hint = ValueHint::FilePath,
+        conflicts_with = "show_config"
+    )]
+    write_config: Option<PathBuf>,
+
+    /// Overwrite FILE_OUT or the FILE of `--write-config` without asking if it exists
+    #[arg(long)]
+    force: bool,
+
+    /// Read FILE_OUT back after writing it and check that it has the computed hash
+    ///
+    /// Catches output files that are not written as intended, at the cost of reading them once
+    /// more. The files of `--output-dir` are checked before they replace existing files. A
+    /// mismatch is an error. Only applies when writing FILE_OUT or `--output-dir`, except for
+    /// standard input.
+    #[arg(long)]
+    self_check: bool,
+
+    /// Flush FILE_OUT to the storage device before reporting success
+    ///
+    /// The file and, on Unix, its directory are synced, so a power loss right after a successful
+    /// run cannot leave an empty or missing file behind. This waits for the storage device and
+    /// can make writing many small files considerably slower, so it is off by default. Only
+    /// applies when writing FILE_OUT or `--output-dir`, except for standard input.
+    #[arg(long)]
+    sync: bool,
+
+    /// Print what FILE_IN looks like instead of hashing it
+    ///
+    /// Reports the guessed encoding, `UTF-8`, `UTF-16LE`, `UTF-16BE`, likely `Windows-1252` or
+    /// `binary`, whether the file starts with a byte order mark, its dominant line ending and
+    /// whether it mixes different ones, whether it ends with a newline, and the number of
+    /// invisible characters like zero-width spaces or control characters. With `--json`, the
+    /// findings are printed as JSON document.
+    #[arg(
+        long,
+        conflicts_with_all = ["output_file", "check", "recursive", "expected", "append", "copy"]
+    )]
+    detect: bool,
+
+    /// Normalize stdin to stdout and print the hash to stderr at the end
+    ///
+    /// Works as a stage in a pipeline, like `generate | normalized-hasher --filter | gzip`, where
+    /// each line is passed on as soon as it is read. If the reader of stdout exits early, the
+    /// rest of stdin is not read, no hash is printed, and the exit code is 3.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "file_in", "output_file", "files_from", "check", "detect", "recursive", "watch",
+            "output_dir", "output", "append", "write_sidecar", "verify_sidecar", "json",
+            "json_lines", "csv", "copy", "expected", "cache", "relative_to",
+        ]
+    )]
+    filter: bool,
+
+    /// Write the hash of `--filter` into FILE instead of stderr
+    #[arg(long, value_name = "FILE", requires = "filter", value_hint = ValueHint::FilePath)]
+    hash_file: Option<PathBuf>,
+
+    /// Hash all files listed in FILE instead of FILE_IN, or from stdin if FILE is `-`
+    ///
+    /// The list contains one path per line, blank lines and lines starting with `#` are ignored.
+    /// The files are hashed in the order of the list, like with `--recursive`.
+    #[arg(
+        long,
+        value_name = "FILE",
+        value_hint = ValueHint::FilePath,
+        conflicts_with_all = ["file_in", "output_file", "expected", "check", "detect"]
+    )]
+    files_from: Option<OsString>,
+
+    /// Paths in the `--files-from` list are separated by NUL bytes instead of newlines
+    ///
+    /// This is the format of `find -print0`. Only empty entries are ignored.
+    #[arg(short = '0', long, requires = "files_from")]
+    null: bool,
+
+    /// Hash all files in the directory FILE_IN and its subdirectories
+    ///
+    /// For each file, a line like `HASH  FILE` is printed, sorted by path. Binary files are
+    /// skipped with a note on stderr. Symbolic links are not followed, unless `--follow-symlinks`
+    /// is given. Hidden files and
+    /// directories are included, unless `--no-hidden` is given. Files ignored by `.gitignore`
+    /// files are skipped, unless `--no-ignore` is given. `.git` directories are always skipped.
+    #[arg(
+        short,
+        long,
+        conflicts_with_all = ["output_file", "expected", "check", "write_sidecar", "verify_sidecar"]
+    )]
+    recursive: bool,
+
+    /// Skip hidden files and directories with `--recursive`
+    #[arg(long, requires = "recursive")]
+    no_hidden: bool,
+
+    /// Don't respect `.gitignore` files with `--recursive`
+    #[arg(long, requires = "recursive")]
+    no_ignore: bool,
+
+    /// Exclude files matching a glob pattern
+    ///
+    /// The pattern follows the `.gitignore` syntax and is matched against the path relative to
+    /// the directory given to `--recursive`, or against the path of a file matched by a glob
+    /// pattern in FILE_IN or listed with `--files-from`. Can be given multiple times. An
+    /// explicitly named FILE_IN is never excluded.
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Descend at most N directory levels with `--recursive`
+    ///
+    /// With a depth of 1, only the files directly in FILE_IN are hashed.
+    #[arg(long, value_name = "N", requires = "recursive")]
+    max_depth: Option<usize>,
+
+    /// Follow symbolic links with `--recursive`
+    ///
+    /// Links to directories that are already being traversed are skipped with a warning.
+    /// Directories and files that can be reached by more than one path are only hashed once.
+    #[arg(long, requires = "recursive")]
+    follow_symlinks: bool,
+
+    /// Fail on input files that are symbolic links, instead of hashing the files they point to
+    ///
+    /// Applies to FILE_IN, to files matched by a glob pattern and to files listed with
+    /// `--files-from`. A link whose target does not exist is always reported as such.
+    #[arg(long, conflicts_with = "follow_symlinks")]
+    no_follow_input_symlinks: bool,
+
+    /// Hash files that are given more than once every time
+    ///
+    /// By default, a file that is given by several paths, like `./a.txt` and `a.txt`, a
+    /// symbolic link and its target, or an overlapping glob pattern and `--files-from` list, is
+    /// only hashed once, by the path it is given first.
+    #[arg(long)]
+    no_dedup: bool,
+
+    /// Only hash files with this extension with `--recursive`
+    ///
+    /// The extension is given without the leading dot. Can be given multiple times. An empty
+    /// extension selects files without an extension. Extensions are compared case-insensitively on Windows and case-sensitively elsewhere.
+    /// Explicitly named files and files matched by a glob pattern are not filtered.
+    #[arg(long, value_name = "EXT", requires = "recursive")]
+    ext: Vec<OsString>,
+
+    /// Only hash files without an extension with `--recursive`, like `--ext ''`
+    #[arg(long, requires = "recursive")]
+    no_ext: bool,
+
+    /// Abort on the first file that cannot be read with multiple files
+    ///
+    /// By default, such files are skipped with a warning and the run completes with exit
+    /// code 5.
+    #[arg(long)]
+    fail_fast: bool,
+
+    #[command(flatten)]
+    retry: RetryArgs,
+
+    /// Hash up to N files at the same time with multiple files
+    ///
+    /// Defaults to the number of CPUs, `-j 1` hashes one file after the other. The results are
+    /// printed in the same order either way. With `--time`, the total is followed by the elapsed
+    /// wall time, which shows the speedup.
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+
+    /// Reuse the hashes of unchanged files from FILE with multiple files
+    ///
+    /// FILE stores the hash, size and modification time of each hashed file. If the size and
+    /// modification time of a file are unchanged, its hash is taken from FILE without reading
+    /// the file. Otherwise, the file is hashed and FILE is updated. FILE is created if it does
+    /// not exist and is replaced atomically. If it cannot be read, all files are hashed, with a
+    /// warning. Cached hashes are only used with the same normalization options. Files whose
+    /// hash is taken from FILE are not checked for mixed line endings, so this cannot be
+    /// combined with `--deny-mixed-eol`. Only applies when hashing multiple files.
+    #[arg(
+        long,
+        value_name = "FILE",
+        value_hint = ValueHint::FilePath,
+        conflicts_with_all = ["check", "watch", "detect", "deny_mixed_eol", "verify_sidecar"]
+    )]
+    cache: Option<PathBuf>,
+
+    /// Hash all files with `--cache`, but still update the cache file
+    #[arg(long, requires = "cache")]
+    no_cache_read: bool,
+
+    /// Hash a random fraction of the files anyway with `--cache`, like `5%`
+    ///
+    /// A file whose hash differs from its cached hash, despite the same size and modification
+    /// time, is reported with a warning and updated in the cache.
+    #[arg(long, value_name = "PERCENT", requires = "cache", value_parser = cache::parse_sample)]
+    cache_verify_sample: Option<f64>,
+
+    /// Don't fail if a glob pattern in FILE_IN matches no files
+    #[arg(long)]
+    allow_empty_glob: bool,
+
+    /// Report what normalization did and print notes about skipped files
+    ///
+    /// For each hashed file, a note on stderr reports the number of lines read, the number of
+    /// converted CRLF line endings, whether the file starts with a byte order mark, which is kept,
+    /// and whether it ends with a newline. Given twice, a second note counts the line endings
+    /// found and converted, and the changes to the final newline and to line contents.
+    ///
+    /// With multiple files, files that are neither regular files nor directories, like named
+    /// pipes, sockets or devices, are skipped, since reading from them might block forever. With
+    /// this flag, a note is printed for each of them, and for each duplicate skipped due to
+    /// `--follow-symlinks` or given more than once.
+    #[arg(short, long, action = ArgAction::Count)]
+    verbose: u8,
+
+    /// Report how long hashing took and the effective throughput
+    ///
+    /// After the results, the duration and throughput of hashing each file are printed to
+    /// stderr, followed by the total and the elapsed wall time with multiple files. Only opening,
+    /// reading and hashing the files is measured, not the startup or the traversal of
+    /// directories. With `--json`, the figures are included in the document instead, as `time`
+    /// objects with the fields `milliseconds` and `bytes_per_second`.
+    #[arg(long)]
+    time: bool,
+
+    /// Don't warn about files that mix line endings
+    ///
+    /// By default, a warning on stderr lists the counts of each style if a hashed file mixes
+    /// CRLF, LF and lone CR line endings, like after a partial paste. The hash is not affected.
+    #[arg(long, conflicts_with = "deny_mixed_eol")]
+    no_warn_mixed_eol: bool,
+
+    /// Fail if a hashed file mixes line endings
+    ///
+    /// The warning about mixed line endings becomes an error, and the run exits with code 1
+    /// after hashing all files, like for a mismatch.
+    #[arg(long)]
+    deny_mixed_eol: bool,
+
+    /// Wait for Enter before exiting, to keep the console window open
+    ///
+    /// After the results or errors are printed, `Press Enter to close...` is printed to stderr
+    /// and a line is read from stdin. This is only done if stdin is a terminal, so the flag
+    /// never blocks scripts. Also applies if the arguments are invalid.
+    #[arg(long)]
+    pause: bool,
+
+    /// Keep running and hash FILE_IN again whenever it changes
+    ///
+    /// The hash is printed right away and after each change, in lines like
+    /// `TIMESTAMP  HASH  FILE` with the time in UTC. Changes in quick succession, like an editor
+    /// writing a file twice, only print one line. A file that is replaced by renaming another
+    /// file over it, like editors do to save atomically, is still watched afterwards.
+    /// Multiple files can be watched with a glob pattern. Stops with exit code 0 on Ctrl-C.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "output_file", "check", "expected", "recursive", "detect", "append", "copy", "json",
+            "json_lines", "csv", "output", "write_sidecar", "verify_sidecar", "time"
+        ]
+    )]
+    watch: bool,
+
+    /// Don't show a progress bar
+    ///
+    /// By default, a progress bar is shown on stderr while hashing, if stderr is a terminal. For
+    /// a single file, it shows the bytes read, for multiple files the number of hashed files.
+    #[arg(long)]
+    no_progress: bool,
+
+    /// Don't warn before reading standard input from a terminal
+    ///
+    /// If FILE_IN is `-` and standard input is an interactive terminal, a note on stderr tells
+    /// that typed input is awaited, since the program seems to hang otherwise. `--filter` even
+    /// refuses to read from a terminal. With this flag, standard input is read without either.
+    #[arg(long)]
+    no_tty_warning: bool,
+
+    /// Verify the hash against an expected value
+    ///
+    /// Instead of printing the hash, compare it to the given value and print `OK` or `MISMATCH`
+    /// together with both values, which go to stderr on a mismatch. The expected value can be in
+    /// any encoding of `--format`. Whitespace, quotes, invisible characters like zero-width
+    /// spaces, an algorithm prefix like `SHA-256:` and the case of hex values are ignored, so
+    /// hashes can be pasted from documents. Exits with 1 on mismatch. FILE_OUT is written in
+    /// either case.
+    #[arg(
+        long,
+        visible_alias = "expect",
+        value_name = "HASH",
+        value_parser = parse_expected
+    )]
+    expected: Option<String>,
+
+    /// Create a BSD-style checksum line
+    ///
+    /// Instead of the bare hash, print a line like `SHA256 (FILE_IN) = HASH`.
+    #[arg(long, conflicts_with = "expected")]
+    tag: bool,
+
+    /// Create a GNU-style checksum line
+    ///
+    /// Instead of the bare hash, print a line like `HASH  FILE_IN`, as printed for each file when
+    /// hashing multiple files. As for multiple files, it is an error if its name contains a line
+    /// break, which would split the line, unless `--print0` is given.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "tag", "no_filename", "filename_only", "expected", "template", "check",
+            "verify_sidecar", "json", "json_lines", "csv"
+        ]
+    )]
+    with_filename: bool,
+
+    /// Copy the printed hash to the clipboard
+    ///
+    /// The hash is printed as usual and copied in the encoding of `--format`, without the file
+    /// name of `--tag`. Only a single file can be hashed, it is an error to use `--copy` with
+    /// multiple files. Fails with exit code 3 if no clipboard is available, like on systems
+    /// without a desktop session.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "check", "expected", "verify_sidecar", "filename_only", "json", "json_lines", "csv"
+        ]
+    )]
+    copy: bool,
+
+    /// Print each hash into a text snippet instead of the checksum line
+    ///
+    /// TEMPLATE is the text itself, or `@FILE` to read it from FILE. The placeholders `{hash}`,
+    /// `{file}`, `{filename}`, `{algorithm}`, `{date}`, `{lines}` and `{size}` are replaced by
+    /// the hash in the encoding of `--format`, the printed path, its last component, `SHA256`,
+    /// the current date in UTC like `2023-08-26`, the number of lines and the size in bytes. Write
+    /// `{{` and `}}` for literal braces. With multiple files, the template is printed once per
+    /// file. With `--copy`, the filled in template is copied instead of the bare hash.
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        value_parser = Template::parse,
+        conflicts_with_all = [
+            "check", "expected", "tag", "no_filename", "filename_only", "json", "json_lines",
+            "csv", "verify_sidecar", "detect", "watch"
+        ]
+    )]
+    template: Option<Template>,
+
+    /// Print the hash as a QR code
+    ///
+    /// The QR code is printed with half blocks after the hash and encodes a self-describing line
+    /// like `normalized-hasher: eol=lf ignore_whitespaces=false no_eof=false algorithm=sha256
+    /// format=v1 hash=HASH`, with the hash in the encoding of `--format`, so a scanned code
+    /// tells how to verify it. Light modules are drawn as blocks, for terminals with a dark
+    /// background. Only a single file can be hashed.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "check", "expected", "detect", "watch", "filter", "verify_sidecar", "verify_xattr",
+            "json", "json_lines", "csv"
+        ]
+    )]
+    qr: bool,
+
+    /// Write the QR code of `--qr` as PNG image into FILE
+    ///
+    /// The hash is printed as usual, the QR code is only printed as well if `--qr` is given.
+    #[arg(
+        long,
+        value_name = "FILE",
+        value_hint = ValueHint::FilePath,
+        conflicts_with_all = [
+            "check", "expected", "detect", "watch", "filter", "verify_sidecar", "verify_xattr",
+            "json", "json_lines", "csv"
+        ]
+    )]
+    qr_out: Option<PathBuf>,
+
+    /// Error correction level of QR codes
+    ///
+    /// Higher levels can still be scanned if parts of the code are damaged, but need larger
+    /// codes.
+    #[arg(long, value_name = "LEVEL", value_enum, default_value_t = QrLevel::default())]
+    qr_level: QrLevel,
+
+    /// Write the normalized form of FILE_IN into FILE_OUT
+    ///
+    /// FILE_OUT can also be given as the only path after FILE_IN. An existing FILE_OUT is only
+    /// replaced with `--force`. In an interactive console, it is asked whether to overwrite it
+    /// instead.
+    #[arg(long, value_name = "FILE_OUT", value_hint = ValueHint::FilePath)]
+    output_file: Option<OsString>,
+
+    /// Write the normalized form of each file into DIR
+    ///
+    /// Each file keeps its path relative to the `--relative-to` directory if given, otherwise to
+    /// the common ancestor of the inputs, so with `--recursive src`, the file `src/a/b.txt` is
+    /// written to `DIR/a/b.txt`. Subdirectories are created as needed. Outputs outside of DIR
+    /// and outputs that would overwrite any input are refused before anything is written. Binary
+    /// files are not copied.
+    #[arg(
+        long,
+        value_name = "DIR",
+        value_hint = ValueHint::DirPath,
+        conflicts_with_all = ["output_file", "check", "detect", "watch", "verify_sidecar"]
+    )]
+    output_dir: Option<PathBuf>,
+
+    /// Print and record paths relative to this directory
+    ///
+    /// Applies to the printed output, to `--append` and to `--json` and `--csv` reports. It is an
+    /// error if a file is not inside of DIR, unless `--allow-outside` is given. Paths are resolved
+    /// lexically, without following symlinks.
+    #[arg(
+        long,
+        value_name = "DIR",
+        value_hint = ValueHint::DirPath,
+        conflicts_with_all = ["check", "verify_sidecar"]
+    )]
+    relative_to: Option<PathBuf>,
+
+    /// Use absolute paths for files outside of the `--relative-to` directory
+    #[arg(long, requires = "relative_to")]
+    allow_outside: bool,
+
+    /// Print and record standard input as LABEL instead of `-`
+    ///
+    /// Applies to the checksum lines of `--tag`, to `--append` and to `--json` and `--csv`
+    /// reports, but not to the hash itself. It is an error if FILE_IN is not `-`.
+    #[arg(
+        long,
+        value_name = "LABEL",
+        conflicts_with_all = [
+            "files_from", "filter", "check", "detect", "watch", "relative_to", "verify_sidecar"
+        ]
+    )]
+    stdin_name: Option<PathBuf>,
+
+    /// Hash FILE_IN as committed at the revision REV, like `HEAD`, instead of the working copy
+    ///
+    /// The file is read from the git repository that contains it, so the hash tells if the
+    /// normalized content changed since the commit. It is an error if the file is not tracked at
+    /// that revision.
+    #[arg(
+        long,
+        value_name = "REV",
+        conflicts_with_all = [
+            "output_file", "output_dir", "recursive", "files_from", "filter", "check", "detect",
+            "watch", "cache", "append", "write_sidecar", "verify_sidecar", "xattr", "verify_xattr",
+            "stdin_name",
+        ]
+    )]
+    git: Option<String>,
+
+    /// Hash the text of FILE_IN, a .docx or .odt document
+    ///
+    /// The text of the document body is extracted with one line per paragraph, and normalized
+    /// like the content of a text file. Line breaks within paragraphs start new lines as well.
+    /// Images, comments and footnotes are ignored. With `--ignore-whitespaces`, non-breaking
+    /// spaces of word processors are removed like all other whitespaces. Encrypted documents,
+    /// legacy .doc files and other formats are an error.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "output_file", "output_dir", "recursive", "files_from", "filter", "check", "detect",
+            "watch", "cache", "append", "write_sidecar", "verify_sidecar", "xattr", "verify_xattr",
+            "stdin_name", "git", "text", "from_clipboard",
+        ]
+    )]
+    doc: bool,
+
+    /// Print only the hashes, without file names
+    ///
+    /// Prints one bare hash per file in input order, also when hashing multiple files. With
+    /// `--check`, only the result of each file is printed.
+    #[arg(
+        long,
+        conflicts_with_all = ["tag", "expected", "verify_sidecar", "json", "json_lines", "csv"]
+    )]
+    no_filename: bool,
+
+    /// Print only the file names, without hashes
+    ///
+    /// Prints the path of each hashed file in input order. With `--check`, only the paths of
+    /// failed files are printed.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "no_filename", "tag", "expected", "verify_sidecar", "json", "json_lines", "csv"
+        ]
+    )]
+    filename_only: bool,
+
+    /// Terminate each output record with a NUL byte instead of a newline
+    ///
+    /// Makes the output safe to split with `xargs -0` or `read -d ''`, even if file names
+    /// contain newlines, which are an error in checksum lines otherwise. Composes with
+    /// `--files-from FILE -0`.
+    #[arg(
+        short = 'z',
+        long,
+        conflicts_with_all = ["check", "expected", "json", "json_lines", "csv"]
+    )]
+    print0: bool,
+
+    /// Fail on file names that are not valid UTF-8, instead of escaping them
+    ///
+    /// By default, bytes of such names are printed and recorded as `%` followed by two hex
+    /// digits, like `caf%E9.txt`, which `--check` reads back into the original name.
+    #[arg(long)]
+    strict_names: bool,
+
+    /// Encoding of printed hashes
+    ///
+    /// Applies to all printed hashes, also with `--json` and `--csv`. Checksum and sidecar files
+    /// are always written in hex. Expected hashes, in `--expected`, `--check` and
+    /// `--verify-sidecar`, are accepted in any of these encodings. `sri` and `multihash` already
+    /// name the algorithm and cannot be combined with `--tag`.
+    #[arg(long, value_enum, default_value = "hex")]
+    format: HashFormat,
+
+    /// Print the results as JSON document
+    ///
+    /// The document contains an array `entries` with an object per file, with the fields
+    /// `path`, `hash`, `algorithm`, `bytes` and `changed`, or `path`, `algorithm` and `error` if
+    /// the file could not be hashed. The `error` is an object with the fields `kind`, which is
+    /// `open`, `read`, `decode`, `create` or `write`, and `message`. The object `summary` contains the
+    /// counters `hashed`, `changed`, `skipped` and `errors`. Warnings are still printed to
+    /// stderr. Errors that abort the run are printed to stderr as a JSON line with the fields
+    /// `path`, if the error concerns a single file, and `error`, whose `kind` can also be
+    /// `usage`, `io`, `conflict` or `mixed-eol`.
+    #[arg(long, conflicts_with_all = ["expected", "tag", "check", "verify_sidecar"])]
+    json: bool,
+
+    /// Print each result as a JSON line as soon as it is available
+    ///
+    /// Instead of a single document, each entry of `--json` is printed as an object of its own
+    /// on a single line, with the additional field `type` set to `entry`, as soon as the file is
+    /// hashed. The last line is the summary, with the `type` set to `summary`. Thus, results can
+    /// be consumed while the run is going on, and all finished results are kept if it is
+    /// aborted. Only the summary is kept in memory.
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "expected", "tag", "check", "verify_sidecar", "detect"]
+    )]
+    json_lines: bool,
+
+    /// Print the JSON lines as the files finish, instead of in the order of the files
+    ///
+    /// Each entry gets the field `seq` with the index of its file in the usual order, starting
+    /// at 0. Results of the cache are printed first.
+    #[arg(long, requires = "json_lines")]
+    unordered: bool,
+
+    /// Print the results as CSV
+    ///
+    /// A header row is followed by one row per hashed file, with the columns `path`,
+    /// `algorithm`, `hash`, `size`, `lines` and `changed`. Records are terminated by CRLF.
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "json_lines", "expected", "tag", "check", "verify_sidecar"]
+    )]
+    csv: bool,
+
+    /// Field delimiter for `--csv`
+    #[arg(
+        long,
+        value_name = "CHAR",
+        default_value = ",",
+        value_parser = parse_delimiter,
+        requires = "csv"
+    )]
+    csv_delimiter: u8,
+
+    /// Write the results to FILE instead of stdout
+    ///
+    /// Applies to the checksum lines, the `--check` results and the `--json` and `--csv` reports,
+    /// while errors and warnings are still printed to stderr. Checksum lines list the paths
+    /// relative to the directory of FILE, where `--check` resolves them. FILE is replaced
+    /// atomically once all results are written. If writing fails, or the run fails with exit
+    /// code 2 or 3, FILE is left untouched. The checksum file given to `--append` is updated
+    /// independently and has to be a different file.
+    #[arg(short, long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    output: Option<PathBuf>,
+
+    /// Add the hash to a checksum file
+    ///
+    /// An existing entry for FILE_IN is updated, otherwise a new line is appended. All other
+    /// lines, including comments, are preserved. The checksum file is replaced atomically. The
+    /// path of FILE_IN is listed relative to the directory of the checksum file.
+    #[arg(long, value_name = "CHECKSUM_FILE", value_hint = ValueHint::FilePath)]
+    append: Option<OsString>,
+
+    /// Fail instead of updating an existing entry with a different hash
+    #[arg(long, requires = "append")]
+    no_update: bool,
+
+    /// Write the hash into a sidecar checksum file next to FILE_IN
+    ///
+    /// The sidecar is named like FILE_IN with the sidecar suffix appended.
+    #[arg(long)]
+    write_sidecar: bool,
+
+    /// Verify FILE_IN against its sidecar checksum file
+    ///
+    /// Prints `FILE: OK`, `FILE: FAILED` or `FILE: MISSING`. Exits with 1 if the file could not
+    /// be verified.
+    #[arg(
+        long,
+        conflicts_with_all = ["output_file", "expected", "tag", "append", "check", "write_sidecar"]
+    )]
+    verify_sidecar: bool,
+
+    /// Suffix of sidecar checksum files
+    ///
+    /// Defaults to `.sha256`, or to the name of another `--algorithm`, like `.sha512`.
+    #[arg(long, value_name = "SUFFIX")]
+    sidecar_suffix: Option<String>,
+
+    /// Store the hash in the extended attribute `user.normalized_hash.sha256` of FILE_IN
+    ///
+    /// The attribute also records the normalization options, so that `--verify-xattr` can tell a
+    /// changed file from a hash of other options. With another `--algorithm`, the attribute is
+    /// named after it, like `user.normalized_hash.sha512`. Needs a file system with extended
+    /// attributes.
+    #[arg(long, conflicts_with_all = ["filter", "check", "detect", "watch"])]
+    xattr: bool,
+
+    /// Verify FILE_IN against the hash in its extended attribute
+    ///
+    /// Prints `FILE: OK`, `FILE: FAILED` or `FILE: UNSET`. If the attribute was stored with other
+    /// normalization options, the result is `FAILED` and the stored options are printed. Exits
+    /// with 1 if the hash differs and with 3 if the attribute is not set.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "output_file", "expected", "tag", "append", "check", "write_sidecar", "verify_sidecar",
+            "xattr", "filter", "detect", "watch", "cache", "output_dir", "template", "json",
+            "json_lines", "csv",
+        ]
+    )]
+    verify_xattr: bool,
+
+    /// When to colorize the results of `--check`, `--verify-sidecar`, `--expected` and `compare`
+    ///
+    /// With `auto`, colors are only used if stdout is a terminal and the environment variable
+    /// `NO_COLOR` is not set. Reports of `--json` and `--csv` are never colorized.
+    #[arg(
+        long,
+        value_name = "WHEN",
+        value_enum,
+        default_value = "auto",
+        global = true
+    )]
+    color: ColorChoice,
+
+    /// Also print the results of `lint`, `--check`, `manifest verify` and `verify-dir` for CI
+    ///
+    /// With `github`, each offending file gets an error annotation and each skipped file a
+    /// notice, as workflow commands that GitHub Actions shows on the changed lines. They are
+    /// printed alongside the normal output, regardless of `--quiet` and `--status`, and to
+    /// stderr if stdout is a JSON document.
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        value_enum,
+        default_value = "plain",
+        global = true
+    )]
+    output_format: OutputFormat,
+}
+
+impl Cli {
+    /// Print the header with the normalization options, if the output is a checksum file.
+    ///
+    /// Only files given to `--output` get a header, so the output read by scripts is unchanged.
+    fn print_header(&self, out: &mut Output) {
+        let is_file = self
+            .output
+            .as_deref()
+            .is_some_and(|path| path != Path::new("-"));
+        let is_report = self.is_report();
+
+        let is_plain = self.no_filename || self.filename_only || self.print0;
+        if is_file && !is_report && !is_plain && self.template.is_none() {
+            out.line(self.normalization.header());
+        }
+    }
+
+    /// Print an output record, terminated by a newline or, with `--print0`, a NUL byte.
+    fn print_record(&self, out: &mut Output, record: &str) {
+        out.record(record, if self.print0 { '\0' } else { '\n' });
+    }
+
+    /// Print the checksum line of a file of a batch, in the format given on the command line.
+    fn print_checksum(
+        &self,
+        out: &mut Output,
+        hasher: &Hasher,
+        file: &Path,
+        name: &Path,
+        hash: &str,
+        stats: Option<&Stats>,
+    ) {
+        let hash = self.display_hash(hasher, hash);
+        if let Some(template) = &self.template {
+            self.print_record(out, &template.render(hasher, file, name, &hash, stats));
+        } else if self.no_filename {
+            self.print_record(out, &hash);
+        } else if self.filename_only {
+            self.print_record(out, &normalized_hash::escape_path(name));
+        } else if self.tag {
+            let algorithm = hasher.digest_algorithm();
+            let name = self.listed_path(name);
+            self.print_record(out, &checksum::format_bsd(algorithm, &name, &hash));
+        } else {
+            let name = self.listed_path(name);
+            self.print_record(out, &checksum::format_gnu(&name, &hash));
+        }
+    }
+
+    /// Fail if `name` would split the checksum line that lists it.
+    ///
+    /// Lines of `--append` always end with a newline, printed lines only unless `--print0` is
+    /// given. `listed` tells whether the printed records contain the name.
+    fn check_line_break(&self, name: &Path, listed: bool) -> ExitStatus {
+        let split = (listed && !self.print0) || self.append.is_some();
+        if !split || !checksum::has_line_break(name) {
+            return ExitStatus::Success;
+        }
+
+        let message = format_args!(
+            "cannot print a checksum line for {:?}: its name contains a line break",
+            name
+        );
+        error::fatal("usage", Some(name), message);
+        ExitStatus::Usage
+    }
+
+    /// Whether the records printed for a batch contain the names of the files.
+    fn lists_names(&self) -> bool {
+        !self.is_report() && !self.no_filename && self.template.is_none()
+    }
+
+    /// Path of a file in checksum lines, relative to the directory of the `--output` file.
+    fn listed_path(&self, name: &Path) -> PathBuf {
+        match &self.output {
+            Some(output) => checksum::listed_path(output, name),
+            None => name.to_path_buf(),
+        }
+    }
+
+    /// Hasher with the `normalization` options and those of the command line for reading and
+    /// writing files.
+    fn hasher(&self, normalization: &NormalizationArgs) -> Hasher {
+        self.retry
+            .apply(normalization.hasher())
+            .symlink_input(self.symlink_input())
+            .verify_output(self.self_check)
+            .sync_output(self.sync)
+    }
+
+    /// How input files that are symbolic links are hashed.
+    fn symlink_input(&self) -> SymlinkInput {
+        if self.no_follow_input_symlinks {
+            SymlinkInput::Error
+        } else {
+            SymlinkInput::Follow
+        }
+    }
+
+    /// Suffix of sidecar files, named after the `algorithm` unless given.
+    fn sidecar_suffix(&self, algorithm: Algorithm) -> String {
+        match &self.sidecar_suffix {
+            Some(suffix) => suffix.clone(),
+            None => format!(".{}", algorithm.name()),
+        }
+    }
+
+    /// Encode a hex encoded hash of `hasher` for printing, in the requested format.
+    fn display_hash(&self, hasher: &Hasher, hash: &str) -> String {
+        let encoding = Encoding::from(self.format);
+        let algorithm = hasher.digest_algorithm();
+        encoding
+            .reencode_with(algorithm, hash)
+            .unwrap_or_else(|| hash.to_string())
+    }
+
+    /// Whether a QR code is printed or written, with `--qr` or `--qr-out`.
+    fn wants_qr(&self) -> bool {
+        self.qr || self.qr_out.is_some()
+    }
+
+    /// Whether the results are printed as JSON, either as document or as lines.
+    fn is_json(&self) -> bool {
+        self.json || self.json_lines
+    }
+
+    /// Whether the results are collected into a report instead of printed as checksum lines.
+    fn is_report(&self) -> bool {
+        self.is_json() || self.csv
+    }
+
+    /// Print a report in the requested format.
+    fn print_report(&self, out: &mut Output, report: &mut Report) -> ExitStatus {
+        if self.json_lines {
+            report.print_json_lines(out);
+            return ExitStatus::Success;
+        }
+
+        if !self.csv {
+            report.print_json(out);
+            return ExitStatus::Success;
+        }
+
+        match report.print_csv(out, self.csv_delimiter) {
+            Ok(()) => ExitStatus::Success,
+            Err(err) => {
+                eprintln!("error: cannot write CSV: {}", err);
+                ExitStatus::Io
+            }
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check if two files are equal after normalization
+    #[command(after_help = EXIT_STATUS_HELP)]
+    Compare(compare::CompareArgs),
+
+    /// Show a unified diff of two files after normalization
+    #[command(after_help = DIFF_EXIT_STATUS_HELP)]
+    Diff(diff::DiffArgs),
+
+    /// Show what normalization would change in a file
+    #[command(after_help = EXIT_STATUS_HELP)]
+    Explain(explain::ExplainArgs),
+
+    /// List files that are not in normalized form
+    #[command(after_help = EXIT_STATUS_HELP)]
+    Lint(lint::LintArgs),
+
+    /// Survey line endings and whitespace of files, without hashing them
+    #[command(after_help = EXIT_STATUS_HELP)]
+    Stats(stats::StatsArgs),
+
+    /// Find files with the same content after normalization
+    #[command(after_help = EXIT_STATUS_HELP)]
+    Dupes(dupes::DupesArgs),
+
+    /// Convert files to their normalized form, without hashing them
+    #[command(after_help = EXIT_STATUS_HELP)]
+    Normalize(normalize::NormalizeArgs),
+
+    /// Record the hashes of a directory tree in a manifest and verify them later
+    Manifest(manifest::ManifestArgs),
+
+    /// Check if two directory trees are equal after normalization
+    #[command(after_help = EXIT_STATUS_HELP)]
+    VerifyDir(verify_dir::VerifyDirArgs),
+
+    /// Print a shell completion script to stdout
+    Completions(completions::CompletionsArgs),
+}
+
+/// Encoding of printed hashes.
+#[derive(Clone, Copy, ValueEnum)]
+enum HashFormat {
+    /// Lowercase hex
+    #[value(name = "hex")]
+    Hex,
+
+    /// Uppercase hex
+    #[value(name = "HEX")]
+    UpperHex,
+
+    /// Base64 with padding
+    Base64,
+
+    /// Subresource Integrity, like `sha256-BASE64`
+    Sri,
+
+    /// Multihash in hex, like `1220HEX`
+    Multihash,
+}
+
+/// Digest algorithm of the hashes, given with `--algorithm`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum HashAlgorithm {
+    /// SHA-256, with 64 hex digits
+    Sha256,
+
+    /// SHA-512, with 128 hex digits
+    Sha512,
+
+    /// BLAKE3, with 64 hex digits like SHA-256, so bare hashes need `--algorithm blake3`
+    Blake3,
+}
+
+impl From<HashAlgorithm> for Algorithm {
+    fn from(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha256 => Algorithm::Sha256,
+            HashAlgorithm::Sha512 => Algorithm::Sha512,
+            HashAlgorithm::Blake3 => Algorithm::Blake3,
+        }
+    }
+}
+
+impl From<HashFormat> for Encoding {
+    fn from(format: HashFormat) -> Self {
+        match format {
+            HashFormat::Hex => Encoding::Hex,
+            HashFormat::UpperHex => Encoding::UpperHex,
+            HashFormat::Base64 => Encoding::Base64,
+            HashFormat::Sri => Encoding::Sri,
+            HashFormat::Multihash => Encoding::Multihash,
+        }
+    }
+}
+
+/// Options for reading files again, shared by the main command and check mode.
+#[derive(Args, Clone)]
+struct RetryArgs {
+    /// Hash a file up to N more times if it is modified while it is read
+    ///
+    /// The size and modification time of each file are compared before and after reading it, so
+    /// a file that is still being written is not recorded with the hash of a half-written file.
+    /// A file that is still modified after N retries cannot be hashed, which with multiple files
+    /// is reported like an unreadable file.
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    retry_on_modify: u32,
+
+    /// Hash a file up to N more times after a transient error while reading it
+    ///
+    /// Network filesystems occasionally fail in the middle of reading a file. Such a file is
+    /// opened again and hashed from scratch after `--retry-delay`, which doubles with each
+    /// retry. Interrupted reads, reset connections and timeouts are transient, and so are the
+    /// errors given with `--retry-on-errno`. Missing files and denied permissions fail right
+    /// away.
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    retries: u32,
+
+    /// Wait MS milliseconds before the first retry with `--retries`
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    retry_delay: u64,
+
+    /// Also retry errors with this OS error number with `--retries`, like 5 for EIO on Linux
+    ///
+    /// Can be given multiple times.
+    #[arg(long, value_name = "ERRNO")]
+    retry_on_errno: Vec<i32>,
+}
+
+impl RetryArgs {
+    /// Apply the options to a hasher.
+    fn apply(&self, hasher: Hasher) -> Hasher {
+        let errnos = self.retry_on_errno.clone();
+        hasher
+            .retry_on_modify(self.retry_on_modify)
+            .retries(self.retries)
+            .retry_delay(Duration::from_millis(self.retry_delay))
+            .retry_if(move |err| {
+                err.raw_os_error()
+                    .is_some_and(|errno| errnos.contains(&errno))
+            })
+    }
+}
+
+/// Options that control the normalization, shared by all subcommands.
+#[derive(Args, Clone)]
+struct NormalizationArgs {
+    /// Ids of the options that are not at their built-in default, filled in after parsing.
+    #[arg(skip)]
+    given: Vec<&'static str>,
+
+    /// End-of-line sequence, will be appended to each normalized line for hashing
+    ///
+    /// The names `lf`, `crlf` and `cr` can be used instead of the control characters.
+    #[arg(
+        long,
+        default_value = "\n",
+        global = true,
+        value_parser = EolParser,
+        hide_possible_values = true
+    )]
+    eol: String,
+
+    /// Ignore all whitespaces
+    ///
+    /// This will remove all whitespaces from the input file when generating the hash.
+    #[arg(long, global = true)]
+    ignore_whitespaces: bool,
+
+    /// Replace all matches of a regular expression in each line before hashing
+    ///
+    /// Unlike leaving out whole lines, the line still counts with the replacement instead of the
+    /// sensitive part. The replacement may refer to capture groups like `$1`. Can be given
+    /// multiple times, the patterns are applied in the given order, each to the result of the
+    /// previous ones. Normalized outputs contain the redacted lines. The patterns are not
+    /// recorded in checksum headers, so they have to be given again to `--check`, which applies
+    /// them on top of the options recorded in a header.
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATTERN=>REPLACEMENT",
+        value_parser = parse_redaction
+    )]
+    redact: Vec<(Regex, String)>,
+
+    /// Skip last end-of-line on end-of-file
+    ///
+    /// With this flag, no trailing EOL will be appended at the end of the file.
+    #[arg(long, global = true)]
+    no_eof: bool,
+
+    /// Digest algorithm of the hashes
+    ///
+    /// SHA-256 is the default, so hashes stay the same as with earlier versions. The algorithm
+    /// is recorded in checksum headers and named in `--tag` lines, and `--check` only verifies
+    /// hashes of the selected algorithm.
+    #[arg(short, long, value_enum, default_value_t = HashAlgorithm::Sha256, global = true)]
+    algorithm: HashAlgorithm,
+
+    /// Hash paragraphs instead of lines, so rewrapping text keeps the hash
+    ///
+    /// Paragraphs are separated by blank lines. Within a paragraph, line breaks and the
+    /// whitespaces around them are replaced by single spaces, before `--ignore-whitespaces`
+    /// applies. The paragraphs are joined with a single EOL sequence for hashing and in
+    /// normalized outputs. The flag is not recorded in checksum headers, so it has to be given
+    /// again to `--check`, which applies it on top of the options recorded in a header.
+    #[arg(long, global = true)]
+    paragraph_mode: bool,
+
+    /// Keep line breaks after two trailing spaces or a backslash with `--paragraph-mode`
+    #[arg(long, global = true, requires = "paragraph_mode")]
+    preserve_hard_breaks: bool,
+
+    /// Write normalized files with the line ending that each input predominantly uses
+    ///
+    /// CRLF files stay CRLF and LF files stay LF, while the hash is still computed with the eol
+    /// sequence, so it is the same on every platform. Applies to FILE_OUT, `--output-dir`,
+    /// `--filter` and the `normalize` subcommand. Only the first 64 KiB of each input are
+    /// counted. Without line endings there, or with as many CRLF as LF, the eol sequence is
+    /// written.
+    #[arg(long, global = true)]
+    keep_style: bool,
+}
+
+/// Parser of `--eol`, which takes any sequence or the name of a common line ending.
+///
+/// The names are offered by shell completions, since the sequences are hard to type.
+#[derive(Clone)]
+struct EolParser;
+
+impl EolParser {
+    const NAMES: [(&'static str, &'static str, &'static str); 3] = [
+        ("lf", "\n", "Line feed, like on Unix"),
+        (
+            "crlf",
+            "\r\n",
+            "Carriage return and line feed, like on Windows",
+        ),
+        ("cr", "\r", "Carriage return, like on classic Mac OS"),
+    ];
+}
+
+impl TypedValueParser for EolParser {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<String, clap::Error> {
+        let value = StringValueParser::new().parse_ref(cmd, arg, value)?;
+
+        Ok(Self::NAMES
+            .iter()
+            .find(|(name, _, _)| *name == value)
+            .map_or(value, |(_, eol, _)| eol.to_string()))
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(
+            Self::NAMES
+                .iter()
+                .map(|(name, _, help)| PossibleValue::new(name).help(help)),
+        ))
+    }
+}
+
+impl NormalizationArgs {
+    fn hasher(&self) -> Hasher {
+        self.with_header(&self.header())
+    }
+
+    /// Hasher with the options recorded in `header` and the given ones that headers don't record.
+    fn with_header(&self, header: &checksum::Header) -> Hasher {
+        let hasher = header
+            .hasher()
+            .paragraph_mode(self.paragraph_mode)
+            .preserve_hard_breaks(self.preserve_hard_breaks)
+            .output_eol(if self.keep_style {
+                OutputEol::MatchInput
+            } else {
+                OutputEol::Hash
+            });
+
+        self.redact
+            .iter()
+            .fold(hasher, |hasher, (pattern, replacement)| {
+                hasher.redact(pattern.clone(), replacement)
+            })
+    }
+
+    /// Options as recorded in the header of generated checksum files.
+    fn header(&self) -> checksum::Header {
+        checksum::Header {
+            eol: self.eol.clone(),
+            ignore_whitespaces: self.ignore_whitespaces,
+            no_eof: self.no_eof,
+            algorithm: self.algorithm.into(),
+        }
+    }
+
+    /// Describe the given options that differ from those of a checksum file, like `eol=crlf`.
+    fn conflicts(&self, recorded: &checksum::Header) -> Vec<(String, String)> {
+        self.header()
+            .fields()
+            .into_iter()
+            .zip(recorded.fields())
+            .filter(|((key, given), (_, recorded))| given != recorded && self.given.contains(key))
+            .map(|((key, given), (_, recorded))| {
+                (
+                    format!("{}={}", key, given),
+                    format!("{}={}", key, recorded),
+                )
+            })
+            .collect()
+    }
+
+    /// Describe the options in a cache file, where hashes are only valid for the same options.
+    fn cache_options(&self) -> String {
+        let eol = self
+            .eol
+            .bytes()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+
+        let mut options = format!(
+            "eol={} ignore-whitespaces={} no-eof={}",
+            eol, self.ignore_whitespaces, self.no_eof
+        );
+        for (pattern, replacement) in &self.redact {
+            let redaction = format!("{}=>{}", pattern, replacement);
+            options.push_str(&format!(" redact={:?}", redaction));
+        }
+        if self.paragraph_mode {
+            options.push_str(&format!(
+                " paragraph-mode=true preserve-hard-breaks={}",
+                self.preserve_hard_breaks
+            ));
+        }
+        // Hashes cached before other algorithms were supported stay valid
+        if self.algorithm != HashAlgorithm::Sha256 {
+            options.push_str(&format!(" algorithm={}", Algorithm::from(self.algorithm)));
+        }
+        options
+    }
+}
+
+/// Normalize an expected hash value for comparison.
+///
+/// The value is decoded tolerantly with [`Encoding::decode_pasted`], the SHA-256 hash is
+/// converted to lowercase hex.
+fn parse_expected(expected: &str) -> Result<String, String> {
+    Encoding::decode_pasted(expected)
+        .map(|digest| Encoding::Hex.encode(&digest))
+        .map_err(|err| err.to_string())
+}
+
+/// Parse a redaction like `password=\S+=>password=***` into its pattern and replacement.
+///
+/// The value is split at the last `=>`, so the pattern may contain it, but not the replacement.
+fn parse_redaction(redaction: &str) -> Result<(Regex, String), String> {
+    let Some((pattern, replacement)) = redaction.rsplit_once("=>") else {
+        return Err(String::from("expected PATTERN=>REPLACEMENT"));
+    };
+    let pattern = Regex::new(pattern).map_err(|err| err.to_string())?;
+    Ok((pattern, replacement.to_string()))
+}
+
+/// Parse a CSV delimiter, which has to be a single ASCII character.
+fn parse_delimiter(delimiter: &str) -> Result<u8, String> {
+    match delimiter.as_bytes() {
+        [delimiter] if delimiter.is_ascii() => Ok(*delimiter),
+        _ => Err(String::from("expected a single ASCII character")),
+    }
+}
+
+fn verify_sidecar(
+    hasher: &Hasher,
+    out: &mut Output,
+    palette: Palette,
+    file_in: &Path,
+    suffix: &str,
+) -> ExitStatus {
+    let status = match hasher.verify_sidecar(file_in, suffix) {
+        Ok(status) => status,
+        Err(err) => {
+            eprintln!("error: cannot verify {}: {}", file_in.display(), err);
+            return ExitStatus::Io;
+        }
+    };
+
+    let (status, exit_status) = match status {
+        SidecarStatus::Ok => (palette.ok("OK"), ExitStatus::Success),
+        SidecarStatus::Failed => (palette.failed("FAILED"), ExitStatus::Mismatch),
+        SidecarStatus::Missing => (palette.missing("MISSING"), ExitStatus::Io),
+    };
+
+    out.line(format_args!("{}: {}", file_in.display(), status));
+
+    exit_status
+}
+
+/// Paths of `files` as printed and recorded, relative to `--relative-to` if given.
+///
+/// With `--strict-names`, names that are not valid UTF-8 are an error.
+fn printed_paths(cli: &Cli, files: &[PathBuf]) -> Result<Vec<PathBuf>, ExitStatus> {
+    let names = match &cli.relative_to {
+        Some(base) => relative_paths(cli, base, files)?,
+        None => files.to_vec(),
+    };
+
+    if cli.strict_names {
+        if let Some((file, name)) = files
+            .iter()
+            .zip(&names)
+            .find(|(_, name)| name.to_str().is_none())
+        {
+            let message = format_args!(
+                "the name of {} is not valid UTF-8, it can only be recorded escaped as {}",
+                file.display(),
+                normalized_hash::escape_path(name)
+            );
+            error::fatal("usage", Some(file), message);
+            return Err(ExitStatus::Usage);
+        }
+    }
+
+    Ok(names)
+}
+
+/// Resolve the paths of the files relative to the `--relative-to` directory.
+fn relative_paths(cli: &Cli, base: &Path, files: &[PathBuf]) -> Result<Vec<PathBuf>, ExitStatus> {
+    let relative_to = RelativeTo::new(base, cli.allow_outside).map_err(|err| {
+        let message = format_args!("cannot resolve {}: {}", base.display(), err);
+        error::fatal(error::kind(&err), Some(base), message);
+        ExitStatus::Io
+    })?;
+
+    files
+        .iter()
+        .map(|file| match relative_to.apply(file) {
+            Ok(Some(path)) => Ok(path),
+            Ok(None) => {
+                let message = format_args!(
+                    "{} is not inside of {}, use --allow-outside to print its absolute path",
+                    file.display(),
+                    base.display()
+                );
+                error::fatal("usage", Some(file), message);
+                Err(ExitStatus::Usage)
+            }
+            Err(err) => {
+                let message = format_args!("cannot resolve {}: {}", file.display(), err);
+                error::fatal(error::kind(&err), Some(file), message);
+                Err(ExitStatus::Io)
+            }
+        })
+        .collect()
+}
+
+/// Hash standard input for FILE_IN `-`, writing the normalized content to `file_out`, if given.
+fn hash_stdin(hasher: &Hasher, file_out: Option<fs::File>) -> io::Result<(String, Stats, Timing)> {
+    let start = Instant::now();
+    let stdin = io::stdin().lock();
+
+    let (hash, stats) = match file_out {
+        Some(file_out) => {
+            let mut file_out = io::BufWriter::new(file_out);
+            let result = hasher.filter(stdin, &mut file_out)?;
+            file_out.flush()?;
+            result
+        }
+        None => hasher.filter(stdin, io::sink())?,
+    };
+
+    let timing = Timing::new(stats.bytes, start.elapsed());
+    Ok((hash, stats, timing))
+}
+
+/// Hash content that is not read from FILE_IN as is, like with `--git`, `--doc`, `--text` and
+/// `--from-clipboard`.
+fn hash_content(hasher: &Hasher, content: &[u8]) -> io::Result<(String, Stats, Timing)> {
+    let start = Instant::now();
+    let (hash, stats) = hasher.filter(content, io::sink())?;
+
+    let timing = Timing::new(stats.bytes, start.elapsed());
+    Ok((hash, stats, timing))
+}
+
+/// Hash multiple texts of `--text`, each printed like a file of a batch.
+fn hash_texts(cli: &Cli, hasher: &Hasher, out: &mut Output, texts: &[text::Text]) -> ExitStatus {
+    if cli.expected.is_some() || cli.copy || cli.wants_qr() {
+        let message = "--expected, --copy and --qr cannot be used with multiple texts";
+        error::fatal("usage", None, message);
+        return ExitStatus::Usage;
+    }
+
+    let mut report = cli.is_report().then(Report::default);
+    let mut status = ExitStatus::Success;
+    cli.print_header(out);
+
+    for text in texts {
+        let (hash, stats, _) = match hash_content(hasher, text.content.as_bytes()) {
+            Ok(hashed) => hashed,
+            Err(err) => {
+                let message = error::describe(&err, &text.label, None);
+                error::fatal(error::kind(&err), Some(&text.label), message);
+                return ExitStatus::Io;
+            }
+        };
+        status = status.max(check_mixed_eol(cli, &text.label, &stats));
+
+        if let Some(report) = &mut report {
+            report.add_hash(
+                hasher,
+                &text.label,
+                &text.label,
+                cli.display_hash(hasher, &hash),
+            );
+            if cli.json_lines {
+                report.print_entry_lines(out, None);
+            }
+        } else {
+            cli.print_checksum(out, hasher, &text.label, &text.label, &hash, Some(&stats));
+        }
+    }
+
+    match &mut report {
+        Some(report) => status.max(cli.print_report(out, report)),
+        None => status,
+    }
+}
+
+/// Record the hash of a file in sidecar and checksum files and extended attributes, as requested
+/// on the command line.
+///
+/// The sidecar is written next to `file_in`, while `name` is the path recorded in the checksum
+/// file. The options of `normalization` are recorded with the hash.
+fn record_hash(
+    cli: &Cli,
+    normalization: &NormalizationArgs,
+    file_in: &Path,
+    name: &Path,
+    hash: &str,
+) -> ExitStatus {
+    if cli.write_sidecar {
+        let suffix = cli.sidecar_suffix(normalization.algorithm.into());
+        if let Err(err) = normalized_hash::store_sidecar(file_in, hash, &suffix) {
+            let sidecar = normalized_hash::sidecar_path(file_in, &suffix);
+            let message = format_args!("cannot write {}: {}", sidecar.display(), err);
+            error::fatal(error::kind(&err), Some(&sidecar), message);
+            return ExitStatus::Io;
+        }
+    }
+
+    if cli.xattr {
+        let options = normalization.cache_options();
+        let algorithm = normalization.algorithm.into();
+        if let Err(err) = xattr::store(file_in, algorithm, &options, hash) {
+            let message = format_args!(
+                "cannot write {} of {}: {}",
+                xattr::attribute(algorithm),
+                file_in.display(),
+                err
+            );
+            error::fatal(error::kind(&err), Some(file_in), message);
+            return ExitStatus::Io;
+        }
+    }
+
+    if let Some(checksum_file) = &cli.append {
+        let checksum_file = Path::new(checksum_file);
+        let header = normalization.header();
+        let name = checksum::listed_path(checksum_file, name);
+        let result = append::append(checksum_file, &name, hash, cli.tag, !cli.no_update, &header);
+
+        if let Err(err) = result {
+            let (kind, status) = match &err {
+                AppendError::Io(err) => (error::kind(err), ExitStatus::Io),
+                AppendError::Conflict { .. } => ("conflict", ExitStatus::Mismatch),
+                AppendError::Options { .. } => ("conflict", ExitStatus::Usage),
+            };
+            let message = format_args!("cannot update {}: {}", checksum_file.display(), err);
+            error::fatal(kind, Some(checksum_file), message);
+            return status;
+        }
+    }
+
+    ExitStatus::Success
+}
+
+/// Warn about a file that mixes line endings, or fail with `--deny-mixed-eol`.
+///
+/// Returns [`ExitStatus::Mismatch`] if the file is denied, otherwise success.
+fn check_mixed_eol(cli: &Cli, file: &Path, stats: &Stats) -> ExitStatus {
+    if !stats.is_mixed_eol() || cli.no_warn_mixed_eol {
+        return ExitStatus::Success;
+    }
+
+    let counts = [("CRLF", stats.crlf), ("LF", stats.lf), ("CR", stats.cr)]
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(style, count)| format!("{} {}", count, style))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let message = format!("{} mixes line endings ({})", file.display(), counts);
+
+    if cli.deny_mixed_eol {
+        error::fatal("mixed-eol", Some(file), message);
+        return ExitStatus::Mismatch;
+    }
+
+    if !cli.quiet {
+        eprintln!("warning: {}", message);
+    }
+
+    ExitStatus::Success
+}
+
+/// Result of a file of a batch, `None` if it is a binary file.
+///
+/// The statistics and timing are missing if the hash was taken from the cache.
+type BatchResult = io::Result<Option<(String, Option<(Stats, Timing)>)>>;
+
+/// Report the results of a batch that are ready, starting with the file at index `next`.
+///
+/// Stops at the first file whose result is still missing, `next` is then its index.
+fn report_ready(
+    ready: &mut [Option<BatchResult>],
+    next: &mut usize,
+    handle: &mut impl FnMut(usize, BatchResult) -> ControlFlow<ExitStatus>,
+) -> ControlFlow<ExitStatus> {
+    while let Some(result) = ready.get_mut(*next).and_then(Option::take) {
+        *next += 1;
+        handle(*next - 1, result)?;
+    }
+
+    ControlFlow::Continue(())
+}
+
+/// Hashers of the given files, selected by the configuration files of their directories.
+type Select<'a> = &'a mut dyn FnMut(&[&PathBuf]) -> Result<Hashers, ExitStatus>;
+
+/// Hashers of a batch, one for each distinct normalization options of its files.
+///
+/// The options of a file depend on the configuration files of its directory.
+struct Hashers {
+    normalizations: Vec<NormalizationArgs>,
+    hashers: Vec<Hasher>,
+    cache_options: Vec<String>,
+
+    /// Index of the options of each file.
+    of_file: Vec<usize>,
+}
+
+impl Hashers {
+    fn new(
+        cli: &Cli,
+        dirs: &mut config::Directories,
+        files: &[impl AsRef<Path>],
+    ) -> Result<Self, ExitStatus> {
+        let mut used = Vec::new();
+        let mut of_file = Vec::with_capacity(files.len());
+        for file in files {
+            let index = dirs.index(file.as_ref())?;
+            let position = used.iter().position(|&used| used == index);
+            of_file.push(position.unwrap_or_else(|| {
+                used.push(index);
+                used.len() - 1
+            }));
+        }
+
+        let normalizations = used
+            .into_iter()
+            .map(|index| dirs.options(index).clone())
+            .collect::<Vec<_>>();
+        Ok(Self {
+            hashers: normalizations.iter().map(|n| cli.hasher(n)).collect(),
+            cache_options: normalizations.iter().map(|n| n.cache_options()).collect(),
+            normalizations,
+            of_file,
+        })
+    }
+
+    /// Hasher of the file at `index`.
+    fn of(&self, index: usize) -> &Hasher {
+        &self.hashers[self.of_file[index]]
+    }
+
+    /// Indices of the files among `indices` that are hashed with the hasher at `options`.
+    fn files_of(&self, options: usize, indices: impl IntoIterator<Item = usize>) -> Vec<usize> {
+        indices
+            .into_iter()
+            .filter(|&index| self.of_file[index] == options)
+            .collect()
+    }
+}
+
+/// Hash a batch of files, printing one checksum line per file.
+///
+/// Each file is printed with its path from `names` and hashed with its hasher of `hashers`. With
+/// `outputs`, the normalized form of each file is written to the path at the same index. Files
+/// that cannot be read are skipped with a warning, unless `--fail-fast` is given. The number of
+/// hashed files is shown in a progress bar.
+fn hash_batch(
+    cli: &Cli,
+    out: &mut Output,
+    hashers: &Hashers,
+    files: &[PathBuf],
+    names: &[PathBuf],
+    outputs: Option<&[PathBuf]>,
+    mut cache: Option<&mut Cache>,
+) -> ExitStatus {
+    let mut report = cli.is_report().then(Report::default);
+    let (mut hashed, mut skipped, mut unreadable) = (0, 0, 0);
+    let mut timings = Vec::new();
+    let mut eol_status = ExitStatus::Success;
+
+    // Names are checked before anything is printed, so that no partial output is left
+    for name in names {
+        let status = cli.check_line_break(name, cli.lists_names());
+        if status != ExitStatus::Success {
+            return status;
+        }
+    }
+
+    // A header cannot record the options of files that are hashed with different ones
+    if hashers.hashers.len() <= 1 {
+        cli.print_header(out);
+    }
+
+    let bar = progress::files(!cli.no_progress && !cli.quiet, files.len());
+    let start = Instant::now();
+    let jobs = cli.jobs.map_or(0, NonZeroUsize::get);
+
+    // Cached results are reported in order between the results of the files that are hashed, or
+    // up front with `--unordered`
+    let mut ready = files
+        .iter()
+        .zip(&hashers.of_file)
+        .map(|(file, &index)| {
+            let options = &hashers.cache_options[index];
+            let hash = cache.as_ref().and_then(|cache| cache.get(file, options))?;
+            Some(Ok(hash.map(|hash| (hash, None))))
+        })
+        .collect::<Vec<_>>();
+    let pending = (0..files.len())
+        .filter(|&index| ready[index].is_none())
+        .collect::<Vec<_>>();
+
+    let mut done = 0;
+    let mut handle = |index: usize, result: BatchResult| {
+        let (file, name) = (&files[index], &names[index]);
+        let file_out = outputs.map(|outputs| outputs[index].as_path());
+        let options = hashers.of_file[index];
+        let hasher = &hashers.hashers[options];
+        done += 1;
+        bar.set_position(done);
+
+        // Entries of JSON lines are printed as soon as they are added
+        let seq = cli.unordered.then_some(index);
+
+        if let (Some(cache), Ok(result)) = (&mut cache, &result) {
+            let hash = result.as_ref().map(|(hash, _)| hash.as_str());
+            if cache.insert(file, &hashers.cache_options[options], hash) && !cli.quiet {
+                let message = format_args!("warning: cached hash of {} was stale", file.display());
+                bar.suspend(|| eprintln!("{}", message));
+            }
+        }
+        // Copies are written separately, since cached files are not read otherwise
+        let result = match (result, file_out) {
+            (Ok(Some(hashed)), Some(file_out)) => {
+                hasher.normalize_file(file, file_out).map(|_| Some(hashed))
+            }
+            (result, _) => result,
+        };
+
+        let (hash, stats, timing) = match result {
+            Ok(Some((hash, Some((stats, timing))))) => {
+                bar.suspend(|| {
+                    verbose::report(file, &stats, cli.verbose);
+                    eol_status = eol_status.max(check_mixed_eol(cli, file, &stats));
+                });
+                (hash, Some(stats), Some(timing))
+            }
+            Ok(Some((hash, None))) => {
+                if cli.verbose > 0 {
+                    bar.suspend(|| {
+                        eprintln!("note: {}: unchanged, using cached hash", file.display())
+                    });
+                }
+                (hash, None, None)
+            }
+            Ok(None) => {
+                if !cli.quiet {
+                    bar.suspend(|| eprintln!("note: skipping binary file {}", file.display()));
+                }
+                if let Some(report) = &mut report {
+                    report.add_skipped();
+                }
+                skipped += 1;
+                return ControlFlow::Continue(());
+            }
+            Err(err) => {
+                if let Some(report) = &mut report {
+                    report.add_error(hasher, name, &err);
+                    if cli.json_lines {
+                        report.print_entry_lines(out, seq);
+                    }
+                }
+
+                if cli.fail_fast {
+                    bar.finish_and_clear();
+                    let message = error::describe(&err, file, file_out);
+                    error::fatal(error::kind(&err), Some(file), message);
+                    if let Some(report) = &mut report {
+                        cli.print_report(out, report);
+                    }
+                    return ControlFlow::Break(ExitStatus::Io);
+                }
+
+                if !cli.quiet {
+                    let message = error::describe(&err, file, file_out);
+                    bar.suspend(|| eprintln!("warning: {}, skipping", message));
+                }
+                unreadable += 1;
+                return ControlFlow::Continue(());
+            }
+        };
+
+        let normalization = &hashers.normalizations[options];
+        let status = bar.suspend(|| record_hash(cli, normalization, file, name, &hash));
+        if status != ExitStatus::Success {
+            bar.finish_and_clear();
+            return ControlFlow::Break(status);
+        }
+
+        hashed += 1;
+        if let Some(report) = &mut report {
+            report.add_hash(hasher, file, name, cli.display_hash(hasher, &hash));
+            if let (Some(timing), true) = (timing, cli.time && cli.is_json()) {
+                report.add_timing(timing);
+            }
+            if cli.json_lines {
+                report.print_entry_lines(out, seq);
+            }
+        } else {
+            cli.print_checksum(out, hasher, file, name, &hash, stats.as_ref());
+        }
+
+        if let Some(timing) = timing {
+            timings.push((name.as_path(), timing));
+        }
+        ControlFlow::Continue(())
+    };
+
+    let hashed_result = |result: io::Result<Option<(String, Stats, Timing)>>| {
+        result.map(|hashed| hashed.map(|(hash, stats, timing)| (hash, Some((stats, timing)))))
+    };
+
+    let groups = (0..hashers.hashers.len())
+        .map(|options| hashers.files_of(options, pending.iter().copied()))
+        .collect::<Vec<_>>();
+
+    let flow = (|| {
+        if cli.unordered {
+            for (index, result) in ready.iter_mut().enumerate() {
+                if let Some(result) = result.take() {
+                    handle(index, result)?;
+                }
+            }
+            for (hasher, group) in hashers.hashers.iter().zip(&groups) {
+                let group_files = group.iter().map(|&index| &files[index]).collect::<Vec<_>>();
+                hasher.hash_many_unordered(&group_files, jobs, |index, result| {
+                    handle(group[index], hashed_result(result))
+                })?;
+            }
+        } else {
+            // Results of files with other options wait until the files before them are reported
+            let mut next = 0;
+            report_ready(&mut ready, &mut next, &mut handle)?;
+            for (hasher, group) in hashers.hashers.iter().zip(&groups) {
+                let group_files = group.iter().map(|&index| &files[index]).collect::<Vec<_>>();
+                hasher.hash_many(&group_files, jobs, |index, result| {
+                    ready[group[index]] = Some(hashed_result(result));
+                    report_ready(&mut ready, &mut next, &mut handle)
+                })?;
+            }
+        }
+        ControlFlow::Continue(())
+    })();
+
+    if let ControlFlow::Break(status) = flow {
+        return status;
+    }
+
+    let elapsed = start.elapsed();
+    bar.finish_and_clear();
+
+    if let Some(report) = &mut report {
+        report.set_elapsed(elapsed);
+        let status = cli.print_report(out, report);
+        if status != ExitStatus::Success {
+            return status;
+        }
+    }
+
+    if cli.time && !cli.is_json() {
+        timing::report(&timings, Some(elapsed));
+    }
+
+    if unreadable > 0 && !cli.quiet {
+        eprintln!(
+            "warning: {} {} could not be read",
+            unreadable,
+            check::plural(unreadable, "file", "files")
+        );
+    }
+
+    if !cli.no_summary && !cli.quiet && !cli.is_json() {
+        let counts = [
+            (hashed, "hashed"),
+            (skipped, "skipped"),
+            (unreadable, "unreadable"),
+        ];
+        eprintln!("{}", summary::line(&counts, elapsed));
+    }
+
+    if unreadable > 0 {
+        return ExitStatus::Incomplete;
+    }
+
+    eol_status
+}
+
+fn run(mut cli: Cli, out: &mut Output) -> ExitStatus {
+    let mut hasher = cli.hasher(&cli.normalization);
+    let mut dirs = config::Directories::new(&cli);
+    let palette = Palette::new(cli.color, out.is_terminal());
+
+    if let Some(command) = cli.command.take() {
+        // Files of subcommands are processed with the configuration files of their directories
+        let format = cli.output_format;
+        let mut select = |files: &[&PathBuf]| Hashers::new(&cli, &mut dirs, files);
+        return match command {
+            Command::Compare(args) => compare::run(&mut select, &args, palette),
+            Command::Diff(args) => diff::run(&mut select, &args),
+            Command::Explain(args) => explain::run(&mut select, &args),
+            Command::Lint(args) => lint::run(&mut select, &args, format),
+            Command::Stats(args) => stats::run(&mut select, out, &args),
+            Command::Dupes(args) => dupes::run(&mut select, out, &args),
+            Command::Normalize(args) => normalize::run(&mut select, &args),
+            Command::Manifest(args) => manifest::run(&hasher, &args, &mut select, format),
+            Command::VerifyDir(args) => verify_dir::run(&mut select, out, &args, palette, format),
+            Command::Completions(args) => completions::run(&args),
+        };
+    }
+
+    if cli.filter {
+        let selection = tty::Selection::Implicit("--filter");
+        if let Err(status) = tty::check(&SystemStdin, selection, cli.no_tty_warning) {
+            return status;
+        }
+        return filter::run(&hasher, cli.hash_file.as_deref(), cli.verbose, &|hash| {
+            cli.display_hash(&hasher, hash)
+        });
+    }
+
+    if cli.tag && matches!(cli.format, HashFormat::Sri | HashFormat::Multihash) {
+        let message = "--tag cannot be combined with --format sri or multihash";
+        error::fatal("usage", None, message);
+        return ExitStatus::Usage;
+    }
+
+    // Expected hashes of any algorithm are decoded, but only those of the selected one can match
+    let algorithm = Algorithm::from(cli.normalization.algorithm);
+    if let Some(expected) = &cli.expected {
+        if expected.len() != algorithm.hex_len() {
+            let message = format_args!(
+                "--expected has {} hex digits, but a {} hash has {}, select the algorithm with --algorithm",
+                expected.len(),
+                algorithm.label(),
+                algorithm.hex_len()
+            );
+            error::fatal("usage", None, message);
+            return ExitStatus::Usage;
+        }
+    }
+
+    if cli.output.is_some() && cli.output.as_deref() == cli.append.as_deref().map(Path::new) {
+        error::fatal(
+            "usage",
+            None,
+            "--output and --append cannot write the same file",
+        );
+        return ExitStatus::Usage;
+    }
+
+    // A single path after FILE_IN is FILE_OUT, unless the flags take multiple inputs anyway
+    let takes_inputs = cli.recursive
+        || cli.output_dir.is_some()
+        || cli.watch
+        || cli.verify_sidecar
+        || cli.verify_xattr;
+    if cli.files.len() == 1 && !takes_inputs {
+        cli.output_file = cli.files.pop();
+    }
+
+    let options = input::InputOptions {
+        recursive: cli.recursive,
+        hidden: !cli.no_hidden,
+        gitignore: !cli.no_ignore,
+        exclude: cli.exclude.clone(),
+        extensions: cli
+            .ext
+            .iter()
+            .cloned()
+            .chain(cli.no_ext.then(OsString::new))
+            .collect(),
+        max_depth: cli.max_depth,
+        follow_symlinks: cli.follow_symlinks,
+        allow_empty_glob: cli.allow_empty_glob,
+        verbose: cli.verbose > 0,
+        quiet: cli.quiet,
+        verb: "hash",
+    };
+
+    let texts = text::inputs(&cli.text, cli.text_join);
+    let inputs = if texts.len() > 1 {
+        return hash_texts(&cli, &hasher, out, &texts);
+    } else if let Some(text) = texts.first() {
+        Ok(Inputs::Single(text.label.clone()))
+    } else if cli.from_clipboard {
+        Ok(Inputs::Single(PathBuf::from("<clipboard>")))
+    } else if let Some(list) = &cli.files_from {
+        if list == "-" {
+            let selection = tty::Selection::Explicit;
+            if let Err(status) = tty::check(&SystemStdin, selection, cli.no_tty_warning) {
+                return status;
+            }
+        }
+        input::collect_list(Path::new(list), cli.null, &options)
+    } else if !cli.files.is_empty() {
+        // Guaranteed by clap, since FILE_IN precedes the other paths
+        let file_in = cli.file_in.as_deref().unwrap();
+        let files: Vec<_> = iter::once(file_in)
+            .chain(cli.files.iter().map(OsString::as_os_str))
+            .map(Path::new)
+            .collect();
+        if files.contains(&Path::new("-")) {
+            let message = "standard input cannot be hashed together with other files";
+            error::fatal("usage", None, message);
+            return ExitStatus::Usage;
+        }
+        input::collect_all(&files, &options)
+    } else {
+        // Guaranteed by clap, since the argument is required without a subcommand or a list
+        let file_in = Path::new(cli.file_in.as_deref().unwrap());
+
+        if cli.detect {
+            return detect::run(&hasher, out, file_in, cli.json);
+        }
+
+        if cli.check {
+            let options = check::CheckOptions {
+                override_options: cli.override_options,
+                relative_to_current_dir: cli.relative_to_current_dir,
+                summary: !cli.no_summary,
+                retry: cli.retry.clone(),
+                quiet: cli.quiet,
+                status: cli.status,
+                no_filename: cli.no_filename,
+                filename_only: cli.filename_only,
+                palette,
+                annotations: Annotations::new(cli.output_format, false),
+            };
+            if file_in == Path::new("-") {
+                let selection = tty::Selection::Explicit;
+                if let Err(status) = tty::check(&SystemStdin, selection, cli.no_tty_warning) {
+                    return status;
+                }
+            }
+            return check::run(&mut dirs, out, file_in, &options);
+        }
+
+        if cli.stdin_name.is_some() && file_in != Path::new("-") {
+            let message = "--stdin-name can only be used if FILE_IN is standard input";
+            error::fatal("usage", None, message);
+            return ExitStatus::Usage;
+        }
+
+        input::collect(file_in, &options)
+    };
+
+    let inputs = match inputs {
+        Ok(Inputs::Batch(files)) if !cli.no_dedup => {
+            Ok(Inputs::Batch(input::dedup(files, options.verbose)))
+        }
+        inputs => inputs,
+    };
+
+    let file_in = match inputs {
+        Ok(Inputs::Single(file_in)) => file_in,
+        Ok(Inputs::Batch(files)) if cli.watch => {
+            // Watched files keep the options of the current directory
+            return match printed_paths(&cli, &files) {
+                Ok(names) => watch::run(&hasher, out, &files, &names, cli.quiet, &|hash| {
+                    cli.display_hash(&hasher, hash)
+                }),
+                Err(status) => status,
+            };
+        }
+        Ok(Inputs::Batch(files)) => {
+            let hashers = match Hashers::new(&cli, &mut dirs, &files) {
+                Ok(hashers) => hashers,
+                Err(status) => return status,
+            };
+            if let [normalization] = hashers.normalizations.as_slice() {
+                cli.normalization = normalization.clone();
+            }
+
+            if cli.verify_sidecar {
+                return files
+                    .iter()
+                    .zip(&hashers.of_file)
+                    .map(|(file, &options)| {
+                        let hasher = &hashers.hashers[options];
+                        let suffix = cli.sidecar_suffix(hasher.digest_algorithm());
+                        verify_sidecar(hasher, out, palette, file, &suffix)
+                    })
+                    .max()
+                    .unwrap_or(ExitStatus::Success);
+            }
+
+            if cli.verify_xattr {
+                return files
+                    .iter()
+                    .zip(&hashers.of_file)
+                    .map(|(file, &options)| {
+                        let (hasher, options) =
+                            (&hashers.hashers[options], &hashers.cache_options[options]);
+                        xattr::print_verify(hasher, out, palette, file, options)
+                    })
+                    .max()
+                    .unwrap_or(ExitStatus::Success);
+            }
+
+            if cli.copy {
+                eprintln!("error: --copy cannot be used with multiple files");
+                return ExitStatus::Usage;
+            }
+
+            if cli.wants_qr() {
+                error::fatal("usage", None, "--qr cannot be used with multiple files");
+                return ExitStatus::Usage;
+            }
+
+            if cli.output_file.is_some() || cli.expected.is_some() {
+                let message = "FILE_OUT and --expected cannot be used with multiple files";
+                error::fatal("usage", None, message);
+                return ExitStatus::Usage;
+            }
+
+            if cli.git.is_some() {
+                error::fatal("usage", None, "--git cannot be used with multiple files");
+                return ExitStatus::Usage;
+            }
+
+            if cli.doc {
+                error::fatal("usage", None, "--doc cannot be used with multiple files");
+                return ExitStatus::Usage;
+            }
+
+            let names = match printed_paths(&cli, &files) {
+                Ok(names) => names,
+                Err(status) => return status,
+            };
+
+            let mut cache = cli.cache.as_deref().map(|path| {
+                let sample = cli.cache_verify_sample.unwrap_or(0.0);
+                Cache::load(path, !cli.no_cache_read, sample, cli.quiet)
+            });
+
+            let outputs = match &cli.output_dir {
+                Some(dir) => {
+                    let base = cli.relative_to.as_deref();
+                    let inputs = cli.file_in.iter().chain(&cli.files).map(Path::new);
+                    match outdir::outputs(dir, base, &files, inputs) {
+                        Ok(outputs) => Some(outputs),
+                        Err(status) => return status,
+                    }
+                }
+                None => None,
+            };
+
+            let status = hash_batch(
+                &cli,
+                out,
+                &hashers,
+                &files,
+                &names,
+                outputs.as_deref(),
+                cache.as_mut(),
+            );
+
+            if let Some(Err(err)) = cache.map(Cache::save) {
+                if !cli.quiet {
+                    // Guaranteed by clap, since a cache only exists with the argument
+                    let path = cli.cache.as_deref().unwrap();
+                    eprintln!(
+                        "warning: cannot write cache {}: {}",
+                        path.display(),
+                        error::message(&err)
+                    );
+                }
+            }
+
+            return status;
+        }
+        Err(status) => return status,
+    };
+
+    // A file is hashed with the configuration files of its directory
+    if texts.is_empty() && !cli.from_clipboard && file_in != Path::new("-") {
+        match dirs.index(&file_in) {
+            Ok(index) => cli.normalization = dirs.options(index).clone(),
+            Err(status) => return status,
+        }
+        hasher = cli.hasher(&cli.normalization);
+    }
+
+    if cli.watch {
+        if file_in == Path::new("-") {
+            error::fatal("usage", None, "--watch cannot watch standard input");
+            return ExitStatus::Usage;
+        }
+
+        return match printed_paths(&cli, std::slice::from_ref(&file_in)) {
+            Ok(names) => watch::run(&hasher, out, &[file_in], &names, cli.quiet, &|hash| {
+                cli.display_hash(&hasher, hash)
+            }),
+            Err(status) => status,
+        };
+    }
+
+    if cli.verify_sidecar {
+        let suffix = cli.sidecar_suffix(hasher.digest_algorithm());
+        return verify_sidecar(&hasher, out, palette, &file_in, &suffix);
+    }
+
+    if cli.verify_xattr {
+        let options = cli.normalization.cache_options();
+        return xattr::print_verify(&hasher, out, palette, &file_in, &options);
+    }
+
+    if file_in == Path::new("-") {
+        let flag = [
+            ("--write-sidecar", cli.write_sidecar),
+            ("--xattr", cli.xattr),
+            ("--verify-xattr", cli.verify_xattr),
+        ]
+        .into_iter()
+        .find_map(|(flag, given)| given.then_some(flag));
+        if let Some(flag) = flag {
+            let message = format_args!("{} cannot be used with standard input", flag);
+            error::fatal("usage", None, message);
+            return ExitStatus::Usage;
+        }
+    }
+
+    if file_in == Path::new("-") {
+        let flag = [
+            ("--self-check", cli.self_check),
+            ("--sync", cli.sync),
+            ("--git", cli.git.is_some()),
+            ("--doc", cli.doc),
+        ]
+        .into_iter()
+        .find_map(|(flag, given)| given.then_some(flag));
+        if let Some(flag) = flag {
+            let message = format_args!("{} cannot be used with standard input", flag);
+            error::fatal("usage", None, message);
+            return ExitStatus::Usage;
+        }
+    }
+
+    let name = match (
+        &cli.stdin_name,
+        printed_paths(&cli, std::slice::from_ref(&file_in)),
+    ) {
+        (Some(label), _) => label.clone(),
+        (None, Ok(mut names)) => names.remove(0),
+        (None, Err(status)) => return status,
+    };
+
+    let status = cli.check_line_break(&name, cli.tag || cli.with_filename);
+    if status != ExitStatus::Success {
+        return status;
+    }
+
+    let file_out = match &cli.output_dir {
+        Some(_) if file_in == Path::new("-") => {
+            error::fatal(
+                "usage",
+                None,
+                "--output-dir cannot be used with standard input",
+            );
+            return ExitStatus::Usage;
+        }
+        Some(dir) => {
+            let base = cli.relative_to.as_deref();
+            let files = std::slice::from_ref(&file_in);
+            let file_out = match outdir::outputs(dir, base, files, None) {
+                Ok(mut outputs) => outputs.remove(0),
+                Err(status) => return status,
+            };
+            if let Some(parent) = file_out.parent() {
+                if let Err(err) = fs::create_dir_all(normalized_hash::long_path(parent)) {
+                    let message = format_args!("cannot create {}: {}", parent.display(), err);
+                    error::fatal(error::kind(&err), Some(parent), message);
+                    return ExitStatus::Io;
+                }
+            }
+            Some(file_out)
+        }
+        None => {
+            let file_out = cli.output_file.as_deref().map(PathBuf::from);
+            if let Some(path) = &file_out {
+                if let Err(status) = prompt::confirm_overwrite(&mut Terminal, path, cli.force) {
+                    return status;
+                }
+            }
+            file_out
+        }
+    };
+
+    // Content that is not read from FILE_IN
+    let content = if let Some(rev) = &cli.git {
+        match git::read_blob(&file_in, rev) {
+            Ok(blob) => Some(blob.content),
+            Err(err) => {
+                let message = format_args!("cannot read {} at {}: {}", file_in.display(), rev, err);
+                error::fatal(error::kind(&err), Some(&file_in), message);
+                return ExitStatus::Io;
+            }
+        }
+    } else if cli.doc {
+        match document::extract(&file_in) {
+            Ok(text) => Some(text.into_bytes()),
+            Err(err) => {
+                let message = format_args!("cannot read {}: {}", file_in.display(), err);
+                error::fatal(error::kind(&err), Some(&file_in), message);
+                return ExitStatus::Io;
+            }
+        }
+    } else if let Some(text) = texts.first() {
+        Some(text.content.clone().into_bytes())
+    } else if cli.from_clipboard {
+        match clipboard::paste(SystemClipboard::new()) {
+            Ok(text) => Some(text.into_bytes()),
+            Err(status) => return status,
+        }
+    } else {
+        None
+    };
+
+    if content.is_none() && file_in == Path::new("-") {
+        let selection = tty::Selection::Explicit;
+        if let Err(status) = tty::check(&SystemStdin, selection, cli.no_tty_warning) {
+            return status;
+        }
+    }
+
+    let len = match &content {
+        Some(content) => Some(content.len() as u64),
+        None => fs::metadata(normalized_hash::long_path(&file_in))
+            .ok()
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.len()),
+    };
+    let bar = progress::bytes(!cli.no_progress && !cli.quiet, len);
+
+    let progress_hasher = cli.hasher(&cli.normalization).progress({
+        let bar = bar.clone();
+        move |bytes| bar.inc(bytes)
+    });
+    let result = if let Some(content) = &content {
+        hash_content(&progress_hasher, content)
+    } else if file_in == Path::new("-") {
+        // Created up front, since errors of the library are only tagged while hashing
+        let file = match file_out
+            .as_deref()
+            .map(|path| fs::File::create(normalized_hash::long_path(path)))
+            .transpose()
+        {
+            Ok(file) => file,
+            Err(err) => {
+                bar.finish_and_clear();
+                // The file can only fail to be created if it is given
+                let path = file_out.as_deref().unwrap();
+                let message = format_args!("cannot write '{}': {}", path.display(), err);
+                error::fatal("create", Some(path), message);
+                return ExitStatus::Io;
+            }
+        };
+        hash_stdin(&progress_hasher, file)
+    } else {
+        progress_hasher.try_hash_file_timed(&file_in, file_out.as_deref())
+    };
+
+    bar.finish_and_clear();
+
+    let (hash, stats, timing, eol_status) = match result {
+        Ok((hash, stats, timing)) => {
+            verbose::report(&file_in, &stats, cli.verbose);
+            let eol_status = check_mixed_eol(&cli, &file_in, &stats);
+            (hash, stats, timing, eol_status)
+        }
+        Err(err) => {
+            let message = error::describe(&err, &file_in, file_out.as_deref());
+            error::fatal(error::kind(&err), Some(&file_in), message);
+            if cli.is_report() {
+                let mut report = Report::default();
+                report.add_error(&hasher, &name, &err);
+                cli.print_report(out, &mut report);
+            }
+            return ExitStatus::Io;
+        }
+    };
+
+    let status = record_hash(&cli, &cli.normalization, &file_in, &name, &hash);
+    if status != ExitStatus::Success {
+        return status;
+    }
+
+    let Some(expected) = &cli.expected else {
+        let display_hash = cli.display_hash(&hasher, &hash);
+        let printed = match &cli.template {
+            Some(template) => {
+                template.render(&hasher, &file_in, &name, &display_hash, Some(&stats))
+            }
+            None => display_hash,
+        };
+
+        if cli.is_report() {
+            let mut report = Report::default();
+            report.add_hash(&hasher, &file_in, &name, cli.display_hash(&hasher, &hash));
+            if cli.time && cli.is_json() {
+                report.add_timing(timing);
+            }
+            let status = cli.print_report(out, &mut report);
+            if cli.time && !cli.is_json() {
+                timing::report(&[(&name, timing)], None);
+            }
+            return status.max(eol_status);
+        } else if cli.filename_only {
+            cli.print_record(out, &normalized_hash::escape_path(&name));
+        } else if cli.tag {
+            cli.print_header(out);
+            let listed = cli.listed_path(&name);
+            let line = checksum::format_bsd(
+                hasher.digest_algorithm(),
+                &listed,
+                &cli.display_hash(&hasher, &hash),
+            );
+            cli.print_record(out, &line);
+        } else if cli.with_filename {
+            cli.print_header(out);
+            let line =
+                checksum::format_gnu(&cli.listed_path(&name), &cli.display_hash(&hasher, &hash));
+            cli.print_record(out, &line);
+        } else {
+            cli.print_record(out, &printed);
+        }
+
+        if cli.time {
+            timing::report(&[(&name, timing)], None);
+        }
+
+        if cli.wants_qr() {
+            let payload = qr::payload(
+                &cli.normalization.header(),
+                &cli.display_hash(&hasher, &hash),
+            );
+            let status = qr::show(out, &payload, cli.qr_level, cli.qr, cli.qr_out.as_deref());
+            if status != ExitStatus::Success {
+                return status;
+            }
+        }
+
+        if cli.copy {
+            let status = clipboard::copy(SystemClipboard::new(), &printed);
+            return status.max(eol_status);
+        }
+
+        return eol_status;
+    };
+
+    let matches = &hash == expected;
+
+    if matches {
+        out.line(palette.ok("OK"));
+    } else {
+        out.line(palette.failed("MISMATCH"));
+    }
+    if !cli.quiet {
+        let details = [
+            format!("expected: {}", cli.display_hash(&hasher, expected)),
+            format!("actual:   {}", cli.display_hash(&hasher, &hash)),
+        ];
+        // The details of a mismatch are diagnostics, like the reasons of other failures
+        for line in details {
+            if matches {
+                out.line(line);
+            } else {
+                eprintln!("{}", line);
+            }
+        }
+    }
+
+    if cli.time {
+        timing::report(&[(&name, timing)], None);
+    }
+
+    if matches {
+        eol_status
+    } else {
+        ExitStatus::Mismatch
+    }
+}
+
+/// Print panics like other errors, without the hint to enable backtraces.
+///
+/// With `RUST_BACKTRACE` set, the default output is kept for debugging.
+fn set_panic_hook() {
+    if std::env::var_os("RUST_BACKTRACE").is_some() {
+        return;
+    }
+
+    std::panic::set_hook(Box::new(|info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown cause");
+        eprintln!("error: internal error: {}", message);
+    }));
+}
+
+/// Parse the command line, with the settings of the configuration files as defaults.
+///
+/// The command line is parsed once without configuration files to find `--config` and
+/// `--no-config`, and again with the loaded settings, if there are any.
+fn parse() -> Result<(Cli, ArgMatches, Vec<config::Layer>), ExitStatus> {
+    let (mut cli, matches) = parse_with(&[])?;
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let layers = config::load(&cwd, cli.config.as_deref(), cli.no_config)?;
+    if layers.is_empty() {
+        cli.normalization.given = config::given(&matches, &layers);
+        return Ok((cli, matches, layers));
+    }
+
+    let (mut cli, matches) = parse_with(&layers)?;
+    cli.normalization.given = config::given(&matches, &layers);
+    Ok((cli, matches, layers))
+}
+
+/// Parse the command line, with the settings of `layers` as defaults.
+fn parse_with(layers: &[config::Layer]) -> Result<(Cli, ArgMatches), ExitStatus> {
+    config::apply(environment::apply(Cli::command()), layers)
+        .try_get_matches()
+        .and_then(|matches| Ok((Cli::from_arg_matches(&matches)?, matches)))
+        .map_err(|err| {
+            let _ = err.print();
+            // Only `--help` and `--version` exit with code 0
+            if err.exit_code() == 0 {
+                ExitStatus::Success
+            } else {
+                ExitStatus::Usage
+            }
+        })
+}
+
+fn main() -> ExitCode {
+    set_panic_hook();
+
+    if interactive::is_wanted() {
+        return interactive::run().into();
+    }
+
+    let (cli, matches, layers) = match parse() {
+        Ok(parsed) => parsed,
+        Err(status) => {
+            pause::pause_if_requested(pause::is_requested());
+            return status.into();
+        }
+    };
+    error::print_json(cli.is_json());
+    let pause = cli.pause;
+
+    let target = cli.output.clone();
+    let mut out = match &target {
+        Some(path) => Output::create(path),
+        None => Output::stdout(),
+    };
+
+    let mut status = if let Some(path) = &cli.show_config {
+        // The settings of another directory are parsed again with its configuration files
+        let parsed = match path {
+            Some(path) => {
+                let dir = config::directory_of(path);
+                config::load(&dir, cli.config.as_deref(), cli.no_config)
+                    .and_then(|layers| Ok((parse_with(&layers)?.1, layers)))
+            }
+            None => Ok((matches, layers)),
+        };
+        match parsed {
+            Ok((matches, layers)) => {
+                config::show(&mut out, &matches, &layers);
+                ExitStatus::Success
+            }
+            Err(status) => status,
+        }
+    } else if cli.self_test {
+        let palette = Palette::new(cli.color, out.is_terminal());
+        self_test::run(&mut out, palette)
+    } else if let Some(path) = &cli.write_config {
+        config::write(path, cli.force, &matches, &layers)
+    } else {
+        run(cli, &mut out)
+    };
+
+    if let Err(err) = out.finish(status < ExitStatus::Io) {
+        let path = target.as_deref().filter(|path| *path != Path::new("-"));
+        let name = match path {
+            Some(path) => format!("'{}'", path.display()),
+            None => "standard output".to_string(),
+        };
+        let message = format_args!("cannot write {}: {}", name, error::message(&err));
+        error::fatal("write", path, message);
+        status = status.max(ExitStatus::Io);
+    }
+
+    pause::pause_if_requested(pause);
+
+    status.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_app() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert()
+    }
+
+    #[test]
+    fn check_parse_expected() {
+        let hash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+        assert_eq!(parse_expected(hash).as_deref(), Ok(hash));
+        assert_eq!(
+            parse_expected(&format!("  SHA256:{}\n", hash.to_uppercase())).as_deref(),
+            Ok(hash)
+        );
+        assert!(parse_expected("sha256:").is_err());
+        assert!(parse_expected(&hash[1..]).is_err());
+        assert!(parse_expected(&hash.replace('e', "x")).is_err());
+
+        let grouped = hash
+            .as_bytes()
+            .chunks(16)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap())
+            .collect::<Vec<_>>()
+            .join("\u{a0}");
+        assert_eq!(
+            parse_expected(&format!("\u{201c}SHA-256: {}\u{201d}", grouped)).as_deref(),
+            Ok(hash)
+        );
+        assert_eq!(
+            parse_expected(&hash[1..]),
+            Err(String::from(
+                "found 63 hex digits, but a hash has 64 or 128"
+            ))
+        );
     }
 }
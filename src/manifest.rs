@@ -0,0 +1,476 @@
+use std::collections::{BTreeMap, HashSet};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Subcommand, ValueHint};
+
+use normalized_hash::{Hasher, Manifest, ManifestEntry};
+
+use crate::annotate::{self, Annotations, OutputFormat};
+use crate::error;
+use crate::exit::{ExitStatus, EXIT_STATUS_HELP};
+use crate::input::{self, InputOptions, Inputs};
+use crate::relative;
+use crate::{Hashers, Select};
+
+#[derive(Args)]
+pub struct ManifestArgs {
+    #[command(subcommand)]
+    command: ManifestCommand,
+}
+
+#[derive(Subcommand)]
+enum ManifestCommand {
+    /// Hash all files of a directory tree and write them into a manifest
+    #[command(after_help = EXIT_STATUS_HELP)]
+    Generate(GenerateArgs),
+
+    /// Check a directory tree against a manifest
+    #[command(after_help = EXIT_STATUS_HELP)]
+    Verify(VerifyArgs),
+
+    /// Hash files again whose size or modification time changed and update the manifest
+    #[command(after_help = EXIT_STATUS_HELP)]
+    Update(UpdateArgs),
+}
+
+/// Filters for walking the directory tree, the same as for hashing with `--recursive`.
+#[derive(Args)]
+pub struct TreeArgs {
+    /// Skip hidden files and directories
+    #[arg(long)]
+    no_hidden: bool,
+
+    /// Don't respect `.gitignore` files
+    #[arg(long)]
+    no_ignore: bool,
+
+    /// Exclude files matching a glob pattern
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Descend at most N directory levels
+    #[arg(long, value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// Follow symbolic links
+    #[arg(long)]
+    follow_symlinks: bool,
+}
+
+#[derive(Args)]
+struct GenerateArgs {
+    /// Root of the directory tree
+    #[arg(value_hint = ValueHint::DirPath)]
+    root: PathBuf,
+
+    /// Write the manifest into MANIFEST instead of stdout
+    ///
+    /// The file is replaced atomically. If it is inside of the tree, it is not recorded itself.
+    #[arg(short, long, value_name = "MANIFEST")]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    tree: TreeArgs,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// Root of the directory tree
+    #[arg(value_hint = ValueHint::DirPath)]
+    root: PathBuf,
+
+    /// Manifest to check the tree against
+    manifest: PathBuf,
+
+    #[command(flatten)]
+    tree: TreeArgs,
+}
+
+#[derive(Args)]
+struct UpdateArgs {
+    /// Root of the directory tree
+    #[arg(value_hint = ValueHint::DirPath)]
+    root: PathBuf,
+
+    /// Manifest to be updated in place
+    manifest: PathBuf,
+
+    #[command(flatten)]
+    tree: TreeArgs,
+}
+
+/// Collect the files of the tree below `root` with their paths in the manifest.
+///
+/// The manifest file itself is skipped if it is inside of the tree.
+pub fn walk(
+    root: &Path,
+    tree: &TreeArgs,
+    manifest: Option<&Path>,
+) -> Result<Vec<(String, PathBuf)>, ExitStatus> {
+    if !root.is_dir() {
+        let message = format_args!("{} is not a directory", root.display());
+        error::fatal("usage", Some(root), message);
+        return Err(ExitStatus::Usage);
+    }
+
+    let options = InputOptions {
+        recursive: true,
+        hidden: !tree.no_hidden,
+        gitignore: !tree.no_ignore,
+        exclude: tree.exclude.clone(),
+        extensions: Vec::new(),
+        max_depth: tree.max_depth,
+        follow_symlinks: tree.follow_symlinks,
+        allow_empty_glob: false,
+        verbose: false,
+        quiet: false,
+        verb: "hash",
+    };
+
+    let files = match input::collect(root, &options)? {
+        Inputs::Single(file) => vec![file],
+        Inputs::Batch(files) => files,
+    };
+    let manifest = manifest.and_then(|manifest| relative::absolute(manifest).ok());
+
+    let mut paths = Vec::with_capacity(files.len());
+    for file in files {
+        if manifest.is_some() && relative::absolute(&file).ok() == manifest {
+            continue;
+        }
+
+        match normalized_hash::manifest_path(root, &file) {
+            Some(path) => paths.push((path, file)),
+            None => eprintln!(
+                "warning: the path of {} cannot be recorded, skipping",
+                file.display()
+            ),
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Read a manifest created with the same normalization options as `hasher`.
+fn load(hasher: &Hasher, path: &Path) -> Result<Manifest, ExitStatus> {
+    let manifest = Manifest::load(path).map_err(|err| {
+        let message = format_args!("cannot read {}: {}", path.display(), error::message(&err));
+        if err.kind() == std::io::ErrorKind::InvalidData {
+            error::fatal("malformed", Some(path), message);
+            ExitStatus::Malformed
+        } else {
+            error::fatal(error::kind(&err), Some(path), message);
+            ExitStatus::Io
+        }
+    })?;
+
+    if !manifest.is_compatible(hasher) {
+        let message = format_args!(
+            "{} was generated with other normalization options ({}), pass the same flags",
+            path.display(),
+            manifest.options()
+        );
+        error::fatal("usage", Some(path), message);
+        return Err(ExitStatus::Usage);
+    }
+
+    Ok(manifest)
+}
+
+/// Hash the files, calling `each` with the index and the hash, `None` for binary files.
+///
+/// Each file is hashed with the options of its directory. Files that cannot be hashed are
+/// reported with a warning. Returns the number of such files.
+fn hash(
+    hashers: &Hashers,
+    files: &[&PathBuf],
+    mut each: impl FnMut(usize, Option<String>),
+) -> usize {
+    let mut unreadable = 0;
+
+    for (options, hasher) in hashers.hashers.iter().enumerate() {
+        let group = hashers.files_of(options, 0..files.len());
+        let group_files = group.iter().map(|&index| files[index]).collect::<Vec<_>>();
+
+        let _ = hasher.hash_many(&group_files, 0, |index, result| {
+            match result {
+                Ok(hashed) => each(group[index], hashed.map(|(hash, _, _)| hash)),
+                Err(err) => {
+                    let message = error::describe(&err, group_files[index], None);
+                    eprintln!("warning: {}, skipping", message);
+                    unreadable += 1;
+                }
+            }
+            ControlFlow::<()>::Continue(())
+        });
+    }
+
+    unreadable
+}
+
+/// Create a manifest of the tree.
+fn generate(hasher: &Hasher, args: &GenerateArgs, select: Select) -> ExitStatus {
+    let paths = match walk(&args.root, &args.tree, args.output.as_deref()) {
+        Ok(paths) => paths,
+        Err(status) => return status,
+    };
+    let files = paths.iter().map(|(_, file)| file).collect::<Vec<_>>();
+    let hashers = match select(&files) {
+        Ok(hashers) => hashers,
+        Err(status) => return status,
+    };
+
+    let mut manifest = Manifest::new(hasher);
+    let mut failed = 0;
+    let unreadable = hash(&hashers, &files, |index, hash| {
+        let (path, file) = &paths[index];
+        let Some(hash) = hash else {
+            eprintln!("note: skipping binary file {}", file.display());
+            return;
+        };
+        match ManifestEntry::new(file, hash) {
+            Ok(entry) => manifest.insert(path.as_str(), entry),
+            Err(err) => {
+                let message = error::describe(&err, file, None);
+                eprintln!("warning: {}, skipping", message);
+                failed += 1;
+            }
+        }
+    });
+
+    match &args.output {
+        Some(output) => {
+            if let Err(err) = manifest.save(output) {
+                let message = error::describe(&err, output, Some(output));
+                error::fatal(error::kind(&err), Some(output), message);
+                return ExitStatus::Io;
+            }
+        }
+        None => print!("{}", manifest),
+    }
+
+    if unreadable + failed > 0 {
+        ExitStatus::Incomplete
+    } else {
+        ExitStatus::Success
+    }
+}
+
+/// Result of a file when verifying a tree against a manifest.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Verified {
+    Ok,
+    Changed,
+    Missing,
+    Extra,
+    Unreadable,
+}
+
+impl Verified {
+    fn label(self) -> &'static str {
+        match self {
+            Verified::Ok => "OK",
+            Verified::Changed => "CHANGED",
+            Verified::Missing => "MISSING",
+            Verified::Extra => "EXTRA",
+            Verified::Unreadable => "FAILED open or read",
+        }
+    }
+
+    /// Message of the annotation of a file that failed verification.
+    fn problem(self) -> Option<&'static str> {
+        match self {
+            Verified::Ok => None,
+            Verified::Changed => Some("file changed since the manifest was generated"),
+            Verified::Missing => Some("file is listed in the manifest, but missing"),
+            Verified::Extra => Some("file is not listed in the manifest"),
+            Verified::Unreadable => Some("file could not be read"),
+        }
+    }
+}
+
+/// Check the tree against a manifest and print the result of each file.
+fn verify(hasher: &Hasher, args: &VerifyArgs, select: Select, format: OutputFormat) -> ExitStatus {
+    let annotations = Annotations::new(format, false);
+    let manifest = match load(hasher, &args.manifest) {
+        Ok(manifest) => manifest,
+        Err(status) => return status,
+    };
+    let paths = match walk(&args.root, &args.tree, Some(&args.manifest)) {
+        Ok(paths) => paths,
+        Err(status) => return status,
+    };
+    let files = paths.iter().map(|(_, file)| file).collect::<Vec<_>>();
+    let hashers = match select(&files) {
+        Ok(hashers) => hashers,
+        Err(status) => return status,
+    };
+
+    // Files that cannot be hashed stay unreadable
+    let mut results = paths
+        .iter()
+        .map(|(path, _)| (path.as_str(), Verified::Unreadable))
+        .collect::<BTreeMap<_, _>>();
+
+    hash(&hashers, &files, |index, hash| {
+        let path = paths[index].0.as_str();
+        let result = match (manifest.get(path), hash) {
+            (Some(entry), Some(hash)) if entry.hash == hash => Verified::Ok,
+            (Some(_), _) => Verified::Changed,
+            (None, Some(_)) => Verified::Extra,
+            (None, None) => {
+                results.remove(path);
+                return;
+            }
+        };
+        results.insert(path, result);
+    });
+
+    for (path, _) in manifest.entries() {
+        results.entry(path).or_insert(Verified::Missing);
+    }
+
+    for (path, result) in &results {
+        println!("{}: {}", path, result.label());
+        if let Some(problem) = result.problem() {
+            annotations.error(annotate::path(&args.root.join(path)), None, problem);
+        }
+    }
+    let count = |wanted| results.values().filter(|result| **result == wanted).count();
+
+    let mut summary = format!(
+        "{} OK, {} changed, {} missing, {} extra",
+        count(Verified::Ok),
+        count(Verified::Changed),
+        count(Verified::Missing),
+        count(Verified::Extra)
+    );
+    if count(Verified::Unreadable) > 0 {
+        summary.push_str(&format!(", {} unreadable", count(Verified::Unreadable)));
+    }
+    println!("{}", summary);
+
+    if count(Verified::Unreadable) > 0 {
+        ExitStatus::Io
+    } else if count(Verified::Ok) < results.len() {
+        ExitStatus::Mismatch
+    } else {
+        ExitStatus::Success
+    }
+}
+
+/// Hash files whose metadata changed, add new files and remove missing ones.
+fn update(hasher: &Hasher, args: &UpdateArgs, select: Select) -> ExitStatus {
+    let mut manifest = match load(hasher, &args.manifest) {
+        Ok(manifest) => manifest,
+        Err(status) => return status,
+    };
+    let paths = match walk(&args.root, &args.tree, Some(&args.manifest)) {
+        Ok(paths) => paths,
+        Err(status) => return status,
+    };
+
+    let walked = paths
+        .iter()
+        .map(|(path, _)| path.as_str())
+        .collect::<HashSet<_>>();
+    let removed = manifest
+        .entries()
+        .map(|(path, _)| path.to_string())
+        .filter(|path| !walked.contains(path.as_str()))
+        .collect::<Vec<_>>();
+
+    // Files with unchanged size and modification time are not read at all
+    let (stale, current): (Vec<_>, Vec<_>) = paths.iter().partition(|(path, file)| {
+        manifest
+            .get(path)
+            .is_none_or(|entry| !entry.is_current(file))
+    });
+    let files = stale.iter().map(|(_, file)| file).collect::<Vec<_>>();
+    let hashers = match select(&files) {
+        Ok(hashers) => hashers,
+        Err(status) => return status,
+    };
+
+    let mut changes = BTreeMap::new();
+    let mut unchanged = current.len();
+    let mut failed = 0;
+
+    for path in removed {
+        manifest.remove(&path);
+        changes.insert(path, "removed");
+    }
+
+    let unreadable = hash(&hashers, &files, |index, hash| {
+        let (path, file) = stale[index];
+        let Some(hash) = hash else {
+            if manifest.remove(path).is_some() {
+                changes.insert(path.clone(), "removed");
+            }
+            return;
+        };
+
+        let entry = match ManifestEntry::new(file, hash) {
+            Ok(entry) => entry,
+            Err(err) => {
+                let message = error::describe(&err, file, None);
+                eprintln!("warning: {}, skipping", message);
+                failed += 1;
+                return;
+            }
+        };
+
+        match manifest.get(path) {
+            None => {
+                changes.insert(path.clone(), "added");
+            }
+            Some(old) if old.hash != entry.hash => {
+                changes.insert(path.clone(), "updated");
+            }
+            Some(_) => unchanged += 1,
+        }
+        manifest.insert(path.as_str(), entry);
+    });
+
+    if let Err(err) = manifest.save(&args.manifest) {
+        let message = error::describe(&err, &args.manifest, Some(&args.manifest));
+        error::fatal(error::kind(&err), Some(&args.manifest), message);
+        return ExitStatus::Io;
+    }
+
+    for (path, change) in &changes {
+        println!("{}: {}", path, change);
+    }
+    let count = |label| changes.values().filter(|change| **change == label).count();
+    println!(
+        "{} updated, {} added, {} removed, {} unchanged",
+        count("updated"),
+        count("added"),
+        count("removed"),
+        unchanged
+    );
+
+    if unreadable + failed > 0 {
+        ExitStatus::Incomplete
+    } else {
+        ExitStatus::Success
+    }
+}
+
+/// Generate, verify or update a manifest of a directory tree.
+///
+/// The manifest records the options of `hasher`, while each file is hashed with the hasher that
+/// `select` gives for it.
+pub fn run(
+    hasher: &Hasher,
+    args: &ManifestArgs,
+    select: Select,
+    format: OutputFormat,
+) -> ExitStatus {
+    match &args.command {
+        ManifestCommand::Generate(args) => generate(hasher, args, select),
+        ManifestCommand::Verify(args) => verify(hasher, args, select, format),
+        ManifestCommand::Update(args) => update(hasher, args, select),
+    }
+}
@@ -0,0 +1,345 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::{ArgGroup, Args, ValueHint};
+
+use normalized_hash::Hasher;
+
+use crate::error;
+use crate::exit::ExitStatus;
+use crate::input::{self, InputOptions, Inputs};
+use crate::outdir;
+use crate::relative;
+use crate::Select;
+
+#[derive(Args)]
+#[command(group(
+    ArgGroup::new("mode")
+        .args(["in_place", "output_dir", "dry_run", "mirror"])
+        .required(true)
+        .multiple(true)
+))]
+#[command(group(ArgGroup::new("tree").args(["recursive", "mirror"])))]
+pub struct NormalizeArgs {
+    /// Files to be normalized, directories with `--recursive`
+    #[arg(
+        required_unless_present = "mirror",
+        conflicts_with = "mirror",
+        value_hint = ValueHint::AnyPath
+    )]
+    files: Vec<OsString>,
+
+    /// Normalize all files in the given directories and their subdirectories
+    ///
+    /// Binary files are skipped with a note on stderr. The same rules apply as for hashing with
+    /// `--recursive`.
+    #[arg(short, long)]
+    recursive: bool,
+
+    /// Skip hidden files and directories with `--recursive` or `--mirror`
+    #[arg(long, requires = "tree")]
+    no_hidden: bool,
+
+    /// Don't respect `.gitignore` files with `--recursive` or `--mirror`
+    #[arg(long, requires = "tree")]
+    no_ignore: bool,
+
+    /// Exclude files matching a glob pattern
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Descend at most N directory levels with `--recursive` or `--mirror`
+    #[arg(long, value_name = "N", requires = "tree")]
+    max_depth: Option<usize>,
+
+    /// Follow symbolic links with `--recursive` or `--mirror`
+    #[arg(long, requires = "tree")]
+    follow_symlinks: bool,
+
+    /// Rewrite the files in place
+    ///
+    /// Each file that is not in normalized form is atomically replaced by its normalized form.
+    /// Files that are already normalized are not touched.
+    #[arg(long, conflicts_with = "output_dir")]
+    in_place: bool,
+
+    /// Write the normalized files to DIR instead
+    ///
+    /// Each file keeps its path relative to the common ancestor of the inputs, so with
+    /// `--recursive src`, the file `src/a/b.txt` is written to `DIR/a/b.txt`, creating
+    /// subdirectories as needed. Outputs that would overwrite any input are refused before
+    /// anything is written.
+    #[arg(long, value_name = "DIR", value_hint = ValueHint::DirPath)]
+    output_dir: Option<PathBuf>,
+
+    /// Only list the files that would change, without writing anything
+    ///
+    /// Exits with code 1 if any file would change.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Keep a copy of each rewritten file with `--in-place`
+    #[arg(long, requires = "in_place")]
+    backup: bool,
+
+    /// Reproduce the directory tree SRC in DST with every text file normalized
+    ///
+    /// Binary files are copied verbatim. A file in DST is only written if its content differs,
+    /// so repeated runs only read both trees. Nothing in DST is ever deleted. Each written file
+    /// is printed, followed by a summary.
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["SRC", "DST"],
+        value_hint = ValueHint::DirPath,
+        conflicts_with_all = ["in_place", "output_dir", "dry_run", "recursive"]
+    )]
+    mirror: Option<Vec<PathBuf>>,
+
+    /// Skip binary files with `--mirror` instead of copying them
+    #[arg(long, requires = "mirror")]
+    skip_binary: bool,
+
+    /// File name suffix of the copies with `--backup`
+    #[arg(
+        long,
+        value_name = "SUFFIX",
+        default_value = ".orig",
+        requires = "backup"
+    )]
+    backup_suffix: OsString,
+}
+
+/// Options for collecting the files to be normalized.
+fn input_options(args: &NormalizeArgs) -> InputOptions {
+    InputOptions {
+        recursive: args.recursive || args.mirror.is_some(),
+        hidden: !args.no_hidden,
+        gitignore: !args.no_ignore,
+        exclude: args.exclude.clone(),
+        extensions: Vec::new(),
+        max_depth: args.max_depth,
+        follow_symlinks: args.follow_symlinks,
+        allow_empty_glob: false,
+        verbose: false,
+        quiet: false,
+        verb: "normalize",
+    }
+}
+
+/// Collect the files to be normalized.
+fn collect(args: &NormalizeArgs) -> Result<Vec<PathBuf>, ExitStatus> {
+    let options = input_options(args);
+
+    let mut files = Vec::new();
+    for file in &args.files {
+        match input::collect(Path::new(file), &options)? {
+            Inputs::Single(file) => files.push(file),
+            Inputs::Batch(batch) => files.extend(batch),
+        }
+    }
+
+    Ok(files)
+}
+
+/// Path of the backup copy of `file`, with `suffix` appended to its name.
+fn backup_path(file: &Path, suffix: &OsString) -> PathBuf {
+    let mut path = file.as_os_str().to_os_string();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Rewrite a file in place if it is not normalized, returning whether it changed.
+fn normalize_in_place(hasher: &Hasher, file: &Path, args: &NormalizeArgs) -> io::Result<bool> {
+    if hasher.is_normalized(file)? {
+        return Ok(false);
+    }
+
+    if args.backup {
+        fs::copy(file, backup_path(file, &args.backup_suffix))?;
+    }
+    hasher.normalize_in_place(file)?;
+
+    Ok(true)
+}
+
+/// Outcome of mirroring a file, counted for the summary.
+#[derive(Default)]
+struct MirrorCounts {
+    changed: usize,
+    unchanged: usize,
+    copied: usize,
+    skipped: usize,
+    failed: usize,
+}
+
+/// Normalize the tree `src` into `dst`, only writing files whose content would change.
+fn mirror(select: Select, args: &NormalizeArgs, src: &Path, dst: &Path) -> ExitStatus {
+    if !src.is_dir() {
+        let message = format_args!("{} is not a directory", src.display());
+        error::fatal("usage", Some(src), message);
+        return ExitStatus::Usage;
+    }
+
+    // Otherwise the next run would mirror the destination into itself
+    match (relative::absolute(src), relative::absolute(dst)) {
+        (Ok(src_path), Ok(dst_path)) if dst_path.starts_with(&src_path) => {
+            let message = format_args!("{} is inside of {}", dst.display(), src.display());
+            error::fatal("usage", Some(dst), message);
+            return ExitStatus::Usage;
+        }
+        (Err(err), _) | (_, Err(err)) => {
+            let message = format_args!("cannot resolve output paths: {}", err);
+            error::fatal(error::kind(&err), None, message);
+            return ExitStatus::Io;
+        }
+        _ => {}
+    }
+
+    let files = match input::collect(src, &input_options(args)) {
+        Ok(Inputs::Batch(files)) => files,
+        Ok(Inputs::Single(file)) => vec![file],
+        Err(status) => return status,
+    };
+    let outputs = match outdir::outputs(dst, Some(src), &files, None) {
+        Ok(outputs) => outputs,
+        Err(status) => return status,
+    };
+    let hashers = match select(&files.iter().collect::<Vec<_>>()) {
+        Ok(hashers) => hashers,
+        Err(status) => return status,
+    };
+
+    let mut counts = MirrorCounts::default();
+
+    for (index, (file, file_out)) in files.iter().zip(&outputs).enumerate() {
+        let hasher = hashers.of(index);
+        let result = normalized_hash::is_binary(file).and_then(|binary| {
+            if !binary {
+                let written = hasher.update_normalized(file, file_out)?;
+                Ok(Some((written, false)))
+            } else if args.skip_binary {
+                Ok(None)
+            } else {
+                let written = normalized_hash::update_copy(file, file_out)?;
+                Ok(Some((written, true)))
+            }
+        });
+
+        match result {
+            Ok(Some((true, binary))) => {
+                let action = if binary { "copied" } else { "changed" };
+                println!("{}: {}", file_out.display(), action);
+                if binary {
+                    counts.copied += 1;
+                } else {
+                    counts.changed += 1;
+                }
+            }
+            Ok(Some((false, _))) => counts.unchanged += 1,
+            Ok(None) => {
+                eprintln!("note: skipping binary file {}", file.display());
+                counts.skipped += 1;
+            }
+            Err(err) => {
+                eprintln!(
+                    "warning: {}, skipping",
+                    error::describe(&err, file, Some(file_out))
+                );
+                counts.failed += 1;
+            }
+        }
+    }
+
+    let mut summary = format!(
+        "{} changed, {} unchanged, {} copied, {} skipped",
+        counts.changed, counts.unchanged, counts.copied, counts.skipped
+    );
+    if counts.failed > 0 {
+        summary.push_str(&format!(", {} failed", counts.failed));
+    }
+    println!("{}", summary);
+
+    if counts.failed > 0 {
+        ExitStatus::Incomplete
+    } else {
+        ExitStatus::Success
+    }
+}
+
+/// Convert files to their normalized form and report for each file whether it changed.
+///
+/// Each file is normalized with the options of its directory.
+pub fn run(select: Select, args: &NormalizeArgs) -> ExitStatus {
+    if let Some([src, dst]) = args.mirror.as_deref() {
+        return mirror(select, args, src, dst);
+    }
+
+    let files = match collect(args) {
+        Ok(files) => files,
+        Err(status) => return status,
+    };
+    let hashers = match select(&files.iter().collect::<Vec<_>>()) {
+        Ok(hashers) => hashers,
+        Err(status) => return status,
+    };
+
+    let outputs = match &args.output_dir {
+        Some(dir) => {
+            let inputs = args.files.iter().map(Path::new);
+            match outdir::outputs(dir, None, &files, inputs) {
+                Ok(outputs) => Some(outputs),
+                Err(status) => return status,
+            }
+        }
+        None => None,
+    };
+
+    let mut status = ExitStatus::Success;
+
+    for (index, file) in files.iter().enumerate() {
+        let file_out = outputs.as_ref().map(|outputs| outputs[index].as_path());
+        let hasher = hashers.of(index);
+
+        let result = normalized_hash::is_binary(file).and_then(|binary| {
+            if binary {
+                Ok(None)
+            } else if args.dry_run {
+                hasher
+                    .is_normalized(file)
+                    .map(|normalized| Some(!normalized))
+            } else if let Some(file_out) = file_out {
+                let stats = hasher.normalize_file(file, file_out)?;
+                Ok(Some(stats.is_changed()))
+            } else {
+                normalize_in_place(hasher, file, args).map(Some)
+            }
+        });
+
+        match result {
+            Ok(Some(changed)) if args.dry_run => {
+                if changed {
+                    println!("{}", file.display());
+                    status = status.max(ExitStatus::Mismatch);
+                }
+            }
+            Ok(Some(changed)) => println!(
+                "{}: {}",
+                file.display(),
+                if changed { "normalized" } else { "unchanged" }
+            ),
+            Ok(None) => eprintln!("note: skipping binary file {}", file.display()),
+            Err(err) => {
+                eprintln!(
+                    "warning: {}, skipping",
+                    error::describe(&err, file, file_out)
+                );
+                status = status.max(ExitStatus::Incomplete);
+            }
+        }
+    }
+
+    status
+}
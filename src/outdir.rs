@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error;
+use crate::exit::ExitStatus;
+use crate::relative::{self, RelativeTo};
+
+/// Deepest directory that contains all of `dirs`, resolved lexically.
+pub fn common_ancestor<'a>(dirs: impl IntoIterator<Item = &'a Path>) -> io::Result<PathBuf> {
+    let mut ancestor: Option<PathBuf> = None;
+
+    for dir in dirs {
+        let dir = relative::absolute(dir)?;
+        ancestor = Some(match ancestor {
+            None => dir,
+            Some(ancestor) => ancestor
+                .components()
+                .zip(dir.components())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+
+    ancestor.map_or_else(|| relative::absolute(Path::new(".")), Ok)
+}
+
+/// Directories whose common ancestor is the base of `--output-dir`: the parent directory of
+/// each file and each given directory itself, so a tree keeps its structure below DIR.
+pub fn base_dirs<'a>(
+    files: &'a [PathBuf],
+    inputs: impl IntoIterator<Item = &'a Path>,
+) -> impl Iterator<Item = &'a Path> {
+    files
+        .iter()
+        .map(|file| match file.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        })
+        .chain(inputs.into_iter().filter(|input| input.is_dir()))
+}
+
+/// Paths of the files to be written to an output directory, with `--output-dir`.
+///
+/// Each file keeps its path relative to `base` below `dir`, so `BASE/a/b.txt` is written to
+/// `DIR/a/b.txt`. The paths are checked before anything is written: files outside of the base
+/// directory, outputs that would overwrite any of the input files, and different files that
+/// would be written to the same path result in an error message.
+pub fn plan(dir: &Path, base: &Path, files: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let describe =
+        |err: io::Error, file: &Path| format!("cannot resolve {}: {}", file.display(), err);
+
+    // Paths outside of the base are refused, so no output can escape DIR
+    let relative_to = RelativeTo::new(base, false).map_err(|err| describe(err, base))?;
+
+    let mut sources = HashMap::new();
+    for file in files {
+        let source = relative::absolute(file).map_err(|err| describe(err, file))?;
+        sources.entry(source).or_insert(file);
+    }
+
+    let mut outputs = Vec::with_capacity(files.len());
+    let mut targets = HashMap::new();
+
+    for file in files {
+        let path = match relative_to.apply(file).map_err(|err| describe(err, file))? {
+            Some(path) if path != Path::new(".") => path,
+            _ => {
+                return Err(format!(
+                    "{} is not inside of {}",
+                    file.display(),
+                    base.display()
+                ))
+            }
+        };
+        let output = dir.join(path);
+
+        let source = relative::absolute(file).map_err(|err| describe(err, file))?;
+        let target = relative::absolute(&output).map_err(|err| describe(err, &output))?;
+
+        match sources.get(&target) {
+            Some(_) if target == source => {
+                return Err(format!(
+                    "{} would be overwritten by its own output",
+                    file.display()
+                ))
+            }
+            Some(input) => {
+                return Err(format!(
+                    "{} would be written to {}, overwriting the input {}",
+                    file.display(),
+                    output.display(),
+                    input.display()
+                ))
+            }
+            None => {}
+        }
+
+        // The same file given twice is written only once, but is not a collision
+        match targets.insert(target, (source.clone(), file)) {
+            Some((other, other_file)) if other != source => {
+                return Err(format!(
+                    "{} and {} would both be written to {}",
+                    other_file.display(),
+                    file.display(),
+                    output.display()
+                ))
+            }
+            _ => {}
+        }
+
+        outputs.push(output);
+    }
+
+    Ok(outputs)
+}
+
+/// Paths of the files to be written to `dir`, printing an error if they cannot be planned.
+///
+/// The base directory is `base` if given, like with `--relative-to`, otherwise the common
+/// ancestor of the files and the given `inputs` that are directories.
+pub fn outputs<'a>(
+    dir: &Path,
+    base: Option<&Path>,
+    files: &'a [PathBuf],
+    inputs: impl IntoIterator<Item = &'a Path>,
+) -> Result<Vec<PathBuf>, ExitStatus> {
+    let base = match base {
+        Some(base) => base.to_path_buf(),
+        None => common_ancestor(base_dirs(files, inputs)).map_err(|err| {
+            let message = format_args!("cannot resolve output paths: {}", err);
+            error::fatal(error::kind(&err), None, message);
+            ExitStatus::Io
+        })?,
+    };
+
+    plan(dir, &base, files).map_err(|message| {
+        error::fatal("usage", None, message);
+        ExitStatus::Usage
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_common_ancestor() {
+        let ancestor = common_ancestor([Path::new("base/a/x"), Path::new("base/./b")]).unwrap();
+        assert_eq!(ancestor, relative::absolute(Path::new("base")).unwrap());
+
+        let ancestor = common_ancestor([Path::new("base/a")]).unwrap();
+        assert_eq!(ancestor, relative::absolute(Path::new("base/a")).unwrap());
+    }
+
+    #[test]
+    fn check_plan() {
+        let base = Path::new("base");
+        let files = [
+            PathBuf::from("base/a.txt"),
+            PathBuf::from("base/x/../sub/b.txt"),
+        ];
+
+        assert_eq!(
+            plan(Path::new("out"), base, &files).unwrap(),
+            [PathBuf::from("out/a.txt"), PathBuf::from("out/sub/b.txt")]
+        );
+
+        let files = [PathBuf::from("base/a.txt"), PathBuf::from("base/./a.txt")];
+        assert!(plan(Path::new("out"), base, &files).is_ok());
+
+        let files = [PathBuf::from("other/a.txt")];
+        assert!(plan(Path::new("out"), base, &files).is_err());
+
+        let files = [PathBuf::from("base/a.txt")];
+        assert!(plan(Path::new("base"), base, &files).is_err());
+
+        let files = [PathBuf::from("base/a.txt"), PathBuf::from("base/out/a.txt")];
+        assert!(plan(Path::new("base/out"), base, &files).is_err());
+    }
+}
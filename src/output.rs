@@ -0,0 +1,201 @@
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tempfile::NamedTempFile;
+
+/// Serialize a JSON document, pretty-printed or on a single line like for `--json-lines`.
+///
+/// All JSON output consists of plain structs with string keys, whose serialization cannot fail.
+pub fn json(value: &impl Serialize, pretty: bool) -> String {
+    let json = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    };
+    json.expect("JSON output only consists of plain structs with string keys")
+}
+
+/// Destination of the primary output, either stdout or a file given with `--output`.
+///
+/// A file is first written to a temporary file next to it, which only replaces the target in
+/// [`Output::finish`], so readers never see a half-written file. The temporary file is only
+/// created on the first write, so it is not picked up when collecting the input files. The first write error is
+/// remembered and reported by [`Output::finish`], so single lines can be printed without
+/// handling errors each time.
+pub struct Output {
+    sink: Sink,
+    error: Option<io::Error>,
+}
+
+enum Sink {
+    Stdout(io::Stdout),
+    File {
+        file: Option<NamedTempFile>,
+        path: PathBuf,
+    },
+}
+
+impl Output {
+    /// Print to stdout.
+    pub fn stdout() -> Self {
+        Self {
+            sink: Sink::Stdout(io::stdout()),
+            error: None,
+        }
+    }
+
+    /// Write to a file, which is created or replaced in [`Output::finish`].
+    ///
+    /// The path `-` stands for stdout.
+    pub fn create(path: &Path) -> Self {
+        if path == Path::new("-") {
+            return Self::stdout();
+        }
+
+        Self {
+            sink: Sink::File {
+                file: None,
+                path: normalized_hash::long_path(path),
+            },
+            error: None,
+        }
+    }
+
+    /// Print a line, terminated by a newline.
+    pub fn line(&mut self, line: impl Display) {
+        self.record(line, '\n');
+    }
+
+    /// Print a record, terminated by `terminator`.
+    pub fn record(&mut self, record: impl Display, terminator: char) {
+        // Errors are remembered by the writer itself
+        let _ = write!(self, "{}{}", record, terminator);
+    }
+
+    /// Flush the output and, if it is a file, move it into place.
+    ///
+    /// If any write failed before, the error is returned and the target file is left untouched.
+    /// With `keep` set to false, the output is discarded.
+    pub fn finish(self, keep: bool) -> io::Result<()> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        match self.sink {
+            Sink::Stdout(mut stdout) => stdout.flush(),
+            Sink::File { file, path } => {
+                if !keep {
+                    return Ok(());
+                }
+
+                let file = match file {
+                    Some(file) => file,
+                    None => temp_file(&path)?,
+                };
+
+                if let Ok(metadata) = fs::metadata(&path) {
+                    file.as_file().set_permissions(metadata.permissions())?;
+                }
+
+                file.as_file().sync_all()?;
+                file.persist(path).map_err(|err| err.error)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether the output goes to a terminal.
+    pub fn is_terminal(&self) -> bool {
+        match &self.sink {
+            Sink::Stdout(stdout) => stdout.is_terminal(),
+            Sink::File { .. } => false,
+        }
+    }
+
+    fn remember<T>(&mut self, result: io::Result<T>) -> io::Result<T> {
+        if let Err(err) = &result {
+            if self.error.is_none() {
+                self.error = Some(io::Error::new(err.kind(), err.to_string()));
+            }
+        }
+        result
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.error.is_some() {
+            return Err(io::Error::other("output failed before"));
+        }
+
+        let result = match &mut self.sink {
+            Sink::Stdout(stdout) => stdout.write(buf),
+            Sink::File {
+                file: Some(file), ..
+            } => file.write(buf),
+            Sink::File { file, path } => {
+                temp_file(path).and_then(|new| file.insert(new).write(buf))
+            }
+        };
+        self.remember(result)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let result = match &mut self.sink {
+            Sink::Stdout(stdout) => stdout.flush(),
+            Sink::File {
+                file: Some(file), ..
+            } => file.flush(),
+            Sink::File { file: None, .. } => Ok(()),
+        };
+        self.remember(result)
+    }
+}
+
+/// Create a temporary file in the directory of `path`.
+fn temp_file(path: &Path) -> io::Result<NamedTempFile> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    NamedTempFile::new_in(dir).map_err(|err| {
+        // Report a missing directory without the random name of the temporary file
+        fs::metadata(dir).err().unwrap_or(err)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_file_replaced_on_finish() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("SHA256SUMS");
+
+        let mut output = Output::create(&path);
+        output.line("first");
+        output.record("second", '\0');
+        assert!(!path.exists());
+
+        output.finish(true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first\nsecond\0");
+    }
+
+    #[test]
+    fn check_file_discarded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("SHA256SUMS");
+
+        let mut output = Output::create(&path);
+        output.line("first");
+        output.finish(false).unwrap();
+
+        assert!(!path.exists());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
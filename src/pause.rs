@@ -0,0 +1,46 @@
+use std::env;
+use std::ffi::OsStr;
+use std::io::{self, IsTerminal, Write};
+
+/// Check if the console should be kept open before exiting, with `--pause`.
+///
+/// This is only done if stdin is a `terminal`, so scripts that pass the flag by accident don't
+/// hang. The exit status does not matter, since errors are what people most need to read.
+pub fn should_pause(flag: bool, terminal: bool) -> bool {
+    flag && terminal
+}
+
+/// Check if `--pause` is among the arguments, even if they cannot be parsed.
+pub fn is_requested() -> bool {
+    env::args_os()
+        .skip(1)
+        .take_while(|arg| arg != OsStr::new("--"))
+        .any(|arg| arg == OsStr::new("--pause"))
+}
+
+/// Wait until Enter is pressed, so the console stays open to read the result.
+pub fn wait() {
+    eprint!("Press Enter to close...");
+    let _ = io::stderr().flush();
+    let _ = io::stdin().read_line(&mut String::new());
+}
+
+/// Wait before exiting if `--pause` is given, see [`should_pause`].
+pub fn pause_if_requested(flag: bool) {
+    if should_pause(flag, io::stdin().is_terminal()) {
+        wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_should_pause() {
+        assert!(should_pause(true, true));
+        assert!(!should_pause(true, false));
+        assert!(!should_pause(false, true));
+        assert!(!should_pause(false, false));
+    }
+}
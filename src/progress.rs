@@ -0,0 +1,35 @@
+use std::io::{self, IsTerminal};
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+/// Create a progress bar on stderr, hidden if `enabled` is false or stderr is not a terminal.
+fn new(enabled: bool, len: Option<u64>, template: &str) -> ProgressBar {
+    if !enabled || !io::stderr().is_terminal() {
+        return ProgressBar::hidden();
+    }
+
+    let bar = ProgressBar::with_draw_target(len, ProgressDrawTarget::stderr());
+
+    match ProgressStyle::with_template(template) {
+        Ok(style) => bar.with_style(style),
+        Err(_) => bar,
+    }
+}
+
+/// Progress of reading a single file with `len` bytes, `None` if the size is unknown.
+pub fn bytes(enabled: bool, len: Option<u64>) -> ProgressBar {
+    let template = if len.is_some() {
+        "{wide_bar} {bytes}/{total_bytes} ({binary_bytes_per_sec}, ETA {eta})"
+    } else {
+        "{spinner} {bytes} ({binary_bytes_per_sec})"
+    };
+
+    new(enabled, len, template)
+}
+
+/// Progress of hashing a batch of `len` files.
+pub fn files(enabled: bool, len: usize) -> ProgressBar {
+    let template = "{wide_bar} {human_pos}/{human_len} files ({per_sec}, ETA {eta})";
+
+    new(enabled, Some(len as u64), template)
+}
@@ -0,0 +1,145 @@
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+
+use crate::error;
+use crate::exit::ExitStatus;
+
+/// Source of answers to questions, behind a trait so that it can be replaced in tests.
+pub trait Console {
+    /// Check if a person can answer questions, since stdin and stderr are terminals.
+    fn is_interactive(&self) -> bool;
+
+    /// Print `question` and read the answer.
+    fn ask(&mut self, question: &str) -> io::Result<String>;
+}
+
+/// The console the program runs in, asking on stderr so that stdout stays clean.
+pub struct Terminal;
+
+impl Console for Terminal {
+    fn is_interactive(&self) -> bool {
+        io::stdin().is_terminal() && io::stderr().is_terminal()
+    }
+
+    fn ask(&mut self, question: &str) -> io::Result<String> {
+        eprint!("{} ", question);
+        io::stderr().flush()?;
+
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        Ok(answer)
+    }
+}
+
+/// Make sure that an existing `path` may be overwritten.
+///
+/// With `force` or if nothing exists at `path`, there is nothing to confirm. Otherwise, an
+/// interactive console is asked, where only `y` or `yes` allow to overwrite the file. Without an
+/// interactive console, an existing file is an error.
+pub fn confirm_overwrite(
+    console: &mut impl Console,
+    path: &Path,
+    force: bool,
+) -> Result<(), ExitStatus> {
+    if force || !normalized_hash::long_path(path).exists() {
+        return Ok(());
+    }
+
+    if !console.is_interactive() {
+        let message = format_args!(
+            "{} already exists, use --force to overwrite it",
+            path.display()
+        );
+        error::fatal("usage", Some(path), message);
+        return Err(ExitStatus::Usage);
+    }
+
+    let question = format!("overwrite '{}'? [y/N]", path.display());
+    match console.ask(&question) {
+        Ok(answer) if ["y", "yes"].contains(&answer.trim().to_lowercase().as_str()) => Ok(()),
+        Ok(_) => {
+            let message = format_args!("not overwriting {}", path.display());
+            error::fatal("usage", Some(path), message);
+            Err(ExitStatus::Usage)
+        }
+        Err(err) => {
+            let message = format_args!("cannot read standard input: {}", error::message(&err));
+            error::fatal("io", None, message);
+            Err(ExitStatus::Io)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StubConsole {
+        interactive: bool,
+        answer: &'static str,
+        questions: Vec<String>,
+    }
+
+    impl StubConsole {
+        fn new(interactive: bool, answer: &'static str) -> Self {
+            Self {
+                interactive,
+                answer,
+                questions: Vec::new(),
+            }
+        }
+    }
+
+    impl Console for StubConsole {
+        fn is_interactive(&self) -> bool {
+            self.interactive
+        }
+
+        fn ask(&mut self, question: &str) -> io::Result<String> {
+            self.questions.push(question.to_string());
+            Ok(self.answer.to_string())
+        }
+    }
+
+    #[test]
+    fn check_confirm_overwrite() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path();
+
+        for (answer, allowed) in [
+            ("y\n", true),
+            ("YES\r\n", true),
+            ("\n", false),
+            ("no\n", false),
+        ] {
+            let mut console = StubConsole::new(true, answer);
+            assert_eq!(
+                confirm_overwrite(&mut console, path, false).is_ok(),
+                allowed
+            );
+            assert_eq!(
+                console.questions,
+                [format!("overwrite '{}'? [y/N]", path.display())]
+            );
+        }
+
+        let mut console = StubConsole::new(true, "n\n");
+        assert!(confirm_overwrite(&mut console, path, true).is_ok());
+        assert!(console.questions.is_empty());
+    }
+
+    #[test]
+    fn check_confirm_overwrite_non_interactive() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+
+        let mut console = StubConsole::new(false, "y\n");
+        assert_eq!(
+            confirm_overwrite(&mut console, file.path(), false),
+            Err(ExitStatus::Usage)
+        );
+        assert!(console.questions.is_empty());
+
+        let missing = file.path().with_extension("missing");
+        assert!(confirm_overwrite(&mut console, &missing, false).is_ok());
+    }
+}
@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use crate::checksum::Header;
+use crate::exit::ExitStatus;
+use crate::output::Output;
+
+/// Pixels per module of the QR code in written images.
+#[cfg(feature = "qr")]
+const MODULE_PIXELS: usize = 8;
+
+/// Modules of light border around the QR code in written images, which scanners need.
+#[cfg(feature = "qr")]
+const QUIET_ZONE: usize = 4;
+
+/// Error correction level of QR codes, given with `--qr-level`.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum QrLevel {
+    /// Recover about 7% of the code
+    Low,
+
+    /// Recover about 15% of the code
+    #[default]
+    Medium,
+
+    /// Recover about 25% of the code
+    Quartile,
+
+    /// Recover about 30% of the code, for prints that might get damaged
+    High,
+}
+
+#[cfg(feature = "qr")]
+impl From<QrLevel> for qrcode::EcLevel {
+    fn from(level: QrLevel) -> Self {
+        match level {
+            QrLevel::Low => qrcode::EcLevel::L,
+            QrLevel::Medium => qrcode::EcLevel::M,
+            QrLevel::Quartile => qrcode::EcLevel::Q,
+            QrLevel::High => qrcode::EcLevel::H,
+        }
+    }
+}
+
+/// Text encoded in the QR code, like `normalized-hasher: eol=lf ignore_whitespaces=false
+/// no_eof=false algorithm=sha256 format=v1 hash=HASH`.
+///
+/// It is the header of generated checksum files without the comment sign, followed by the
+/// hash, so a scanned code tells the options to verify the hash with.
+pub fn payload(header: &Header, hash: &str) -> String {
+    let header = header.to_string();
+    let fields = header.trim_start_matches("# ");
+    format!("{} hash={}", fields, hash)
+}
+
+#[cfg(feature = "qr")]
+fn encode(payload: &str, level: QrLevel) -> Result<qrcode::QrCode, String> {
+    qrcode::QrCode::with_error_correction_level(payload, level.into())
+        .map_err(|err| err.to_string())
+}
+
+/// Render the QR code of `payload` with half blocks, two modules per character.
+///
+/// Light modules are drawn as blocks, so the code can be scanned from terminals with a dark
+/// background.
+#[cfg(feature = "qr")]
+pub fn render_text(payload: &str, level: QrLevel) -> Result<String, String> {
+    use qrcode::render::unicode::Dense1x2;
+
+    let code = encode(payload, level)?;
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
+}
+
+/// Write the QR code of `payload` as a grayscale PNG image into `path`.
+#[cfg(feature = "qr")]
+pub fn write_png(payload: &str, level: QrLevel, path: &Path) -> Result<(), String> {
+    let code = encode(payload, level)?;
+    let modules = code.width();
+    let colors = code.to_colors();
+
+    let size = (modules + 2 * QUIET_ZONE) * MODULE_PIXELS;
+    let mut pixels = vec![u8::MAX; size * size];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != qrcode::Color::Dark {
+            continue;
+        }
+
+        let x = (index % modules + QUIET_ZONE) * MODULE_PIXELS;
+        let y = (index / modules + QUIET_ZONE) * MODULE_PIXELS;
+        for row in y..y + MODULE_PIXELS {
+            pixels[row * size + x..row * size + x + MODULE_PIXELS].fill(0);
+        }
+    }
+
+    let file = std::fs::File::create(normalized_hash::long_path(path))
+        .map_err(|err| format!("cannot write '{}': {}", path.display(), err))?;
+    let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(|err| format!("cannot write '{}': {}", path.display(), err))
+}
+
+/// Placeholder if the binary was built without the `qr` feature.
+#[cfg(not(feature = "qr"))]
+pub fn render_text(_payload: &str, _level: QrLevel) -> Result<String, String> {
+    Err(unsupported())
+}
+
+/// Placeholder if the binary was built without the `qr` feature.
+#[cfg(not(feature = "qr"))]
+pub fn write_png(_payload: &str, _level: QrLevel, _path: &Path) -> Result<(), String> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "qr"))]
+fn unsupported() -> String {
+    String::from("this build does not support QR codes, enable the feature `qr`")
+}
+
+/// Print the QR code of `payload` if `terminal` is set and write it into `image` if given,
+/// printing an error if that is not possible.
+pub fn show(
+    out: &mut Output,
+    payload: &str,
+    level: QrLevel,
+    terminal: bool,
+    image: Option<&Path>,
+) -> ExitStatus {
+    let result = terminal
+        .then(|| render_text(payload, level))
+        .transpose()
+        .and_then(|art| {
+            if let Some(path) = image {
+                write_png(payload, level, path)?;
+            }
+            Ok(art)
+        });
+
+    match result {
+        Ok(art) => {
+            if let Some(art) = art {
+                out.line(art);
+            }
+            ExitStatus::Success
+        }
+        Err(err) => {
+            eprintln!("error: cannot create QR code: {}", err);
+            ExitStatus::Io
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use normalized_hash::Algorithm;
+
+    use super::*;
+
+    #[test]
+    fn check_payload() {
+        let header = Header {
+            eol: String::from("\r\n"),
+            ignore_whitespaces: true,
+            no_eof: false,
+            algorithm: Algorithm::Sha256,
+        };
+        assert_eq!(
+            payload(&header, "abc"),
+            "normalized-hasher: eol=crlf ignore_whitespaces=true no_eof=false algorithm=sha256 \
+             format=v1 hash=abc"
+        );
+    }
+}
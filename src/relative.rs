@@ -0,0 +1,78 @@
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Make a path absolute and resolve `.` and `..` components lexically, without following
+/// symlinks.
+pub fn absolute(path: &Path) -> io::Result<PathBuf> {
+    let mut normalized = PathBuf::new();
+
+    for component in std::path::absolute(path)?.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    Ok(normalized)
+}
+
+/// Printed paths, relative to a base directory.
+pub struct RelativeTo {
+    base: PathBuf,
+    allow_outside: bool,
+}
+
+impl RelativeTo {
+    pub fn new(base: &Path, allow_outside: bool) -> io::Result<Self> {
+        Ok(Self {
+            base: absolute(base)?,
+            allow_outside,
+        })
+    }
+
+    /// Path of `file` relative to the base directory.
+    ///
+    /// The base directory itself is `.`. Paths outside of the base directory are returned as
+    /// absolute paths if allowed, otherwise `None` is returned.
+    pub fn apply(&self, file: &Path) -> io::Result<Option<PathBuf>> {
+        let file = absolute(file)?;
+
+        let path = match file.strip_prefix(&self.base) {
+            Ok(path) if path.as_os_str().is_empty() => PathBuf::from("."),
+            Ok(path) => path.to_path_buf(),
+            Err(_) if self.allow_outside => file,
+            Err(_) => return Ok(None),
+        };
+
+        Ok(Some(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_apply() {
+        let relative_to = RelativeTo::new(Path::new("base/./dir"), false).unwrap();
+
+        assert_eq!(
+            relative_to.apply(Path::new("base/dir")).unwrap(),
+            Some(PathBuf::from("."))
+        );
+        assert_eq!(
+            relative_to.apply(Path::new("base/x/../dir/a.txt")).unwrap(),
+            Some(PathBuf::from("a.txt"))
+        );
+        assert_eq!(relative_to.apply(Path::new("base/a.txt")).unwrap(), None);
+
+        let relative_to = RelativeTo::new(Path::new("base/dir"), true).unwrap();
+        assert_eq!(
+            relative_to.apply(Path::new("base/a.txt")).unwrap(),
+            Some(absolute(Path::new("base/a.txt")).unwrap())
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use normalized_hash::{Hasher, Timing};
+
+use crate::checksum;
+use crate::error::ErrorInfo;
+use crate::output::{self, Output};
+use crate::timing::TimeInfo;
+
+/// Result of hashing a single file, as written in the JSON output.
+#[derive(Serialize)]
+pub struct Entry {
+    /// Path of the file, as given or found.
+    path: String,
+
+    /// Hash of the normalized content, missing on error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+
+    /// Name of the hash algorithm.
+    algorithm: String,
+
+    /// Size of the original file in bytes, missing on error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bytes: Option<u64>,
+
+    /// Number of lines in the original file, missing on error or if the file cannot be read
+    /// twice, like a named pipe.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lines: Option<usize>,
+
+    /// Whether normalization changes the file, missing like `lines`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changed: Option<bool>,
+
+    /// Kind and message of the error if the file could not be hashed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorInfo>,
+
+    /// Duration and throughput of hashing, only with `--time`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<TimeInfo>,
+}
+
+/// Counters over all files of a run.
+#[derive(Default, Serialize)]
+pub struct Summary {
+    /// Number of files that were hashed successfully.
+    hashed: usize,
+
+    /// Number of hashed files that are changed by normalization.
+    changed: usize,
+
+    /// Number of binary files that were skipped.
+    skipped: usize,
+
+    /// Number of files that could not be hashed.
+    errors: usize,
+
+    /// Total duration and throughput of hashing all files, only with `--time`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<TimeInfo>,
+
+    /// Wall time of the whole run in milliseconds, missing for a single file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elapsed_milliseconds: Option<f64>,
+}
+
+/// Object of a JSON line, an entry or the summary marked with its type.
+#[derive(Serialize)]
+struct Line<'a, T> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+
+    /// Index of the file in the deterministic order, only with `--unordered`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<usize>,
+
+    #[serde(flatten)]
+    value: &'a T,
+}
+
+/// Collected results of a run, printed as a single JSON document.
+///
+/// With `--json-lines`, entries are instead printed and removed as soon as they are added, so
+/// only the summary is kept.
+#[derive(Default, Serialize)]
+pub struct Report {
+    entries: Vec<Entry>,
+    summary: Summary,
+
+    #[serde(skip)]
+    total_timing: Option<Timing>,
+}
+
+impl Report {
+    /// Add a successfully hashed file, listed as `name`.
+    pub fn add_hash(&mut self, hasher: &Hasher, file: &Path, name: &Path, hash: String) {
+        let metadata = fs::metadata(file).ok();
+
+        // Only regular files can be read a second time to gather statistics
+        let stats = metadata
+            .as_ref()
+            .filter(|metadata| metadata.is_file())
+            .and_then(|_| line_stats(hasher, file).ok());
+
+        self.summary.hashed += 1;
+        if stats.is_some_and(|(_, changed)| changed) {
+            self.summary.changed += 1;
+        }
+
+        self.entries.push(Entry {
+            path: normalized_hash::escape_path(name).into_owned(),
+            hash: Some(hash),
+            algorithm: checksum::tag(hasher.digest_algorithm()),
+            bytes: metadata.map(|metadata| metadata.len()),
+            lines: stats.map(|(lines, _)| lines),
+            changed: stats.map(|(_, changed)| changed),
+            error: None,
+            time: None,
+        });
+    }
+
+    /// Add a file that could not be hashed.
+    pub fn add_error(&mut self, hasher: &Hasher, file: &Path, err: &io::Error) {
+        self.summary.errors += 1;
+
+        self.entries.push(Entry {
+            path: normalized_hash::escape_path(file).into_owned(),
+            hash: None,
+            algorithm: checksum::tag(hasher.digest_algorithm()),
+            bytes: None,
+            lines: None,
+            changed: None,
+            error: Some(ErrorInfo::new(err)),
+            time: None,
+        });
+    }
+
+    /// Add the timing of the last added file and to the total.
+    pub fn add_timing(&mut self, timing: Timing) {
+        if let Some(entry) = self.entries.last_mut() {
+            entry.time = Some(timing.into());
+        }
+
+        let total = self.total_timing.unwrap_or_default() + timing;
+        self.total_timing = Some(total);
+        self.summary.time = Some(total.into());
+    }
+
+    /// Set the wall time of the whole run.
+    pub fn set_elapsed(&mut self, elapsed: Duration) {
+        self.summary.elapsed_milliseconds = Some(elapsed.as_secs_f64() * 1000.0);
+    }
+
+    /// Count a binary file that was skipped.
+    pub fn add_skipped(&mut self) {
+        self.summary.skipped += 1;
+    }
+
+    /// Print the report as pretty JSON document.
+    pub fn print_json(&self, out: &mut Output) {
+        out.line(output::json(self, true));
+    }
+
+    /// Print the entries added since the last call as JSON lines, removing them from the report.
+    ///
+    /// Each entry is marked with `"type":"entry"` and, if `seq` is given, with the field `seq`.
+    pub fn print_entry_lines(&mut self, out: &mut Output, seq: Option<usize>) {
+        for entry in self.entries.drain(..) {
+            let line = Line {
+                kind: "entry",
+                seq,
+                value: &entry,
+            };
+
+            out.line(output::json(&line, false));
+        }
+    }
+
+    /// Print the remaining entries and the summary as JSON lines, for `--json-lines`.
+    pub fn print_json_lines(&mut self, out: &mut Output) {
+        self.print_entry_lines(out, None);
+
+        let line = Line {
+            kind: "summary",
+            seq: None,
+            value: &self.summary,
+        };
+        out.line(serde_json::to_string(&line).unwrap());
+    }
+
+    /// Print the report as CSV, with a header row and one row per hashed file.
+    ///
+    /// Records are terminated by CRLF, as expected by spreadsheet applications. Files that could
+    /// not be hashed are not included.
+    pub fn print_csv(&self, out: &mut Output, delimiter: u8) -> csv::Result<()> {
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .terminator(csv::Terminator::CRLF)
+            .from_writer(out);
+
+        writer.write_record(["path", "algorithm", "hash", "size", "lines", "changed"])?;
+
+        for entry in &self.entries {
+            let Some(hash) = &entry.hash else {
+                continue;
+            };
+
+            let optional = |value: Option<String>| value.unwrap_or_default();
+            writer.write_record([
+                entry.path.clone(),
+                entry.algorithm.to_string(),
+                hash.clone(),
+                optional(entry.bytes.map(|bytes| bytes.to_string())),
+                optional(entry.lines.map(|lines| lines.to_string())),
+                optional(entry.changed.map(|changed| changed.to_string())),
+            ])?;
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Count the lines of a file and check if normalization changes it.
+pub fn line_stats(hasher: &Hasher, file: &Path) -> io::Result<(usize, bool)> {
+    let mut lines = 0;
+    let mut changed = false;
+
+    for line in hasher.line_changes(file)? {
+        let line = line?;
+
+        // An empty input results in a single empty pseudo-line without line ending
+        if !line.original.is_empty() || !line.original_eol.is_empty() {
+            lines += 1;
+        }
+        changed |= line.is_changed();
+    }
+
+    Ok((lines, changed))
+}
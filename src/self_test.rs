@@ -0,0 +1,45 @@
+use normalized_hash::vectors::VECTORS;
+
+use crate::check::plural;
+use crate::color::Palette;
+use crate::exit::ExitStatus;
+use crate::output::Output;
+
+/// Check the golden test vectors of the library and print the result of each, for
+/// `--self-test`.
+///
+/// Exits with [`ExitStatus::Mismatch`] if any vector does not result in its expected hash.
+pub fn run(out: &mut Output, palette: Palette) -> ExitStatus {
+    let mut failed = 0;
+
+    for vector in VECTORS {
+        match vector.check() {
+            Ok(()) => out.line(format_args!(
+                "{}  {}",
+                palette.ok("PASS"),
+                vector.description
+            )),
+            Err(failure) => {
+                failed += 1;
+                out.line(format_args!("{}  {}", palette.failed("FAIL"), failure));
+            }
+        }
+    }
+
+    if failed == 0 {
+        out.line(format_args!(
+            "all {} test {} passed",
+            VECTORS.len(),
+            plural(VECTORS.len(), "vector", "vectors")
+        ));
+        ExitStatus::Success
+    } else {
+        out.line(format_args!(
+            "{} of {} test {} failed",
+            failed,
+            VECTORS.len(),
+            plural(VECTORS.len(), "vector", "vectors")
+        ));
+        ExitStatus::Mismatch
+    }
+}
@@ -0,0 +1,308 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueHint};
+use serde::Serialize;
+
+use normalized_hash::Stats;
+
+use crate::check::plural;
+use crate::error::{self, ErrorInfo};
+use crate::exit::ExitStatus;
+use crate::input::{self, InputOptions, Inputs};
+use crate::output::{self, Output};
+use crate::Select;
+
+#[derive(Args)]
+pub struct StatsArgs {
+    /// Files to be surveyed, directories with `--recursive`
+    #[arg(required = true, value_hint = ValueHint::AnyPath)]
+    files: Vec<OsString>,
+
+    /// Survey all files in the given directories and their subdirectories
+    ///
+    /// Binary files are skipped with a note on stderr. The same rules apply as for hashing with
+    /// `--recursive`.
+    #[arg(short, long)]
+    recursive: bool,
+
+    /// Skip hidden files and directories with `--recursive`
+    #[arg(long, requires = "recursive")]
+    no_hidden: bool,
+
+    /// Don't respect `.gitignore` files with `--recursive`
+    #[arg(long, requires = "recursive")]
+    no_ignore: bool,
+
+    /// Exclude files matching a glob pattern
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Descend at most N directory levels with `--recursive`
+    #[arg(long, value_name = "N", requires = "recursive")]
+    max_depth: Option<usize>,
+
+    /// Follow symbolic links with `--recursive`
+    #[arg(long, requires = "recursive")]
+    follow_symlinks: bool,
+
+    /// Only print the totals over all files
+    #[arg(long)]
+    summary_only: bool,
+
+    /// Print the survey as JSON document
+    #[arg(long)]
+    json: bool,
+}
+
+/// Survey of a single file, as written in the JSON output.
+#[derive(Serialize)]
+struct Entry {
+    /// Path of the file, as given or found.
+    path: String,
+
+    /// Number of lines in the file.
+    lines: usize,
+
+    /// Number of lines ending with CRLF.
+    crlf: usize,
+
+    /// Number of lines ending with a single LF.
+    lf: usize,
+
+    /// Number of CR characters that do not end a line.
+    cr: usize,
+
+    /// Number of lines with whitespace at their end.
+    trailing_whitespace: usize,
+
+    /// Whether the file starts with a byte order mark.
+    bom: bool,
+
+    /// Whether the last line of a non-empty file has no line ending.
+    missing_final_newline: bool,
+}
+
+impl Entry {
+    fn new(path: &Path, stats: &Stats) -> Self {
+        Self {
+            path: normalized_hash::escape_path(path).into_owned(),
+            lines: stats.lines,
+            crlf: stats.crlf,
+            lf: stats.lf,
+            cr: stats.cr,
+            trailing_whitespace: stats.trailing_whitespace,
+            bom: stats.bom,
+            missing_final_newline: stats.lines > 0 && !stats.final_newline,
+        }
+    }
+}
+
+/// Totals over all surveyed files.
+#[derive(Default, Serialize)]
+struct Total {
+    /// Number of surveyed files.
+    files: usize,
+
+    lines: usize,
+    crlf: usize,
+    lf: usize,
+    cr: usize,
+    trailing_whitespace: usize,
+
+    /// Number of files starting with a byte order mark.
+    bom: usize,
+
+    /// Number of files without final newline.
+    missing_final_newline: usize,
+}
+
+impl Total {
+    fn add(&mut self, entry: &Entry) {
+        self.files += 1;
+        self.lines += entry.lines;
+        self.crlf += entry.crlf;
+        self.lf += entry.lf;
+        self.cr += entry.cr;
+        self.trailing_whitespace += entry.trailing_whitespace;
+        self.bom += usize::from(entry.bom);
+        self.missing_final_newline += usize::from(entry.missing_final_newline);
+    }
+}
+
+/// Survey as written in the JSON output.
+#[derive(Default, Serialize)]
+struct Survey {
+    /// Surveyed files, missing with `--summary-only`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    files: Option<Vec<Entry>>,
+
+    /// Files that could not be read.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<ErrorEntry>,
+
+    total: Total,
+}
+
+/// File that could not be read, as written in the JSON output.
+#[derive(Serialize)]
+struct ErrorEntry {
+    path: String,
+    error: ErrorInfo,
+}
+
+/// Format a row of the table, where `bom` and `missing_final_newline` are already formatted.
+fn row(
+    counts: [usize; 5],
+    bom: impl AsRef<str>,
+    missing_final_newline: impl AsRef<str>,
+    name: &str,
+) -> String {
+    let [lines, crlf, lf, cr, trailing_whitespace] = counts;
+    format!(
+        "{:>8} {:>8} {:>8} {:>8} {:>8} {:>5} {:>10}  {}",
+        lines,
+        crlf,
+        lf,
+        cr,
+        trailing_whitespace,
+        bom.as_ref(),
+        missing_final_newline.as_ref(),
+        name
+    )
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// Collect the files to be surveyed, in the order given.
+fn collect(args: &StatsArgs) -> Result<Vec<PathBuf>, ExitStatus> {
+    let options = InputOptions {
+        recursive: args.recursive,
+        hidden: !args.no_hidden,
+        gitignore: !args.no_ignore,
+        exclude: args.exclude.clone(),
+        extensions: Vec::new(),
+        max_depth: args.max_depth,
+        follow_symlinks: args.follow_symlinks,
+        allow_empty_glob: false,
+        verbose: false,
+        quiet: false,
+        verb: "analyze",
+    };
+
+    let mut files = Vec::new();
+    for file in &args.files {
+        match input::collect(Path::new(file), &options)? {
+            Inputs::Single(file) => files.push(file),
+            Inputs::Batch(batch) => files.extend(batch),
+        }
+    }
+
+    Ok(files)
+}
+
+/// Survey the line endings and whitespace of files, without hashing them.
+///
+/// Each file is analyzed with the options of its directory.
+pub fn run(select: Select, out: &mut Output, args: &StatsArgs) -> ExitStatus {
+    let files = match collect(args) {
+        Ok(files) => files,
+        Err(status) => return status,
+    };
+    let hashers = match select(&files.iter().collect::<Vec<_>>()) {
+        Ok(hashers) => hashers,
+        Err(status) => return status,
+    };
+
+    let mut survey = Survey::default();
+    let mut entries = Vec::new();
+    let mut status = ExitStatus::Success;
+
+    for (index, file) in files.iter().enumerate() {
+        let hasher = hashers.of(index);
+        let stats = normalized_hash::is_binary(file)
+            .and_then(|binary| (!binary).then(|| hasher.analyze_file(file)).transpose());
+
+        match stats {
+            Ok(Some(stats)) => {
+                let entry = Entry::new(file, &stats);
+                survey.total.add(&entry);
+                entries.push(entry);
+            }
+            Ok(None) => eprintln!("note: skipping binary file {}", file.display()),
+            Err(err) => {
+                if !args.json {
+                    eprintln!("warning: {}, skipping", error::describe(&err, file, None));
+                }
+                survey.errors.push(ErrorEntry {
+                    path: normalized_hash::escape_path(file).into_owned(),
+                    error: ErrorInfo::new(&err),
+                });
+                status = ExitStatus::Incomplete;
+            }
+        }
+    }
+
+    if args.json {
+        survey.files = (!args.summary_only).then_some(entries);
+
+        out.line(output::json(&survey, true));
+        return status;
+    }
+
+    println!(
+        "{:>8} {:>8} {:>8} {:>8} {:>8} {:>5} {:>10}  FILE",
+        "LINES", "CRLF", "LF", "CR", "TRAILING", "BOM", "NO-NEWLINE"
+    );
+
+    if !args.summary_only {
+        for entry in &entries {
+            println!(
+                "{}",
+                row(
+                    [
+                        entry.lines,
+                        entry.crlf,
+                        entry.lf,
+                        entry.cr,
+                        entry.trailing_whitespace
+                    ],
+                    yes_no(entry.bom),
+                    yes_no(entry.missing_final_newline),
+                    &entry.path,
+                )
+            );
+        }
+    }
+
+    if args.summary_only || entries.len() > 1 {
+        let total = &survey.total;
+        println!(
+            "{}",
+            row(
+                [
+                    total.lines,
+                    total.crlf,
+                    total.lf,
+                    total.cr,
+                    total.trailing_whitespace
+                ],
+                total.bom.to_string(),
+                total.missing_final_newline.to_string(),
+                &format!(
+                    "total ({} {})",
+                    total.files,
+                    plural(total.files, "file", "files")
+                ),
+            )
+        );
+    }
+
+    status
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use crate::timing::format_duration;
+
+/// Format the last line of a batch or check run, like `summary: 9 OK, 1 failed, 2.50 s`.
+pub fn line(counts: &[(usize, &str)], elapsed: Duration) -> String {
+    let counts = counts
+        .iter()
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect::<Vec<_>>();
+
+    format!(
+        "summary: {}, {}",
+        counts.join(", "),
+        format_duration(elapsed)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_line() {
+        assert_eq!(
+            line(&[(9, "OK"), (1, "failed")], Duration::from_millis(2500)),
+            "summary: 9 OK, 1 failed, 2.50 s"
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use normalized_hash::{Hasher, Stats};
+
+use crate::checksum;
+use crate::report;
+use crate::timing;
+
+/// Placeholders of templates, in the order they are listed in error messages.
+const PLACEHOLDERS: [&str; 7] = [
+    "hash",
+    "file",
+    "filename",
+    "algorithm",
+    "date",
+    "lines",
+    "size",
+];
+
+/// Piece of a template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    /// Text that is printed as it is.
+    Text(String),
+
+    /// Name of a placeholder, one of [`PLACEHOLDERS`].
+    Placeholder(&'static str),
+}
+
+/// Text with placeholders like `{hash}`, printed instead of the checksum line with `--template`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+/// List the known placeholders for error messages, like `{hash}, {file} or {size}`.
+fn known() -> String {
+    let names = PLACEHOLDERS.map(|name| format!("{{{}}}", name));
+    let (last, names) = names.split_last().unwrap();
+    format!("{} or {}", names.join(", "), last)
+}
+
+impl Template {
+    /// Parse a template given on the command line, or read it from a file if it starts with `@`.
+    ///
+    /// A single trailing newline of a file is removed, since every record is terminated anyway.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let Some(path) = value.strip_prefix('@') else {
+            return value.parse();
+        };
+
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("cannot read template {}: {}", path, err))?;
+        let content = content.strip_suffix('\n').unwrap_or(&content);
+        let content = content.strip_suffix('\r').unwrap_or(content);
+        content.parse()
+    }
+
+    /// Check if a placeholder is used in the template.
+    fn uses(&self, name: &str) -> bool {
+        self.parts
+            .iter()
+            .any(|part| matches!(part, Part::Placeholder(known) if *known == name))
+    }
+
+    /// Fill in the placeholders for a hashed file.
+    ///
+    /// `file` is the file that was read and `name` the path that is printed. Without `stats`,
+    /// like for cached hashes, the file is read again if `{lines}` is used.
+    pub fn render(
+        &self,
+        hasher: &Hasher,
+        file: &Path,
+        name: &Path,
+        hash: &str,
+        stats: Option<&Stats>,
+    ) -> String {
+        let size = match stats {
+            Some(stats) => Some(stats.bytes),
+            None => fs::metadata(file).ok().map(|metadata| metadata.len()),
+        };
+        let lines = match stats {
+            Some(stats) => Some(stats.lines),
+            None if self.uses("lines") => report::line_stats(hasher, file).ok().map(|(l, _)| l),
+            None => None,
+        };
+
+        let mut rendered = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => rendered.push_str(text),
+                Part::Placeholder("hash") => rendered.push_str(hash),
+                Part::Placeholder("file") => rendered.push_str(&normalized_hash::escape_path(name)),
+                Part::Placeholder("filename") => {
+                    let filename = name.file_name().map_or(name, Path::new);
+                    rendered.push_str(&normalized_hash::escape_path(filename));
+                }
+                Part::Placeholder("algorithm") => {
+                    rendered.push_str(&checksum::tag(hasher.digest_algorithm()))
+                }
+                Part::Placeholder("date") => rendered.push_str(&format_date(SystemTime::now())),
+                Part::Placeholder("lines") => {
+                    rendered.push_str(&lines.map_or_else(String::new, |lines| lines.to_string()))
+                }
+                Part::Placeholder("size") => {
+                    rendered.push_str(&size.map_or_else(String::new, |size| size.to_string()))
+                }
+                Part::Placeholder(name) => unreachable!("unknown placeholder {}", name),
+            }
+        }
+
+        rendered
+    }
+}
+
+impl std::str::FromStr for Template {
+    type Err = String;
+
+    /// Parse a template, where `{{` and `}}` stand for literal braces.
+    fn from_str(template: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let Some(end) = rest.find('}') else {
+                        return Err(String::from(
+                            "unclosed placeholder in template, write {{ for a literal {",
+                        ));
+                    };
+
+                    let name = &rest[..end];
+                    let Some(&name) = PLACEHOLDERS.iter().find(|known| **known == name) else {
+                        return Err(format!(
+                            "unknown placeholder {{{}}} in template, expected {}",
+                            name,
+                            known()
+                        ));
+                    };
+
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Placeholder(name));
+                    chars = rest[end + 1..].chars();
+                }
+                '}' => {
+                    return Err(String::from(
+                        "unmatched } in template, write }} for a literal }",
+                    ));
+                }
+                c => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Self { parts })
+    }
+}
+
+/// Format the date of a point in time in UTC, like `2023-08-26`.
+fn format_date(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+    let (year, month, day) = timing::civil_date(seconds / 86400);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_parse() {
+        let template: Template = "File {file}, {{{algorithm}}}: {hash}".parse().unwrap();
+        assert_eq!(
+            template.parts,
+            [
+                Part::Text(String::from("File ")),
+                Part::Placeholder("file"),
+                Part::Text(String::from(", {")),
+                Part::Placeholder("algorithm"),
+                Part::Text(String::from("}: ")),
+                Part::Placeholder("hash"),
+            ]
+        );
+
+        assert_eq!("".parse::<Template>().unwrap().parts, []);
+    }
+
+    #[test]
+    fn check_parse_errors() {
+        let err = "{hash} {md5}".parse::<Template>().unwrap_err();
+        assert_eq!(
+            err,
+            "unknown placeholder {md5} in template, expected {hash}, {file}, {filename}, \
+             {algorithm}, {date}, {lines} or {size}"
+        );
+
+        assert!("{hash"
+            .parse::<Template>()
+            .unwrap_err()
+            .contains("unclosed"));
+        assert!("hash}"
+            .parse::<Template>()
+            .unwrap_err()
+            .contains("unmatched"));
+    }
+
+    #[test]
+    fn check_format_date() {
+        assert_eq!(format_date(UNIX_EPOCH), "1970-01-01");
+
+        let time = UNIX_EPOCH + Duration::from_secs(951_825_599);
+        assert_eq!(format_date(time), "2000-02-29");
+    }
+}
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+/// Literal text of `--text`, hashed like the content of a file.
+pub struct Text {
+    /// Name that is printed and recorded instead of a path, like `<text:1>`.
+    pub label: PathBuf,
+
+    pub content: String,
+}
+
+/// Label of the text with the given (1-based) number.
+fn label(number: usize) -> PathBuf {
+    PathBuf::from(format!("<text:{}>", number))
+}
+
+/// Turn the texts given on the command line into inputs, labeled in the order they are given.
+///
+/// With `join`, all texts are lines of a single input, as if they were written into one file.
+pub fn inputs(texts: &[String], join: bool) -> Vec<Text> {
+    if join && !texts.is_empty() {
+        return vec![Text {
+            label: label(1),
+            content: texts.join("\n"),
+        }];
+    }
+
+    texts
+        .iter()
+        .enumerate()
+        .map(|(index, text)| Text {
+            label: label(index + 1),
+            content: text.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_inputs() {
+        let texts = [String::from("A B"), String::from("C D")];
+
+        let separate = inputs(&texts, false);
+        assert_eq!(separate.len(), 2);
+        assert_eq!(separate[1].label, PathBuf::from("<text:2>"));
+        assert_eq!(separate[1].content, "C D");
+
+        let joined = inputs(&texts, true);
+        assert_eq!(joined.len(), 1);
+        assert_eq!(joined[0].label, PathBuf::from("<text:1>"));
+        assert_eq!(joined[0].content, "A B\nC D");
+
+        assert!(inputs(&[], true).is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use normalized_hash::Timing;
+
+use crate::check::plural;
+
+/// Timing of hashing a file or all files, as written in the JSON output.
+#[derive(Serialize)]
+pub struct TimeInfo {
+    /// Duration of hashing in milliseconds.
+    milliseconds: f64,
+
+    /// Effective throughput in bytes per second, missing if no time was measured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bytes_per_second: Option<f64>,
+}
+
+impl From<Timing> for TimeInfo {
+    fn from(timing: Timing) -> Self {
+        Self {
+            milliseconds: timing.duration.as_secs_f64() * 1000.0,
+            bytes_per_second: timing.bytes_per_second(),
+        }
+    }
+}
+
+/// Year, month and day of a number of days since the Unix epoch.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html> for the algorithm.
+pub fn civil_date(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Format a duration with a unit that keeps the number readable, like `12.34 ms`.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    if seconds >= 1.0 {
+        format!("{:.2} s", seconds)
+    } else {
+        format!("{:.2} ms", seconds * 1000.0)
+    }
+}
+
+/// Format the duration and throughput of a timing, like `12.34 ms, 56.7 MB/s`.
+fn format_timing(timing: &Timing) -> String {
+    match timing.bytes_per_second() {
+        Some(bytes_per_second) => format!(
+            "{}, {:.1} MB/s",
+            format_duration(timing.duration),
+            bytes_per_second / 1_000_000.0
+        ),
+        None => format_duration(timing.duration),
+    }
+}
+
+/// Print the timing of each hashed file to stderr, with `--time`.
+///
+/// With more than one file, a line with the total over all files follows, together with the
+/// `elapsed` wall time if given, which is shorter than the total if files are hashed in parallel.
+pub fn report(timings: &[(&Path, Timing)], elapsed: Option<Duration>) {
+    for (file, timing) in timings {
+        eprintln!("time: {}: {}", file.display(), format_timing(timing));
+    }
+
+    if timings.len() > 1 {
+        let total = timings
+            .iter()
+            .fold(Timing::default(), |total, &(_, timing)| total + timing);
+
+        let elapsed = elapsed
+            .map(|elapsed| format!("{} elapsed, ", format_duration(elapsed)))
+            .unwrap_or_default();
+
+        eprintln!(
+            "time: total: {} {}, {}{}",
+            timings.len(),
+            plural(timings.len(), "file", "files"),
+            elapsed,
+            format_timing(&total)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_format_timing() {
+        let timing = Timing::new(3_000_000, Duration::from_millis(1500));
+        assert_eq!(format_timing(&timing), "1.50 s, 2.0 MB/s");
+
+        let timing = Timing::new(1000, Duration::from_micros(12_346));
+        assert_eq!(format_timing(&timing), "12.35 ms, 0.1 MB/s");
+
+        assert_eq!(format_timing(&Timing::default()), "0.00 ms");
+    }
+}
@@ -0,0 +1,135 @@
+use std::io::{self, IsTerminal};
+
+use crate::error;
+use crate::exit::ExitStatus;
+
+/// Keys that end the input typed into a terminal.
+const EOF_KEYS: &str = if cfg!(windows) {
+    "Ctrl-Z and Enter"
+} else {
+    "Ctrl-D"
+};
+
+/// Standard input of the program, behind a trait so that it can be replaced in tests.
+pub trait Stdin {
+    /// Check if standard input is an interactive terminal instead of a pipe or a file.
+    fn is_terminal(&self) -> bool;
+}
+
+/// The actual standard input of the process.
+pub struct SystemStdin;
+
+impl Stdin for SystemStdin {
+    fn is_terminal(&self) -> bool {
+        io::stdin().is_terminal()
+    }
+}
+
+/// How standard input was chosen to be read.
+#[derive(Clone, Copy)]
+pub enum Selection {
+    /// Given as `-`, like for FILE_IN or `--files-from`.
+    Explicit,
+
+    /// Read without being named by a mode that works in pipelines, like the given `--filter`.
+    Implicit(&'static str),
+}
+
+/// What to do before reading standard input.
+#[derive(Debug, PartialEq, Eq)]
+enum Decision {
+    /// Read it without further notice.
+    Read,
+
+    /// Tell that typed input is awaited, then read it.
+    Notice,
+
+    /// Refuse to read it for the given flag, since waiting for typed input was most likely not
+    /// intended.
+    Refuse(&'static str),
+}
+
+fn decide(stdin: &impl Stdin, selection: Selection, no_warning: bool) -> Decision {
+    if no_warning || !stdin.is_terminal() {
+        return Decision::Read;
+    }
+
+    match selection {
+        Selection::Explicit => Decision::Notice,
+        Selection::Implicit(flag) => Decision::Refuse(flag),
+    }
+}
+
+/// Make sure that reading standard input does not silently wait for typed input.
+///
+/// If standard input is a terminal, a notice is printed before it is read, unless `no_warning`
+/// is given. Modes that read it without being told to with `-` are refused instead, since
+/// they were most likely started by accident.
+pub fn check(stdin: &impl Stdin, selection: Selection, no_warning: bool) -> Result<(), ExitStatus> {
+    match decide(stdin, selection, no_warning) {
+        Decision::Read => Ok(()),
+        Decision::Notice => {
+            eprintln!(
+                "note: reading from terminal; type your text and press {} to finish, or pass a \
+                 file path",
+                EOF_KEYS
+            );
+            Ok(())
+        }
+        Decision::Refuse(flag) => {
+            let message = format_args!(
+                "{} reads standard input, which is a terminal; pipe the input into it, or pass \
+                 --no-tty-warning to type it",
+                flag
+            );
+            error::fatal("usage", None, message);
+            Err(ExitStatus::Usage)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StubStdin(bool);
+
+    impl Stdin for StubStdin {
+        fn is_terminal(&self) -> bool {
+            self.0
+        }
+    }
+
+    #[test]
+    fn check_decide() {
+        let implicit = Selection::Implicit("--filter");
+        let cases = [
+            (false, Selection::Explicit, false, Decision::Read),
+            (false, implicit, false, Decision::Read),
+            (true, Selection::Explicit, false, Decision::Notice),
+            (true, implicit, false, Decision::Refuse("--filter")),
+            (true, Selection::Explicit, true, Decision::Read),
+            (true, implicit, true, Decision::Read),
+            (false, Selection::Explicit, true, Decision::Read),
+            (false, implicit, true, Decision::Read),
+        ];
+
+        for (terminal, selection, no_warning, expected) in cases {
+            assert_eq!(
+                decide(&StubStdin(terminal), selection, no_warning),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn check_refuses_implicit_terminal() {
+        let implicit = Selection::Implicit("--filter");
+        assert_eq!(
+            check(&StubStdin(true), implicit, false),
+            Err(ExitStatus::Usage)
+        );
+        assert_eq!(check(&StubStdin(true), Selection::Explicit, false), Ok(()));
+        assert_eq!(check(&StubStdin(false), implicit, false), Ok(()));
+    }
+}
@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use normalized_hash::Stats;
+
+use crate::check::plural;
+
+/// Print what normalization did to a file as notes on stderr, with `--verbose`.
+///
+/// With `level` 1, the number of lines, converted CRLF line endings, the byte order mark and the
+/// final newline are reported. With `level` 2 or higher, a second note counts the line endings
+/// and the changes by category.
+pub fn report(file: &Path, stats: &Stats, level: u8) {
+    if level == 0 {
+        return;
+    }
+
+    eprintln!(
+        "note: {}: {} {} read, {} CRLF {} converted, {}, {}",
+        file.display(),
+        stats.lines,
+        plural(stats.lines, "line", "lines"),
+        stats.converted_crlf,
+        plural(stats.converted_crlf, "line ending", "line endings"),
+        if stats.bom {
+            "byte order mark kept"
+        } else {
+            "no byte order mark"
+        },
+        if stats.final_newline {
+            "ends with a newline"
+        } else {
+            "no final newline"
+        },
+    );
+
+    if stats.retries > 0 {
+        eprintln!(
+            "note: {}: read again {} {}",
+            file.display(),
+            stats.retries,
+            plural(stats.retries as usize, "time", "times")
+        );
+    }
+
+    if level >= 2 {
+        let final_newline = if stats.added_final_newline {
+            "added"
+        } else if stats.removed_final_newline {
+            "removed"
+        } else {
+            "unchanged"
+        };
+
+        eprintln!(
+            "note: {}: found {} CRLF, {} LF, {} CR; converted {} CRLF, {} LF; final newline {}; {} {} changed, {} with trailing whitespace",
+            file.display(),
+            stats.crlf,
+            stats.lf,
+            stats.cr,
+            stats.converted_crlf,
+            stats.converted_lf,
+            final_newline,
+            stats.changed_lines,
+            plural(stats.changed_lines, "line", "lines"),
+            stats.trailing_whitespace,
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use clap::{Args, ValueHint};
+use serde::Serialize;
+
+use normalized_hash::Hasher;
+
+use crate::annotate::{self, Annotations, OutputFormat};
+use crate::color::Palette;
+use crate::compare::{self, Comparison};
+use crate::error::{self, ErrorInfo};
+use crate::exit::ExitStatus;
+use crate::manifest::{self, TreeArgs};
+use crate::output::{self, Output};
+use crate::summary;
+use crate::Select;
+
+#[derive(Args)]
+pub struct VerifyDirArgs {
+    /// First directory tree, called A in the results
+    #[arg(value_hint = ValueHint::DirPath)]
+    dir_a: PathBuf,
+
+    /// Second directory tree, called B in the results
+    #[arg(value_hint = ValueHint::DirPath)]
+    dir_b: PathBuf,
+
+    #[command(flatten)]
+    tree: TreeArgs,
+
+    /// Print the results as JSON document
+    #[arg(long)]
+    json: bool,
+}
+
+/// Result of a relative path when comparing two trees.
+enum Verified {
+    /// Both files are equal after normalization.
+    Identical,
+
+    /// The files differ, starting with the given (1-based) line number.
+    Different(usize),
+
+    OnlyInA,
+    OnlyInB,
+
+    /// At least one of the files is binary.
+    Skipped,
+
+    /// The files could not be compared.
+    Unreadable(io::Error),
+}
+
+impl Verified {
+    /// Name of the result in the JSON output and the summary.
+    fn name(&self) -> &'static str {
+        match self {
+            Verified::Identical => "identical",
+            Verified::Different(_) => "different",
+            Verified::OnlyInA => "only_in_a",
+            Verified::OnlyInB => "only_in_b",
+            Verified::Skipped => "skipped",
+            Verified::Unreadable(_) => "unreadable",
+        }
+    }
+
+    /// Annotate the file of `pair` that the result is about, if it is not identical.
+    fn annotate(&self, annotations: Annotations, pair: &(Option<PathBuf>, Option<PathBuf>)) {
+        let (Some(file), other) = (pair.0.as_ref().or(pair.1.as_ref()), pair.1.as_ref()) else {
+            return;
+        };
+        let path = annotate::path(file);
+        let other = other.map(|other| annotate::path(other)).unwrap_or_default();
+
+        match self {
+            Verified::Identical => {}
+            Verified::Different(line_number) => {
+                let message = format_args!("file differs from {}", other);
+                annotations.error(path, Some(*line_number), message);
+            }
+            Verified::OnlyInA => annotations.error(path, None, "file is only in A"),
+            Verified::OnlyInB => annotations.error(path, None, "file is only in B"),
+            Verified::Skipped => annotations.notice(path, None, "binary file, skipping"),
+            Verified::Unreadable(err) => {
+                let message =
+                    format_args!("cannot compare with {}: {}", other, error::message(err));
+                annotations.error(path, None, message);
+            }
+        }
+    }
+}
+
+/// Result of a relative path, as written in the JSON output.
+#[derive(Serialize)]
+struct Entry<'a> {
+    path: &'a str,
+
+    /// One of `identical`, `different`, `only_in_a`, `only_in_b`, `skipped` or `unreadable`.
+    status: &'static str,
+
+    /// First differing line, only for different files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+
+    /// Kind and message of the error, only for unreadable files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorInfo>,
+}
+
+/// Number of paths per result, as written in the JSON output.
+#[derive(Default, Serialize)]
+struct Summary {
+    identical: usize,
+    different: usize,
+    only_in_a: usize,
+    only_in_b: usize,
+    skipped: usize,
+    unreadable: usize,
+}
+
+impl Summary {
+    fn add(&mut self, result: &Verified) {
+        let count = match result {
+            Verified::Identical => &mut self.identical,
+            Verified::Different(_) => &mut self.different,
+            Verified::OnlyInA => &mut self.only_in_a,
+            Verified::OnlyInB => &mut self.only_in_b,
+            Verified::Skipped => &mut self.skipped,
+            Verified::Unreadable(_) => &mut self.unreadable,
+        };
+        *count += 1;
+    }
+
+    fn status(&self) -> ExitStatus {
+        if self.unreadable > 0 {
+            ExitStatus::Io
+        } else if self.different + self.only_in_a + self.only_in_b > 0 {
+            ExitStatus::Mismatch
+        } else {
+            ExitStatus::Success
+        }
+    }
+}
+
+/// Results of comparing two trees, as written in the JSON output.
+#[derive(Serialize)]
+struct Results<'a> {
+    entries: Vec<Entry<'a>>,
+    summary: Summary,
+}
+
+/// Compare two files line by line, without reading either of them completely.
+///
+/// Each file is normalized with its own hasher, which has the options of its directory.
+fn verify(hashers: [&Hasher; 2], file_a: &Path, file_b: &Path) -> Verified {
+    for file in [file_a, file_b] {
+        match normalized_hash::is_binary(file) {
+            Ok(false) => {}
+            Ok(true) => {
+                eprintln!("note: skipping binary file {}", file.display());
+                return Verified::Skipped;
+            }
+            Err(err) => return Verified::Unreadable(err),
+        }
+    }
+
+    let [hasher_a, hasher_b] = hashers;
+    let result = hasher_a
+        .normalized_lines(file_a)
+        .and_then(|lines_a| compare::compare(lines_a, hasher_b.normalized_lines(file_b)?));
+
+    match result {
+        Ok(Comparison::Equal) => Verified::Identical,
+        Ok(Comparison::Different(line_number)) => Verified::Different(line_number),
+        Err(err) => Verified::Unreadable(err),
+    }
+}
+
+/// Pair the files of both trees by their relative path and compare them.
+pub fn run(
+    select: Select,
+    out: &mut Output,
+    args: &VerifyDirArgs,
+    palette: Palette,
+    format: OutputFormat,
+) -> ExitStatus {
+    let start = Instant::now();
+    let annotations = Annotations::new(format, args.json);
+
+    let mut pairs = BTreeMap::<String, (Option<PathBuf>, Option<PathBuf>)>::new();
+    for (root, is_a) in [(&args.dir_a, true), (&args.dir_b, false)] {
+        let paths = match manifest::walk(root, &args.tree, None) {
+            Ok(paths) => paths,
+            Err(status) => return status,
+        };
+        for (path, file) in paths {
+            let pair = pairs.entry(path).or_default();
+            if is_a {
+                pair.0 = Some(file);
+            } else {
+                pair.1 = Some(file);
+            }
+        }
+    }
+
+    // Both files of each pair, in the order of the pairs
+    let compared = pairs
+        .values()
+        .filter_map(|pair| match pair {
+            (Some(file_a), Some(file_b)) => Some([file_a, file_b]),
+            _ => None,
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+    let hashers = match select(&compared) {
+        Ok(hashers) => hashers,
+        Err(status) => return status,
+    };
+
+    let mut summary = Summary::default();
+    let mut entries = Vec::new();
+    let mut next = 0;
+
+    for (path, pair) in &pairs {
+        let result = match pair {
+            (Some(file_a), Some(file_b)) => {
+                let pair_hashers = [hashers.of(next), hashers.of(next + 1)];
+                next += 2;
+                verify(pair_hashers, file_a, file_b)
+            }
+            (Some(_), None) => Verified::OnlyInA,
+            (None, _) => Verified::OnlyInB,
+        };
+        summary.add(&result);
+
+        if args.json {
+            entries.push(Entry {
+                path,
+                status: result.name(),
+                line: match result {
+                    Verified::Different(line_number) => Some(line_number),
+                    _ => None,
+                },
+                error: match &result {
+                    Verified::Unreadable(err) => Some(ErrorInfo::new(err)),
+                    _ => None,
+                },
+            });
+            result.annotate(annotations, pair);
+            continue;
+        }
+
+        match &result {
+            Verified::Identical => println!("{}: {}", path, palette.ok("identical")),
+            Verified::Different(line_number) => {
+                println!(
+                    "{}: {} in line {}",
+                    path,
+                    palette.failed("different"),
+                    line_number
+                );
+            }
+            Verified::OnlyInA => println!("{}: {}", path, palette.missing("only in A")),
+            Verified::OnlyInB => println!("{}: {}", path, palette.missing("only in B")),
+            Verified::Skipped => {}
+            Verified::Unreadable(err) => {
+                eprintln!(
+                    "warning: cannot compare {} in both trees: {}",
+                    path,
+                    error::message(err)
+                );
+                println!("{}: {}", path, palette.failed("FAILED open or read"));
+            }
+        }
+        result.annotate(annotations, pair);
+    }
+
+    let status = summary.status();
+    if args.json {
+        let results = Results { entries, summary };
+
+        out.line(output::json(&results, true));
+    } else {
+        let counts = [
+            (summary.identical, "identical"),
+            (summary.different, "different"),
+            (summary.only_in_a, "only in A"),
+            (summary.only_in_b, "only in B"),
+            (summary.skipped, "skipped"),
+            (summary.unreadable, "unreadable"),
+        ];
+        println!("{}", summary::line(&counts, start.elapsed()));
+    }
+
+    status
+}
@@ -0,0 +1,237 @@
+#[cfg(feature = "watch")]
+use std::path::Path;
+use std::path::PathBuf;
+#[cfg(feature = "watch")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use normalized_hash::Hasher;
+
+use crate::error;
+use crate::exit::ExitStatus;
+use crate::output::Output;
+#[cfg(feature = "watch")]
+use crate::timing;
+
+/// Time without further changes before a file is hashed again.
+///
+/// Editors often write a file more than once when saving, which should only print one line.
+#[cfg(feature = "watch")]
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Format a point in time in UTC, like `2023-08-26T14:05:09Z`.
+#[cfg(feature = "watch")]
+fn format_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+    let (year, month, day) = timing::civil_date(seconds / 86400);
+    let seconds = seconds % 86400;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Hash a file and print a line like `TIMESTAMP  HASH  FILE`.
+///
+/// Errors are only printed as warnings, since the file might be fixed with the next change.
+#[cfg(feature = "watch")]
+fn hash(
+    hasher: &Hasher,
+    out: &mut Output,
+    file: &Path,
+    name: &Path,
+    quiet: bool,
+    display: &dyn Fn(&str) -> String,
+) {
+    match hasher.try_hash_file(file, None::<&Path>) {
+        Ok(hash) => out.line(format_args!(
+            "{}  {}  {}",
+            format_timestamp(SystemTime::now()),
+            display(&hash),
+            normalized_hash::escape_path(name)
+        )),
+        Err(_) if !file.exists() => {
+            if !quiet {
+                eprintln!(
+                    "warning: {} was removed, waiting for it to reappear",
+                    file.display()
+                );
+            }
+        }
+        Err(err) => {
+            if !quiet {
+                eprintln!("warning: {}", error::describe(&err, file, None));
+            }
+        }
+    }
+}
+
+/// Path under which change events for a file are reported, `None` if it has no file name.
+///
+/// The parent directory is resolved, so paths of events in the watched directory can be
+/// compared directly.
+#[cfg(feature = "watch")]
+fn watched_path(file: &Path) -> Option<(PathBuf, PathBuf)> {
+    let parent = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let dir = std::fs::canonicalize(parent).ok()?;
+    let path = dir.join(file.file_name()?);
+
+    Some((dir, path))
+}
+
+/// Hash the files, then hash them again whenever they change, until interrupted with Ctrl-C.
+///
+/// The parent directories are watched instead of the files themselves, so a file that is
+/// replaced by renaming another file over it, like editors do to save atomically, is still
+/// watched afterwards. A file that is removed is hashed again once it reappears.
+#[cfg(feature = "watch")]
+pub fn run(
+    hasher: &Hasher,
+    out: &mut Output,
+    files: &[PathBuf],
+    names: &[PathBuf],
+    quiet: bool,
+    display: &dyn Fn(&str) -> String,
+) -> ExitStatus {
+    use std::collections::BTreeSet;
+    use std::sync::mpsc::{self, RecvTimeoutError};
+
+    use notify::{EventKind, RecursiveMode, Watcher};
+
+    enum Message {
+        Changed(Vec<PathBuf>),
+        Failed(notify::Error),
+        Interrupted,
+    }
+
+    let mut watched = Vec::with_capacity(files.len());
+    let mut dirs = BTreeSet::new();
+    for file in files {
+        let Some((dir, path)) = watched_path(file) else {
+            let message = format_args!("cannot watch {}", file.display());
+            error::fatal("io", Some(file), message);
+            return ExitStatus::Io;
+        };
+        dirs.insert(dir);
+        watched.push(path);
+    }
+
+    let (sender, receiver) = mpsc::channel();
+
+    let interrupt = sender.clone();
+    if let Err(err) = ctrlc::set_handler(move || {
+        let _ = interrupt.send(Message::Interrupted);
+    }) {
+        let message = format_args!("cannot handle Ctrl-C: {}", err);
+        error::fatal("io", None, message);
+        return ExitStatus::Io;
+    }
+
+    let watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let message = match result {
+            // Reading the files to hash them is reported as access as well
+            Ok(event) if matches!(event.kind, EventKind::Access(_)) => return,
+            Ok(event) => Message::Changed(event.paths),
+            Err(err) => Message::Failed(err),
+        };
+        let _ = sender.send(message);
+    });
+
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            error::fatal("io", None, format_args!("cannot watch files: {}", err));
+            return ExitStatus::Io;
+        }
+    };
+
+    for dir in &dirs {
+        if let Err(err) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            let message = format_args!("cannot watch {}: {}", dir.display(), err);
+            error::fatal("io", Some(dir), message);
+            return ExitStatus::Io;
+        }
+    }
+
+    for (file, name) in files.iter().zip(names) {
+        hash(hasher, out, file, name, quiet, display);
+    }
+
+    // Indices of the files that changed since they were hashed last
+    let mut pending = BTreeSet::<usize>::new();
+
+    loop {
+        let message = if pending.is_empty() {
+            receiver.recv().ok()
+        } else {
+            match receiver.recv_timeout(DEBOUNCE) {
+                Ok(message) => Some(message),
+                Err(RecvTimeoutError::Timeout) => {
+                    for index in std::mem::take(&mut pending) {
+                        hash(hasher, out, &files[index], &names[index], quiet, display);
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => None,
+            }
+        };
+
+        match message {
+            Some(Message::Changed(paths)) => pending.extend(
+                watched
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, path)| paths.contains(path))
+                    .map(|(index, _)| index),
+            ),
+            Some(Message::Failed(err)) => {
+                if !quiet {
+                    eprintln!("warning: cannot watch files: {}", err);
+                }
+            }
+            Some(Message::Interrupted) | None => return ExitStatus::Success,
+        }
+    }
+}
+
+/// Placeholder if the binary was built without the `watch` feature.
+#[cfg(not(feature = "watch"))]
+pub fn run(
+    _hasher: &Hasher,
+    _out: &mut Output,
+    _files: &[PathBuf],
+    _names: &[PathBuf],
+    _quiet: bool,
+    _display: &dyn Fn(&str) -> String,
+) -> ExitStatus {
+    let message = "this build does not support --watch, enable the feature `watch`";
+    error::fatal("usage", None, message);
+    ExitStatus::Usage
+}
+
+#[cfg(all(test, feature = "watch"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_format_timestamp() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+
+        let time = UNIX_EPOCH + Duration::from_secs(1_693_058_709);
+        assert_eq!(format_timestamp(time), "2023-08-26T14:05:09Z");
+
+        let time = UNIX_EPOCH + Duration::from_secs(951_825_599);
+        assert_eq!(format_timestamp(time), "2000-02-29T11:59:59Z");
+    }
+}
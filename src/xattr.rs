@@ -0,0 +1,207 @@
+use std::io;
+use std::path::Path;
+
+use normalized_hash::{Algorithm, Encoding, Hasher};
+
+use crate::color::Palette;
+use crate::exit::ExitStatus;
+use crate::output::Output;
+
+/// Name of the extended attribute that holds the hash of a file, like
+/// `user.normalized_hash.sha256`.
+///
+/// Each algorithm has an attribute of its own, so hashes of several algorithms can be stored.
+pub fn attribute(algorithm: Algorithm) -> String {
+    format!("user.normalized_hash.{}", algorithm.name())
+}
+
+/// Version of the attribute value, to be raised when its format changes.
+const VERSION: &str = "v1";
+
+/// Result of verifying a file against its extended attribute.
+#[derive(Debug, PartialEq, Eq)]
+pub enum XattrStatus {
+    /// The hash matches the one in the attribute.
+    Ok,
+
+    /// The hash differs from the one in the attribute.
+    Failed,
+
+    /// The attribute was written with other normalization options, which are returned.
+    Options(String),
+
+    /// The file has no such attribute.
+    Unset,
+}
+
+/// Format the value of the attribute, like `v1 eol=0a ignore-whitespaces=false no-eof=false HASH`.
+///
+/// `options` describe the normalization, so that hashes of other options are not mistaken for a
+/// mismatch of the content.
+fn format_value(options: &str, hash: &str) -> String {
+    format!("{} {} {}", VERSION, options, hash)
+}
+
+/// Split the value of the `attribute` into the options and the hash.
+fn parse_value<'a>(attribute: &str, value: &'a str) -> Result<(&'a str, &'a str), String> {
+    let invalid = || format!("invalid value of {}: {:?}", attribute, value);
+
+    let (version, rest) = value.split_once(' ').ok_or_else(invalid)?;
+    if version != VERSION {
+        return Err(format!(
+            "unsupported version {} of {}, expected {}",
+            version, attribute, VERSION
+        ));
+    }
+
+    rest.rsplit_once(' ').ok_or_else(invalid)
+}
+
+/// Explain errors of file systems without extended attributes, which are otherwise cryptic.
+#[cfg(feature = "xattr")]
+fn describe(err: io::Error) -> io::Error {
+    if err.kind() == io::ErrorKind::Unsupported {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the file system does not support extended attributes",
+        )
+    } else {
+        err
+    }
+}
+
+#[cfg(feature = "xattr")]
+fn get(file: &Path, attribute: &str) -> io::Result<Option<Vec<u8>>> {
+    ::xattr::get(normalized_hash::long_path(file), attribute).map_err(describe)
+}
+
+#[cfg(feature = "xattr")]
+fn set(file: &Path, attribute: &str, value: &str) -> io::Result<()> {
+    ::xattr::set(
+        normalized_hash::long_path(file),
+        attribute,
+        value.as_bytes(),
+    )
+    .map_err(describe)
+}
+
+/// Placeholder if the binary was built without the `xattr` feature.
+#[cfg(not(feature = "xattr"))]
+fn get(_file: &Path, _attribute: &str) -> io::Result<Option<Vec<u8>>> {
+    Err(unsupported())
+}
+
+/// Placeholder if the binary was built without the `xattr` feature.
+#[cfg(not(feature = "xattr"))]
+fn set(_file: &Path, _attribute: &str, _value: &str) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "xattr"))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "this build does not support extended attributes, enable the feature `xattr`",
+    )
+}
+
+/// Store the hex `hash` of `file`, computed with the normalization `options`, in the attribute
+/// of its `algorithm`.
+pub fn store(file: &Path, algorithm: Algorithm, options: &str, hash: &str) -> io::Result<()> {
+    set(file, &attribute(algorithm), &format_value(options, hash))
+}
+
+/// Hash `file` and compare it with the hash in its attribute, which had to be computed with the
+/// same normalization `options`.
+pub fn verify(hasher: &Hasher, file: &Path, options: &str) -> io::Result<XattrStatus> {
+    let attribute = attribute(hasher.digest_algorithm());
+    let Some(value) = get(file, &attribute)? else {
+        return Ok(XattrStatus::Unset);
+    };
+
+    let value = String::from_utf8_lossy(&value);
+    let (stored, expected) = parse_value(&attribute, &value)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    if stored != options {
+        return Ok(XattrStatus::Options(stored.to_string()));
+    }
+
+    let hash = hasher.try_hash_file(file, None::<&Path>)?;
+    if Encoding::decode(expected) == Encoding::decode(&hash) {
+        Ok(XattrStatus::Ok)
+    } else {
+        Ok(XattrStatus::Failed)
+    }
+}
+
+/// Verify `file` against its attribute and print the result, like `FILE: OK`.
+pub fn print_verify(
+    hasher: &Hasher,
+    out: &mut Output,
+    palette: Palette,
+    file: &Path,
+    options: &str,
+) -> ExitStatus {
+    let status = match verify(hasher, file, options) {
+        Ok(status) => status,
+        Err(err) => {
+            eprintln!("error: cannot verify {}: {}", file.display(), err);
+            return ExitStatus::Io;
+        }
+    };
+
+    match status {
+        XattrStatus::Ok => {
+            out.line(format_args!("{}: {}", file.display(), palette.ok("OK")));
+            ExitStatus::Success
+        }
+        XattrStatus::Failed => {
+            out.line(format_args!(
+                "{}: {}",
+                file.display(),
+                palette.failed("FAILED")
+            ));
+            ExitStatus::Mismatch
+        }
+        XattrStatus::Options(stored) => {
+            out.line(format_args!(
+                "{}: {} (stored with {})",
+                file.display(),
+                palette.failed("FAILED"),
+                stored
+            ));
+            ExitStatus::Mismatch
+        }
+        XattrStatus::Unset => {
+            out.line(format_args!(
+                "{}: {}",
+                file.display(),
+                palette.missing("UNSET")
+            ));
+            ExitStatus::Io
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_value() {
+        let attribute = attribute(Algorithm::Sha256);
+        assert_eq!(attribute, "user.normalized_hash.sha256");
+
+        let options = "eol=0a ignore-whitespaces=false no-eof=false";
+        let value = format_value(options, "abc");
+        assert_eq!(value, format!("v1 {} abc", options));
+        assert_eq!(parse_value(&attribute, &value), Ok((options, "abc")));
+
+        assert!(parse_value(&attribute, "v2 eol=0a abc")
+            .unwrap_err()
+            .contains("unsupported version v2 of user.normalized_hash.sha256"));
+        assert!(parse_value(&attribute, "abc")
+            .unwrap_err()
+            .contains("invalid value"));
+    }
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Hash of the test content `"A B\nC D\n"` with SHA-512.
+const HASH_SHA512: &str = "29c0a3194e931afdd9c70a72a66eefd025f2a69b7d9b87c7a9c7ae7f18c08a96\
+                           74c6b791ba694dd06f8e51d593e8cec0750d7b3fab630a4492bd64f62bcb0e76";
+
+/// Hash of the test content `"A B\nC D\n"` with BLAKE3.
+const HASH_BLAKE3: &str = "42e408265b443f8e341d1fb91eec9b6983af08f784c5c9827523749b77b26349";
+
+#[test]
+fn algorithm_hashes() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let cases = [
+        (None, HASH_DEFAULT),
+        (Some("sha256"), HASH_DEFAULT),
+        (Some("sha512"), HASH_SHA512),
+        (Some("blake3"), HASH_BLAKE3),
+    ];
+
+    for (algorithm, hash) in cases {
+        let output = match algorithm {
+            Some(algorithm) => env.run(["--algorithm", algorithm, "input.txt"]),
+            None => env.run(["input.txt"]),
+        };
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(stdout(&output), format!("{hash}\n"));
+    }
+
+    let output = env.run(["-a", "sha512", "--tag", "input.txt"]);
+    assert_eq!(
+        stdout(&output),
+        format!("SHA512 (input.txt) = {HASH_SHA512}\n")
+    );
+
+    // Encodings naming the algorithm tell BLAKE3 apart from SHA-256
+    let output = env.run(["-a", "blake3", "--format", "sri", "input.txt"]);
+    assert_eq!(
+        stdout(&output),
+        "blake3-QuQIJltEP440HR+5HuybaYOvCPeExcmCdSN0m3eyY0k=\n"
+    );
+    let output = env.run(["-a", "blake3", "--format", "multihash", "input.txt"]);
+    assert_eq!(stdout(&output), format!("1e20{HASH_BLAKE3}\n"));
+}
+
+#[test]
+fn algorithm_invalid() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\n");
+
+    let output = env.run(["--algorithm", "md5", "input.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("[possible values: sha256, sha512, blake3]"));
+}
+
+#[test]
+fn algorithm_recorded_for_check() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["-a", "sha512", "--tag", "input.txt", "-o", "sums.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(env.path().join("sums.txt")).unwrap(),
+        format!(
+            "# normalized-hasher: eol=lf ignore_whitespaces=false no_eof=false algorithm=sha512 \
+             format=v1\nSHA512 (input.txt) = {HASH_SHA512}\n"
+        )
+    );
+
+    // The recorded algorithm is used without giving it again
+    let output = env.run(["--check", "sums.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("input.txt: OK\n"));
+
+    let output = env.run(["-a", "sha256", "--check", "sums.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("generated with algorithm=sha512"));
+}
+
+#[test]
+fn algorithm_check_other_lines() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+    env.write(
+        "sums.txt",
+        format!("SHA512 (input.txt) = {HASH_SHA512}\n{HASH_DEFAULT}  input.txt\n"),
+    );
+
+    let output = env.run(["--algorithm", "sha512", "--check", "sums.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("input.txt: OK\n"));
+    assert!(stderr(&output).contains("1 line is improperly formatted"));
+
+    // Without a chosen algorithm, each BSD line is checked with the one it names
+    let output = env.run(["--check", "sums.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("input.txt: OK\ninput.txt: OK\n"));
+    assert_eq!(stderr(&output), "");
+
+    // An explicitly chosen algorithm conflicts with the named one, which is still supported
+    let output = env.run(["-a", "sha256", "--check", "sums.txt"]);
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = stderr(&output);
+    assert!(stderr.contains(
+        "sums.txt:1: SHA512 line skipped, checking with SHA256 (--algorithm or configuration)"
+    ));
+    assert!(stderr.contains("WARNING: 1 line uses another algorithm than the selected one"));
+    assert!(!stderr.contains("unsupported"));
+}
+
+#[test]
+fn algorithm_expected() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["-a", "sha512", "--expected", HASH_SHA512, "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("OK\n"));
+
+    let output = env.run(["--expected", HASH_SHA512, "input.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains(
+        "--expected has 128 hex digits, but a SHA-256 hash has 64, select the algorithm with \
+         --algorithm"
+    ));
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use serde_json::Value;
+
+use common::{fixture, normalized_hasher, stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn annotate_lint_offender() {
+    let output = normalized_hasher()
+        .current_dir(fixture("annotate"))
+        .args(["--output-format", "github", "lint", "clean.md", "crlf.md"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "crlf.md\n::error file=crlf.md,line=2::file is not normalized\n"
+    );
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn annotate_plain_by_default() {
+    let output = normalized_hasher()
+        .current_dir(fixture("annotate"))
+        .args(["lint", "clean.md", "crlf.md"])
+        .output()
+        .unwrap();
+
+    assert_eq!(stdout(&output), "crlf.md\n");
+}
+
+#[test]
+fn annotate_check() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b,c.txt", "E F\n");
+    env.write(
+        "sums.txt",
+        format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  b,c.txt\nnot a checksum\n"),
+    );
+
+    let output = env.run([
+        "--output-format",
+        "github",
+        "--quiet",
+        "--no-summary",
+        "--check",
+        "sums.txt",
+    ]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "b,c.txt: FAILED\n\
+         ::error file=b%2Cc.txt::computed checksum did NOT match\n\
+         ::notice file=sums.txt,line=3::improperly formatted checksum line, skipping\n"
+    );
+}
+
+#[test]
+fn annotate_manifest_verify() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\n");
+    env.write("tree/b.txt", "C D\n");
+    env.run(["manifest", "generate", "tree", "-o", "MANIFEST"]);
+    env.write("tree/b.txt", "C D E\n");
+
+    let output = env.run([
+        "--output-format",
+        "github",
+        "manifest",
+        "verify",
+        "tree",
+        "MANIFEST",
+    ]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).starts_with(
+        "a.txt: OK\n\
+         b.txt: CHANGED\n\
+         ::error file=tree/b.txt::file changed since the manifest was generated\n"
+    ));
+}
+
+#[test]
+fn annotate_verify_dir() {
+    let env = TestEnv::new();
+    env.write("a/x.txt", "A B\nC D\n");
+    env.write("b/x.txt", "A B\r\nC E\r\n");
+
+    let output = env.run(["--output-format", "github", "verify-dir", "a", "b"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).starts_with(
+        "x.txt: different in line 2\n\
+         ::error file=a/x.txt,line=2::file differs from b/x.txt\n"
+    ));
+
+    // The JSON document stays intact, annotations go to stderr instead
+    let output = env.run([
+        "--output-format",
+        "github",
+        "verify-dir",
+        "--json",
+        "a",
+        "b",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let results: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(results["entries"][0]["line"], 2);
+    assert_eq!(
+        stderr(&output),
+        "::error file=a/x.txt,line=2::file differs from b/x.txt\n"
+    );
+}
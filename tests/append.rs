@@ -0,0 +1,125 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, TestEnv, HASH_DEFAULT, HEADER_DEFAULT};
+
+const HASH_OTHER: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[test]
+fn append_new_entries() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("b.txt", "A B\nC D\n");
+
+    let output = env.run(["--append", "SHA256SUMS", "a.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = env.run(["--append", "SHA256SUMS", "--tag", "b.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    assert_eq!(
+        fs::read_to_string(env.path().join("SHA256SUMS")).unwrap(),
+        format!("{HEADER_DEFAULT}\n{HASH_DEFAULT}  a.txt\nSHA256 (b.txt) = {HASH_DEFAULT}\n")
+    );
+}
+
+#[test]
+fn append_in_subdirectory() {
+    let env = TestEnv::new();
+    env.write("dist/a.tar", "A B\nC D\n");
+    env.write("src/b.txt", "A B\nC D\n");
+
+    for file in ["dist/a.tar", "src/b.txt"] {
+        let output = env.run(["--append", "dist/APPEND", file]);
+        assert_eq!(output.status.code(), Some(0));
+    }
+
+    // Paths are listed relative to the checksum file, which `--check` resolves them against
+    assert_eq!(
+        fs::read_to_string(env.path().join("dist/APPEND")).unwrap(),
+        format!("{HEADER_DEFAULT}\n{HASH_DEFAULT}  a.tar\n{HASH_DEFAULT}  ../src/b.txt\n")
+    );
+
+    let output = env.run(["--check", "dist/APPEND"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+}
+
+#[test]
+fn append_replaces_changed_entry_and_preserves_comments() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    let sums = env.write(
+        "SHA256SUMS",
+        format!(
+            "# release 1.0\n{HASH_OTHER}  b.txt\n{HASH_OTHER}  a.txt\n# end\n{HASH_OTHER}  a.txt\n"
+        ),
+    );
+
+    let output = env.run(["--append", "SHA256SUMS", "a.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(sums).unwrap(),
+        format!("# release 1.0\n{HASH_OTHER}  b.txt\n{HASH_DEFAULT}  a.txt\n# end\n")
+    );
+}
+
+#[test]
+fn append_no_update_rejects_conflict() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    let content = format!("{HASH_OTHER}  a.txt\n");
+    let sums = env.write("SHA256SUMS", &content);
+
+    let output = env.run(["--append", "SHA256SUMS", "--no-update", "a.txt"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("different hash"));
+    assert_eq!(fs::read_to_string(&sums).unwrap(), content);
+
+    // The same hash is no conflict
+    env.write("SHA256SUMS", format!("{HASH_DEFAULT}  a.txt\n"));
+
+    let output = env.run(["--append", "SHA256SUMS", "--no-update", "a.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[cfg(unix)]
+#[test]
+fn append_replaces_file_atomically() {
+    use std::os::unix::fs::MetadataExt;
+
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    let sums = env.write("SHA256SUMS", "# checksums\n");
+
+    // Keep the original file open, like a concurrent reader would
+    let before = fs::File::open(&sums).unwrap();
+
+    let output = env.run(["--append", "SHA256SUMS", "a.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    // The file has been replaced by a new one instead of being rewritten
+    assert_ne!(
+        before.metadata().unwrap().ino(),
+        fs::metadata(&sums).unwrap().ino()
+    );
+    assert_eq!(fs::read_to_string(&sums).unwrap().lines().count(), 2);
+}
+
+#[test]
+fn append_refuses_other_options() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b.txt", "A B\nC D\n");
+
+    let output = env.run(["--append", "SHA256SUMS", "--no-eof", "a.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = env.run(["--append", "SHA256SUMS", "b.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output)
+        .contains("it was generated with eol=lf ignore_whitespaces=false no_eof=true"));
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use std::fs::{self, File};
+use std::time::{Duration, SystemTime};
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Number of notes about hashes that were taken from the cache.
+fn cached_notes(output: &std::process::Output) -> usize {
+    stderr(output)
+        .lines()
+        .filter(|line| line.ends_with(": unchanged, using cached hash"))
+        .count()
+}
+
+fn setup() -> TestEnv {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    env.write("tree/b.txt", "A B\nC D\n");
+    env.write("tree/sub/c.txt", "E F\n");
+    env.write("tree/image.bin", b"\0\x01\x02");
+    env
+}
+
+#[test]
+fn cache_reuses_unchanged_files() {
+    let env = setup();
+
+    let first = env.run(["--cache", "hashes.cache", "-v", "-r", "tree"]);
+    assert_eq!(first.status.code(), Some(0));
+    assert_eq!(cached_notes(&first), 0);
+    assert!(env.path().join("hashes.cache").exists());
+
+    let second = env.run(["--cache", "hashes.cache", "-v", "-r", "tree"]);
+    assert_eq!(second.status.code(), Some(0));
+    assert_eq!(cached_notes(&second), 3);
+    assert!(stderr(&second).contains("note: skipping binary file tree/image.bin"));
+    assert_eq!(first.stdout, second.stdout);
+}
+
+#[test]
+fn cache_does_not_read_unchanged_files() {
+    let env = setup();
+    env.run(["--cache", "hashes.cache", "-r", "tree"]);
+
+    // Same size and modification time, so only reading the file reveals the change
+    let path = env.path().join("tree/b.txt");
+    let modified = fs::metadata(&path).unwrap().modified().unwrap();
+    fs::write(&path, "X Y\nZ W\n").unwrap();
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+
+    let output = env.run(["--cache", "hashes.cache", "-r", "tree"]);
+    assert!(stdout(&output).contains(&format!("{HASH_DEFAULT}  tree/b.txt\n")));
+
+    let output = env.run(["--cache", "hashes.cache", "--no-cache-read", "-r", "tree"]);
+    assert!(!stdout(&output).contains(&format!("{HASH_DEFAULT}  tree/b.txt\n")));
+}
+
+#[test]
+fn cache_verify_sample_detects_stale_entries() {
+    let env = setup();
+    env.run(["--cache", "hashes.cache", "-r", "tree"]);
+
+    let path = env.path().join("tree/b.txt");
+    let modified = fs::metadata(&path).unwrap().modified().unwrap();
+    fs::write(&path, "X Y\nZ W\n").unwrap();
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+
+    let args = ["--cache", "hashes.cache", "--cache-verify-sample", "100%"];
+    let output = env.run(args.iter().chain(&["-v", "-r", "tree"]));
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(cached_notes(&output), 0);
+    assert!(!stdout(&output).contains(&format!("{HASH_DEFAULT}  tree/b.txt\n")));
+    assert!(stderr(&output).contains("warning: cached hash of tree/b.txt was stale\n"));
+
+    // The entry was updated
+    let output = env.run(["--cache", "hashes.cache", "-r", "tree"]);
+    assert!(!stdout(&output).contains(&format!("{HASH_DEFAULT}  tree/b.txt\n")));
+}
+
+#[test]
+fn cache_touched_file_is_hashed_again() {
+    let env = setup();
+    env.run(["--cache", "hashes.cache", "-r", "tree"]);
+
+    let path = env.path().join("tree/sub/c.txt");
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(10))
+        .unwrap();
+
+    let output = env.run(["--cache", "hashes.cache", "-v", "-r", "tree"]);
+    assert_eq!(cached_notes(&output), 2);
+    assert!(!stderr(&output).contains("tree/sub/c.txt: unchanged"));
+    assert!(stderr(&output).contains("note: tree/sub/c.txt: 1 line read"));
+}
+
+#[test]
+fn cache_corrupt_file_hashes_everything() {
+    let env = setup();
+    let reference = env.run(["-r", "tree"]);
+
+    env.write("hashes.cache", "\0garbage\n");
+    let output = env.run(["--cache", "hashes.cache", "-v", "-r", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(cached_notes(&output), 0);
+    assert!(
+        stderr(&output).contains("warning: hashes.cache is not a valid cache, hashing all files\n")
+    );
+    assert_eq!(output.stdout, reference.stdout);
+
+    // The cache was written from scratch
+    let output = env.run(["--cache", "hashes.cache", "-v", "-r", "tree"]);
+    assert_eq!(cached_notes(&output), 3);
+
+    let content = fs::read_to_string(env.path().join("hashes.cache")).unwrap();
+    env.write("hashes.cache", content.replace('\t', " "));
+    let output = env.run(["--cache", "hashes.cache", "-v", "-r", "tree"]);
+    assert_eq!(cached_notes(&output), 0);
+    assert!(stderr(&output).contains("warning: cache hashes.cache is corrupt, hashing all files\n"));
+    assert_eq!(output.stdout, reference.stdout);
+}
+
+#[test]
+fn cache_depends_on_options() {
+    let env = setup();
+    env.run(["--cache", "hashes.cache", "-r", "tree"]);
+
+    let reference = env.run(["--eol", "\r\n", "-r", "tree"]);
+    let output = env.run([
+        "--cache",
+        "hashes.cache",
+        "--eol",
+        "\r\n",
+        "-v",
+        "-r",
+        "tree",
+    ]);
+
+    assert_eq!(cached_notes(&output), 0);
+    assert_eq!(output.stdout, reference.stdout);
+}
+
+#[test]
+fn cache_conflicts_with_deny_mixed_eol() {
+    let env = setup();
+
+    let output = env.run(["--cache", "hashes.cache", "--deny-mixed-eol", "-r", "tree"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
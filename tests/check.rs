@@ -0,0 +1,256 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn check_mixed_formats() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("b (1).txt", "A B\nC D\n");
+    env.write(
+        "sums.txt",
+        format!("# checksums\n{HASH_DEFAULT}  a.txt\n\nSHA256 (b (1).txt) = {HASH_DEFAULT}\n"),
+    );
+
+    let output = env.run(["--no-summary", "--check", "sums.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "a.txt: OK\nb (1).txt: OK\n");
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn check_mismatch_and_missing() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b.txt", "A B\nC D\nE F\n");
+    env.write(
+        "sums.txt",
+        format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  b.txt\n{HASH_DEFAULT}  c.txt\n"),
+    );
+
+    let output = env.run(["--no-summary", "-c", "sums.txt"]);
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        stdout(&output),
+        "a.txt: OK\nb.txt: FAILED\nc.txt: FAILED open or read\n"
+    );
+
+    let stderr = stderr(&output);
+    assert!(stderr.contains("WARNING: 1 listed file could not be read"));
+    assert!(stderr.contains("WARNING: 1 computed checksum did NOT match"));
+}
+
+#[test]
+fn check_malformed_lines() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("sums.txt", format!("{HASH_DEFAULT}  a.txt\ngarbage\n"));
+
+    let output = env.run(["--no-summary", "--check", "sums.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "a.txt: OK\n");
+    assert!(stderr(&output).contains("WARNING: 1 line is improperly formatted"));
+
+    env.write("sums.txt", "garbage\n");
+
+    let output = env.run(["--no-summary", "--check", "sums.txt"]);
+
+    assert_eq!(output.status.code(), Some(4));
+    assert!(stderr(&output).contains("no properly formatted checksum lines found"));
+}
+
+#[test]
+fn check_unsupported_algorithm() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("sums.txt", format!("MD5 (a.txt) = {HASH_DEFAULT}\n"));
+
+    let output = env.run(["--no-summary", "--check", "sums.txt"]);
+
+    assert_eq!(output.status.code(), Some(4));
+    assert!(stdout(&output).is_empty());
+    assert!(stderr(&output).contains("sums.txt:1: unsupported algorithm MD5"));
+}
+
+#[test]
+fn check_from_stdin() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("dir/b.txt", "A B\nC D\n");
+
+    let list = format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  dir/b.txt\n");
+    let output = env.run_with_stdin(["--no-summary", "--check", "-"], list);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "a.txt: OK\ndir/b.txt: OK\n");
+}
+
+#[test]
+fn check_from_stdin_rejects_stdin_entry() {
+    let env = TestEnv::new();
+
+    let list = format!("{HASH_DEFAULT}  -\n");
+    let output = env.run_with_stdin(["--check", "-"], list);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("cannot read file content from standard input"));
+}
+
+#[test]
+fn check_quiet_and_status() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b.txt", "A B\nC D\nE F\n");
+    let list = format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  b.txt\n");
+
+    let output = env.run_with_stdin(["--no-summary", "--check", "--quiet", "-"], &list);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "b.txt: FAILED\n");
+
+    let output = env.run_with_stdin(["--no-summary", "--check", "--status", "-"], &list);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn check_uses_recorded_options() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A  B\r\nC D");
+    env.write("tree/b.txt", "AB\nCD\n");
+
+    let output = env.run([
+        "--eol",
+        "crlf",
+        "--no-eof",
+        "--ignore-whitespaces",
+        "-r",
+        "tree",
+        "-o",
+        "SUMS",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    let sums = std::fs::read_to_string(env.path().join("SUMS")).unwrap();
+    assert!(sums.starts_with(
+        "# normalized-hasher: eol=crlf ignore_whitespaces=true no_eof=true algorithm=sha256 format=v1\n"
+    ));
+
+    let output = env.run(["--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+
+    // Without the header, the default options are used
+    let bare = sums.lines().skip(1).collect::<Vec<_>>().join("\n");
+    env.write("BARE", bare);
+    let output = env.run(["--check", "BARE"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn check_applies_unrecorded_options() {
+    let env = TestEnv::new();
+    env.write("a.md", "First line\nwrapped, id=42.\n\nSecond.\n");
+    env.write("b.md", "Another\nparagraph.\n");
+
+    let options = ["--paragraph-mode", "--redact", r"id=\d+=>id=N"];
+    let output = env.run(options.iter().copied().chain(["*.md", "-o", "SUMS"]));
+    assert_eq!(output.status.code(), Some(0));
+
+    // Options that the header does not record are applied on top of the recorded ones
+    let output = env.run(options.iter().copied().chain(["--check", "SUMS"]));
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert!(stdout(&output).starts_with("a.md: OK\nb.md: OK\n"));
+
+    let output = env.run(["--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn check_conflicting_options() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.run(["--eol", "crlf", "--tag", "a.txt", "-o", "SUMS"]);
+
+    let output = env.run(["--check", "--eol", "lf", "SUMS"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        stderr(&output).contains("generated with eol=crlf, but eol=lf is given"),
+        "{}",
+        stderr(&output)
+    );
+
+    // The same options as recorded are no conflict
+    let output = env.run(["--check", "--eol", "\r\n", "SUMS"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = env.run(["--check", "--eol", "lf", "--override-options", "SUMS"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn check_unsupported_header() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write(
+        "SUMS",
+        format!("# normalized-hasher: eol=lf ignore_whitespaces=false no_eof=false format=v9\n{HASH_DEFAULT}  a.txt\n"),
+    );
+
+    let output = env.run(["--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(stderr(&output).contains("unsupported header format v9"));
+}
+
+#[test]
+fn check_single_space() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b.txt", "E F\n");
+    env.write(
+        "sums.txt",
+        format!("{HASH_DEFAULT} a.txt\n{HASH_DEFAULT} b.txt\n{HASH_DEFAULT} c.txt\n"),
+    );
+
+    let output = env.run(["--no-summary", "--check", "sums.txt"]);
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        stdout(&output),
+        "a.txt: OK\nb.txt: FAILED\nc.txt: FAILED open or read\n"
+    );
+    assert!(stderr(&output).contains("cannot open 'c.txt'"));
+    assert!(!stderr(&output).contains("improperly formatted"));
+}
+
+#[test]
+fn check_relative_to_checksum_file() {
+    let env = TestEnv::new();
+    env.write("dist/a.txt", "A B\nC D\n");
+    env.write("dist/sums.txt", format!("{HASH_DEFAULT}  a.txt\n"));
+
+    let output = env.run(["--no-summary", "--check", "dist/sums.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "a.txt: OK\n");
+
+    // With the flag, paths are resolved against the current directory, like by sha256sum
+    let output = env.run([
+        "--no-summary",
+        "--check",
+        "--relative-to-current-dir",
+        "dist/sums.txt",
+    ]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "a.txt: FAILED open or read\n");
+
+    // Checksums from stdin are resolved against the current directory
+    let output = env.run_with_stdin(
+        ["--no-summary", "--check", "-"],
+        format!("{HASH_DEFAULT}  dist/a.txt\n"),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "dist/a.txt: OK\n");
+}
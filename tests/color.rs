@@ -0,0 +1,85 @@
+mod common;
+
+use common::{stdout, TestEnv, HASH_DEFAULT};
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+fn check_env() -> TestEnv {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("b.txt", "A B\n");
+    env.write(
+        "sums.txt",
+        format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  b.txt\n"),
+    );
+    env
+}
+
+#[test]
+fn color_always_check() {
+    let env = check_env();
+
+    let output = env.run(["--no-summary", "--check", "sums.txt", "--color", "always"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        format!("a.txt: {GREEN}OK{RESET}\nb.txt: {RED}FAILED{RESET}\n")
+    );
+}
+
+#[test]
+fn color_never_and_auto() {
+    let env = check_env();
+
+    let output = env.run(["--no-summary", "--check", "sums.txt", "--color", "never"]);
+    assert_eq!(stdout(&output), "a.txt: OK\nb.txt: FAILED\n");
+
+    // The output of the tests is not a terminal
+    let output = env.run(["--no-summary", "--check", "sums.txt"]);
+    assert_eq!(stdout(&output), "a.txt: OK\nb.txt: FAILED\n");
+
+    let output = env.run(["--no-summary", "-r", ".", "--json", "--color", "always"]);
+    assert!(!stdout(&output).contains('\x1b'));
+}
+
+#[test]
+fn color_always_sidecar() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--verify-sidecar", "input.txt", "--color", "always"]);
+    assert_eq!(
+        stdout(&output),
+        format!("input.txt: {YELLOW}MISSING{RESET}\n")
+    );
+
+    env.run(["--write-sidecar", "input.txt"]);
+    let output = env.run(["--verify-sidecar", "input.txt", "--color", "always"]);
+    assert_eq!(stdout(&output), format!("input.txt: {GREEN}OK{RESET}\n"));
+}
+
+#[test]
+fn color_always_compare() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("b.txt", "A B\nC D\n");
+    env.write("c.txt", "A B\n");
+
+    let output = env.run(["compare", "a.txt", "b.txt", "--color", "always"]);
+    assert_eq!(
+        stdout(&output),
+        format!("a.txt and b.txt are {GREEN}equal{RESET}\n")
+    );
+
+    let output = env.run(["compare", "--color=always", "a.txt", "c.txt"]);
+    assert_eq!(
+        stdout(&output),
+        format!("a.txt and c.txt {RED}differ{RESET} in line 2\n")
+    );
+
+    let output = env.run(["compare", "--color", "never", "a.txt", "c.txt"]);
+    assert_eq!(stdout(&output), "a.txt and c.txt differ in line 2\n");
+}
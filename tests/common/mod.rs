@@ -0,0 +1,107 @@
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+use tempfile::TempDir;
+
+/// Hash of the test content `"A B\nC D\n"` with default options.
+pub const HASH_DEFAULT: &str = "b62e3392d1ef3737f39339a43976186a039d53f30a95b987ccf8c5843dba94a2";
+
+/// Header of generated checksum files with default options.
+pub const HEADER_DEFAULT: &str =
+    "# normalized-hasher: eol=lf ignore_whitespaces=false no_eof=false algorithm=sha256 format=v1";
+
+pub struct TestEnv {
+    dir: TempDir,
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        Self {
+            dir: TempDir::new().unwrap(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Platform configuration directory of the processes started by this environment.
+    pub fn user_config_dir(&self) -> PathBuf {
+        self.dir.path().join("user-config")
+    }
+
+    pub fn write(&self, name: impl AsRef<Path>, content: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.dir.path().join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    pub fn command(&self) -> Command {
+        let mut command = normalized_hasher();
+        command.current_dir(self.dir.path());
+
+        // Keep the user configuration file and settings of the developer out of the tests
+        command.env("XDG_CONFIG_HOME", self.user_config_dir());
+        command.env("APPDATA", self.user_config_dir());
+        for (name, _) in std::env::vars_os() {
+            if name.to_string_lossy().starts_with("NORMALIZED_HASHER_") {
+                command.env_remove(name);
+            }
+        }
+
+        command
+    }
+
+    pub fn run<I, S>(&self, args: I) -> Output
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<std::ffi::OsStr>,
+    {
+        self.command().args(args).output().unwrap()
+    }
+
+    pub fn run_with_stdin<I, S>(&self, args: I, stdin: impl AsRef<[u8]>) -> Output
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<std::ffi::OsStr>,
+    {
+        let mut child = self
+            .command()
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        // Ignore errors, the process might exit without reading everything
+        let _ = child.stdin.take().unwrap().write_all(stdin.as_ref());
+
+        child.wait_with_output().unwrap()
+    }
+}
+
+pub fn normalized_hasher() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_normalized-hasher"))
+}
+
+pub fn fixture(name: impl AsRef<Path>) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
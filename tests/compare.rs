@@ -0,0 +1,65 @@
+mod common;
+
+use common::{stdout, TestEnv};
+
+#[test]
+fn compare_crlf_and_lf_are_equal() {
+    let env = TestEnv::new();
+    env.write("crlf.txt", "A B\r\nC D\r\n");
+    env.write("lf.txt", "A B\nC D\n");
+
+    let output = env.run(["compare", "crlf.txt", "lf.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "crlf.txt and lf.txt are equal\n");
+}
+
+#[test]
+fn compare_reports_first_differing_line() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\nE F\r\nG H\r\n");
+    env.write("b.txt", "A B\nC D\nE X\nG H\n");
+
+    let output = env.run(["compare", "a.txt", "b.txt"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "a.txt and b.txt differ in line 3\n");
+}
+
+#[test]
+fn compare_reports_additional_lines() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b.txt", "A B\nC D\nE F\n");
+
+    let output = env.run(["compare", "a.txt", "b.txt"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "a.txt and b.txt differ in line 3\n");
+}
+
+#[test]
+fn compare_applies_normalization_to_both_sides() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("b.txt", "AB\nC  D\n");
+
+    let output = env.run(["compare", "a.txt", "b.txt"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = env.run(["--ignore-whitespaces", "compare", "a.txt", "b.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = env.run(["compare", "a.txt", "b.txt", "--ignore-whitespaces"]);
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn compare_missing_file() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+
+    let output = env.run(["compare", "a.txt", "missing.txt"]);
+
+    assert_eq!(output.status.code(), Some(3));
+}
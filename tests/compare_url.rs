@@ -0,0 +1,133 @@
+#![cfg(feature = "http")]
+
+mod common;
+
+use std::thread;
+use std::time::Duration;
+
+use tiny_http::{Header, Response, Server};
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Serve a few fixed routes on a local port, returning the base URL.
+fn serve() -> String {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let authorized = request.headers().iter().any(|header| {
+                header.field.equiv("Authorization") && header.value == "Bearer secret"
+            });
+
+            let response = match request.url() {
+                "/install.sql" => Response::from_string("A B\nC D\n"),
+                "/other.sql" => Response::from_string("A B\nC X\n"),
+                "/install.sql.sha256" => {
+                    Response::from_string(format!("{HASH_DEFAULT}  install.sql\n"))
+                }
+                "/moved" => Response::from_string("")
+                    .with_status_code(302)
+                    .with_header(Header::from_bytes("Location", "/install.sql").unwrap()),
+                "/private.sql" if authorized => Response::from_string("A B\nC D\n"),
+                "/private.sql" => Response::from_string("").with_status_code(401),
+                "/slow.sql" => {
+                    thread::sleep(Duration::from_secs(5));
+                    Response::from_string("A B\nC D\n")
+                }
+                _ => Response::from_string("").with_status_code(404),
+            };
+            let _ = request.respond(response);
+        }
+    });
+
+    url
+}
+
+#[test]
+fn compare_with_url() {
+    let env = TestEnv::new();
+    env.write("local.sql", "A B\r\nC D\r\n");
+    let base = serve();
+
+    let url = format!("{base}/install.sql");
+    let output = env.run(["compare", "local.sql", "--url", &url]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("local.sql and {url} are equal\n"));
+
+    let url = format!("{base}/other.sql");
+    let output = env.run(["compare", "local.sql", "--url", &url]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        format!("local.sql and {url} differ in line 2\n")
+    );
+}
+
+#[test]
+fn compare_with_url_follows_redirects_and_sends_headers() {
+    let env = TestEnv::new();
+    env.write("local.sql", "A B\r\nC D\r\n");
+    let base = serve();
+
+    let output = env.run(["compare", "local.sql", "--url", &format!("{base}/moved")]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let url = format!("{base}/private.sql");
+    let output = env.run(["compare", "local.sql", "--url", &url]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        stderr(&output),
+        format!("error: cannot download {url}: the server responded with 401\n")
+    );
+
+    let args = ["--header", "Authorization: Bearer secret"];
+    let output = env.run(
+        ["compare", "local.sql", "--url", &url]
+            .into_iter()
+            .chain(args),
+    );
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn compare_with_url_times_out() {
+    let env = TestEnv::new();
+    env.write("local.sql", "A B\r\nC D\r\n");
+    let base = serve();
+
+    let url = format!("{base}/slow.sql");
+    let output = env.run(["compare", "local.sql", "--url", &url, "--timeout", "1"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        stderr(&output),
+        format!("error: cannot download {url}: no response within 1 second\n")
+    );
+}
+
+#[test]
+fn compare_with_expected_url() {
+    let env = TestEnv::new();
+    env.write("local.sql", "A B\r\nC D\r\n");
+    env.write("other.sql", "A B\r\nC X\r\n");
+    let base = serve();
+
+    let url = format!("{base}/install.sql.sha256");
+    let output = env.run(["compare", "local.sql", "--expected-url", &url]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("local.sql matches the hash from {url}\n")
+    );
+
+    let output = env.run(["compare", "other.sql", "--expected-url", &url]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).starts_with(&format!(
+        "other.sql does not match the hash from {url}\nexpected: {HASH_DEFAULT}\n"
+    )));
+
+    let url = format!("{base}/missing");
+    let output = env.run(["compare", "local.sql", "--expected-url", &url]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains(&url));
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::{stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn completions_for_each_shell() {
+    let env = TestEnv::new();
+
+    for shell in ["bash", "zsh", "fish", "powershell", "elvish"] {
+        let output = env.run(["completions", shell]);
+
+        assert_eq!(output.status.code(), Some(0), "{}", shell);
+        let script = stdout(&output);
+        assert!(!script.is_empty(), "{}", shell);
+        // Fish names long flags without the dashes
+        assert!(script.contains("ignore-whitespaces"), "{}", shell);
+        assert!(script.contains("files-from"), "{}", shell);
+    }
+}
+
+#[test]
+fn completions_suggest_values() {
+    let env = TestEnv::new();
+
+    let output = env.run(["completions", "fish"]);
+    let script = stdout(&output);
+
+    let eol = script.lines().find(|line| line.contains("-l eol")).unwrap();
+    assert!(eol.contains("crlf"), "{}", eol);
+    let format = script
+        .lines()
+        .find(|line| line.contains("-l format"))
+        .unwrap();
+    assert!(format.contains("base64"), "{}", format);
+    let output = script
+        .lines()
+        .find(|line| line.contains("-l output "))
+        .unwrap();
+    assert!(output.ends_with("-F"), "{}", output);
+}
+
+#[test]
+fn completions_unknown_shell() {
+    let env = TestEnv::new();
+
+    let output = env.run(["completions", "tcsh"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn eol_accepts_names() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let named = env.run(["--eol", "crlf", "input.txt"]);
+    let literal = env.run(["--eol", "\r\n", "input.txt"]);
+    let default = env.run(["--eol", "lf", "input.txt"]);
+
+    assert_eq!(named.status.code(), Some(0));
+    assert_eq!(named.stdout, literal.stdout);
+    assert_ne!(stdout(&named).trim(), HASH_DEFAULT);
+    assert_eq!(stdout(&default).trim(), HASH_DEFAULT);
+}
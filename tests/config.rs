@@ -0,0 +1,365 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Hash of the test content `"A B\nC D\n"` with CRLF line endings.
+const HASH_CRLF: &str = "9b0197338a25019411e776a2d2cdb68a23d5a1ad7d79350aea527a3189ffe20d";
+
+/// Hash of the test content `"A B\nC D\n"` with CRLF line endings and `--no-eof`.
+const HASH_CRLF_NO_EOF: &str = "25680a5e93d2c705761eb0777a22aece08f0030b6e73722ddb8c5912c9ad9f9a";
+
+/// Write the user configuration file of the environment.
+fn write_user_config(env: &TestEnv, content: &str) {
+    env.write(
+        env.user_config_dir().join("normalized-hasher/config.toml"),
+        content,
+    );
+}
+
+#[test]
+fn config_project_file_in_parent() {
+    let env = TestEnv::new();
+    env.write(".normalized-hasher.toml", "eol = \"crlf\"\n");
+    env.write("sub/dir/input.txt", "A B\nC D\n");
+
+    let output = env
+        .command()
+        .current_dir(env.path().join("sub"))
+        .args(["dir/input.txt"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{}\n", HASH_CRLF));
+}
+
+#[test]
+fn config_precedence() {
+    let env = TestEnv::new();
+    write_user_config(&env, "eol = \"cr\"\nno-eof = true\n");
+    env.write("input.txt", "A B\nC D\n");
+
+    // No-eof of the user config, with its eol overridden
+    let output = env.run(["--eol", "\r\n", "input.txt"]);
+    assert_eq!(stdout(&output), format!("{}\n", HASH_CRLF_NO_EOF));
+
+    // Project config overrides the eol of the user config, but keeps its no-eof
+    env.write(".normalized-hasher.toml", "eol = \"crlf\"\n");
+    let output = env.run(["input.txt"]);
+    assert_eq!(stdout(&output), format!("{}\n", HASH_CRLF_NO_EOF));
+
+    // Flags override both
+    let output = env.run(["--eol", "lf", "input.txt"]);
+    assert_ne!(stdout(&output), format!("{}\n", HASH_CRLF_NO_EOF));
+
+    let output = env.run(["--no-config", "input.txt"]);
+    assert_eq!(stdout(&output), format!("{}\n", HASH_DEFAULT));
+}
+
+#[test]
+fn config_explicit_file() {
+    let env = TestEnv::new();
+    env.write(".normalized-hasher.toml", "no-eof = true\n");
+    env.write("policy.toml", "eol = \"crlf\"\n");
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--config", "policy.toml", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{}\n", HASH_CRLF));
+}
+
+#[test]
+fn config_exclude_applies_to_subcommands() {
+    let env = TestEnv::new();
+    env.write(".normalized-hasher.toml", "exclude = [\"*.bak\"]\n");
+    env.write("tree/a.txt", "A B\nC D\n");
+    env.write("tree/a.txt.bak", "A B\nC D\n");
+
+    let output = env.run(["-r", "tree"]);
+    assert_eq!(stdout(&output).lines().count(), 1);
+
+    let output = env.run(["dupes", "-r", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+
+    let output = env.run(["--no-config", "dupes", "-r", "tree"]);
+    assert!(stdout(&output).contains("(2 files)"), "{}", stdout(&output));
+}
+
+#[test]
+fn config_show() {
+    let env = TestEnv::new();
+    write_user_config(&env, "no-eof = true\n");
+    env.write(
+        ".normalized-hasher.toml",
+        "eol = \"crlf\"\nexclude = [\"*.bak\"]\n",
+    );
+
+    let output = env.run(["--show-config", "--ignore-whitespaces"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = stdout(&output);
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert!(lines[0].starts_with("eol = \"\\r\\n\"  # project config "));
+    assert!(lines[0].ends_with(".normalized-hasher.toml"));
+    assert_eq!(lines[1], "ignore-whitespaces = true  # command line");
+    assert!(lines[2].starts_with("no-eof = true  # user config "));
+    assert_eq!(lines[3], "algorithm = \"sha256\"  # default");
+    assert!(lines[4].starts_with("exclude = [\"*.bak\"]  # project config "));
+    assert_eq!(lines[5], "no-hidden = false  # default");
+}
+
+#[test]
+fn config_invalid() {
+    let env = TestEnv::new();
+    env.write(
+        ".normalized-hasher.toml",
+        "eol = \"crlf\"\nno-eof = \"yes\"\n",
+    );
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["input.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("line 2: "), "{}", stderr(&output));
+
+    let output = env.run(["--config", "missing.toml", "input.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn config_write_round_trip() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    env.write("tree/b.txt", " A  B\nC D");
+    env.write("tree/skip.bak", "A B\n");
+    env.write("tree/.hidden.txt", "A B\n");
+
+    let flags = [
+        "--eol",
+        "crlf",
+        "--ignore-whitespaces",
+        "--algorithm",
+        "sha512",
+        "--exclude",
+        "*.bak",
+        "--no-hidden",
+    ];
+    let mut args = flags.to_vec();
+    args.extend(["-r", "tree"]);
+    let mut write = args.clone();
+    write.extend(["--write-config", ".normalized-hasher.toml"]);
+
+    let expected = env
+        .command()
+        .env("NORMALIZED_HASHER_NO_EOF", "1")
+        .args(&args)
+        .output()
+        .unwrap();
+    assert_eq!(stdout(&expected).lines().count(), 2);
+
+    // The flags of the experiment are kept, nothing is hashed
+    let output = env
+        .command()
+        .env("NORMALIZED_HASHER_NO_EOF", "1")
+        .args(&write)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+
+    let output = env.run(["-r", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout, expected.stdout);
+
+    let config = std::fs::read_to_string(env.path().join(".normalized-hasher.toml")).unwrap();
+    assert!(config.contains("\nalgorithm = \"sha512\"\n"), "{}", config);
+}
+
+#[test]
+fn config_algorithm() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+    env.write("c.toml", "algorithm = \"sha512\"\n");
+
+    let output = env.run(["--config", "c.toml", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output).trim_end().len(), 128);
+
+    // The configured algorithm is chosen explicitly, so other BSD lines are not checked with theirs
+    env.write("sums.txt", format!("SHA256 (input.txt) = {HASH_DEFAULT}\n"));
+    let output = env.run(["--config", "c.toml", "--check", "sums.txt"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(stderr(&output)
+        .contains("SHA256 line skipped, checking with SHA512 (--algorithm or configuration)"));
+
+    let output = env.run(["--config", "c.toml", "--show-config"]);
+    assert!(stdout(&output).contains("\nalgorithm = \"sha512\"  # config "));
+
+    env.write("c.toml", "algorithm = \"md5\"\n");
+    let output = env.run(["--config", "c.toml", "input.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("unknown digest algorithm 'md5'"));
+}
+
+#[test]
+fn config_write_refuses_overwrite() {
+    let env = TestEnv::new();
+    let path = env.write("policy.toml", "# handwritten\n");
+
+    let output = env.run(["--write-config", "policy.toml", "--no-eof"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "# handwritten\n");
+
+    let output = env.run(["--write-config", "policy.toml", "--no-eof", "--force"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(std::fs::read_to_string(&path)
+        .unwrap()
+        .contains("\nno-eof = true\n"));
+}
+
+/// Write a repository whose vendored directory overrides the eol of the repository.
+///
+/// The configuration file above the repository does not apply to it.
+fn write_nested_configs(env: &TestEnv) {
+    env.write(".normalized-hasher.toml", "ignore-whitespaces = true\n");
+    std::fs::create_dir_all(env.path().join("repo/.git")).unwrap();
+    env.write("repo/.normalized-hasher.toml", "no-eof = true\n");
+    env.write("repo/vendor/.normalized-hasher.toml", "eol = \"crlf\"\n");
+    env.write("repo/src/a.txt", "A B\nC D\n");
+    env.write("repo/vendor/b.sql", "A B\r\nC D\r\n");
+}
+
+#[test]
+fn config_per_directory() {
+    let env = TestEnv::new();
+    write_nested_configs(&env);
+    let hash_no_eof = stdout(&env.run(["--no-config", "--no-eof", "repo/src/a.txt"]));
+
+    // Each file is hashed with the settings of its directory, also in recursive mode
+    let output = env.run(["repo/vendor/b.sql"]);
+    assert_eq!(stdout(&output), format!("{}\n", HASH_CRLF_NO_EOF));
+    let output = env.run(["repo/src/a.txt"]);
+    assert_eq!(stdout(&output), hash_no_eof);
+
+    let output = env.run(["-r", "repo", "--exclude", "*.toml"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "{}  repo/src/a.txt\n{}  repo/vendor/b.sql\n",
+            hash_no_eof.trim_end(),
+            HASH_CRLF_NO_EOF
+        )
+    );
+
+    // Flags still override all configuration files
+    let output = env.run(["--eol", "lf", "repo/vendor/b.sql"]);
+    assert_eq!(stdout(&output), hash_no_eof);
+}
+
+#[test]
+fn config_per_directory_in_subcommands() {
+    let env = TestEnv::new();
+    std::fs::create_dir_all(env.path().join("repo/.git")).unwrap();
+    env.write("repo/vendor/.normalized-hasher.toml", "eol = \"crlf\"\n");
+    env.write("repo/src/a.txt", "A B\nC D\n");
+    env.write("repo/vendor/b.sql", "A B\r\nC D\r\n");
+
+    // The CRLF file is normalized by the settings of its directory
+    let output = env.run(["lint", "-r", "repo", "--exclude", "*.toml"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+    assert_eq!(stdout(&output), "");
+
+    env.write("repo/vendor/c.sql", "A B\nC D\n");
+    let output = env.run(["lint", "-r", "repo", "--exclude", "*.toml"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "repo/vendor/c.sql\n");
+
+    // Each file of a pair is read with the settings of its own directory
+    let output = env.run(["explain", "repo/vendor/b.sql"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+    let output = env.run(["diff", "repo/src/a.txt", "repo/vendor/b.sql"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+
+    // Hashes are encoded for the algorithm of their directory
+    env.write(
+        "repo/vendor/.normalized-hasher.toml",
+        "eol = \"crlf\"\nalgorithm = \"sha512\"\n",
+    );
+    let output = env.run([
+        "--format",
+        "sri",
+        "-r",
+        "repo/vendor",
+        "--exclude",
+        "*.toml",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(
+        stdout(&output).starts_with("sha512-"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn config_show_for_path() {
+    let env = TestEnv::new();
+    write_nested_configs(&env);
+
+    let output = env.run(["--show-config", "repo/vendor/b.sql"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = stdout(&output);
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert!(lines[0].starts_with("eol = \"\\r\\n\"  # project config "));
+    assert!(lines[0].ends_with("vendor/.normalized-hasher.toml"));
+    assert_eq!(lines[1], "ignore-whitespaces = false  # default");
+    assert!(lines[2].starts_with("no-eof = true  # project config "));
+    assert!(lines[2].ends_with("repo/.normalized-hasher.toml"));
+
+    // Without a path, the settings of the current directory are shown
+    let output = env.run(["--show-config"]);
+    assert!(common::stdout(&output).starts_with("eol = \"\\n\"  # default\n"));
+}
+
+#[test]
+fn config_per_directory_in_manifest_check_and_cache() {
+    let env = TestEnv::new();
+    write_nested_configs(&env);
+    let tree = ["--exclude", "*.toml"];
+
+    let output = env.run(
+        [
+            &["manifest", "generate", "repo", "-o", "MANIFEST"],
+            &tree[..],
+        ]
+        .concat(),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let output = env.run([&["manifest", "verify", "repo", "MANIFEST"], &tree[..]].concat());
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+
+    // Checksum files of mixed options have no header, so each file is checked with its options
+    let output = env.run([&["-r", "repo", "--output", "sums.txt"], &tree[..]].concat());
+    assert_eq!(output.status.code(), Some(0));
+    let sums = std::fs::read_to_string(env.path().join("sums.txt")).unwrap();
+    assert!(!sums.starts_with('#'), "{}", sums);
+    let output = env.run(["--check", "sums.txt"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+
+    let cached = [&["--cache", "hashes.cache", "-v", "-r", "repo"], &tree[..]].concat();
+    env.run(&cached);
+    let output = env.run(&cached);
+    assert_eq!(stderr(&output).matches("using cached hash").count(), 2);
+
+    // Changed settings of a directory invalidate the cached hashes of its files
+    env.write("repo/vendor/.normalized-hasher.toml", "eol = \"cr\"\n");
+    let output = env.run(&cached);
+    assert_eq!(stderr(&output).matches("using cached hash").count(), 1);
+    let output = env.run([&["manifest", "verify", "repo", "MANIFEST"], &tree[..]].concat());
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("vendor/b.sql: CHANGED\n"));
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn copy_output_unchanged() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    // Without a display, copying fails on Linux, but the hash is still printed
+    let output = env
+        .command()
+        .args(["--copy", "input.txt"])
+        .env_remove("DISPLAY")
+        .env_remove("WAYLAND_DISPLAY")
+        .output()
+        .unwrap();
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+
+    match output.status.code() {
+        Some(0) => assert_eq!(stderr(&output), ""),
+        Some(3) => assert!(stderr(&output).contains("error: cannot copy to clipboard")),
+        code => panic!("unexpected exit code {code:?}"),
+    }
+}
+
+#[test]
+fn copy_multiple_files() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("b.txt", "A B\nC D\n");
+
+    let output = env.run(["--copy", "*.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains("--copy cannot be used with multiple files"));
+}
+
+/// Without a display, there is no clipboard on Linux, which is an error before hashing anything.
+#[cfg(target_os = "linux")]
+#[test]
+fn from_clipboard_headless() {
+    let env = TestEnv::new();
+
+    let output = env
+        .command()
+        .args(["--from-clipboard", "--expected", HASH_DEFAULT])
+        .env_remove("DISPLAY")
+        .env_remove("WAYLAND_DISPLAY")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains("error: cannot read from clipboard"));
+}
+
+#[test]
+fn from_clipboard_conflicts_with_file_in() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--from-clipboard", "input.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+}
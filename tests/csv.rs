@@ -0,0 +1,43 @@
+mod common;
+
+use common::{stdout, TestEnv, HASH_DEFAULT};
+
+// Quotes are not allowed in file names on Windows
+#[cfg(unix)]
+#[test]
+fn csv_quoting_roundtrip() {
+    let env = TestEnv::new();
+    let name = "tree/a, \"quoted\".sql";
+    env.write(name, "A B\r\nC D\r\n");
+    env.write("tree/b.sql", "A B\nC D\n");
+
+    let output = env.run(["-r", "--csv", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("path,algorithm,hash,size,lines,changed\r\n"));
+
+    let mut reader = csv::Reader::from_reader(output.stdout.as_slice());
+    let records: Vec<_> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(records.len(), 2);
+
+    assert_eq!(
+        records[0].iter().collect::<Vec<_>>(),
+        [name, "SHA256", HASH_DEFAULT, "10", "2", "true"]
+    );
+    assert_eq!(&records[1][5], "false");
+}
+
+#[test]
+fn csv_delimiter() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--csv", "--csv-delimiter", ";", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("path;algorithm;hash;size;lines;changed\r\ninput.txt;SHA256;{HASH_DEFAULT};10;2;true\r\n")
+    );
+
+    let output = env.run(["--csv", "--csv-delimiter", ";;", "input.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+}
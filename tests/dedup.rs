@@ -0,0 +1,41 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn same_file_is_hashed_once() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("list.txt", "./a.txt\na.txt\n");
+
+    let output = env.run(["--files-from", "list.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}  ./a.txt\n"));
+
+    let output = env.run(["--verbose", "--files-from", "list.txt"]);
+    assert!(stderr(&output).contains("note: skipping a.txt, it is the same file as ./a.txt\n"));
+
+    let output = env.run(["--no-dedup", "--files-from", "list.txt"]);
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  ./a.txt\n{HASH_DEFAULT}  a.txt\n")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn symlink_is_hashed_once() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    std::os::unix::fs::symlink("a.txt", env.path().join("link.txt")).unwrap();
+
+    let output = env.run(["*.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}  a.txt\n"));
+
+    let output = env.run(["--no-dedup", "*.txt"]);
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  link.txt\n")
+    );
+}
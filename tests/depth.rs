@@ -0,0 +1,94 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv};
+
+fn setup_tree(env: &TestEnv) {
+    for file in ["tree/a.txt", "tree/sub/b.txt", "tree/sub/deeper/c.txt"] {
+        env.write(file, "A B\r\nC D\r\n");
+    }
+}
+
+/// Hashed files from the output, with `/` as separator.
+fn files(output: &std::process::Output) -> Vec<String> {
+    stdout(output)
+        .lines()
+        .map(|line| line.split_once("  ").unwrap().1.replace('\\', "/"))
+        .collect()
+}
+
+#[test]
+fn max_depth() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["-r", "--max-depth", "1", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(files(&output), ["tree/a.txt"]);
+
+    let output = env.run(["-r", "tree"]);
+    assert_eq!(
+        files(&output),
+        ["tree/a.txt", "tree/sub/b.txt", "tree/sub/deeper/c.txt"]
+    );
+}
+
+#[test]
+fn max_depth_with_exclude() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["-r", "--max-depth", "2", "--exclude", "a.txt", "tree"]);
+    assert_eq!(files(&output), ["tree/sub/b.txt"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn follow_symlinks_cycle() {
+    use std::os::unix::fs::symlink;
+
+    let env = TestEnv::new();
+    setup_tree(&env);
+    symlink("..", env.path().join("tree/sub/up")).unwrap();
+
+    let output = env.run(["-r", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(files(&output).len(), 3);
+
+    let output = env.run(["-r", "--follow-symlinks", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        files(&output),
+        ["tree/a.txt", "tree/sub/b.txt", "tree/sub/deeper/c.txt"]
+    );
+    assert!(stderr(&output).contains("tree/sub/up"));
+}
+
+#[cfg(unix)]
+#[test]
+fn follow_symlinks_diamond() {
+    use std::os::unix::fs::symlink;
+
+    let env = TestEnv::new();
+    env.write("tree/shared/a.txt", "A B\r\nC D\r\n");
+    std::fs::create_dir_all(env.path().join("tree/left")).unwrap();
+    std::fs::create_dir_all(env.path().join("tree/right")).unwrap();
+    symlink("../shared", env.path().join("tree/left/shared")).unwrap();
+    symlink("../shared", env.path().join("tree/right/shared")).unwrap();
+
+    let output = env.run([
+        "--no-summary",
+        "-r",
+        "--follow-symlinks",
+        "--verbose",
+        "tree",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(files(&output), ["tree/left/shared/a.txt"]);
+    assert_eq!(
+        stderr(&output),
+        "note: skipping tree/right/shared, it is the same as tree/left/shared\n\
+         note: skipping tree/shared, it is the same as tree/left/shared\n\
+         note: tree/left/shared/a.txt: 2 lines read, 2 CRLF line endings converted, no byte \
+         order mark, ends with a newline\n"
+    );
+}
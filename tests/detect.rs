@@ -0,0 +1,108 @@
+mod common;
+
+use common::{fixture, normalized_hasher, stdout, TestEnv};
+
+fn detect(args: &[&str]) -> std::process::Output {
+    normalized_hasher()
+        .current_dir(fixture("detect"))
+        .arg("--detect")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn detect_utf16() {
+    let output = detect(&["utf16.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "utf16.txt:\n  \
+         encoding:        UTF-16LE\n  \
+         byte order mark: yes\n  \
+         line endings:    CRLF (2 CRLF, 0 LF, 0 CR)\n  \
+         final newline:   yes\n  \
+         invisible:       0 characters\n"
+    );
+}
+
+#[test]
+fn detect_utf8_with_bom() {
+    let output = detect(&["bom.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "bom.txt:\n  \
+         encoding:        UTF-8\n  \
+         byte order mark: yes\n  \
+         line endings:    mixed, mostly LF (1 CRLF, 2 LF, 0 CR)\n  \
+         final newline:   yes\n  \
+         invisible:       1 character\n"
+    );
+}
+
+#[test]
+fn detect_binary() {
+    let output = detect(&["binary.bin"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "binary.bin:\n  \
+         encoding:        binary\n  \
+         byte order mark: no\n"
+    );
+}
+
+#[test]
+fn detect_json() {
+    let output = detect(&["--json", "bom.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "path": "bom.txt",
+            "encoding": "UTF-8",
+            "bom": true,
+            "line_endings": {
+                "dominant": "LF",
+                "mixed": true,
+                "crlf": 1,
+                "lf": 2,
+                "cr": 0,
+            },
+            "final_newline": true,
+            "invisible_characters": 1,
+        })
+    );
+
+    let output = detect(&["--json", "binary.bin"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["encoding"], "binary");
+    assert!(json.get("line_endings").is_none());
+}
+
+#[test]
+fn detect_windows_1252() {
+    let env = TestEnv::new();
+    env.write("latin.txt", b"caf\xe9\n");
+
+    let output = env.run(["--detect", "latin.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("encoding:        Windows-1252 (likely)\n"));
+}
+
+#[test]
+fn detect_missing_file() {
+    let env = TestEnv::new();
+
+    let output = env.run(["--detect", "missing.txt"]);
+
+    assert_eq!(output.status.code(), Some(3));
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use std::fs;
+
+use common::{fixture, normalized_hasher, stderr, stdout, TestEnv};
+
+#[test]
+fn diff_matches_snapshot() {
+    let output = normalized_hasher()
+        .current_dir(fixture("diff"))
+        .args(["diff", "old.sql", "new.sql"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        fs::read_to_string(fixture("diff/expected.diff")).unwrap()
+    );
+}
+
+#[test]
+fn diff_with_custom_context() {
+    let output = normalized_hasher()
+        .current_dir(fixture("diff"))
+        .args(["diff", "--context", "0", "old.sql", "new.sql"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "--- old.sql\n+++ new.sql\n@@ -5 +5 @@\n-SELECT 5;\n+SELECT 5 + 0;\n@@ -8,0 +9 @@\n+SELECT 9;\n"
+    );
+}
+
+#[test]
+fn diff_without_differences() {
+    let env = TestEnv::new();
+    env.write("crlf.txt", "A B\r\nC D\r\n");
+    env.write("lf.txt", "A B\nC D\n");
+
+    let output = env.run(["diff", "crlf.txt", "lf.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn diff_missing_file() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+
+    let output = env.run(["diff", "a.txt", "missing.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(stderr(&output).contains("cannot diff a.txt and missing.txt"));
+}
+
+#[test]
+fn diff_help_documents_exit_status() {
+    let output = normalized_hasher()
+        .args(["diff", "--help"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("2  Trouble, like invalid arguments or a file"));
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "doc")]
+
+mod common;
+
+use common::{fixture, normalized_hasher, stderr, stdout, TestEnv};
+
+/// Hash of the text of `spec.docx` and `spec.odt`, whose code listing is indented with
+/// non-breaking spaces and uses typographic quotes.
+const HASH_SPEC: &str = "491eb7920964ae283689a631050dd90cf6bb61520a47ec3565a8d3fba7f845be";
+
+/// Text of the fixture documents as a plain text file.
+const TEXT_SPEC: &str = "Listing 1\r\n\
+                         CREATE TABLE users (\r\n\
+                         \u{a0}\u{a0}\u{a0}\u{a0}id INT,\r\n\
+                         \u{a0}\u{a0}\u{a0}\u{a0}name TEXT DEFAULT \u{2018}none\u{2019}\r\n\
+                         );\r\n\
+                         \r\n\
+                         Signed off by & for QA.\r\n";
+
+fn doc(args: &[&str]) -> std::process::Output {
+    normalized_hasher()
+        .current_dir(fixture("doc"))
+        .arg("--doc")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn doc_docx_and_odt() {
+    for file in ["spec.docx", "spec.odt"] {
+        let output = doc(&[file]);
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(stdout(&output), format!("{HASH_SPEC}\n"));
+    }
+
+    let env = TestEnv::new();
+    env.write("spec.txt", TEXT_SPEC);
+    assert_eq!(stdout(&env.run(["spec.txt"])), format!("{HASH_SPEC}\n"));
+}
+
+#[test]
+fn doc_ignore_whitespaces_neutralizes_nbsp() {
+    let env = TestEnv::new();
+    env.write("typed.txt", TEXT_SPEC.replace('\u{a0}', " "));
+    let output = env.run(["--ignore-whitespaces", "typed.txt"]);
+    let expected = stdout(&output);
+
+    for file in ["spec.docx", "spec.odt"] {
+        let output = doc(&["--ignore-whitespaces", file]);
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(stdout(&output), expected);
+    }
+}
+
+#[test]
+fn doc_with_expected() {
+    let output = doc(&["--expected", HASH_SPEC, "spec.docx"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("OK\n"));
+}
+
+#[test]
+fn doc_unsupported() {
+    let cases = [
+        (
+            "encrypted.docx",
+            "the document is encrypted or in the legacy .doc format",
+        ),
+        ("encrypted.odt", "the document is encrypted"),
+        (
+            "table.ods",
+            "unsupported document type application/vnd.oasis.opendocument.spreadsheet",
+        ),
+        (
+            "../diff/old.sql",
+            "it is neither a .docx nor an .odt document",
+        ),
+    ];
+
+    for (file, message) in cases {
+        let output = doc(&[file]);
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(stdout(&output), "");
+        assert_eq!(
+            stderr(&output),
+            format!("error: cannot read {file}: {message}\n")
+        );
+    }
+}
+
+#[test]
+fn doc_refuses_standard_input() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(["--doc", "-"], "A B\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--doc cannot be used with standard input"));
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::{fixture, normalized_hasher, stderr, stdout, TestEnv};
+
+const SCHEMA: &str = "c6faba4228728a4b882ed1cf2e8e1d351106434c70fb0f74a6afec0f7b3d17ac";
+const SEED: &str = "1cda4cca3718db5cf3533cbd0a573011ddcd82e1ee5f1214d65f58fc4e59ee4b";
+
+fn dupes(args: &[&str]) -> std::process::Output {
+    normalized_hasher()
+        .current_dir(fixture("dupes"))
+        .arg("dupes")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn dupes_groups_equal_files() {
+    let output = dupes(&["-r", "--min-size", "1", "project"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "{SCHEMA} (2 files)\n  project/alpha/schema.sql\n  project/beta/schema.sql\n\n\
+             {SEED} (2 files)\n  project/alpha/seed.sql\n  project/gamma/seed-copy.sql\n"
+        )
+    );
+    assert_eq!(
+        stderr(&output),
+        "note: skipping binary file project/gamma/logo.bin\n"
+    );
+}
+
+#[test]
+fn dupes_includes_empty_files_without_min_size() {
+    let output = dupes(&["-r", "project"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("  project/alpha/empty.txt\n  project/beta/empty.txt\n"));
+    assert_eq!(stdout(&output).matches(" (2 files)\n").count(), 3);
+}
+
+#[test]
+fn dupes_json() {
+    let output = dupes(&["-r", "--min-size", "1", "--json", "project"]);
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let groups = json["groups"].as_array().unwrap();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0]["hash"], SCHEMA);
+    assert_eq!(
+        groups[0]["files"],
+        serde_json::json!(["project/alpha/schema.sql", "project/beta/schema.sql"])
+    );
+    assert_eq!(groups[1]["hash"], SEED);
+    assert!(json.get("errors").is_none());
+}
+
+#[test]
+fn dupes_json_output_file() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A\r\n");
+    env.write("b.txt", "A\n");
+
+    let output = env.run(["-o", "out.json", "dupes", "--json", "a.txt", "b.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+    let json = std::fs::read(env.path().join("out.json")).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(
+        json["groups"][0]["files"],
+        serde_json::json!(["a.txt", "b.txt"])
+    );
+
+    // A failed write is an error with the exit code of I/O errors
+    let output = env.run([
+        "-o",
+        "missing/out.json",
+        "dupes",
+        "--json",
+        "a.txt",
+        "b.txt",
+    ]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("cannot write 'missing/out.json'"));
+}
+
+#[test]
+fn dupes_fail_if_dupes() {
+    let output = dupes(&["-r", "--fail-if-dupes", "project"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = dupes(&[
+        "--fail-if-dupes",
+        "project/alpha/schema.sql",
+        "project/gamma/unique.sql",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn dupes_same_file_twice_is_no_duplicate() {
+    let output = dupes(&["project/alpha/seed.sql", "project/alpha/seed.sql"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn dupes_unreadable_file() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\n");
+    env.write("b.txt", "A B\r\n");
+
+    let output = env.run(["dupes", "a.txt", "b.txt", "missing.txt"]);
+
+    assert_eq!(output.status.code(), Some(5));
+    assert!(stdout(&output).contains("  a.txt\n  b.txt\n"));
+    assert!(stderr(&output).starts_with("warning: "));
+}
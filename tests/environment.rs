@@ -0,0 +1,91 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Hash of the test content `"A B\nC D\n"` with CRLF line endings.
+const HASH_CRLF: &str = "9b0197338a25019411e776a2d2cdb68a23d5a1ad7d79350aea527a3189ffe20d";
+
+/// Hash of the test content `"A B\nC D\n"` with CRLF line endings and `--no-eof`.
+const HASH_CRLF_NO_EOF: &str = "25680a5e93d2c705761eb0777a22aece08f0030b6e73722ddb8c5912c9ad9f9a";
+
+#[test]
+fn environment_sets_options_and_flags() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env
+        .command()
+        .env("NORMALIZED_HASHER_EOL", "crlf")
+        .env("NORMALIZED_HASHER_NO_EOF", "Yes")
+        .arg("input.txt")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{}\n", HASH_CRLF_NO_EOF));
+}
+
+#[test]
+fn environment_flags_accept_booleans() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    for (value, hash) in [
+        ("1", HASH_CRLF_NO_EOF),
+        ("TRUE", HASH_CRLF_NO_EOF),
+        ("no", HASH_CRLF),
+    ] {
+        let output = env
+            .command()
+            .env("NORMALIZED_HASHER_NO_EOF", value)
+            .args(["--eol", "crlf", "input.txt"])
+            .output()
+            .unwrap();
+
+        assert_eq!(stdout(&output), format!("{}\n", hash), "{}", value);
+    }
+}
+
+#[test]
+fn environment_invalid_flag_names_variable() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env
+        .command()
+        .env("NORMALIZED_HASHER_IGNORE_WHITESPACES", "maybe")
+        .arg("input.txt")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        stderr(&output).contains("'maybe' for NORMALIZED_HASHER_IGNORE_WHITESPACES"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn environment_loses_to_flags_and_wins_over_config() {
+    let env = TestEnv::new();
+    env.write(".normalized-hasher.toml", "eol = \"cr\"\n");
+    env.write("input.txt", "A B\nC D\n");
+
+    let run = |args: &[&str]| {
+        env.command()
+            .env("NORMALIZED_HASHER_EOL", "crlf")
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    assert_eq!(stdout(&run(&["input.txt"])), format!("{}\n", HASH_CRLF));
+    assert_eq!(
+        stdout(&run(&["--eol", "lf", "input.txt"])),
+        format!("{}\n", HASH_DEFAULT)
+    );
+
+    let output = run(&["--show-config"]);
+    assert!(stdout(&output).starts_with("eol = \"\\r\\n\"  # environment NORMALIZED_HASHER_EOL\n"));
+}
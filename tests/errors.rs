@@ -0,0 +1,69 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv};
+
+#[test]
+fn error_missing_input() {
+    let env = TestEnv::new();
+
+    let output = env.run(["missing.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "error: cannot open 'missing.txt': No such file or directory\n"
+    );
+}
+
+#[test]
+fn error_unwritable_output() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["input.txt", "--output-file", "missing/output.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        stderr(&output),
+        "error: cannot write 'missing/output.txt': No such file or directory\n"
+    );
+
+    let output = env.run(["input.txt", "--output", "missing/SHA256SUMS"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        stderr(&output),
+        "error: cannot write 'missing/SHA256SUMS': No such file or directory\n"
+    );
+}
+
+#[test]
+fn error_invalid_utf8() {
+    let env = TestEnv::new();
+    env.write("input.txt", b"A B\r\n\xff\xfe\r\n");
+
+    let output = env.run(["input.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        stderr(&output),
+        "error: cannot read 'input.txt': it is not valid UTF-8 text\n"
+    );
+
+    let output = env.run(["lint", "input.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        stderr(&output),
+        "error: cannot read 'input.txt': it is not valid UTF-8 text\n"
+    );
+}
+
+#[test]
+fn error_without_panic() {
+    let env = TestEnv::new();
+    env.write("input.txt", b"\xff");
+
+    for args in [["missing.txt"], ["input.txt"], ["."]] {
+        let output = env.run(args);
+        assert_ne!(output.status.code(), Some(0));
+        assert!(!stderr(&output).contains("panicked"));
+        assert!(!stderr(&output).contains("RUST_BACKTRACE"));
+    }
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::{stdout, TestEnv, HASH_DEFAULT};
+
+fn setup_tree(env: &TestEnv) {
+    for file in [
+        "tree/a.sql",
+        "tree/debug.log",
+        "tree/migrations/001.sql",
+        "tree/migrations/archive/000.sql",
+        "tree/migrations/archive/old.log",
+    ] {
+        env.write(file, "A B\r\nC D\r\n");
+    }
+}
+
+/// Hashed files from the output, with `/` as separator.
+fn files(output: &std::process::Output) -> Vec<String> {
+    stdout(output)
+        .lines()
+        .map(|line| line.split_once("  ").unwrap().1.replace('\\', "/"))
+        .collect()
+}
+
+#[test]
+fn exclude_extension() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["-r", "--exclude", "*.log", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        files(&output),
+        [
+            "tree/a.sql",
+            "tree/migrations/001.sql",
+            "tree/migrations/archive/000.sql"
+        ]
+    );
+}
+
+#[test]
+fn exclude_subtree() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["-r", "--exclude", "migrations/archive", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        files(&output),
+        ["tree/a.sql", "tree/debug.log", "tree/migrations/001.sql"]
+    );
+
+    let output = env.run(["--exclude", "tree/migrations/archive", "tree/**/*.sql"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(files(&output), ["tree/a.sql", "tree/migrations/001.sql"]);
+}
+
+#[test]
+fn exclude_explicit_file() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["--exclude", "*.log", "tree/debug.log"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+}
+
+#[test]
+fn exclude_invalid_pattern() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["-r", "--exclude", "[", "tree"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn exclude_with_gitignore() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+    env.write("tree/.gitignore", "*.log\n");
+
+    let output = env.run(["-r", "--exclude", "archive", "tree"]);
+    assert_eq!(
+        files(&output),
+        ["tree/.gitignore", "tree/a.sql", "tree/migrations/001.sql"]
+    );
+
+    // Excludes still apply without gitignore rules
+    let output = env.run(["-r", "--no-ignore", "--exclude", "archive", "tree"]);
+    assert_eq!(
+        files(&output),
+        [
+            "tree/.gitignore",
+            "tree/a.sql",
+            "tree/debug.log",
+            "tree/migrations/001.sql"
+        ]
+    );
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use common::{TestEnv, HASH_DEFAULT};
+
+#[test]
+fn exit_status_success() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b.txt", "A B\r\nC D\r\n");
+    env.write("sums.txt", format!("{HASH_DEFAULT}  a.txt\n"));
+
+    for args in [
+        vec!["a.txt"],
+        vec!["--expected", HASH_DEFAULT, "a.txt"],
+        vec!["--check", "sums.txt"],
+        vec!["compare", "a.txt", "b.txt"],
+        vec!["lint", "a.txt"],
+    ] {
+        assert_eq!(env.run(&args).status.code(), Some(0), "{:?}", args);
+    }
+}
+
+#[test]
+fn exit_status_mismatch() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b.txt", "A B\r\nC D\r\nE F\r\n");
+    env.write("sums.txt", format!("{HASH_DEFAULT}  b.txt\n"));
+
+    for args in [
+        vec!["--expected", HASH_DEFAULT, "b.txt"],
+        vec!["--check", "sums.txt"],
+        vec!["compare", "a.txt", "b.txt"],
+        vec!["lint", "b.txt"],
+    ] {
+        assert_eq!(env.run(&args).status.code(), Some(1), "{:?}", args);
+    }
+}
+
+#[test]
+fn exit_status_usage() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+
+    for args in [
+        vec![],
+        vec!["--unknown", "a.txt"],
+        vec!["--expected", "not-a-hash", "a.txt"],
+        vec!["compare", "a.txt"],
+        vec!["lint"],
+    ] {
+        assert_eq!(env.run(&args).status.code(), Some(2), "{:?}", args);
+    }
+}
+
+#[test]
+fn exit_status_io() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("sums.txt", format!("{HASH_DEFAULT}  missing.txt\n"));
+
+    for args in [
+        vec!["missing.txt"],
+        vec!["--expected", HASH_DEFAULT, "missing.txt"],
+        vec!["--check", "missing.txt"],
+        vec!["--check", "sums.txt"],
+        vec!["compare", "a.txt", "missing.txt"],
+        vec!["lint", "a.txt", "missing.txt"],
+    ] {
+        assert_eq!(env.run(&args).status.code(), Some(3), "{:?}", args);
+    }
+}
+
+#[test]
+fn exit_status_malformed() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("sums.txt", "garbage\n");
+
+    let output = env.run(["--check", "sums.txt"]);
+
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn exit_status_precedence() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\nE F\n");
+    env.write(
+        "sums.txt",
+        format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  missing.txt\n"),
+    );
+
+    // An unreadable file takes precedence over a mismatch
+    let output = env.run(["--check", "sums.txt"]);
+
+    assert_eq!(output.status.code(), Some(3));
+}
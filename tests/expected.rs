@@ -0,0 +1,95 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn expected_match() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--expected", HASH_DEFAULT, "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("OK\nexpected: {HASH_DEFAULT}\nactual:   {HASH_DEFAULT}\n")
+    );
+}
+
+#[test]
+fn expected_match_with_prefix_and_case() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let expected = format!(" sha256:{} ", HASH_DEFAULT.to_uppercase());
+    let output = env.run(["--expected", &expected, "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("OK\n"));
+}
+
+#[test]
+fn expected_mismatch() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\nE F\n");
+
+    let output = env.run(["--expected", HASH_DEFAULT, "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "MISMATCH\n");
+    let stderr = stderr(&output);
+    assert!(stderr.contains(&format!("expected: {HASH_DEFAULT}\n")));
+    assert!(stderr.contains("actual:   "));
+}
+
+#[test]
+fn expected_malformed() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--expected", "not-a-hash", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stdout(&output).is_empty());
+    assert!(stderr(&output).contains("--expected"));
+}
+
+#[test]
+fn expected_writes_file_out() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\nE F\r\n");
+
+    let output = env.run([
+        "--expect",
+        HASH_DEFAULT,
+        "input.txt",
+        "--output-file",
+        "output.txt",
+    ]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).starts_with("MISMATCH\n"));
+    assert_eq!(
+        std::fs::read_to_string(env.path().join("output.txt")).unwrap(),
+        "A B\nC D\nE F\n"
+    );
+}
+
+#[test]
+fn expected_wrong_length() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run([
+        "--expect",
+        &HASH_DEFAULT[1..],
+        "input.txt",
+        "--output-file",
+        "output.txt",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stdout(&output).is_empty());
+    assert!(stderr(&output).contains("found 63 hex digits"));
+    assert!(!env.path().join("output.txt").exists());
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::{stdout, TestEnv};
+
+#[test]
+fn explain_canonical_file() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["explain", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn explain_crlf_file() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\nE F\r\n");
+
+    let output = env.run(["explain", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "--- input.txt\n\
+         +++ input.txt (normalized)\n\
+         @@ -1,3 +1,3 @@\n\
+         -A B\\r\\n\n\
+         -C D\\r\\n\n\
+         -E F\\r\\n\n\
+         +A B\\n\n\
+         +C D\\n\n\
+         +E F\\n\n\
+         3 lines: 3 CRLF→LF\n"
+    );
+}
+
+#[test]
+fn explain_whitespace_and_missing_eof() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nCD\nE F");
+
+    let output = env.run(["explain", "--ignore-whitespaces", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).ends_with("\n3 lines: 1 none→LF, 2 whitespace\n"));
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::{stdout, TestEnv, HASH_DEFAULT};
+
+fn setup_tree(env: &TestEnv) {
+    for file in [
+        "tree/a.sql",
+        "tree/b.txt",
+        "tree/c.log",
+        "tree/scripts/deploy",
+        "tree/scripts/d.sql",
+    ] {
+        env.write(file, "A B\r\nC D\r\n");
+    }
+}
+
+/// Hashed files from the output, with `/` as separator.
+fn files(output: &std::process::Output) -> Vec<String> {
+    stdout(output)
+        .lines()
+        .map(|line| line.split_once("  ").unwrap().1.replace('\\', "/"))
+        .collect()
+}
+
+#[test]
+fn ext_selects_files() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["-r", "--ext", "sql", "--ext", "txt", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        files(&output),
+        ["tree/a.sql", "tree/b.txt", "tree/scripts/d.sql"]
+    );
+}
+
+#[test]
+fn ext_without_extension() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["-r", "--no-ext", "tree"]);
+    assert_eq!(files(&output), ["tree/scripts/deploy"]);
+
+    let output = env.run(["-r", "--ext", "", "--ext", "log", "tree"]);
+    assert_eq!(files(&output), ["tree/c.log", "tree/scripts/deploy"]);
+}
+
+#[test]
+fn ext_requires_recursive() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["--ext", "sql", "tree/b.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = env.run(["tree/b.txt"]);
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+}
+
+#[test]
+fn ext_bypassed_by_explicit_file() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["-r", "--ext", "sql", "tree/b.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(files(&output), ["tree/b.txt"]);
+}
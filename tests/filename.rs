@@ -0,0 +1,94 @@
+mod common;
+
+use common::{stdout, TestEnv, HASH_DEFAULT};
+
+/// Hash of "A B\nC D\nE F\n".
+const HASH_OTHER: &str = "8ed4bf77bac50090168f95ac1911f947454f0092a86b7381aa72d113585315c3";
+
+fn setup(env: &TestEnv) {
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("b.txt", "A B\nC D\nE F\n");
+    env.write("c.txt", "A B\nC D\n");
+}
+
+#[test]
+fn no_filename() {
+    let env = TestEnv::new();
+    setup(&env);
+
+    let output = env.run(["--no-filename", "*.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}\n{HASH_OTHER}\n{HASH_DEFAULT}\n")
+    );
+}
+
+#[test]
+fn filename_only() {
+    let env = TestEnv::new();
+    setup(&env);
+
+    let output = env.run(["--filename-only", "*.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "a.txt\nb.txt\nc.txt\n");
+}
+
+#[test]
+fn filename_only_recursive() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\n");
+    env.write("tree/b.txt", "A B\n");
+
+    let output = env.run(["--filename-only", "-r", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let names: Vec<_> = stdout(&output)
+        .lines()
+        .map(|line| line.replace('\\', "/"))
+        .collect();
+    assert_eq!(names, ["tree/a.txt", "tree/b.txt"]);
+}
+
+#[test]
+fn check_no_filename() {
+    let env = TestEnv::new();
+    setup(&env);
+    env.write(
+        "sums.txt",
+        format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  b.txt\n{HASH_DEFAULT}  c.txt\n"),
+    );
+
+    let output = env.run(["--no-summary", "--check", "--no-filename", "sums.txt"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "OK\nFAILED\nOK\n");
+}
+
+#[test]
+fn check_filename_only() {
+    let env = TestEnv::new();
+    setup(&env);
+    env.write(
+        "sums.txt",
+        format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  b.txt\n{HASH_DEFAULT}  c.txt\n{HASH_DEFAULT}  d.txt\n"),
+    );
+
+    let output = env.run(["--check", "--filename-only", "sums.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "b.txt\nd.txt\n");
+}
+
+#[test]
+fn filename_flags_conflict() {
+    let env = TestEnv::new();
+    setup(&env);
+
+    for args in [
+        ["--no-filename", "--filename-only"],
+        ["--no-filename", "--json"],
+        ["--filename-only", "--csv"],
+    ] {
+        let output = env.run(args.into_iter().chain(["a.txt"]));
+        assert_eq!(output.status.code(), Some(2));
+    }
+}
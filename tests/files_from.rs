@@ -0,0 +1,56 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn files_from_newlines() {
+    let env = TestEnv::new();
+    env.write("b.txt", "A B\r\nC D\r\n");
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("list.txt", "# generated\nb.txt\r\n\na.txt\n");
+
+    let output = env.run(["--files-from", "list.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  b.txt\n{HASH_DEFAULT}  a.txt\n")
+    );
+}
+
+#[test]
+fn files_from_stdin_null() {
+    let env = TestEnv::new();
+    env.write("b.txt", "A B\r\nC D\r\n");
+    env.write("# a.txt", "A B\r\nC D\r\n");
+
+    let output = env.run_with_stdin(["--files-from", "-", "-0"], "b.txt\0# a.txt\0");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  b.txt\n{HASH_DEFAULT}  # a.txt\n")
+    );
+}
+
+#[test]
+fn files_from_nonexistent_path() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("c.txt", "A B\r\nC D\r\n");
+    env.write("list.txt", "a.txt\nb.txt\nc.txt\n");
+
+    let output = env.run(["--files-from", "list.txt"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  c.txt\n")
+    );
+    assert!(stderr(&output).contains("b.txt"));
+}
+
+#[test]
+fn files_from_missing_list() {
+    let env = TestEnv::new();
+
+    let output = env.run(["--files-from", "list.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use std::fs;
+use std::io::Write;
+use std::process::Stdio;
+use std::thread;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn filter_normalizes_stdin_to_stdout() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(["--filter"], "A B\r\nC D\r\n");
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout, b"A B\nC D\n");
+    assert_eq!(stderr(&output), format!("{HASH_DEFAULT}\n"));
+}
+
+#[test]
+fn filter_writes_hash_file() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(["--filter", "--hash-file", "hash.txt"], "A B\r\nC D\r\n");
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "A B\nC D\n");
+    assert_eq!(stderr(&output), "");
+    assert_eq!(
+        fs::read_to_string(env.path().join("hash.txt")).unwrap(),
+        format!("{HASH_DEFAULT}\n")
+    );
+}
+
+#[test]
+fn filter_handles_broken_pipe() {
+    let env = TestEnv::new();
+
+    let mut child = env
+        .command()
+        .arg("--filter")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Close the pipe like a reader that exits early, before any output is read
+    drop(child.stdout.take());
+
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = thread::spawn(move || {
+        let line = "A B\r\n".repeat(1000);
+        for _ in 0..10_000 {
+            if stdin.write_all(line.as_bytes()).is_err() {
+                break;
+            }
+        }
+    });
+
+    let output = child.wait_with_output().unwrap();
+    writer.join().unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn filter_refuses_file_out() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(["--filter", "out.txt"], "A B\r\nC D\r\n");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!env.path().join("out.txt").exists());
+}
@@ -0,0 +1,2 @@
+CREATE TABLE users (id INT);
+CREATE TABLE orders (id INT);
@@ -0,0 +1,46 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn existing_file_out_is_refused() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+    env.write("output.txt", "handwritten notes\n");
+
+    let output = env.run(["input.txt", "--output-file", "output.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("use --force to overwrite it"));
+    assert!(!stderr(&output).contains("overwrite 'output.txt'?"));
+    assert_eq!(
+        fs::read_to_string(env.path().join("output.txt")).unwrap(),
+        "handwritten notes\n"
+    );
+}
+
+#[test]
+fn force_overwrites_file_out() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+    env.write("output.txt", "old content\n");
+
+    let output = env.run(["--force", "input.txt", "--output-file", "output.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+    assert_eq!(
+        fs::read_to_string(env.path().join("output.txt")).unwrap(),
+        "A B\nC D\n"
+    );
+}
+
+#[test]
+fn new_file_out_needs_no_force() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["input.txt", "--output-file", "output.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(env.path().join("output.txt").is_file());
+}
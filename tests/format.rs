@@ -0,0 +1,74 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv};
+
+/// Hash of the empty input, the result for an empty file with `--no-eof`.
+const EMPTY: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+const EMPTY_BASE64: &str = "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=";
+
+fn run_format(env: &TestEnv, format: &str) -> String {
+    let output = env.run(["--no-eof", "--format", format, "empty.txt"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    stdout(&output)
+}
+
+#[test]
+fn format_golden_outputs() {
+    let env = TestEnv::new();
+    env.write("empty.txt", "");
+
+    assert_eq!(run_format(&env, "hex"), format!("{EMPTY}\n"));
+    assert_eq!(
+        run_format(&env, "HEX"),
+        format!("{}\n", EMPTY.to_uppercase())
+    );
+    assert_eq!(run_format(&env, "base64"), format!("{EMPTY_BASE64}\n"));
+    assert_eq!(run_format(&env, "sri"), format!("sha256-{EMPTY_BASE64}\n"));
+    assert_eq!(run_format(&env, "multihash"), format!("1220{EMPTY}\n"));
+}
+
+#[test]
+fn format_batch_and_tag() {
+    let env = TestEnv::new();
+    env.write("empty.txt", "");
+
+    let output = env.run(["--no-eof", "--format", "base64", "--tag", "empty.txt"]);
+    assert_eq!(
+        stdout(&output),
+        format!("SHA256 (empty.txt) = {EMPTY_BASE64}\n")
+    );
+
+    let output = env.run(["--no-eof", "--format", "base64", "*.txt"]);
+    assert_eq!(stdout(&output), format!("{EMPTY_BASE64}  empty.txt\n"));
+
+    let output = env.run(["--format", "sri", "--tag", "empty.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--tag"));
+}
+
+#[test]
+fn verification_detects_encoding() {
+    let env = TestEnv::new();
+    env.write("empty.txt", "");
+
+    let output = env.run([
+        "--no-summary",
+        "--no-eof",
+        "--expected",
+        &format!("sha256-{EMPTY_BASE64}"),
+        "empty.txt",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+
+    env.write("SUMS", format!("1220{EMPTY}  empty.txt\n"));
+    let output = env.run([
+        "--no-summary",
+        "--no-eof",
+        "--format",
+        "HEX",
+        "--check",
+        "SUMS",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "empty.txt: OK\n");
+}
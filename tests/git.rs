@@ -0,0 +1,129 @@
+#![cfg(feature = "git")]
+
+mod common;
+
+use std::path::Path;
+
+use git2::{Repository, Signature};
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Initialize a repository in the test directory and commit `docs/input.txt` with CRLF endings.
+fn commit_crlf(env: &TestEnv) {
+    env.write("docs/input.txt", "A B\r\nC D\r\n");
+
+    let repo = Repository::init(env.path()).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("docs/input.txt")).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+    let signature = Signature::now("Test", "test@example.com").unwrap();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "Add input",
+        &tree,
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn git_compare_head() {
+    let env = TestEnv::new();
+    commit_crlf(&env);
+
+    // Only the line endings changed, which are normalized on both sides
+    env.write("docs/input.txt", "A B\nC D\n");
+    let output = env.run(["compare", "--git", "HEAD", "docs/input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "docs/input.txt and HEAD:docs/input.txt are equal\n"
+    );
+
+    env.write("docs/input.txt", "A B\nC E\n");
+    let output = env.run(["compare", "--git", "HEAD", "docs/input.txt"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "docs/input.txt and HEAD:docs/input.txt differ in line 2\n"
+    );
+}
+
+#[test]
+fn git_hash_committed_version() {
+    let env = TestEnv::new();
+    commit_crlf(&env);
+    env.write("docs/input.txt", "A B\nC E\n");
+
+    let output = env.run(["--git", "HEAD", "docs/input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+
+    let output = env.run([
+        "--git",
+        "HEAD",
+        "--expected",
+        HASH_DEFAULT,
+        "docs/input.txt",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = env.run(["docs/input.txt"]);
+    assert_ne!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+}
+
+#[test]
+fn git_untracked() {
+    let env = TestEnv::new();
+    commit_crlf(&env);
+    env.write("docs/new.txt", "A B\nC D\n");
+
+    let output = env.run(["--git", "HEAD", "docs/new.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "");
+    assert!(
+        stderr(&output).contains("cannot read docs/new.txt at HEAD: it is not tracked"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = env.run(["compare", "--git", "HEAD", "docs/new.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(
+        stderr(&output).contains("cannot read docs/new.txt at HEAD: it is not tracked"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn git_unknown_revision() {
+    let env = TestEnv::new();
+    commit_crlf(&env);
+
+    let output = env.run(["--git", "v9.9", "docs/input.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(
+        stderr(&output).contains("cannot read docs/input.txt at v9.9: unknown revision"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn git_outside_repository() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--git", "HEAD", "input.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(
+        stderr(&output).contains("cannot read input.txt at HEAD"),
+        "{}",
+        stderr(&output)
+    );
+}
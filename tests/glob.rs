@@ -0,0 +1,55 @@
+mod common;
+
+use std::path::PathBuf;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn glob_matches_in_subdirectories() {
+    let env = TestEnv::new();
+    for file in [
+        "b.sql",
+        "a.sql",
+        "sub/c.sql",
+        "sub/deeper/d.sql",
+        "sub/e.txt",
+    ] {
+        env.write(file, "A B\r\nC D\r\n");
+    }
+
+    let output = env.run(["**/*.sql"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let expected: String = ["a.sql", "b.sql", "sub/c.sql", "sub/deeper/d.sql"]
+        .iter()
+        .map(|file| {
+            let file: PathBuf = file.split('/').collect();
+            format!("{HASH_DEFAULT}  {}\n", file.display())
+        })
+        .collect();
+    assert_eq!(stdout(&output), expected);
+}
+
+#[test]
+fn glob_without_matches() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["*.sql"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(stderr(&output), "error: pattern *.sql matches no files\n");
+
+    let output = env.run(["--allow-empty-glob", "*.sql"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn glob_rejects_expected() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--expected", HASH_DEFAULT, "*.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+}
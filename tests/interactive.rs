@@ -0,0 +1,13 @@
+mod common;
+
+use common::{stderr, TestEnv};
+
+#[test]
+fn no_arguments_without_console_is_usage_error() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(Vec::<&str>::new(), "input.txt\n");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Usage:"));
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv};
+
+/// Create a tree of a few hundred small files with different content.
+fn generate_tree(env: &TestEnv) {
+    for dir in 0..10 {
+        for file in 0..30 {
+            env.write(
+                format!("tree/dir{dir}/file{file}.txt"),
+                format!("dir {dir}\r\nfile {file}\r\n").repeat(file + 1),
+            );
+        }
+    }
+    env.write("tree/dir3/binary.bin", b"\0\x01\x02");
+}
+
+#[test]
+fn jobs_recursive_same_output() {
+    let env = TestEnv::new();
+    generate_tree(&env);
+
+    let serial = env.run(["--no-summary", "-j1", "-r", "tree"]);
+    let parallel = env.run(["--no-summary", "-j8", "-r", "tree"]);
+
+    assert_eq!(serial.status.code(), Some(0));
+    assert_eq!(parallel.status.code(), Some(0));
+    assert_eq!(stdout(&serial).lines().count(), 300);
+    assert_eq!(serial.stdout, parallel.stdout);
+    assert_eq!(serial.stderr, parallel.stderr);
+}
+
+#[test]
+fn jobs_errors_are_isolated() {
+    let env = TestEnv::new();
+    generate_tree(&env);
+
+    let mut list = String::new();
+    for file in 0..30 {
+        list.push_str(&format!("tree/dir1/file{file}.txt\n"));
+        if file % 7 == 0 {
+            list.push_str(&format!("tree/missing{file}.txt\n"));
+        }
+    }
+    env.write("list.txt", list);
+
+    let serial = env.run(["--no-summary", "-j1", "--files-from", "list.txt"]);
+    let parallel = env.run(["--no-summary", "--jobs", "8", "--files-from", "list.txt"]);
+
+    assert_eq!(serial.status.code(), Some(5));
+    assert_eq!(parallel.status.code(), Some(5));
+    assert_eq!(stdout(&serial).lines().count(), 30);
+    assert_eq!(serial.stdout, parallel.stdout);
+    assert_eq!(stderr(&serial), stderr(&parallel));
+    assert!(stderr(&parallel).ends_with("warning: 5 files could not be read\n"));
+}
+
+#[test]
+fn jobs_fail_fast() {
+    let env = TestEnv::new();
+    generate_tree(&env);
+    env.write(
+        "list.txt",
+        "tree/dir1/file1.txt\nmissing.txt\ntree/dir1/file2.txt\n",
+    );
+
+    let output = env.run(["-j8", "--fail-fast", "--files-from", "list.txt"]);
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output).lines().count(), 1);
+}
+
+#[test]
+fn jobs_time_reports_elapsed() {
+    let env = TestEnv::new();
+    generate_tree(&env);
+
+    let output = env.run(["--no-summary", "-j4", "--time", "-r", "tree"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = stderr(&output);
+    let total = stderr.lines().last().unwrap();
+    assert!(total.starts_with("time: total: 300 files, "), "{}", total);
+    assert!(total.contains(" elapsed, "), "{}", total);
+}
+
+#[test]
+fn jobs_zero_is_usage_error() {
+    let env = TestEnv::new();
+    generate_tree(&env);
+
+    let output = env.run(["-j0", "-r", "tree"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
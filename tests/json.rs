@@ -0,0 +1,137 @@
+mod common;
+
+use serde_json::Value;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn json_batch() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    env.write("tree/b.txt", "A B\nC D\n");
+    env.write("tree/c.txt", b"\xff\xfe\n");
+    env.write("tree/d.bin", b"\0\0");
+
+    let output = env.run(["-r", "--json", "tree"]);
+    assert_eq!(output.status.code(), Some(5));
+
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let entries = report["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 3);
+
+    for entry in &entries[..2] {
+        assert!(entry["path"].is_string());
+        assert_eq!(entry["hash"], HASH_DEFAULT);
+        assert_eq!(entry["algorithm"], "SHA256");
+        assert!(entry["error"].is_null());
+    }
+    assert_eq!(entries[0]["bytes"], 10);
+    assert_eq!(entries[0]["changed"], true);
+    assert_eq!(entries[1]["bytes"], 8);
+    assert_eq!(entries[1]["changed"], false);
+
+    assert!(entries[2]["path"].as_str().unwrap().ends_with("c.txt"));
+    assert_eq!(entries[2]["error"]["kind"], "decode");
+    assert!(entries[2]["error"]["message"].is_string());
+    assert!(entries[2]["hash"].is_null());
+
+    let summary = &report["summary"];
+    assert_eq!(summary["hashed"], 2);
+    assert_eq!(summary["changed"], 1);
+    assert_eq!(summary["skipped"], 1);
+    assert_eq!(summary["errors"], 1);
+}
+
+#[test]
+fn json_single_file() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--json", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["entries"][0]["path"], "input.txt");
+    assert_eq!(report["entries"][0]["hash"], HASH_DEFAULT);
+    assert_eq!(report["summary"]["hashed"], 1);
+
+    let output = env.run(["--json", "missing.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["entries"][0]["error"]["kind"], "open");
+    assert!(report["entries"][0]["error"]["message"].is_string());
+    assert_eq!(report["summary"]["errors"], 1);
+}
+
+#[test]
+fn json_create_error() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\n");
+
+    let output = env.run(["--json", "--output-file", "missing/out.txt", "input.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["entries"][0]["error"]["kind"], "create");
+
+    let output = env.run_with_stdin(["--json", "--output-file", "missing/out.txt", "-"], "A B\n");
+    assert_eq!(output.status.code(), Some(3));
+
+    let error: Value = serde_json::from_str(&stderr(&output)).unwrap();
+    assert_eq!(error["path"], "missing/out.txt");
+    assert_eq!(error["error"]["kind"], "create");
+}
+
+#[cfg(unix)]
+#[test]
+fn json_unreadable_file() {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    let file = env.write("tree/b.txt", "A B\r\nC D\r\n");
+    fs::set_permissions(&file, fs::Permissions::from_mode(0o000)).unwrap();
+
+    // Permissions are not enforced for privileged users
+    if fs::read(&file).is_ok() {
+        return;
+    }
+
+    let output = env.run(["-r", "--json", "tree"]);
+    assert_eq!(output.status.code(), Some(5));
+
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    let error = &report["entries"][1]["error"];
+    assert!(report["entries"][1]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("b.txt"));
+    assert_eq!(error["kind"], "open");
+    assert!(error["message"]
+        .as_str()
+        .unwrap()
+        .contains("ermission denied"));
+}
+
+#[test]
+fn json_fatal_error() {
+    let env = TestEnv::new();
+
+    let output = env.run(["--json", "*.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "");
+
+    let error: Value = serde_json::from_str(&stderr(&output)).unwrap();
+    assert_eq!(error["error"]["kind"], "io");
+    assert_eq!(error["error"]["message"], "pattern *.txt matches no files");
+
+    env.write("dir/a.txt", "A B\n");
+    let output = env.run(["--json", "dir"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let error: Value = serde_json::from_str(&stderr(&output)).unwrap();
+    assert_eq!(error["path"], "dir");
+    assert_eq!(error["error"]["kind"], "usage");
+}
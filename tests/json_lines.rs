@@ -0,0 +1,147 @@
+mod common;
+
+use serde_json::Value;
+
+use common::{stdout, TestEnv, HASH_DEFAULT};
+
+/// Parse each line of the output as a JSON object of its own.
+fn parse_lines(stdout: &str) -> Vec<Value> {
+    stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn json_lines_batch_shares_schema_with_json() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    env.write("tree/b.txt", "A B\nC D\n");
+    env.write("tree/c.txt", b"\xff\xfe\n");
+    env.write("tree/d.bin", b"\0\0");
+
+    let output = env.run(["-r", "--json-lines", "tree"]);
+    assert_eq!(output.status.code(), Some(5));
+    let mut lines = parse_lines(&stdout(&output));
+
+    let output = env.run(["-r", "--json", "tree"]);
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+
+    let summary = lines.pop().unwrap();
+    assert_eq!(summary["type"], "summary");
+    assert_eq!(summary["hashed"], 2);
+    assert_eq!(summary["skipped"], 1);
+    assert_eq!(summary["errors"], 1);
+
+    // Apart from the type, each line is exactly an entry of the document
+    let entries = lines
+        .into_iter()
+        .map(|mut line| {
+            assert_eq!(line["type"], "entry");
+            line.as_object_mut().unwrap().remove("type");
+            line
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(&entries, report["entries"].as_array().unwrap());
+}
+
+#[test]
+fn json_lines_unordered() {
+    let env = TestEnv::new();
+    let names = ["a.txt", "b.txt", "c.txt", "d.txt", "e.txt", "f.txt"];
+    for name in names {
+        env.write(format!("tree/{name}"), "A B\r\nC D\r\n");
+    }
+
+    let output = env.run(["-r", "--json-lines", "--unordered", "--jobs", "3", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    let mut lines = parse_lines(&stdout(&output));
+    assert_eq!(lines.pop().unwrap()["type"], "summary");
+
+    lines.sort_by_key(|line| line["seq"].as_u64().unwrap());
+    for (seq, line) in lines.iter().enumerate() {
+        assert_eq!(line["seq"], seq);
+        assert_eq!(line["hash"], HASH_DEFAULT);
+        assert_eq!(line["path"], format!("tree/{}", names[seq]));
+    }
+}
+
+#[test]
+fn json_lines_single_file() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--json-lines", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let lines = parse_lines(&stdout(&output));
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["type"], "entry");
+    assert_eq!(lines[0]["hash"], HASH_DEFAULT);
+    assert!(lines[0]["seq"].is_null());
+    assert_eq!(lines[1]["type"], "summary");
+}
+
+#[test]
+fn json_lines_unordered_requires_json_lines() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--unordered", "input.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+/// Each line is printed while the run is still going on, which is blocked on a named pipe.
+#[cfg(unix)]
+#[test]
+fn json_lines_streams_results() {
+    use std::fs;
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::{Command, Stdio};
+
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("list.txt", "a.txt\npipe\n");
+    let status = Command::new("mkfifo")
+        .arg(env.path().join("pipe"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let mut child = env
+        .command()
+        .args(["--json-lines", "--jobs", "1", "--files-from", "list.txt"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    let entry: Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(entry["type"], "entry");
+    assert_eq!(entry["path"], "a.txt");
+    assert_eq!(entry["hash"], HASH_DEFAULT);
+
+    // The hasher waits for the pipe, so it cannot have finished yet
+    assert!(child.try_wait().unwrap().is_none());
+
+    let mut pipe = fs::OpenOptions::new()
+        .write(true)
+        .open(env.path().join("pipe"))
+        .unwrap();
+    pipe.write_all(b"A B\nC D\n").unwrap();
+    drop(pipe);
+
+    let lines = stdout
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(&line.unwrap()).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["path"], "pipe");
+    assert_eq!(lines[0]["hash"], HASH_DEFAULT);
+    assert_eq!(lines[1]["type"], "summary");
+    assert_eq!(lines[1]["hashed"], 2);
+
+    assert_eq!(child.wait().unwrap().code(), Some(0));
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use std::fs;
+
+use common::{stdout, TestEnv, HASH_DEFAULT};
+
+/// Mostly CRLF, with a few LF lines, whose LF twin is `"A B\nC D\n"` repeated.
+const MOSTLY_CRLF: &str = "A B\r\nC D\r\nA B\nC D\r\nA B\r\nC D\n";
+
+#[test]
+fn keep_style_file_out() {
+    let env = TestEnv::new();
+    env.write("input.txt", MOSTLY_CRLF);
+    env.write("twin.txt", "A B\nC D\n".repeat(3));
+
+    let output = env.run(["--keep-style", "input.txt", "--output-file", "output.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    let hash = stdout(&output);
+
+    assert_eq!(
+        fs::read(env.path().join("output.txt")).unwrap(),
+        "A B\r\nC D\r\n".repeat(3).as_bytes()
+    );
+    assert_eq!(stdout(&env.run(["twin.txt"])), hash);
+}
+
+#[test]
+fn keep_style_normalize_in_place() {
+    let env = TestEnv::new();
+    let mixed = env.write("mixed.txt", MOSTLY_CRLF);
+    let crlf = env.write("crlf.txt", "A B\r\nC D\r\n");
+    let lf = env.write("lf.txt", "A B\r\nC D\n\n");
+
+    let output = env.run([
+        "normalize",
+        "--in-place",
+        "--keep-style",
+        "mixed.txt",
+        "crlf.txt",
+        "lf.txt",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "mixed.txt: normalized\ncrlf.txt: unchanged\nlf.txt: normalized\n"
+    );
+
+    assert_eq!(
+        fs::read(&mixed).unwrap(),
+        "A B\r\nC D\r\n".repeat(3).as_bytes()
+    );
+    assert_eq!(fs::read(&crlf).unwrap(), b"A B\r\nC D\r\n");
+    assert_eq!(fs::read(&lf).unwrap(), b"A B\nC D\n\n");
+
+    assert_eq!(stdout(&env.run(["crlf.txt"])), format!("{HASH_DEFAULT}\n"));
+}
+
+#[test]
+fn keep_style_filter() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(["--keep-style", "--filter"], "A B\r\nC D\n\r\n");
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "A B\r\nC D\r\n\r\n");
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv};
+
+#[test]
+fn lint_clean_files() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b.txt", "E F\n");
+
+    let output = env.run(["lint", "a.txt", "b.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn lint_lists_offenders() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b.txt", "E F\r\n");
+    env.write("c.txt", "G H");
+
+    let output = env.run(["lint", "a.txt", "b.txt", "c.txt"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "b.txt\nc.txt\n");
+}
+
+#[test]
+fn lint_fix_offenders() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    let b = env.write("b.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["lint", "--fix", "a.txt", "b.txt"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "b.txt\n");
+    assert_eq!(fs::read_to_string(b).unwrap(), "A B\nC D\n");
+
+    let output = env.run(["lint", "a.txt", "b.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn lint_recursive() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\nC D\n");
+    env.write("tree/sub/b.txt", "E F\r\n");
+    env.write("tree/sub/skip.bak", "G H");
+    env.write("tree/.hidden.txt", "I J");
+    fs::write(env.path().join("tree/image.bin"), [0x89, b'P', 0, 0, b'\r']).unwrap();
+
+    let output = env.run(["lint", "-r", "tree", "--exclude", "*.bak"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "tree/.hidden.txt\ntree/sub/b.txt\n");
+    assert!(stderr(&output).contains("skipping binary file"));
+
+    let output = env.run(["lint", "-r", "--no-hidden", "tree/sub", "tree/a.txt"]);
+    assert_eq!(stdout(&output), "tree/sub/b.txt\ntree/sub/skip.bak\n");
+
+    // Glob patterns are expanded as for hashing
+    let output = env.run(["lint", "tree/sub/*.txt"]);
+    assert_eq!(stdout(&output), "tree/sub/b.txt\n");
+
+    // Directories need --recursive
+    let output = env.run(["lint", "tree"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        stderr(&output).contains("tree is a directory, use --recursive to lint all files in it")
+    );
+}
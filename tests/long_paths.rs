@@ -0,0 +1,53 @@
+#![cfg(windows)]
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use normalized_hash::long_path;
+
+use common::{stdout, TestEnv, HASH_DEFAULT};
+
+/// Directory below the test directory whose path is longer than 260 characters.
+fn deep_dir(env: &TestEnv) -> PathBuf {
+    let component = "d".repeat(50);
+    let dir = (0..6).fold(env.path().to_path_buf(), |dir, _| dir.join(&component));
+    assert!(dir.as_os_str().len() > 260);
+
+    fs::create_dir_all(long_path(&dir)).unwrap();
+    dir
+}
+
+#[test]
+fn long_path_is_hashed() {
+    let env = TestEnv::new();
+    let file = deep_dir(&env).join("input.txt");
+    fs::write(long_path(&file), "A B\r\nC D\r\n").unwrap();
+
+    let output = env.run([&file]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+
+    let file_out = file.with_file_name("output.txt");
+    let output = env.run([&file, &file_out]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(long_path(&file_out)).unwrap(),
+        "A B\nC D\n"
+    );
+}
+
+#[test]
+fn long_path_is_walked_and_printed_as_given() {
+    let env = TestEnv::new();
+    let dir = deep_dir(&env);
+    fs::write(long_path(dir.join("input.txt")), "A B\r\nC D\r\n").unwrap();
+
+    let output = env.run([std::ffi::OsStr::new("-r"), dir.as_os_str()]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  {}\n", dir.join("input.txt").display())
+    );
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+fn generate_tree(env: &TestEnv) {
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    env.write("tree/sub/b.txt", "A B\nC D\n");
+    env.write("tree/sub/deeper/c.txt", "E F\n");
+    env.write("tree/logo.bin", b"\0\x01\x02");
+}
+
+#[test]
+fn manifest_generate_then_verify() {
+    let env = TestEnv::new();
+    generate_tree(&env);
+
+    let output = env.run(["manifest", "generate", "tree", "-o", "MANIFEST"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stderr(&output),
+        "note: skipping binary file tree/logo.bin\n"
+    );
+
+    let manifest = fs::read_to_string(env.path().join("MANIFEST")).unwrap();
+    let paths = manifest
+        .lines()
+        .skip(2)
+        .map(|line| line.rsplit('\t').next().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, ["a.txt", "sub/b.txt", "sub/deeper/c.txt"]);
+    assert!(manifest.contains(&format!("{HASH_DEFAULT}\t")));
+
+    let output = env.run(["manifest", "verify", "tree", "MANIFEST"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "a.txt: OK\nsub/b.txt: OK\nsub/deeper/c.txt: OK\n3 OK, 0 changed, 0 missing, 0 extra\n"
+    );
+}
+
+#[test]
+fn manifest_verify_reports_changes() {
+    let env = TestEnv::new();
+    generate_tree(&env);
+    env.run(["manifest", "generate", "tree", "-o", "MANIFEST"]);
+
+    // Only line endings change, which is not a change after normalization
+    env.write("tree/sub/b.txt", "A B\r\nC D\r\n");
+    env.write("tree/sub/deeper/c.txt", "E F G\n");
+    fs::remove_file(env.path().join("tree/a.txt")).unwrap();
+    env.write("tree/new.txt", "new\n");
+
+    let output = env.run(["manifest", "verify", "tree", "MANIFEST"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "a.txt: MISSING\nnew.txt: EXTRA\nsub/b.txt: OK\nsub/deeper/c.txt: CHANGED\n\
+         1 OK, 1 changed, 1 missing, 1 extra\n"
+    );
+}
+
+#[test]
+fn manifest_inside_tree_is_not_recorded() {
+    let env = TestEnv::new();
+    generate_tree(&env);
+
+    let output = env.run(["manifest", "generate", "tree", "-o", "tree/MANIFEST"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = env.run(["manifest", "verify", "tree", "tree/MANIFEST"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!stdout(&output).contains("MANIFEST"));
+}
+
+#[test]
+fn manifest_update_refreshes_changed_entries() {
+    let env = TestEnv::new();
+    generate_tree(&env);
+    env.run(["manifest", "generate", "tree", "-o", "MANIFEST"]);
+
+    env.write("tree/sub/deeper/c.txt", "E F G\n");
+    fs::remove_file(env.path().join("tree/a.txt")).unwrap();
+    env.write("tree/new.txt", "new\n");
+
+    let output = env.run(["manifest", "update", "tree", "MANIFEST"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "a.txt: removed\nnew.txt: added\nsub/deeper/c.txt: updated\n\
+         1 updated, 1 added, 1 removed, 1 unchanged\n"
+    );
+
+    let output = env.run(["manifest", "verify", "tree", "MANIFEST"]);
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn manifest_verify_requires_same_options() {
+    let env = TestEnv::new();
+    generate_tree(&env);
+    env.run(["manifest", "generate", "tree", "-o", "MANIFEST"]);
+
+    let output = env.run(["--no-eof", "manifest", "verify", "tree", "MANIFEST"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("other normalization options"));
+}
+
+#[test]
+fn manifest_verify_malformed() {
+    let env = TestEnv::new();
+    generate_tree(&env);
+    env.write("MANIFEST", "a.txt  OK\n");
+
+    let output = env.run(["manifest", "verify", "tree", "MANIFEST"]);
+
+    assert_eq!(output.status.code(), Some(4));
+}
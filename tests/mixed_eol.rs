@@ -0,0 +1,59 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn mixed_eol_warning() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\r\n");
+
+    let output = env.run(["input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{}\n", HASH_DEFAULT));
+    assert_eq!(
+        stderr(&output),
+        "warning: input.txt mixes line endings (1 CRLF, 1 LF)\n"
+    );
+}
+
+#[test]
+fn no_warning_for_consistent_eol() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn mixed_eol_warning_suppressed() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\r\n");
+
+    let output = env.run(["--no-warn-mixed-eol", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn mixed_eol_denied() {
+    let env = TestEnv::new();
+    env.write("dir/a.txt", "A B\nC D\r\n");
+    env.write("dir/b.txt", "A B\nC D\n");
+
+    let output = env.run(["--no-summary", "--deny-mixed-eol", "-r", "dir"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output).lines().count(), 2);
+    assert_eq!(
+        stderr(&output),
+        "error: dir/a.txt mixes line endings (1 CRLF, 1 LF)\n"
+    );
+
+    let output = env.run(["--no-summary", "--deny-mixed-eol", "dir/b.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Append a line to `file` every millisecond while `run` is running, like a generator that is
+/// still writing it.
+fn while_appending<T>(file: &Path, run: impl FnOnce() -> T) -> T {
+    let done = AtomicBool::new(false);
+    let mut file = OpenOptions::new().append(true).open(file).unwrap();
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            while !done.load(Ordering::Relaxed) {
+                file.write_all(b"E F\r\n").unwrap();
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+
+        let result = run();
+        done.store(true, Ordering::Relaxed);
+        result
+    })
+}
+
+#[test]
+fn modified_file_is_an_error() {
+    let env = TestEnv::new();
+    let file = env.write("growing.txt", "A B\r\n".repeat(200_000));
+
+    let output = while_appending(&file, || env.run(["--retry-on-modify", "2", "growing.txt"]));
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "error: cannot hash 'growing.txt': it was modified while it was read\n"
+    );
+}
+
+#[test]
+fn modified_file_is_skipped_in_batch() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    let file = env.write("tree/growing.txt", "A B\r\n".repeat(200_000));
+
+    let output = while_appending(&file, || env.run(["-r", "tree"]));
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}  tree/a.txt\n"));
+    assert!(stderr(&output).starts_with(
+        "warning: cannot hash 'tree/growing.txt': it was modified while it was read, skipping\n"
+    ));
+    assert!(stderr(&output).contains("summary: 1 hashed, 0 skipped, 1 unreadable, "));
+
+    let output = while_appending(&file, || env.run(["-r", "--json", "tree"]));
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["entries"][1]["error"]["kind"], "modified");
+}
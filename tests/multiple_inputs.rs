@@ -0,0 +1,111 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Hash of "E F\n".
+const HASH_OTHER: &str = "c4079a67fee1a22604a6e67767c2efe96ebedb918faa7bf5efd6afa73d5f9519";
+
+fn setup(env: &TestEnv) {
+    env.write("a.sql", "A B\r\nC D\r\n");
+    env.write("b.sql", "E F\n");
+    env.write("c.sql", "A B\nC D\n");
+}
+
+#[test]
+fn multiple_inputs_in_order() {
+    let env = TestEnv::new();
+    setup(&env);
+
+    let output = env.run(["--no-summary", "c.sql", "b.sql", "a.sql"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  c.sql\n{HASH_OTHER}  b.sql\n{HASH_DEFAULT}  a.sql\n")
+    );
+
+    // Two paths are FILE_IN and FILE_OUT, an existing FILE_OUT is not overwritten without --force
+    let output = env.run(["--no-summary", "a.sql", "b.sql"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains("b.sql already exists, use --force to overwrite it"));
+    assert_eq!(
+        fs::read_to_string(env.path().join("b.sql")).unwrap(),
+        "E F\n"
+    );
+
+    // A glob pattern matching two files hashes both
+    let output = env.run(["--no-summary", "[ab].sql"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  a.sql\n{HASH_OTHER}  b.sql\n")
+    );
+
+    // The flag only takes a single input
+    let output = env.run(["a.sql", "b.sql", "--output-file", "out.sql"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!env.path().join("out.sql").exists());
+}
+
+#[test]
+fn two_paths_write_file_out() {
+    let env = TestEnv::new();
+    setup(&env);
+
+    let output = env.run(["a.sql", "out.sql"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+    assert_eq!(
+        fs::read_to_string(env.path().join("out.sql")).unwrap(),
+        "A B\nC D\n"
+    );
+
+    let output = env.run(["--expect", HASH_OTHER, "a.sql", "mismatch.sql"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(env.path().join("mismatch.sql").exists());
+}
+
+#[test]
+fn multiple_inputs_continue_after_failure() {
+    let env = TestEnv::new();
+    setup(&env);
+
+    let output = env.run(["--no-summary", "a.sql", "missing.sql", "b.sql"]);
+    assert_ne!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  a.sql\n{HASH_OTHER}  b.sql\n")
+    );
+    assert!(stderr(&output).contains("cannot open 'missing.sql'"));
+}
+
+#[test]
+fn multiple_inputs_output_dir() {
+    let env = TestEnv::new();
+    setup(&env);
+
+    let output = env.run(["--output-dir", "out", "a.sql", "b.sql", "c.sql"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(env.path().join("out/a.sql")).unwrap(),
+        "A B\nC D\n"
+    );
+    assert!(env.path().join("out/c.sql").exists());
+
+    let output = env.run(["--output-dir", "mirror", "a.sql", "b.sql"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(env.path().join("mirror/b.sql").exists());
+}
+
+#[test]
+fn multiple_inputs_reject_stdin() {
+    let env = TestEnv::new();
+    setup(&env);
+
+    let output = env.run_with_stdin(["a.sql", "-", "b.sql"], "A B\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stdout(&output).is_empty());
+    assert!(stderr(&output).contains("standard input cannot be hashed together"));
+}
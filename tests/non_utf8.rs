@@ -0,0 +1,72 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Create a file whose name is encoded in Latin-1, so it is not valid UTF-8.
+fn latin1_file(env: &TestEnv) {
+    env.write(
+        Path::new("tree").join(OsStr::from_bytes(b"caf\xe9.txt")),
+        "A B\r\nC D\r\n",
+    );
+}
+
+#[test]
+fn non_utf8_names_are_escaped() {
+    let env = TestEnv::new();
+    latin1_file(&env);
+
+    let output = env.run(["-r", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  tree/caf%E9.txt\n")
+    );
+
+    let output = env.run(["-r", "--json", "tree"]);
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["entries"][0]["path"], "tree/caf%E9.txt");
+}
+
+#[test]
+fn non_utf8_names_round_trip() {
+    let env = TestEnv::new();
+    latin1_file(&env);
+
+    let output = env.run(["-r", "tree", "-o", "SUMS"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = env.run(["--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert!(stdout(&output).starts_with("tree/caf%E9.txt: OK\n"));
+
+    let output = env.run(["manifest", "generate", "tree", "-o", "tree.manifest"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+
+    let output = env.run(["manifest", "verify", "tree", "tree.manifest"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+}
+
+#[test]
+fn strict_names_rejects_non_utf8_names() {
+    let env = TestEnv::new();
+    latin1_file(&env);
+    env.write("tree/plain.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--strict-names", "-r", "tree"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "error: the name of tree/caf\u{FFFD}.txt is not valid UTF-8, it can only be recorded \
+         escaped as tree/caf%E9.txt\n"
+    );
+
+    let output = env.run(["--strict-names", "tree/plain.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv};
+
+#[test]
+fn normalize_dry_run_then_fix() {
+    let env = TestEnv::new();
+    let crlf = env.write("crlf.txt", "A B\r\nC D\r\n");
+    env.write("lf.txt", "A B\nC D\n");
+
+    let output = env.run(["normalize", "--dry-run", "crlf.txt", "lf.txt"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "crlf.txt\n");
+    assert_eq!(fs::read(&crlf).unwrap(), b"A B\r\nC D\r\n");
+
+    let output = env.run(["normalize", "--in-place", "crlf.txt", "lf.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "crlf.txt: normalized\nlf.txt: unchanged\n");
+    assert_eq!(fs::read(&crlf).unwrap(), b"A B\nC D\n");
+
+    let output = env.run(["normalize", "--dry-run", "crlf.txt", "lf.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn normalize_in_place_with_backup() {
+    let env = TestEnv::new();
+    env.write("crlf.txt", "A B\r\nC D\r\n");
+    env.write("lf.txt", "A B\nC D\n");
+
+    let output = env.run([
+        "normalize",
+        "--in-place",
+        "--backup",
+        "--backup-suffix",
+        ".bak",
+        "crlf.txt",
+        "lf.txt",
+    ]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read(env.path().join("crlf.txt.bak")).unwrap(),
+        b"A B\r\nC D\r\n"
+    );
+    assert!(!env.path().join("lf.txt.bak").exists());
+}
+
+#[test]
+fn normalize_output_dir_keeps_structure() {
+    let env = TestEnv::new();
+    let crlf = env.write("src/sub/crlf.txt", "A B\r\nC D\r\n");
+    env.write("src/lf.txt", "A B\nC D\n");
+    env.write("src/logo.bin", b"\0\x01\x02");
+
+    let output = env.run(["normalize", "-r", "--output-dir", "out", "src"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "src/lf.txt: unchanged\nsrc/sub/crlf.txt: normalized\n"
+    );
+    assert_eq!(stderr(&output), "note: skipping binary file src/logo.bin\n");
+    assert_eq!(
+        fs::read(env.path().join("out/sub/crlf.txt")).unwrap(),
+        b"A B\nC D\n"
+    );
+    assert_eq!(
+        fs::read(env.path().join("out/lf.txt")).unwrap(),
+        b"A B\nC D\n"
+    );
+    assert!(!env.path().join("out/logo.bin").exists());
+    assert_eq!(fs::read(&crlf).unwrap(), b"A B\r\nC D\r\n");
+}
+
+#[test]
+fn normalize_output_dir_refuses_overwriting_input() {
+    let env = TestEnv::new();
+    let crlf = env.write("crlf.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["normalize", "--output-dir", ".", "crlf.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("would be overwritten by its own output"));
+    assert_eq!(fs::read(&crlf).unwrap(), b"A B\r\nC D\r\n");
+}
+
+#[test]
+fn normalize_requires_mode() {
+    let env = TestEnv::new();
+    env.write("crlf.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["normalize", "crlf.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+/// Normalized hashes of all files below `dir`, with paths relative to it.
+fn tree_hashes(env: &TestEnv, dir: &str) -> String {
+    let output = env
+        .command()
+        .current_dir(env.path().join(dir))
+        .args(["-r", "."])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    stdout(&output)
+}
+
+#[test]
+fn normalize_mirror_is_idempotent() {
+    let env = TestEnv::new();
+    env.write("src/a.txt", "A B\r\nC D\r\n");
+    env.write("src/sub/b.txt", "A B\nC D\n");
+    env.write("src/sub/logo.bin", b"\0\x01\x02");
+
+    let output = env.run(["normalize", "--mirror", "src", "dist"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "dist/a.txt: changed\ndist/sub/b.txt: changed\ndist/sub/logo.bin: copied\n\
+         2 changed, 0 unchanged, 1 copied, 0 skipped\n"
+    );
+    assert_eq!(
+        fs::read(env.path().join("dist/a.txt")).unwrap(),
+        b"A B\nC D\n"
+    );
+    assert_eq!(
+        fs::read(env.path().join("dist/sub/logo.bin")).unwrap(),
+        b"\0\x01\x02"
+    );
+
+    let output = env.run(["normalize", "--mirror", "src", "dist"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "0 changed, 3 unchanged, 0 copied, 0 skipped\n"
+    );
+    assert_eq!(tree_hashes(&env, "src"), tree_hashes(&env, "dist"));
+}
+
+#[test]
+fn normalize_mirror_keeps_extra_files_and_skips_binary() {
+    let env = TestEnv::new();
+    env.write("src/a.txt", "A B\r\nC D\r\n");
+    env.write("src/logo.bin", b"\0\x01\x02");
+    env.write("dist/extra.txt", "keep me\n");
+
+    let output = env.run(["normalize", "--mirror", "src", "dist", "--skip-binary"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "dist/a.txt: changed\n1 changed, 0 unchanged, 0 copied, 1 skipped\n"
+    );
+    assert!(!env.path().join("dist/logo.bin").exists());
+    assert_eq!(
+        fs::read(env.path().join("dist/extra.txt")).unwrap(),
+        b"keep me\n"
+    );
+}
+
+#[test]
+fn normalize_mirror_refuses_destination_inside_source() {
+    let env = TestEnv::new();
+    env.write("src/a.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["normalize", "--mirror", "src", "src/dist"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!env.path().join("src/dist").exists());
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn directory_without_recursive() {
+    let env = TestEnv::new();
+    fs::create_dir(env.path().join("dir")).unwrap();
+
+    let output = env.run(["dir"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "error: dir is a directory, use --recursive to hash all files in it\n"
+    );
+}
+
+#[test]
+fn listed_directory() {
+    let env = TestEnv::new();
+    fs::create_dir(env.path().join("dir")).unwrap();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("list.txt", "dir\na.txt\n");
+    env.write(
+        "SUMS",
+        format!("{HASH_DEFAULT}  dir\n{HASH_DEFAULT}  a.txt\n"),
+    );
+
+    let output = env.run(["--no-summary", "--files-from", "list.txt"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}  a.txt\n"));
+    assert!(stderr(&output).starts_with("warning: 'dir' is a directory, not a file, skipping\n"));
+
+    let output = env.run(["--no-summary", "--files-from", "list.txt", "--json"]);
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["entries"][0]["error"]["kind"], "not_a_file");
+
+    let output = env.run(["--no-summary", "--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "dir: FAILED open or read\na.txt: OK\n");
+    assert!(stderr(&output).starts_with("error: 'dir' is a directory, not a file\n"));
+
+    let output = env.run(["compare", "dir", "a.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        stderr(&output),
+        "error: cannot compare dir and a.txt: dir is a directory\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn socket() {
+    let env = TestEnv::new();
+    let _listener = std::os::unix::net::UnixListener::bind(env.path().join("socket")).unwrap();
+
+    let output = env.run(["socket"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stderr(&output), "error: 'socket' is a socket, not a file\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn device_is_read() {
+    let env = TestEnv::new();
+
+    let output = env.run(["--eol", "", "/dev/null"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855\n"
+    );
+}
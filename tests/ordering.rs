@@ -0,0 +1,82 @@
+mod common;
+
+use common::{stdout, TestEnv};
+
+/// Paths of the `HASH  FILE` lines of an output.
+fn paths(output: &std::process::Output) -> Vec<String> {
+    stdout(output)
+        .lines()
+        .map(|line| line.split_once("  ").unwrap().1.to_string())
+        .collect()
+}
+
+#[test]
+fn recursive_output_is_reproducible() {
+    let env = TestEnv::new();
+    for index in (0..50).rev() {
+        env.write(
+            format!("tree/{}/{index}.txt", index % 7),
+            format!("{index}\n"),
+        );
+    }
+
+    let first = env.run(["-r", "tree"]);
+    let second = env.run(["-r", "tree"]);
+
+    assert_eq!(first.status.code(), Some(0));
+    assert_eq!(stdout(&first).lines().count(), 50);
+    assert_eq!(first.stdout, second.stdout);
+}
+
+#[test]
+fn recursive_order_is_independent_of_creation_order() {
+    let env = TestEnv::new();
+    let files = ["b.txt", "a/z.txt", "a-b.txt", "a.txt", "a/b/c.txt", "B.txt"];
+
+    for file in files {
+        env.write(format!("forward/{file}"), "A B\n");
+    }
+    for file in files.iter().rev() {
+        env.write(format!("backward/{file}"), "A B\n");
+    }
+
+    let forward = env.run(["-r", "forward"]);
+    let backward = env.run(["-r", "backward"]);
+
+    let expected = ["B.txt", "a-b.txt", "a.txt", "a/b/c.txt", "a/z.txt", "b.txt"];
+    assert_eq!(
+        paths(&forward),
+        expected.map(|file| format!("forward/{file}"))
+    );
+    assert_eq!(
+        paths(&backward),
+        expected.map(|file| format!("backward/{file}"))
+    );
+}
+
+#[test]
+fn glob_order_is_bytewise() {
+    let env = TestEnv::new();
+    for file in ["a/b.txt", "a.txt", "a-b.txt", "c/d.txt"] {
+        env.write(file, "A B\n");
+    }
+
+    let output = env.run(["-r", "*"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(paths(&output), ["a-b.txt", "a.txt", "a/b.txt", "c/d.txt"]);
+}
+
+#[test]
+fn files_from_keeps_list_order() {
+    let env = TestEnv::new();
+    for file in ["a.txt", "b.txt", "c.txt"] {
+        env.write(file, "A B\n");
+    }
+    env.write("list.txt", "c.txt\na.txt\nb.txt\n");
+
+    let output = env.run(["--files-from", "list.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(paths(&output), ["c.txt", "a.txt", "b.txt"]);
+}
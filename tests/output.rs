@@ -0,0 +1,113 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT, HEADER_DEFAULT};
+
+#[test]
+fn output_batch() {
+    let env = TestEnv::new();
+    env.write("dist/a.txt", "A B\r\nC D\r\n");
+    env.write("dist/b.txt", "A B\nC D\n");
+
+    // The output file is only created after collecting the input files
+    let output = env.run(["-r", "dist", "-o", "dist/SHA256SUMS"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+
+    // Paths are listed relative to the checksum file, which `--check` resolves them against
+    assert_eq!(
+        fs::read_to_string(env.path().join("dist/SHA256SUMS")).unwrap(),
+        format!("{HEADER_DEFAULT}\n{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  b.txt\n")
+    );
+
+    let output = env.run(["--no-summary", "--check", "dist/SHA256SUMS"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "a.txt: OK\nb.txt: OK\n");
+}
+
+#[test]
+fn output_json_with_warning() {
+    let env = TestEnv::new();
+    env.write("dist/a.txt", "A B\r\nC D\r\n");
+    env.write("dist/b.bin", b"\0\x01");
+
+    let output = env.run(["-r", "dist", "--json", "--output", "report.json"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains("skipping binary file"));
+
+    let report = fs::read_to_string(env.path().join("report.json")).unwrap();
+    assert!(report.contains(HASH_DEFAULT));
+}
+
+#[test]
+fn output_replaces_existing_file() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+    let sums = env.write("SHA256SUMS", "old content\n");
+
+    let output = env.run(["input.txt", "-o", "SHA256SUMS"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(&sums).unwrap(),
+        format!("{HASH_DEFAULT}\n")
+    );
+}
+
+#[test]
+fn output_untouched_on_error() {
+    let env = TestEnv::new();
+    let sums = env.write("SHA256SUMS", "old content\n");
+
+    let output = env.run(["missing.txt", "-o", "SHA256SUMS"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(fs::read_to_string(&sums).unwrap(), "old content\n");
+    assert_eq!(fs::read_dir(env.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn output_missing_directory() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["input.txt", "-o", "missing/SHA256SUMS"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("cannot write 'missing"));
+}
+
+#[test]
+fn output_with_append() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["input.txt", "--append", "SUMS", "-o", "out.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        fs::read_to_string(env.path().join("SUMS")).unwrap(),
+        format!("{HEADER_DEFAULT}\n{HASH_DEFAULT}  input.txt\n")
+    );
+    assert_eq!(
+        fs::read_to_string(env.path().join("out.txt")).unwrap(),
+        format!("{HASH_DEFAULT}\n")
+    );
+
+    let output = env.run(["input.txt", "--append", "SUMS", "-o", "SUMS"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn output_check() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+    env.write("SHA256SUMS", format!("{HASH_DEFAULT}  input.txt\n"));
+
+    let output = env.run(["--no-summary", "--check", "SHA256SUMS", "-o", "result.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        fs::read_to_string(env.path().join("result.txt")).unwrap(),
+        "input.txt: OK\n"
+    );
+}
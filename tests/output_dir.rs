@@ -0,0 +1,118 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn output_dir_keeps_paths_below_common_ancestor() {
+    let env = TestEnv::new();
+    env.write("project/docs/a.txt", "A B\r\nC D\r\n");
+    env.write("project/src/b.txt", "A B\nC D\n");
+    env.write("list.txt", "project/docs/a.txt\nproject/src/b.txt\n");
+
+    let output = env.run(["--output-dir", "out", "--files-from", "list.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  project/docs/a.txt\n{HASH_DEFAULT}  project/src/b.txt\n")
+    );
+    assert_eq!(
+        fs::read(env.path().join("out/docs/a.txt")).unwrap(),
+        b"A B\nC D\n"
+    );
+    assert_eq!(
+        fs::read(env.path().join("out/src/b.txt")).unwrap(),
+        b"A B\nC D\n"
+    );
+}
+
+#[test]
+fn output_dir_recursive_keeps_tree() {
+    let env = TestEnv::new();
+    env.write("tree/sub/a.txt", "A B\r\nC D\r\n");
+    env.write("tree/logo.bin", b"\0\x01\x02");
+
+    let output = env.run(["--output-dir", "out", "-r", "tree"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read(env.path().join("out/sub/a.txt")).unwrap(),
+        b"A B\nC D\n"
+    );
+    assert!(!env.path().join("out/logo.bin").exists());
+}
+
+#[test]
+fn output_dir_with_relative_to() {
+    let env = TestEnv::new();
+    env.write("project/docs/a.txt", "A B\r\nC D\r\n");
+
+    let output = env.run([
+        "--output-dir",
+        "out",
+        "--relative-to",
+        ".",
+        "project/docs/a.txt",
+    ]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+    assert_eq!(
+        fs::read(env.path().join("out/project/docs/a.txt")).unwrap(),
+        b"A B\nC D\n"
+    );
+}
+
+#[test]
+fn output_dir_refuses_files_outside_relative_to() {
+    let env = TestEnv::new();
+    env.write("project/a.txt", "A B\r\nC D\r\n");
+    env.write("other/b.txt", "A B\r\nC D\r\n");
+    env.write("list.txt", "project/a.txt\nother/b.txt\n");
+
+    let output = env.run([
+        "--output-dir",
+        "out",
+        "--relative-to",
+        "project",
+        "--allow-outside",
+        "--files-from",
+        "list.txt",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("other/b.txt is not inside of project"));
+    assert!(!env.path().join("out").exists());
+}
+
+#[test]
+fn output_dir_detects_collision_before_writing() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("out/a.txt", "E F\n");
+    env.write("list.txt", "a.txt\nout/a.txt\n");
+
+    let output = env.run(["--output-dir", "out", "--files-from", "list.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "error: a.txt would be written to out/a.txt, overwriting the input out/a.txt\n"
+    );
+    assert_eq!(stdout(&output), "");
+    assert_eq!(fs::read(env.path().join("out/a.txt")).unwrap(), b"E F\n");
+    assert!(!env.path().join("out/out").exists());
+}
+
+#[test]
+fn output_dir_conflicts_with_file_out() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--output-dir", "out", "a.txt", "--output-file", "b.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("cannot be used with"));
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv};
+
+#[test]
+fn paragraph_mode_ignores_wrapping() {
+    let env = TestEnv::new();
+    env.write(
+        "narrow.md",
+        "Reflowing a paragraph changes\nevery line break but not\nits content.\n\nSecond.\n",
+    );
+    env.write(
+        "wide.md",
+        "Reflowing a paragraph changes every line break\r\nbut not its content.\r\n\r\n\r\nSecond.\r\n",
+    );
+
+    let narrow = env.run(["--paragraph-mode", "narrow.md"]);
+    let wide = env.run(["--paragraph-mode", "wide.md"]);
+    assert_eq!(narrow.status.code(), Some(0));
+    assert_eq!(stdout(&narrow), stdout(&wide));
+
+    assert_ne!(
+        stdout(&env.run(["narrow.md"])),
+        stdout(&env.run(["wide.md"]))
+    );
+}
+
+#[test]
+fn paragraph_mode_keeps_paragraphs_apart() {
+    let env = TestEnv::new();
+    env.write("separated.md", "First.\n\nSecond.\n");
+    env.write("joined.md", "First.\nSecond.\n");
+
+    let separated = env.run(["--paragraph-mode", "separated.md"]);
+    let joined = env.run(["--paragraph-mode", "joined.md"]);
+    assert_ne!(stdout(&separated), stdout(&joined));
+}
+
+#[test]
+fn paragraph_mode_output() {
+    let env = TestEnv::new();
+    env.write("in.md", "A\nB  \nC\\\nD\n\n\nE\n");
+
+    let output = env.run(["--paragraph-mode", "in.md", "--output-file", "out.md"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(env.path().join("out.md")).unwrap(),
+        "A B C\\ D\nE\n"
+    );
+
+    // With one paragraph per line, the output hashes to the same value line by line
+    assert_eq!(stdout(&output), stdout(&env.run(["out.md"])));
+
+    let output = env.run([
+        "--paragraph-mode",
+        "--preserve-hard-breaks",
+        "in.md",
+        "--output-file",
+        "hard.md",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(env.path().join("hard.md")).unwrap(),
+        "A B  \nC\\\nD\nE\n"
+    );
+}
+
+#[test]
+fn preserve_hard_breaks_requires_paragraph_mode() {
+    let env = TestEnv::new();
+    env.write("in.md", "A\n");
+
+    let output = env.run(["--preserve-hard-breaks", "in.md"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--paragraph-mode"));
+}
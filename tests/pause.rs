@@ -0,0 +1,25 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn pause_without_console_does_not_wait() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run_with_stdin(["--pause", "input.txt"], "");
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{}\n", HASH_DEFAULT));
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn pause_with_invalid_arguments() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(["--pause", "--no-such-flag"], "");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!stderr(&output).contains("Press Enter"));
+}
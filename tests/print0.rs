@@ -0,0 +1,52 @@
+mod common;
+
+use common::{stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn print0_single_file() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["-z", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\0"));
+}
+
+#[cfg(unix)]
+#[test]
+fn print0_newline_in_filename() {
+    let env = TestEnv::new();
+    env.write("a\nb.txt", "A B\r\nC D\r\n");
+    env.write("c.txt", "A B\nC D\n");
+
+    let output = env.run(["--print0", "*.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let records: Vec<_> = output.stdout.split(|&b| b == 0).collect();
+    assert_eq!(
+        records,
+        [
+            format!("{HASH_DEFAULT}  a\nb.txt").as_bytes(),
+            format!("{HASH_DEFAULT}  c.txt").as_bytes(),
+            b"",
+        ]
+    );
+
+    let output = env.run_with_stdin(["--files-from", "-", "-0", "-z"], "c.txt\0a\nb.txt\0");
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  c.txt\0{HASH_DEFAULT}  a\nb.txt\0")
+    );
+}
+
+#[test]
+fn print0_lint() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("b.txt", "A B\nC D\n");
+    env.write("c.txt", "A B\r\n");
+
+    let output = env.run(["lint", "-z", "a.txt", "b.txt", "c.txt"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "a.txt\0c.txt\0");
+}
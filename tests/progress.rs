@@ -0,0 +1,30 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn progress_hidden_without_terminal() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n".repeat(10000));
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    env.write("tree/b.txt", "A B\nC D\n");
+
+    let output = env.run(["--no-summary", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stderr(&output), "");
+
+    let output = env.run(["--no-summary", "-r", "tree", "--json"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn progress_disabled() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--no-progress", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+    assert_eq!(stderr(&output), "");
+}
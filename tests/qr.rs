@@ -0,0 +1,121 @@
+#![cfg(feature = "qr")]
+
+mod common;
+
+use std::fs::File;
+use std::path::Path;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT, HEADER_DEFAULT};
+
+/// Decode the only QR code in the PNG image at `path`.
+fn decode(path: &Path) -> String {
+    let decoder = png::Decoder::new(std::io::BufReader::new(File::open(path).unwrap()));
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Grayscale);
+
+    let (width, height) = (info.width as usize, info.height as usize);
+    let mut image =
+        rqrr::PreparedImage::prepare_from_greyscale(width, height, |x, y| pixels[y * width + x]);
+    let grids = image.detect_grids();
+    assert_eq!(grids.len(), 1);
+    grids[0].decode().unwrap().1
+}
+
+/// Expected content of the QR code with default options.
+fn payload(hash: &str) -> String {
+    format!("{} hash={}", HEADER_DEFAULT.trim_start_matches("# "), hash)
+}
+
+#[test]
+fn qr_out_round_trip() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    for level in ["low", "medium", "quartile", "high"] {
+        let output = env.run(["--qr-out", "code.png", "--qr-level", level, "input.txt"]);
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+
+        assert_eq!(decode(&env.path().join("code.png")), payload(HASH_DEFAULT));
+    }
+}
+
+#[test]
+fn qr_out_uses_options_and_format() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run([
+        "--qr-out",
+        "code.png",
+        "--eol",
+        "\r\n",
+        "--format",
+        "base64",
+        "input.txt",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    let hash = stdout(&output);
+
+    let expected = format!(
+        "normalized-hasher: eol=crlf ignore_whitespaces=false no_eof=false algorithm=sha256 \
+         format=v1 hash={}",
+        hash.trim_end()
+    );
+    assert_eq!(decode(&env.path().join("code.png")), expected);
+}
+
+#[test]
+fn qr_terminal() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--qr", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let stdout = stdout(&output);
+    let (hash, art) = stdout.split_once('\n').unwrap();
+    assert_eq!(hash, HASH_DEFAULT);
+    assert!(art.contains('▀') && art.contains('▄') && art.contains('█'));
+
+    // All rows of the code have the same width
+    let widths = art
+        .lines()
+        .map(|line| line.chars().count())
+        .collect::<Vec<_>>();
+    assert!(widths.len() > 10);
+    assert!(widths.iter().all(|&width| width == widths[0]));
+}
+
+#[test]
+fn qr_refuses_multiple_files() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\n");
+    env.write("tree/b.txt", "C D\n");
+
+    let output = env.run(["--qr-out", "code.png", "-r", "tree"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--qr cannot be used with multiple files"));
+    assert!(!env.path().join("code.png").exists());
+}
+
+#[test]
+fn qr_conflicts_with_expected() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\n");
+
+    let output = env.run(["--qr", "--expected", HASH_DEFAULT, "input.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn qr_out_unwritable() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\n");
+
+    let output = env.run(["--qr-out", "missing/code.png", "input.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("cannot create QR code"));
+}
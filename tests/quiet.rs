@@ -0,0 +1,62 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn quiet_prints_only_hash() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["-q", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout, format!("{}\n", HASH_DEFAULT).as_bytes());
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn quiet_suppresses_notes_and_warnings() {
+    let env = TestEnv::new();
+    env.write("dir/a.txt", "A B\nC D\n");
+    env.write("dir/b.bin", "\0");
+
+    let output = env.run(["--quiet", "-r", "dir"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{}  dir/a.txt\n", HASH_DEFAULT));
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn quiet_expected_prints_only_result() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["-q", "--expected", HASH_DEFAULT, "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "OK\n");
+
+    let output = env.run(["-q", "--expected", &"0".repeat(64), "input.txt"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "MISMATCH\n");
+}
+
+#[test]
+fn quiet_keeps_fatal_errors() {
+    let env = TestEnv::new();
+
+    let output = env.run(["-q", "missing.txt"]);
+
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).starts_with("error: cannot open 'missing.txt'"));
+}
+
+#[test]
+fn quiet_conflicts_with_verbose() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["-q", "-v", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use std::path::PathBuf;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Path of a file in the fixture tree, as printed on the current platform.
+fn tree_path(relative: &str) -> String {
+    relative
+        .split('/')
+        .fold(PathBuf::from("tree"), |path, component| {
+            path.join(component)
+        })
+        .display()
+        .to_string()
+}
+
+fn setup_tree(env: &TestEnv) {
+    for file in [
+        "b.txt",
+        "sub/deeper/d.txt",
+        "a.txt",
+        "sub/c.txt",
+        ".hidden/e.txt",
+    ] {
+        env.write(PathBuf::from("tree").join(file), "A B\r\nC D\r\n");
+    }
+    env.write("tree/sub/image.bin", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+}
+
+#[test]
+fn recursive_sorted_output() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["--no-summary", "-r", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let expected: String = [
+        ".hidden/e.txt",
+        "a.txt",
+        "b.txt",
+        "sub/c.txt",
+        "sub/deeper/d.txt",
+    ]
+    .iter()
+    .map(|file| format!("{HASH_DEFAULT}  {}\n", tree_path(file)))
+    .collect();
+    assert_eq!(stdout(&output), expected);
+    assert_eq!(
+        stderr(&output),
+        format!(
+            "note: skipping binary file {}\n",
+            tree_path("sub/image.bin")
+        )
+    );
+}
+
+#[test]
+fn recursive_no_hidden() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["--recursive", "--no-hidden", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!stdout(&output).contains(".hidden"));
+    assert_eq!(stdout(&output).lines().count(), 4);
+}
+
+#[test]
+fn directory_without_recursive() {
+    let env = TestEnv::new();
+    setup_tree(&env);
+
+    let output = env.run(["tree"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains("--recursive"));
+}
+
+#[test]
+fn recursive_gitignore() {
+    let env = TestEnv::new();
+    for file in ["a.txt", "target/debug/b.txt", "sub/c.txt", ".git/HEAD"] {
+        env.write(PathBuf::from("repo").join(file), "A B\r\nC D\r\n");
+    }
+    env.write("repo/.gitignore", "/target/\n");
+
+    let output = env.run(["-r", "repo"]);
+    assert_eq!(output.status.code(), Some(0));
+    let files: Vec<_> = stdout(&output)
+        .lines()
+        .map(|line| line.split_once("  ").unwrap().1.to_string())
+        .collect();
+    assert_eq!(
+        files,
+        [".gitignore", "a.txt", "sub/c.txt"]
+            .map(|file| tree_path(file).replacen("tree", "repo", 1))
+    );
+
+    let output = env.run(["-r", "--no-ignore", "repo"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("b.txt"));
+    assert!(!stdout(&output).contains("HEAD"));
+
+    // Explicitly named files are always hashed
+    let output = env.run(["repo/target/debug/b.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+}
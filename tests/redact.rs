@@ -0,0 +1,70 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv};
+
+#[test]
+fn redact_all_matches_of_line() {
+    let env = TestEnv::new();
+    env.write("secrets.ini", "token=abc user=x token=def\r\n");
+    env.write("redacted.ini", "token=*** user=x token=***\n");
+
+    let output = env.run(["--redact", r"token=\w+=>token=***", "secrets.ini"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), stdout(&env.run(["redacted.ini"])));
+
+    // Capture groups are expanded, like with `Regex::replace_all`
+    let output = env.run(["--redact", r"(token)=\w+=>$1=***", "secrets.ini"]);
+    assert_eq!(stdout(&output), stdout(&env.run(["redacted.ini"])));
+}
+
+#[test]
+fn redact_in_given_order() {
+    let env = TestEnv::new();
+    env.write("a.txt", "id 42 and 7\n");
+
+    let output = env.run(["--redact", r"\d+=>N", "--redact", "N and N=>pair", "a.txt"]);
+    let expected = env.write("expected.txt", "id pair\n");
+    assert_eq!(stdout(&output), stdout(&env.run([&expected])));
+
+    // The second pattern no longer matches if it is applied first
+    let output = env.run(["--redact", "N and N=>pair", "--redact", r"\d+=>N", "a.txt"]);
+    let expected = env.write("expected.txt", "id N and N\n");
+    assert_eq!(stdout(&output), stdout(&env.run([&expected])));
+}
+
+#[test]
+fn redact_output_rehashes_to_same_hash() {
+    let env = TestEnv::new();
+    env.write("db.conf", "host=db\r\npassword=hunter2\r\n");
+
+    let output = env.run([
+        "--redact",
+        "password=.*=>password=<redacted>",
+        "db.conf",
+        "--output-file",
+        "out.conf",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(env.path().join("out.conf")).unwrap(),
+        "host=db\npassword=<redacted>\n"
+    );
+    assert_eq!(stdout(&output), stdout(&env.run(["out.conf"])));
+}
+
+#[test]
+fn redact_invalid() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\n");
+
+    for (redaction, message) in [
+        ("token", "expected PATTERN=>REPLACEMENT"),
+        ("(=>x", "regex parse error"),
+    ] {
+        let output = env.run(["--redact", redaction, "a.txt"]);
+        assert_eq!(output.status.code(), Some(2));
+        assert!(stderr(&output).contains(message), "{}", stderr(&output));
+    }
+}
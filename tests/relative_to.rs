@@ -0,0 +1,99 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT, HEADER_DEFAULT};
+
+#[test]
+fn relative_to_base_itself() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--relative-to", "a.txt", "--tag", "a.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("SHA256 (.) = {HASH_DEFAULT}\n"));
+}
+
+#[test]
+fn relative_to_below_base() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    env.write("tree/sub/b.txt", "A B\nC D\n");
+    let tree = env.path().join("tree");
+
+    let output = env.run([
+        "--relative-to".as_ref(),
+        tree.as_os_str(),
+        "-r".as_ref(),
+        tree.as_os_str(),
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let lines: Vec<_> = stdout(&output)
+        .lines()
+        .map(|line| line.replace('\\', "/"))
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            format!("{HASH_DEFAULT}  a.txt"),
+            format!("{HASH_DEFAULT}  sub/b.txt"),
+        ]
+    );
+}
+
+#[test]
+fn relative_to_append_and_json() {
+    let env = TestEnv::new();
+    let file = env.write("tree/a.txt", "A B\r\nC D\r\n");
+
+    let output = env.run([
+        "--relative-to".as_ref(),
+        "tree".as_ref(),
+        "--append".as_ref(),
+        "SHA256SUMS".as_ref(),
+        file.as_os_str(),
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(env.path().join("SHA256SUMS")).unwrap(),
+        format!("{HEADER_DEFAULT}\n{HASH_DEFAULT}  a.txt\n")
+    );
+
+    let output = env.run([
+        "--relative-to".as_ref(),
+        "tree".as_ref(),
+        "--json".as_ref(),
+        file.as_os_str(),
+    ]);
+    let report: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["entries"][0]["path"], "a.txt");
+}
+
+#[test]
+fn relative_to_outside_base() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    env.write("b.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--relative-to", "tree", "b.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(stderr(&output).contains("is not inside of tree"));
+
+    let output = env.run([
+        "--relative-to",
+        "tree",
+        "--allow-outside",
+        "--filename-only",
+        "b.txt",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    let path = stdout(&output);
+    let path = Path::new(path.trim_end());
+    assert!(path.is_absolute());
+    assert!(path.ends_with("b.txt"));
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn missing_file_is_not_retried() {
+    let env = TestEnv::new();
+
+    let start = Instant::now();
+    let output = env.run(["--retries", "3", "--retry-delay", "10000", "missing.txt"]);
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert_eq!(output.status.code(), Some(3));
+    assert!(
+        stderr(&output).contains("missing.txt"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn retries_do_not_change_readable_files() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+    env.write("SUMS", format!("{HASH_DEFAULT}  input.txt\n"));
+
+    let output = env.run(["--retries", "2", "--retry-on-errno", "5", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+
+    let output = env.run(["--retries", "2", "--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(0));
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn self_check_passes() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    env.write("tree/b.txt", "A B\nC D\n");
+
+    let output = env.run(["--self-check", "input.txt", "--output-file", "output.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+    assert_eq!(
+        fs::read_to_string(env.path().join("output.txt")).unwrap(),
+        "A B\nC D\n"
+    );
+
+    let output = env.run([
+        "--self-check",
+        "--eol",
+        "crlf",
+        "input.txt",
+        "--output-file",
+        "crlf.txt",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = env.run([
+        "--self-check",
+        "--no-summary",
+        "--output-dir",
+        "out",
+        "-r",
+        "tree",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stderr(&output), "");
+    assert_eq!(
+        fs::read_to_string(env.path().join("out/b.txt")).unwrap(),
+        "A B\nC D\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn self_check_detects_lost_output() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    // Everything written to /dev/null is accepted, but nothing can be read back
+    let output = env.run(["--force", "input.txt", "--output-file", "/dev/null"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = env.run([
+        "--force",
+        "--self-check",
+        "input.txt",
+        "--output-file",
+        "/dev/null",
+    ]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "error: self-check of '/dev/null' failed: the written content does not have the computed hash\n"
+    );
+
+    let output = env.run([
+        "--force",
+        "--self-check",
+        "--json",
+        "input.txt",
+        "--output-file",
+        "/dev/null",
+    ]);
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["entries"][0]["error"]["kind"], "verify");
+}
+
+#[test]
+fn self_check_of_stdin_is_refused() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(
+        ["--self-check", "-", "--output-file", "output.txt"],
+        "A B\r\n",
+    );
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "error: --self-check cannot be used with standard input\n"
+    );
+    assert!(!env.path().join("output.txt").exists());
+}
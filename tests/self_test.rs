@@ -0,0 +1,51 @@
+mod common;
+
+use normalized_hash::vectors::VECTORS;
+
+use common::{stdout, TestEnv};
+
+#[test]
+fn self_test_passes() {
+    let env = TestEnv::new();
+
+    let output = env.run(["--self-test"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let stdout = stdout(&output);
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), VECTORS.len() + 1);
+    assert_eq!(lines[0], "PASS  empty file");
+    assert!(lines[..VECTORS.len()]
+        .iter()
+        .all(|line| line.starts_with("PASS  ")));
+    assert_eq!(
+        lines[VECTORS.len()],
+        format!("all {} test vectors passed", VECTORS.len())
+    );
+}
+
+#[test]
+fn vectors_match_command_line() {
+    let env = TestEnv::new();
+
+    for vector in VECTORS {
+        env.write("input.txt", vector.input);
+
+        let mut args = vec!["--no-config", "--eol", vector.eol];
+        if vector.ignore_whitespaces {
+            args.push("--ignore-whitespaces");
+        }
+        if vector.no_eof {
+            args.push("--no-eof");
+        }
+        args.push("input.txt");
+
+        let output = env.run(&args);
+        assert_eq!(
+            stdout(&output),
+            format!("{}\n", vector.expected),
+            "{}",
+            vector.description
+        );
+    }
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn sidecar_write_and_verify() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--verify-sidecar", "input.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "input.txt: MISSING\n");
+
+    let output = env.run(["--write-sidecar", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+    assert_eq!(
+        fs::read_to_string(env.path().join("input.txt.sha256")).unwrap(),
+        format!("{HASH_DEFAULT}  input.txt\n")
+    );
+
+    let output = env.run(["--verify-sidecar", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "input.txt: OK\n");
+}
+
+#[test]
+fn sidecar_custom_suffix() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--write-sidecar", "--sidecar-suffix", ".sum", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(env.path().join("input.txt.sum").exists());
+    assert!(!env.path().join("input.txt.sha256").exists());
+
+    let output = env.run(["--verify-sidecar", "--sidecar-suffix", ".sum", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "input.txt: OK\n");
+}
+
+#[test]
+fn sidecar_stale() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--write-sidecar", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    env.write("input.txt", "A B\nC D\nE F\n");
+
+    let output = env.run(["--verify-sidecar", "input.txt"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "input.txt: FAILED\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn sidecar_read_only_directory() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = TestEnv::new();
+    env.write("dir/input.txt", "A B\nC D\n");
+
+    let dir = env.path().join("dir");
+    fs::set_permissions(&dir, fs::Permissions::from_mode(0o555)).unwrap();
+
+    let output = env.run(["--write-sidecar", "dir/input.txt"]);
+
+    fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+
+    // Privileged users can write anyway, there is nothing to check then
+    if env.path().join("dir/input.txt.sha256").exists() {
+        return;
+    }
+
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).starts_with("error: cannot write dir/input.txt.sha256: "));
+}
@@ -0,0 +1,70 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+fn mkfifo(env: &TestEnv, name: &str) {
+    let status = Command::new("mkfifo")
+        .arg(env.path().join(name))
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn traversal_skips_fifo() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    mkfifo(&env, "tree/pipe");
+
+    let output = env.run(["--no-summary", "-r", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}  tree/a.txt\n"));
+    assert_eq!(stderr(&output), "");
+
+    let output = env.run(["--no-summary", "-r", "--verbose", "tree"]);
+    assert!(stderr(&output).starts_with("note: skipping tree/pipe, it is not a regular file\n"));
+
+    let output = env.run(["--no-summary", "tree/*"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}  tree/a.txt\n"));
+}
+
+#[test]
+fn explicit_fifo_is_read() {
+    let env = TestEnv::new();
+    mkfifo(&env, "pipe");
+
+    let child = env
+        .command()
+        .arg("pipe")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Opening the pipe for writing blocks until the hasher opens it for reading
+    let mut pipe = fs::OpenOptions::new()
+        .write(true)
+        .open(env.path().join("pipe"))
+        .unwrap();
+    pipe.write_all(b"A B\r\nC D\r\n").unwrap();
+    drop(pipe);
+
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+}
+
+#[test]
+fn dev_stdin_is_read() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(["/dev/stdin"], "A B\r\nC D\r\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::{fixture, normalized_hasher, stderr, stdout, TestEnv};
+
+#[test]
+fn stats_lists_each_file() {
+    let output = normalized_hasher()
+        .current_dir(fixture("stats"))
+        .args(["stats", "-r", "legacy"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "   LINES     CRLF       LF       CR TRAILING   BOM NO-NEWLINE  FILE\n\
+         \x20      3        2        0        0        1    no        yes  legacy/a.sql\n\
+         \x20      3        0        3        1        1   yes         no  legacy/sub/b.txt\n\
+         \x20      6        2        3        1        2     1          1  total (2 files)\n"
+    );
+    assert_eq!(stderr(&output), "note: skipping binary file legacy/c.bin\n");
+}
+
+#[test]
+fn stats_summary_only() {
+    let output = normalized_hasher()
+        .current_dir(fixture("stats"))
+        .args([
+            "stats",
+            "-r",
+            "--summary-only",
+            "--exclude",
+            "*.bin",
+            "legacy",
+        ])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "   LINES     CRLF       LF       CR TRAILING   BOM NO-NEWLINE  FILE\n\
+         \x20      6        2        3        1        2     1          1  total (2 files)\n"
+    );
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn stats_json() {
+    let output = normalized_hasher()
+        .current_dir(fixture("stats"))
+        .args(["stats", "--json", "legacy/a.sql"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "files": [{
+                "path": "legacy/a.sql",
+                "lines": 3,
+                "crlf": 2,
+                "lf": 0,
+                "cr": 0,
+                "trailing_whitespace": 1,
+                "bom": false,
+                "missing_final_newline": true,
+            }],
+            "total": {
+                "files": 1,
+                "lines": 3,
+                "crlf": 2,
+                "lf": 0,
+                "cr": 0,
+                "trailing_whitespace": 1,
+                "bom": 0,
+                "missing_final_newline": 1,
+            },
+        })
+    );
+}
+
+#[test]
+fn stats_json_output_file() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A\r\n");
+
+    let output = env.run(["-o", "stats.json", "stats", "--json", "a.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+    let json = std::fs::read(env.path().join("stats.json")).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(json["total"]["crlf"], 1);
+
+    // A failed write is an error with the exit code of I/O errors
+    let output = env.run(["-o", "missing/stats.json", "stats", "--json", "a.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("cannot write 'missing/stats.json'"));
+}
+
+#[test]
+fn stats_missing_file() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A\n");
+
+    let output = env.run(["stats", "a.txt", "missing.txt"]);
+
+    assert_eq!(output.status.code(), Some(5));
+    assert!(stdout(&output).contains("a.txt"));
+    assert!(stderr(&output).starts_with("warning: cannot open 'missing.txt'"));
+}
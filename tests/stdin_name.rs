@@ -0,0 +1,54 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn stdin_name_labels_checksum_line() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(
+        ["--tag", "--stdin-name", "generated/install.sql", "-"],
+        "A B\r\nC D\r\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("SHA256 (generated/install.sql) = {HASH_DEFAULT}\n")
+    );
+}
+
+#[test]
+fn stdin_name_labels_json_and_append() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(
+        ["--json", "--stdin-name", "generated/install.sql", "-"],
+        "A B\nC D\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("\"path\": \"generated/install.sql\""));
+
+    let output = env.run_with_stdin(
+        ["--append", "sums.txt", "--stdin-name", "install.sql", "-"],
+        "A B\nC D\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let sums = std::fs::read_to_string(env.path().join("sums.txt")).unwrap();
+    assert!(
+        sums.ends_with(&format!("{HASH_DEFAULT}  install.sql\n")),
+        "{sums}"
+    );
+}
+
+#[test]
+fn stdin_name_requires_stdin() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--stdin-name", "label", "input.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--stdin-name"));
+
+    let output = env.run_with_stdin(["--stdin-name", "label", "--write-sidecar", "-"], "");
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Last line of the output, without the trailing elapsed time.
+fn summary(output: &str) -> &str {
+    let line = output.lines().last().unwrap();
+    &line[..line.rfind(", ").unwrap()]
+}
+
+#[test]
+fn summary_of_batch_agrees_with_exit_code() {
+    let env = TestEnv::new();
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    env.write("tree/b.txt", b"A B\r\n\xff\xfe\r\n");
+    env.write("tree/c.bin", b"\0\x01\x02");
+    env.write("tree/d.txt", "A B\nC D\n");
+
+    let output = env.run(["-r", "tree"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(
+        summary(&stderr(&output)),
+        "summary: 2 hashed, 1 skipped, 1 unreadable"
+    );
+
+    let output = env.run(["-r", "tree", "--json"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert!(!stderr(&output).contains("summary:"));
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["summary"]["hashed"], 2);
+    assert_eq!(report["summary"]["skipped"], 1);
+    assert_eq!(report["summary"]["errors"], 1);
+    assert!(report["summary"]["elapsed_milliseconds"].as_f64().unwrap() >= 0.0);
+
+    env.write("tree/b.txt", "A B\n");
+    let output = env.run(["-r", "tree"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        summary(&stderr(&output)),
+        "summary: 3 hashed, 1 skipped, 0 unreadable"
+    );
+}
+
+#[test]
+fn summary_of_check_agrees_with_exit_code() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("b.txt", "A B\r\nC X\r\n");
+    env.write(
+        "SUMS",
+        format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  b.txt\nnot a checksum line\n"),
+    );
+
+    let output = env.run(["--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        summary(&stdout(&output)),
+        "summary: 1 OK, 1 failed, 0 missing, 1 skipped"
+    );
+
+    env.write(
+        "SUMS",
+        format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  missing.txt\n"),
+    );
+    let output = env.run(["--check", "--quiet", "SUMS"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        summary(&stdout(&output)),
+        "summary: 1 OK, 0 failed, 1 missing, 0 skipped"
+    );
+}
+
+#[test]
+fn no_summary() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\r\nC D\r\n");
+    env.write("b.txt", "A B\nC D\n");
+    env.write("SUMS", format!("{HASH_DEFAULT}  a.txt\n"));
+
+    let output = env.run(["--no-summary", "*.txt"]);
+    assert_eq!(stderr(&output), "");
+
+    let output = env.run(["--quiet", "*.txt"]);
+    assert_eq!(stderr(&output), "");
+
+    let output = env.run(["--no-summary", "--check", "SUMS"]);
+    assert_eq!(stdout(&output), "a.txt: OK\n");
+
+    let output = env.run(["--status", "--check", "SUMS"]);
+    assert_eq!(stdout(&output), "");
+
+    // A single file is no batch
+    let output = env.run(["a.txt"]);
+    assert_eq!(stderr(&output), "");
+}
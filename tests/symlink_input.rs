@@ -0,0 +1,64 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::os::unix::fs::symlink;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Create `a.txt`, a link `link.txt` to it and a link `broken.txt` to a missing file.
+fn links(env: &TestEnv) {
+    env.write("a.txt", "A B\r\nC D\r\n");
+    symlink("a.txt", env.path().join("link.txt")).unwrap();
+    symlink("missing.txt", env.path().join("broken.txt")).unwrap();
+}
+
+#[test]
+fn symlink_is_followed() {
+    let env = TestEnv::new();
+    links(&env);
+
+    let output = env.run(["link.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+}
+
+#[test]
+fn symlink_is_an_error() {
+    let env = TestEnv::new();
+    links(&env);
+
+    let output = env.run(["--no-follow-input-symlinks", "link.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "error: 'link.txt' is a symbolic link, not a file\n"
+    );
+
+    let output = env.run(["--no-follow-input-symlinks", "a.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = env.run(["--no-follow-input-symlinks", "-r", "--follow-symlinks", "."]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn broken_symlink_names_its_target() {
+    let env = TestEnv::new();
+    links(&env);
+
+    let output = env.run(["broken.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        stderr(&output),
+        "error: cannot open 'broken.txt': it is a symbolic link to 'missing.txt', which does \
+         not exist\n"
+    );
+
+    env.write("list.txt", "a.txt\nbroken.txt\n");
+    let output = env.run(["--no-summary", "--json", "--files-from", "list.txt"]);
+    assert_eq!(output.status.code(), Some(5));
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["entries"][1]["error"]["kind"], "broken_symlink");
+}
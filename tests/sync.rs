@@ -0,0 +1,46 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn sync_writes_output() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--sync", "input.txt", "--output-file", "output.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+    assert_eq!(
+        fs::read_to_string(env.path().join("output.txt")).unwrap(),
+        "A B\nC D\n"
+    );
+
+    let output = env.run([
+        "--sync",
+        "--self-check",
+        "--output-dir",
+        "out",
+        "-r",
+        "tree",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(env.path().join("out/a.txt")).unwrap(),
+        "A B\nC D\n"
+    );
+}
+
+#[test]
+fn sync_of_stdin_is_refused() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(["--sync", "-", "--output-file", "output.txt"], "A B\r\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "error: --sync cannot be used with standard input\n"
+    );
+}
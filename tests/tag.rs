@@ -0,0 +1,41 @@
+mod common;
+
+use common::{stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn tag_output() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--tag", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("SHA256 (input.txt) = {HASH_DEFAULT}\n")
+    );
+}
+
+#[test]
+fn tag_output_with_parentheses() {
+    let env = TestEnv::new();
+    env.write("input (copy) = 1).txt", "A B\nC D\n");
+
+    let output = env.run(["--tag", "input (copy) = 1).txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("SHA256 (input (copy) = 1).txt) = {HASH_DEFAULT}\n")
+    );
+}
+
+#[test]
+fn tag_conflicts_with_expected() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--tag", "--expected", HASH_DEFAULT, "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
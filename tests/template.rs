@@ -0,0 +1,81 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Check that the text is a date like `2023-08-26`.
+fn is_date(text: &str) -> bool {
+    let digits = text.bytes().filter(u8::is_ascii_digit).count();
+    text.len() == 10 && digits == 8 && text.as_bytes()[4] == b'-' && text.as_bytes()[7] == b'-'
+}
+
+#[test]
+fn template_all_placeholders() {
+    let env = TestEnv::new();
+    env.write("dir/install.sql", "A B\r\nC D\r\n");
+
+    let template = "{file}|{filename}|{algorithm}|{hash}|{date}|{lines}|{size}|{{hash}}";
+    let output = env.run(["--template", template, "dir/install.sql"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = stdout(&output);
+    let fields = stdout.trim_end_matches('\n').split('|').collect::<Vec<_>>();
+    assert_eq!(fields.len(), 8, "{}", stdout);
+    assert_eq!(
+        [fields[0], fields[1], fields[2], fields[3]],
+        ["dir/install.sql", "install.sql", "SHA256", HASH_DEFAULT]
+    );
+    assert!(is_date(fields[4]), "{}", fields[4]);
+    assert_eq!([fields[5], fields[6], fields[7]], ["2", "10", "{hash}"]);
+}
+
+#[test]
+fn template_from_file() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+    env.write(
+        "sentence.txt",
+        "File {filename}, SHA-256: {hash}, generated on {date}.\n",
+    );
+
+    let output = env.run(["--template", "@sentence.txt", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = stdout(&output);
+    let prefix = format!("File input.txt, SHA-256: {HASH_DEFAULT}, generated on ");
+    let date = stdout
+        .strip_prefix(&prefix)
+        .and_then(|rest| rest.strip_suffix(".\n"))
+        .unwrap_or_else(|| panic!("{}", stdout));
+    assert!(is_date(date), "{}", date);
+}
+
+#[test]
+fn template_multiple_files() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--template", "{file} has {lines} lines", "*.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "a.txt has 2 lines\nb.txt has 2 lines\n");
+}
+
+#[test]
+fn template_unknown_placeholder() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--template", "{md5}", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert!(
+        stderr(&output).contains(
+            "unknown placeholder {md5} in template, expected {hash}, {file}, {filename}, \
+             {algorithm}, {date}, {lines} or {size}"
+        ),
+        "{}",
+        stderr(&output)
+    );
+}
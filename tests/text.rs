@@ -0,0 +1,80 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn text_matches_file() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A");
+
+    let file = env.run(["input.txt"]);
+    let text = env.run(["--text", "A"]);
+    assert_eq!(text.status.code(), Some(0));
+    assert_eq!(stdout(&text), stdout(&file));
+
+    let file = env.run(["--no-eof", "input.txt"]);
+    let text = env.run(["--no-eof", "--text", "A"]);
+    assert_eq!(stdout(&text), stdout(&file));
+}
+
+#[test]
+fn text_multiple() {
+    let env = TestEnv::new();
+
+    let output = env.run(["--text", "A B\r\nC D\r\n", "--text", "A B\nC D"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  <text:1>\n{HASH_DEFAULT}  <text:2>\n")
+    );
+}
+
+#[test]
+fn text_join() {
+    let env = TestEnv::new();
+
+    let output = env.run(["--text", "A B", "--text", "C D", "--text-join"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+}
+
+#[test]
+fn text_labels_in_reports() {
+    let env = TestEnv::new();
+
+    let output = env.run(["--text", "A B\nC D", "--tag"]);
+    assert_eq!(
+        stdout(&output),
+        format!("SHA256 (<text:1>) = {HASH_DEFAULT}\n")
+    );
+
+    let output = env.run(["--text", "A B\nC D", "--text", "-- comment", "--json"]);
+    assert_eq!(output.status.code(), Some(0));
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["entries"][0]["path"], "<text:1>");
+    assert_eq!(report["entries"][0]["hash"], HASH_DEFAULT);
+    assert_eq!(report["entries"][1]["path"], "<text:2>");
+}
+
+#[test]
+fn text_expected() {
+    let env = TestEnv::new();
+
+    let output = env.run(["--text", "A B\nC D", "--expected", HASH_DEFAULT]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = env.run(["--text", "A", "--text", "B", "--expected", HASH_DEFAULT]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("cannot be used with multiple texts"));
+}
+
+#[test]
+fn text_conflicts_with_file_in() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A");
+
+    let output = env.run(["--text", "A", "input.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+}
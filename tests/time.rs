@@ -0,0 +1,93 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Parse a line like `time: NAME: 1.23 ms, 4.5 MB/s` into milliseconds and MB/s.
+fn parse_time(line: &str) -> (f64, f64) {
+    let mut figures = line.rsplit(", ");
+    let throughput = figures.next().unwrap();
+    let duration = figures.next().unwrap().rsplit(": ").next().unwrap();
+
+    let milliseconds = match duration.split_once(' ').unwrap() {
+        (value, "ms") => value.parse().unwrap(),
+        (value, "s") => value.parse::<f64>().unwrap() * 1000.0,
+        (_, unit) => panic!("unexpected unit {unit}"),
+    };
+    let throughput = throughput.strip_suffix(" MB/s").unwrap().parse().unwrap();
+
+    (milliseconds, throughput)
+}
+
+#[test]
+fn time_single_file() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n".repeat(10000));
+
+    let output = env.run(["--time", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output).lines().count(), 1);
+
+    let stderr = stderr(&output);
+    let lines = stderr.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with("time: input.txt: "));
+
+    let (milliseconds, throughput) = parse_time(lines[0]);
+    assert!(milliseconds > 0.0 && milliseconds < 60_000.0);
+    assert!(throughput >= 0.0);
+}
+
+#[test]
+fn time_multiple_files_with_total() {
+    let env = TestEnv::new();
+    env.write("dir/a.txt", "A B\nC D\n".repeat(1000));
+    env.write("dir/b.txt", "A B\nC D\n".repeat(1000));
+
+    let output = env.run(["--no-summary", "--time", "-r", "dir"]);
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let stderr = stderr(&output);
+    let lines = stderr.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("time: dir/a.txt: "));
+    assert!(lines[1].starts_with("time: dir/b.txt: "));
+    assert!(lines[2].starts_with("time: total: 2 files, "));
+
+    let (a, _) = parse_time(lines[0]);
+    let (b, _) = parse_time(lines[1]);
+    let (total, _) = parse_time(lines[2]);
+    assert!(total >= a && total >= b);
+}
+
+#[test]
+fn time_json() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--time", "--json", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stderr(&output), "");
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["entries"][0]["hash"], HASH_DEFAULT);
+
+    let milliseconds = json["entries"][0]["time"]["milliseconds"].as_f64().unwrap();
+    let total = json["summary"]["time"]["milliseconds"].as_f64().unwrap();
+    assert!(milliseconds > 0.0);
+    assert_eq!(milliseconds, total);
+}
+
+#[test]
+fn no_time_without_flag() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--json", "input.txt"]);
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["entries"][0].get("time").is_none());
+    assert!(json["summary"].get("time").is_none());
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn tty_no_notice_for_piped_stdin() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(["-"], "A B\r\nC D\r\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+    assert_eq!(stderr(&output), "");
+
+    let output = env.run_with_stdin(["--filter"], "A B\r\nC D\r\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "A B\nC D\n");
+    assert_eq!(stderr(&output), format!("{HASH_DEFAULT}\n"));
+}
+
+#[test]
+fn tty_no_tty_warning() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(["--no-tty-warning", "-"], "A B\r\nC D\r\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+    assert_eq!(stderr(&output), "");
+}
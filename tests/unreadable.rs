@@ -0,0 +1,53 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv};
+
+/// Files in the test tree, `b.txt` is the one that cannot be read.
+fn setup_tree(env: &TestEnv, unreadable: &[u8]) {
+    env.write("tree/a.txt", "A B\r\nC D\r\n");
+    env.write("tree/b.txt", unreadable);
+    env.write("tree/c.txt", "A B\r\nC D\r\n");
+}
+
+fn assert_skipped(env: &TestEnv) {
+    let output = env.run(["-r", "tree"]);
+    assert_eq!(output.status.code(), Some(5));
+    assert_eq!(stdout(&output).lines().count(), 2);
+    assert!(!stdout(&output).contains("b.txt"));
+    assert!(stderr(&output).contains("b.txt"));
+    assert!(stderr(&output).contains("warning: 1 file could not be read\n"));
+    assert!(stderr(&output).contains("summary: 2 hashed, 0 skipped, 1 unreadable, "));
+
+    let output = env.run(["-r", "--fail-fast", "tree"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output).lines().count(), 1);
+}
+
+#[test]
+fn skip_invalid_file() {
+    let env = TestEnv::new();
+    setup_tree(&env, b"A B\r\n\xff\xfe\r\n");
+
+    assert_skipped(&env);
+}
+
+#[cfg(unix)]
+#[test]
+fn skip_permission_denied() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = TestEnv::new();
+    setup_tree(&env, b"A B\r\nC D\r\n");
+
+    let file = env.path().join("tree/b.txt");
+    fs::set_permissions(&file, fs::Permissions::from_mode(0o000)).unwrap();
+
+    // Permissions are not enforced for privileged users
+    if fs::read(&file).is_ok() {
+        return;
+    }
+
+    assert_skipped(&env);
+}
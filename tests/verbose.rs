@@ -0,0 +1,76 @@
+mod common;
+
+use common::{fixture, normalized_hasher, stderr, stdout, TestEnv, HASH_DEFAULT};
+
+#[test]
+fn verbose_reports_normalization() {
+    let output = normalized_hasher()
+        .current_dir(fixture("verbose"))
+        .args(["--verbose", "--no-warn-mixed-eol", "mixed.txt"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        stderr(&output),
+        "note: mixed.txt: 4 lines read, 2 CRLF line endings converted, byte order mark kept, \
+         no final newline\n"
+    );
+}
+
+#[test]
+fn verbose_twice_reports_categories() {
+    let output = normalized_hasher()
+        .current_dir(fixture("verbose"))
+        .args([
+            "-vv",
+            "--ignore-whitespaces",
+            "--no-warn-mixed-eol",
+            "mixed.txt",
+        ])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        stderr(&output),
+        "note: mixed.txt: 4 lines read, 2 CRLF line endings converted, byte order mark kept, \
+         no final newline\n\
+         note: mixed.txt: found 2 CRLF, 1 LF, 0 CR; converted 2 CRLF, 0 LF; final newline \
+         added; 1 line changed, 1 with trailing whitespace\n"
+    );
+}
+
+#[test]
+fn verbose_keeps_stdout_clean() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["-v", "input.txt"]);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!("{}\n", HASH_DEFAULT));
+    assert_eq!(
+        stderr(&output),
+        "note: input.txt: 2 lines read, 2 CRLF line endings converted, no byte order mark, \
+         ends with a newline\n"
+    );
+}
+
+#[test]
+fn verbose_reports_each_file() {
+    let env = TestEnv::new();
+    env.write("dir/a.txt", "A\n");
+    env.write("dir/b.txt", "B\r\n");
+
+    let output = env.run(["-v", "--recursive", "dir"]);
+
+    assert!(output.status.success());
+    let stderr = stderr(&output);
+    assert!(stderr.contains(
+        "dir/a.txt: 1 line read, 0 CRLF line endings converted, no byte order mark, ends with a newline"
+    ));
+    assert!(stderr.contains(
+        "dir/b.txt: 1 line read, 1 CRLF line ending converted, no byte order mark, ends with a newline"
+    ));
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use serde_json::{json, Value};
+
+use common::{fixture, normalized_hasher, stderr, stdout, TestEnv};
+
+fn verify_dir(args: &[&str]) -> std::process::Output {
+    normalized_hasher()
+        .current_dir(fixture("verify_dir"))
+        .arg("verify-dir")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn verify_dir_classifies_paths() {
+    let output = verify_dir(&["before", "after"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = stdout(&output);
+    let (results, summary) = stdout.rsplit_once("summary: ").unwrap();
+    assert_eq!(
+        results,
+        "README.md: only in B\n\
+         notes.txt: only in A\n\
+         sql/schema.sql: identical\n\
+         sql/seed.sql: different in line 2\n"
+    );
+    assert!(summary.starts_with(
+        "1 identical, 1 different, 1 only in A, 1 only in B, 1 skipped, 0 unreadable, "
+    ));
+    assert_eq!(
+        stderr(&output),
+        "note: skipping binary file before/logo.bin\n"
+    );
+}
+
+#[test]
+fn verify_dir_json() {
+    let output = verify_dir(&["--json", "before", "after"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let results: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(
+        results,
+        json!({
+            "entries": [
+                { "path": "README.md", "status": "only_in_b" },
+                { "path": "logo.bin", "status": "skipped" },
+                { "path": "notes.txt", "status": "only_in_a" },
+                { "path": "sql/schema.sql", "status": "identical" },
+                { "path": "sql/seed.sql", "status": "different", "line": 2 },
+            ],
+            "summary": {
+                "identical": 1,
+                "different": 1,
+                "only_in_a": 1,
+                "only_in_b": 1,
+                "skipped": 1,
+                "unreadable": 0,
+            },
+        })
+    );
+}
+
+#[test]
+fn verify_dir_json_output_file() {
+    let env = TestEnv::new();
+    env.write("a/notes.txt", "A\r\n");
+    env.write("b/notes.txt", "A\n");
+
+    let output = env.run(["-o", "results.json", "verify-dir", "--json", "a", "b"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+    let json = std::fs::read(env.path().join("results.json")).unwrap();
+    let results: Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(results["summary"]["identical"], 1);
+
+    // A failed write is an error with the exit code of I/O errors
+    let output = env.run([
+        "-o",
+        "missing/results.json",
+        "verify-dir",
+        "--json",
+        "a",
+        "b",
+    ]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).contains("cannot write 'missing/results.json'"));
+}
+
+#[test]
+fn verify_dir_identical_trees() {
+    let output = verify_dir(&[
+        "--exclude",
+        "seed.sql",
+        "--exclude",
+        "*.txt",
+        "--exclude",
+        "*.md",
+        "--exclude",
+        "*.bin",
+        "before",
+        "after",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("sql/schema.sql: identical\nsummary: 1 identical, "));
+
+    let env = TestEnv::new();
+    env.write("a/x.txt", "A B\r\nC D\r\n");
+    env.write("b/x.txt", "A B\nC D");
+    let output = env.run(["verify-dir", "a", "b"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("x.txt: identical\nsummary: 1 identical, 0 different"));
+}
+
+#[test]
+fn verify_dir_options() {
+    let env = TestEnv::new();
+    env.write("a/x.txt", "A B \n");
+    env.write("b/x.txt", "A  B\n");
+
+    let output = env.run(["verify-dir", "a", "b"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = env.run(["--ignore-whitespaces", "verify-dir", "a", "b"]);
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn verify_dir_unreadable() {
+    let env = TestEnv::new();
+    env.write("a/x.txt", "A B\n");
+    env.write("b/x.txt", b"A \xff\n");
+
+    let output = env.run(["verify-dir", "a", "b"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stdout(&output).starts_with("x.txt: FAILED open or read\n"));
+    assert!(stderr(&output).contains("warning: cannot compare x.txt in both trees"));
+}
+
+#[test]
+fn verify_dir_requires_directories() {
+    let env = TestEnv::new();
+    env.write("a/x.txt", "A B\n");
+    env.write("file.txt", "A B\n");
+
+    let output = env.run(["verify-dir", "a", "file.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("file.txt is not a directory"));
+}
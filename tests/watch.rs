@@ -0,0 +1,149 @@
+#![cfg(all(unix, feature = "watch"))]
+
+mod common;
+
+use std::fs;
+use std::io::{BufRead, BufReader, Lines};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use common::{TestEnv, HASH_DEFAULT};
+
+/// Maximum time to wait for a line from the watching process.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Watching {
+    child: Child,
+    lines: Receiver<String>,
+}
+
+impl Watching {
+    fn start(env: &TestEnv, args: &[&str]) -> Self {
+        let mut child = env
+            .command()
+            .arg("--watch")
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        let stdout: Lines<BufReader<ChildStdout>> =
+            BufReader::new(child.stdout.take().unwrap()).lines();
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in stdout {
+                if sender.send(line.unwrap()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { child, lines }
+    }
+
+    /// Wait for the next line, split into timestamp, hash and file.
+    fn next(&self) -> (String, String, String) {
+        let line = self
+            .lines
+            .recv_timeout(TIMEOUT)
+            .expect("no line within timeout");
+        let mut fields = line.splitn(3, "  ").map(str::to_string);
+        (
+            fields.next().unwrap(),
+            fields.next().unwrap(),
+            fields.next().unwrap(),
+        )
+    }
+
+    /// Interrupt the process like Ctrl-C and return its exit code.
+    fn interrupt(mut self) -> Option<i32> {
+        let status = Command::new("kill")
+            .args(["-INT", &self.child.id().to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        self.child.wait().unwrap().code()
+    }
+}
+
+impl Drop for Watching {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+#[test]
+fn watch_prints_hash_on_change() {
+    let env = TestEnv::new();
+    let input = env.write("input.txt", "A B\nC D\n");
+
+    let watching = Watching::start(&env, &["input.txt"]);
+
+    let (timestamp, hash, file) = watching.next();
+    assert!(timestamp.ends_with('Z'), "{}", timestamp);
+    assert_eq!(hash, HASH_DEFAULT);
+    assert_eq!(file, "input.txt");
+
+    fs::write(&input, "A B\nC D\nE F\n").unwrap();
+
+    let (_, hash, file) = watching.next();
+    assert_ne!(hash, HASH_DEFAULT);
+    assert_eq!(file, "input.txt");
+
+    assert_eq!(watching.interrupt(), Some(0));
+}
+
+#[test]
+fn watch_debounces_successive_writes() {
+    let env = TestEnv::new();
+    let input = env.write("input.txt", "A B\nC D\n");
+
+    let watching = Watching::start(&env, &["input.txt"]);
+    watching.next();
+
+    // Like an editor that truncates the file first and then writes the content
+    fs::write(&input, "").unwrap();
+    fs::write(&input, "A B\r\nC D\r\n").unwrap();
+
+    let (_, hash, _) = watching.next();
+    assert_eq!(hash, HASH_DEFAULT);
+    assert!(watching.lines.recv_timeout(Duration::from_secs(1)).is_err());
+
+    assert_eq!(watching.interrupt(), Some(0));
+}
+
+#[test]
+fn watch_follows_atomic_save() {
+    let env = TestEnv::new();
+    let input = env.write("input.txt", "A B\nC D\n");
+
+    let watching = Watching::start(&env, &["input.txt"]);
+    watching.next();
+
+    let temp = env.write("input.txt.tmp", "first\n");
+    fs::rename(&temp, &input).unwrap();
+    let (_, first, _) = watching.next();
+
+    let temp = env.write("input.txt.tmp", "second\n");
+    fs::rename(&temp, &input).unwrap();
+    let (_, second, _) = watching.next();
+
+    assert_ne!(first, HASH_DEFAULT);
+    assert_ne!(second, first);
+
+    assert_eq!(watching.interrupt(), Some(0));
+}
+
+#[test]
+fn watch_conflicts_with_check() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--watch", "--check", "input.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT, HEADER_DEFAULT};
+
+#[test]
+fn with_filename_single_file() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["input.txt"]);
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+
+    let output = env.run(["--with-filename", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}  input.txt\n"));
+
+    let output = env.run(["--tag", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("SHA256 (input.txt) = {HASH_DEFAULT}\n")
+    );
+}
+
+#[test]
+fn with_filename_multiple_files() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--with-filename", "*.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  a.txt\n{HASH_DEFAULT}  b.txt\n")
+    );
+}
+
+#[test]
+fn with_filename_output_is_checkable() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+
+    let output = env.run(["--with-filename", "input.txt", "-o", "sums.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(env.path().join("sums.txt")).unwrap(),
+        format!("{HEADER_DEFAULT}\n{HASH_DEFAULT}  input.txt\n")
+    );
+
+    let output = env.run(["--no-summary", "--check", "sums.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "input.txt: OK\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn with_filename_rejects_line_breaks() {
+    let env = TestEnv::new();
+    env.write("a\nb.txt", "A B\nC D\n");
+
+    for flag in ["--with-filename", "--tag"] {
+        let output = env.run([flag, "a\nb.txt"]);
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(stdout(&output), "");
+        assert!(stderr(&output).contains("its name contains a line break"));
+    }
+
+    let output = env.run(["--with-filename", "--print0", "a\nb.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}  a\nb.txt\0"));
+}
+
+#[test]
+fn with_filename_conflicts() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\n");
+
+    let output = env.run(["--with-filename", "--tag", "input.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = env.run(["--with-filename", "--no-filename", "input.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[cfg(unix)]
+#[test]
+fn batch_rejects_line_breaks() {
+    let env = TestEnv::new();
+    env.write("a\nb.txt", "A B\nC D\n");
+    env.write("c.txt", "A B\nC D\n");
+
+    for args in [
+        &["*.txt"][..],
+        &["--tag", "*.txt"],
+        &["--filename-only", "*.txt"],
+        &["-r", "."],
+        &["--append", "SHA256SUMS", "*.txt"],
+    ] {
+        let output = env.run(args);
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        assert_eq!(stdout(&output), "", "{args:?}");
+        assert!(stderr(&output).contains("its name contains a line break"));
+    }
+    assert!(!env.path().join("SHA256SUMS").exists());
+
+    let output = env.run(["--print0", "*.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{HASH_DEFAULT}  a\nb.txt\0{HASH_DEFAULT}  c.txt\0")
+    );
+
+    let output = env.run(["--no-filename", "*.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n{HASH_DEFAULT}\n"));
+}
@@ -0,0 +1,85 @@
+#![cfg(all(unix, feature = "xattr"))]
+
+mod common;
+
+use common::{stderr, stdout, TestEnv, HASH_DEFAULT};
+
+/// Store the hash of `file` in its extended attribute, or return `false` if the file system of
+/// the test directory does not support extended attributes.
+fn store(env: &TestEnv, file: &str) -> bool {
+    let output = env.run(["--xattr", file]);
+    if stderr(&output).contains("does not support extended attributes") {
+        eprintln!("skipped, the file system does not support extended attributes");
+        return false;
+    }
+
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(stdout(&output), format!("{HASH_DEFAULT}\n"));
+    true
+}
+
+#[test]
+fn xattr_write_and_verify() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\r\nC D\r\n");
+
+    let output = env.run(["--verify-xattr", "input.txt"]);
+    if stderr(&output).contains("does not support extended attributes") {
+        return;
+    }
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "input.txt: UNSET\n");
+
+    if !store(&env, "input.txt") {
+        return;
+    }
+
+    let output = env.run(["--verify-xattr", "input.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "input.txt: OK\n");
+
+    env.write("input.txt", "A B\r\nC D\r\nE F\r\n");
+    let output = env.run(["--verify-xattr", "input.txt"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "input.txt: FAILED\n");
+}
+
+#[test]
+fn xattr_other_options() {
+    let env = TestEnv::new();
+    env.write("input.txt", "A B\nC D\n");
+    if !store(&env, "input.txt") {
+        return;
+    }
+
+    let output = env.run(["--verify-xattr", "--eol", "\r\n", "input.txt"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "input.txt: FAILED (stored with eol=0a ignore-whitespaces=false no-eof=false)\n"
+    );
+}
+
+#[test]
+fn xattr_multiple_files() {
+    let env = TestEnv::new();
+    env.write("a.txt", "A B\nC D\n");
+    env.write("b.txt", "A B\r\nC D\r\n");
+    env.write("c.txt", "A B\nC D\n");
+    if !store(&env, "a.txt") || !store(&env, "b.txt") {
+        return;
+    }
+
+    let output = env.run(["--verify-xattr", "*.txt"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "a.txt: OK\nb.txt: OK\nc.txt: UNSET\n");
+}
+
+#[test]
+fn xattr_stdin() {
+    let env = TestEnv::new();
+
+    let output = env.run_with_stdin(["--xattr", "-"], "A B\nC D\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--xattr cannot be used with standard input"));
+}